@@ -1,5 +1,7 @@
 use crate::features;
 use crate::shared;
+use tauri::Manager;
+use tauri_plugin_deep_link::DeepLinkExt;
 use tauri_plugin_window_state::StateFlags;
 
 include!(concat!(env!("OUT_DIR"), "/icon_stamp.rs"));
@@ -32,8 +34,27 @@ pub fn run() {
     }
 
     tauri::Builder::default()
+        // Must be registered first: a second launch hands its arguments to
+        // this instance. An otterly:// link is forwarded to the deep-link
+        // handler and focuses the main window; a plain launch opens another
+        // window so vaults can still be used side by side.
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            if argv.iter().any(|arg| arg.starts_with("otterly://")) {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.unminimize();
+                    let _ = window.set_focus();
+                }
+                return;
+            }
+            if let Err(e) = features::vault::service::open_window(app, None) {
+                log::warn!("Failed to open window for second launch: {}", e);
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .manage(features::watcher::service::WatcherState::default())
         .manage(features::search::service::SearchDbState::default())
+        .manage(features::local_api::service::LocalApiState::default())
+        .manage(features::deep_link::service::DeepLinkState::default())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(log_builder.build())
@@ -55,12 +76,18 @@ pub fn run() {
             features::vault::service::remove_vault_from_registry,
             features::vault::service::remember_last_vault,
             features::vault::service::get_last_vault_id,
+            features::vault::service::relink_vault,
+            features::vault::service::rename_vault,
+            features::vault::service::pin_vault,
+            features::vault::service::check_vaults,
+            features::vault::service::open_vault_window,
             features::watcher::service::watch_vault,
             features::watcher::service::unwatch_vault,
             features::search::service::index_build,
             features::search::service::index_cancel,
             features::search::service::index_rebuild,
             features::search::service::index_search,
+            features::search::service::index_search_all,
             features::search::service::index_suggest,
             features::search::service::index_suggest_planned,
             features::search::service::index_list_note_paths_by_prefix,
@@ -73,6 +100,7 @@ pub fn run() {
             features::search::service::index_note_links_snapshot,
             features::search::service::index_extract_local_note_links,
             features::search::service::rewrite_note_links,
+            features::search::service::apply_note_link_rewrites,
             features::search::service::resolve_note_link,
             features::notes::service::list_notes,
             features::notes::service::list_folders,
@@ -95,6 +123,26 @@ pub fn run() {
             features::vault_session::service::save_latest_vault_session,
             features::vault_settings::service::get_vault_setting,
             features::vault_settings::service::set_vault_setting,
+            features::templates::service::list_templates,
+            features::templates::service::create_note_from_template,
+            features::periodic_notes::service::open_periodic_note,
+            features::periodic_notes::service::navigate_periodic_note,
+            features::periodic_notes::service::list_periodic_notes,
+            features::tasks::service::tasks_query,
+            features::tasks::service::tasks_toggle,
+            features::export::service::export_html,
+            features::publish::service::publish_site,
+            features::import::service::import_notes,
+            features::archive::service::vault_export_archive,
+            features::archive::service::vault_import_archive,
+            features::replace::service::vault_replace_preview,
+            features::replace::service::vault_replace_apply,
+            features::replace::service::vault_replace_undo,
+            features::journal::service::undo_last_operation,
+            features::journal::service::redo_last_operation,
+            features::journal::service::journal_list,
+            features::refactor::service::merge_notes,
+            features::refactor::service::split_note,
             features::git::service::git_has_repo,
             features::git::service::git_init_repo,
             features::git::service::git_status,
@@ -103,8 +151,48 @@ pub fn run() {
             features::git::service::git_diff,
             features::git::service::git_show_file_at_commit,
             features::git::service::git_restore_file,
-            features::git::service::git_create_tag
+            features::git::service::git_create_tag,
+            features::git::service::git_discard_changes,
+            features::git::service::git_stash_save,
+            features::git::service::git_stash_list,
+            features::git::service::git_stash_apply,
+            features::git::service::git_stash_drop,
+            features::git::service::git_get_identity,
+            features::git::service::git_vault_timeline,
+            features::git::service::git_working_diff,
+            features::git::service::git_stage_hunks,
+            features::git::service::git_unstage_hunks,
+            features::local_api::service::local_api_status,
+            features::local_api::service::local_api_set_enabled,
+            features::local_api::service::local_api_create_token,
+            features::local_api::service::local_api_revoke_token,
+            features::deep_link::service::deep_link_take_pending,
+            features::plugins::service::plugins_list,
+            features::plugins::service::plugins_reload,
+            features::plugins::service::plugin_run_command
         ])
+        .setup(|app| {
+            features::plugins::service::load_on_startup(app.handle());
+            features::local_api::service::start_if_enabled(app.handle());
+
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            app.deep_link().register_all()?;
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                let urls: Vec<String> = event.urls().iter().map(|u| u.to_string()).collect();
+                features::deep_link::service::handle_urls(&handle, &urls);
+            });
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                let urls: Vec<String> = urls.iter().map(|u| u.to_string()).collect();
+                features::deep_link::service::handle_urls(app.handle(), &urls);
+            }
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                features::watcher::service::release_window(window.app_handle(), window.label());
+            }
+        })
         .register_uri_scheme_protocol("otterly-asset", |ctx, req| {
             shared::storage::handle_asset_request(ctx.app_handle(), req)
         })
@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(otterly_lib::run_cli())
+}
@@ -0,0 +1,160 @@
+use crate::features::git::service as git_service;
+use crate::features::journal::service::{self as journal, JournalOp};
+use crate::features::notes::service::{self as notes_service, NoteMeta};
+use crate::features::search::db::{self as search_db, IndexResult};
+use crate::features::search::link_parser;
+use crate::features::search::model::IndexNoteMeta;
+use crate::features::search::service as search_service;
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::vault_context::VaultContext;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::atomic::AtomicBool;
+
+#[derive(Debug, Serialize)]
+pub struct MoveResult {
+    pub from: String,
+    pub to: String,
+    /// Notes whose links were rewritten, the moved note included.
+    pub rewritten: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BrokenLink {
+    pub source: String,
+    pub target: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LinkCheckReport {
+    pub checked: usize,
+    pub broken: Vec<BrokenLink>,
+}
+
+fn note_path(path: &str) -> String {
+    let path = path.trim().trim_matches('/');
+    if path.to_ascii_lowercase().ends_with(".md") {
+        path.to_string()
+    } else {
+        format!("{}.md", path)
+    }
+}
+
+/// Opens the vault's search index and brings it up to date with the disk.
+pub(crate) fn open_synced_index(ctx: &VaultContext) -> AppResult<Connection> {
+    let conn = ctx.open_search_db()?;
+    let cancel = AtomicBool::new(false);
+    search_db::sync_index(&conn, ctx, &cancel, &|_, _| {}, &mut || {})?;
+    Ok(conn)
+}
+
+pub(crate) fn reindex(ctx: &VaultContext) -> AppResult<IndexResult> {
+    let conn = ctx.open_search_db()?;
+    let cancel = AtomicBool::new(false);
+    search_db::rebuild_index(&conn, ctx, &cancel, &|_, _| {}, &mut || {})
+        .map_err(AppError::from_index)
+}
+
+pub(crate) fn list(conn: &Connection, prefix: Option<&str>) -> AppResult<Vec<IndexNoteMeta>> {
+    let prefix = prefix
+        .map(|p| p.trim().trim_matches('/'))
+        .filter(|p| !p.is_empty())
+        .map(|p| format!("{}/", p));
+    Ok(search_db::get_all_notes_from_db(conn)?
+        .into_values()
+        .filter(|note| {
+            prefix
+                .as_deref()
+                .is_none_or(|prefix| note.path.starts_with(prefix))
+        })
+        .collect())
+}
+
+pub(crate) fn backlinks(ctx: &VaultContext, path: &str) -> AppResult<Vec<IndexNoteMeta>> {
+    Ok(search_service::note_links_snapshot_in(ctx, &note_path(path))?.backlinks)
+}
+
+pub(crate) fn check_links(conn: &Connection) -> AppResult<LinkCheckReport> {
+    let notes = search_db::get_all_notes_from_db(conn)?;
+    let mut broken = Vec::new();
+    for source in notes.keys() {
+        for orphan in search_db::get_orphan_outlinks(conn, source)? {
+            broken.push(BrokenLink {
+                source: source.clone(),
+                target: orphan.target_path,
+            });
+        }
+    }
+    Ok(LinkCheckReport {
+        checked: notes.len(),
+        broken,
+    })
+}
+
+pub(crate) fn create(ctx: &VaultContext, path: &str, markdown: &str) -> AppResult<NoteMeta> {
+    notes_service::create_note_file(ctx.root(), &note_path(path), markdown)
+}
+
+/// Moves a note and rewrites links to it in every backlinking note, plus the
+/// moved note's own relative links. Recorded as one `rename_note` entry.
+pub(crate) fn move_note(
+    conn: &Connection,
+    ctx: &VaultContext,
+    from: &str,
+    to: &str,
+) -> AppResult<MoveResult> {
+    let root = ctx.root();
+    let from = note_path(from);
+    let to = note_path(to);
+    let sources: Vec<String> = search_db::get_backlinks(conn, &from)?
+        .into_iter()
+        .map(|note| note.path)
+        .filter(|source| *source != from)
+        .collect();
+
+    notes_service::rename_note_at(root, &from, &to)?;
+    let mut ops = vec![JournalOp::Move {
+        from: from.clone(),
+        to: to.clone(),
+    }];
+    let target_map = HashMap::from([(from.clone(), to.clone())]);
+
+    let mut rewritten = Vec::new();
+    let moved_abs = ctx.abs_for_write(&to)?;
+    let markdown = fs::read_to_string(&moved_abs).map_err(|e| AppError::io(e, &to))?;
+    let moved = link_parser::rewrite_links(&markdown, &from, &to, &target_map);
+    if moved.changed {
+        notes_service::atomic_write(&moved_abs, &moved.markdown)?;
+        ops.push(journal::write_op(
+            root,
+            &to,
+            Some(markdown.as_bytes()),
+            Some(moved.markdown.as_bytes()),
+        )?);
+        rewritten.push(to.clone());
+    }
+    rewritten.extend(search_service::rewrite_links_collecting_ops(
+        root,
+        &sources,
+        &target_map,
+        &mut ops,
+    )?);
+    journal::record_operation_or_warn(root, "rename_note", ops);
+
+    Ok(MoveResult {
+        from,
+        to,
+        rewritten,
+    })
+}
+
+/// Commits `files` when the vault is a git repository; returns the commit id.
+pub(crate) fn commit(ctx: &VaultContext, message: &str, files: Vec<String>) -> AppResult<String> {
+    let vault_path = ctx.root().to_string_lossy().to_string();
+    if !git_service::git_has_repo(vault_path.clone())? {
+        return Err(AppError::git("vault is not a git repository"));
+    }
+    git_service::git_stage_and_commit(vault_path, message.to_string(), Some(files), None)
+}
@@ -0,0 +1,301 @@
+pub(crate) mod commands;
+
+use crate::features::search::model::SearchScope;
+use crate::features::search::service as search_service;
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::vault_context::VaultContext;
+use serde::Serialize;
+use std::io::Read;
+use std::path::PathBuf;
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+const USAGE: &str = "Usage: otterly-cli [--vault <path>] <command> [args]
+
+Commands:
+  search <query> [--scope all|title|path|content] [--limit <n>]
+  list [--prefix <folder>]
+  create <path> [--content <markdown> | --stdin] [--commit <message>]
+  move <from> <to> [--commit <message>]
+  backlinks <path>
+  check-links
+  reindex
+
+The vault defaults to the current directory. Output is JSON on stdout;
+errors are JSON on stderr. check-links exits with 1 when links are broken.";
+
+#[derive(Debug)]
+pub(crate) enum CliCommand {
+    Search {
+        query: String,
+        scope: SearchScope,
+        limit: usize,
+    },
+    List {
+        prefix: Option<String>,
+    },
+    Create {
+        path: String,
+        content: Option<String>,
+        stdin: bool,
+    },
+    Move {
+        from: String,
+        to: String,
+    },
+    Backlinks {
+        path: String,
+    },
+    CheckLinks,
+    Reindex,
+}
+
+#[derive(Debug)]
+pub(crate) struct CliArgs {
+    pub vault: Option<PathBuf>,
+    pub commit: Option<String>,
+    /// `None` prints the usage.
+    pub command: Option<CliCommand>,
+}
+
+fn parse_scope(value: &str) -> Result<SearchScope, String> {
+    match value {
+        "all" => Ok(SearchScope::All),
+        "title" => Ok(SearchScope::Title),
+        "path" => Ok(SearchScope::Path),
+        "content" => Ok(SearchScope::Content),
+        other => Err(format!("unknown search scope: {}", other)),
+    }
+}
+
+fn take_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for {}", flag))
+}
+
+fn expect_positionals(command: &str, positionals: &[String], count: usize) -> Result<(), String> {
+    if positionals.len() == count {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} expects {} argument(s), got {}",
+            command,
+            count,
+            positionals.len()
+        ))
+    }
+}
+
+pub(crate) fn parse_args(args: &[String]) -> Result<CliArgs, String> {
+    let mut vault = None;
+    let mut commit = None;
+    let mut scope = SearchScope::All;
+    let mut limit = DEFAULT_SEARCH_LIMIT;
+    let mut prefix = None;
+    let mut content = None;
+    let mut stdin = false;
+    let mut help = false;
+    let mut positionals = Vec::new();
+
+    let mut iter = args.iter().cloned();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--vault" => vault = Some(PathBuf::from(take_value(&mut iter, &arg)?)),
+            "--commit" => commit = Some(take_value(&mut iter, &arg)?),
+            "--scope" => scope = parse_scope(&take_value(&mut iter, &arg)?)?,
+            "--limit" => {
+                limit = take_value(&mut iter, &arg)?
+                    .parse()
+                    .map_err(|_| "--limit must be a number".to_string())?
+            }
+            "--prefix" => prefix = Some(take_value(&mut iter, &arg)?),
+            "--content" => content = Some(take_value(&mut iter, &arg)?),
+            "--stdin" => stdin = true,
+            "-h" | "--help" => help = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown option: {}", flag)),
+            _ => positionals.push(arg),
+        }
+    }
+
+    if help || positionals.is_empty() {
+        return Ok(CliArgs {
+            vault,
+            commit,
+            command: None,
+        });
+    }
+    let name = positionals.remove(0);
+    let command = match name.as_str() {
+        "search" => {
+            if positionals.is_empty() {
+                return Err("search expects a query".to_string());
+            }
+            CliCommand::Search {
+                query: positionals.join(" "),
+                scope,
+                limit,
+            }
+        }
+        "list" => {
+            expect_positionals(&name, &positionals, 0)?;
+            CliCommand::List { prefix }
+        }
+        "create" => {
+            expect_positionals(&name, &positionals, 1)?;
+            if stdin && content.is_some() {
+                return Err("use either --content or --stdin".to_string());
+            }
+            CliCommand::Create {
+                path: positionals.remove(0),
+                content,
+                stdin,
+            }
+        }
+        "move" => {
+            expect_positionals(&name, &positionals, 2)?;
+            let to = positionals.remove(1);
+            CliCommand::Move {
+                from: positionals.remove(0),
+                to,
+            }
+        }
+        "backlinks" => {
+            expect_positionals(&name, &positionals, 1)?;
+            CliCommand::Backlinks {
+                path: positionals.remove(0),
+            }
+        }
+        "check-links" => {
+            expect_positionals(&name, &positionals, 0)?;
+            CliCommand::CheckLinks
+        }
+        "reindex" => {
+            expect_positionals(&name, &positionals, 0)?;
+            CliCommand::Reindex
+        }
+        other => return Err(format!("unknown command: {}", other)),
+    };
+    if commit.is_some() && !matches!(command, CliCommand::Create { .. } | CliCommand::Move { .. }) {
+        return Err("--commit only applies to create and move".to_string());
+    }
+    Ok(CliArgs {
+        vault,
+        commit,
+        command: Some(command),
+    })
+}
+
+fn to_json<T: Serialize>(value: &T) -> AppResult<String> {
+    Ok(serde_json::to_string_pretty(value).map_err(|e| e.to_string())?)
+}
+
+#[derive(Serialize)]
+struct Committed<T: Serialize> {
+    #[serde(flatten)]
+    result: T,
+    commit: Option<String>,
+}
+
+fn vault_context(vault: Option<PathBuf>) -> AppResult<VaultContext> {
+    let root = match vault {
+        Some(path) => path,
+        None => std::env::current_dir()?,
+    };
+    if !root.is_dir() {
+        return Err(AppError::not_found("vault", Some(&root.to_string_lossy())));
+    }
+    VaultContext::for_root(&root)
+}
+
+/// Runs one command and returns its JSON output and exit code.
+fn execute(
+    ctx: &VaultContext,
+    command: CliCommand,
+    commit: Option<String>,
+) -> AppResult<(String, i32)> {
+    let output = match command {
+        CliCommand::Search {
+            query,
+            scope,
+            limit,
+        } => {
+            commands::open_synced_index(ctx)?;
+            to_json(&search_service::search_in(ctx, &query, scope, limit)?)?
+        }
+        CliCommand::List { prefix } => {
+            let conn = commands::open_synced_index(ctx)?;
+            to_json(&commands::list(&conn, prefix.as_deref())?)?
+        }
+        CliCommand::Backlinks { path } => {
+            commands::open_synced_index(ctx)?;
+            to_json(&commands::backlinks(ctx, &path)?)?
+        }
+        CliCommand::CheckLinks => {
+            let conn = commands::open_synced_index(ctx)?;
+            let report = commands::check_links(&conn)?;
+            let code = if report.broken.is_empty() { 0 } else { 1 };
+            return Ok((to_json(&report)?, code));
+        }
+        CliCommand::Reindex => to_json(&commands::reindex(ctx)?)?,
+        CliCommand::Create {
+            path,
+            content,
+            stdin,
+        } => {
+            let markdown = if stdin {
+                let mut buf = String::new();
+                std::io::stdin().read_to_string(&mut buf)?;
+                buf
+            } else {
+                content.unwrap_or_default()
+            };
+            let meta = commands::create(ctx, &path, &markdown)?;
+            commands::open_synced_index(ctx)?;
+            let commit = match commit {
+                Some(message) => Some(commands::commit(ctx, &message, vec![meta.path.clone()])?),
+                None => None,
+            };
+            to_json(&Committed {
+                result: meta,
+                commit,
+            })?
+        }
+        CliCommand::Move { from, to } => {
+            let conn = commands::open_synced_index(ctx)?;
+            let result = commands::move_note(&conn, ctx, &from, &to)?;
+            commands::open_synced_index(ctx)?;
+            let commit = match commit {
+                Some(message) => {
+                    let mut files = vec![result.from.clone(), result.to.clone()];
+                    files.extend(result.rewritten.iter().cloned());
+                    files.dedup();
+                    Some(commands::commit(ctx, &message, files)?)
+                }
+                None => None,
+            };
+            to_json(&Committed { result, commit })?
+        }
+    };
+    Ok((output, 0))
+}
+
+/// Entry point for the `otterly-cli` binary; returns the process exit code.
+pub fn run(args: Vec<String>) -> i32 {
+    let result = parse_args(&args)
+        .map_err(AppError::invalid_input)
+        .and_then(|args| match args.command {
+            Some(command) => execute(&vault_context(args.vault)?, command, args.commit),
+            None => Ok((USAGE.to_string(), 0)),
+        });
+    match result {
+        Ok((output, code)) => {
+            println!("{}", output);
+            code
+        }
+        Err(error) => {
+            let body = serde_json::json!({ "error": error });
+            eprintln!("{}", body);
+            2
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use crate::features::git::identity::IDENTITY_SETTING_KEY;
+use crate::features::vault::service::{open_vault, OpenVaultArgs};
+use crate::features::vault_settings::service::parse_vault_settings;
+use crate::shared::constants;
+use crate::shared::storage::{self, Vault};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::AppHandle;
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MANIFEST_FILE: &str = "manifest.json";
+/// Vault files live under this prefix so the manifest can never collide
+/// with a note.
+const FILES_PREFIX: &str = "vault/";
+const ARCHIVE_FORMAT_VERSION: u32 = 1;
+const VAULT_SETTINGS_PATH: &str = ".otterly/settings.json";
+
+#[derive(Debug, Deserialize)]
+pub struct ArchiveExportArgs {
+    pub vault_id: String,
+    pub output_path: String,
+    #[serde(default)]
+    pub include_settings: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ArchiveImportArgs {
+    pub archive_path: String,
+    pub dest_path: String,
+    /// Adds the restored folder to the vault registry.
+    #[serde(default)]
+    pub register: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub size: u64,
+    pub blake3: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format_version: u32,
+    pub vault_name: String,
+    pub created_at: i64,
+    pub include_settings: bool,
+    pub files: Vec<ArchiveEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveExportResult {
+    pub archive_path: String,
+    pub files: usize,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveImportResult {
+    pub vault_path: String,
+    pub files: usize,
+    pub vault: Option<Vault>,
+}
+
+fn is_safe_entry_path(rel: &str) -> bool {
+    !rel.is_empty()
+        && !rel.contains('\\')
+        && Path::new(rel)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn hash_reader(reader: &mut impl Read) -> Result<(String, u64), String> {
+    let mut hasher = blake3::Hasher::new();
+    let size = io::copy(reader, &mut hasher).map_err(|e| e.to_string())?;
+    Ok((hasher.finalize().to_hex().to_string(), size))
+}
+
+/// Every file in the vault except the `.otterly` and `.git` folders, plus the
+/// vault settings file when requested. `.otterlyignore` rules are not
+/// applied: they hide files from the app, and a backup should still keep them.
+fn collect_archive_files(root: &Path, include_settings: bool) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            !constants::is_excluded_folder(&name)
+        })
+        .filter_map(|e| e.ok())
+    {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(root).map_err(|e| e.to_string())?;
+        files.push(storage::normalize_relative_path(rel));
+    }
+    if include_settings && root.join(VAULT_SETTINGS_PATH).is_file() {
+        files.push(VAULT_SETTINGS_PATH.to_string());
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn resolve_archive_path(root: &Path, output_path: &str) -> Result<PathBuf, String> {
+    let output = PathBuf::from(output_path.trim());
+    if !output.is_absolute() {
+        return Err("archive path must be an absolute path".to_string());
+    }
+    let (Some(parent), Some(file_name)) = (output.parent(), output.file_name()) else {
+        return Err("invalid archive path".to_string());
+    };
+    let parent = parent
+        .canonicalize()
+        .map_err(|_| "archive folder does not exist".to_string())?;
+    let root = root.canonicalize().map_err(|e| e.to_string())?;
+    if parent.starts_with(&root) {
+        return Err("archive must be written outside the vault".to_string());
+    }
+    let resolved = parent.join(file_name);
+    if resolved.is_dir() {
+        return Err("archive path is a directory".to_string());
+    }
+    Ok(resolved)
+}
+
+fn write_archive(
+    root: &Path,
+    archive: &Path,
+    manifest: &mut ArchiveManifest,
+    files: &[String],
+) -> Result<(), String> {
+    let file = File::create(archive).map_err(|e| format!("failed to create archive: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    for rel in files {
+        let read_error = |e: io::Error| format!("failed to read {}: {}", rel, e);
+        let mut source = File::open(root.join(rel)).map_err(read_error)?;
+        let len = source.metadata().map_err(read_error)?.len();
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(len >= u64::from(u32::MAX));
+        zip.start_file(format!("{}{}", FILES_PREFIX, rel), options)
+            .map_err(|e| e.to_string())?;
+        // Hash the bytes as they go into the zip, so the manifest describes
+        // exactly what was archived even if the file changes meanwhile.
+        let mut out = HashingWriter {
+            inner: &mut zip,
+            hasher: blake3::Hasher::new(),
+            size: 0,
+        };
+        io::copy(&mut source, &mut out).map_err(|e| format!("failed to archive {}: {}", rel, e))?;
+        manifest.files.push(ArchiveEntry {
+            path: rel.clone(),
+            size: out.size,
+            blake3: out.hasher.finalize().to_hex().to_string(),
+        });
+    }
+
+    let manifest_json = serde_json::to_vec_pretty(manifest).map_err(|e| e.to_string())?;
+    zip.start_file(MANIFEST_FILE, SimpleFileOptions::default())
+        .map_err(|e| e.to_string())?;
+    zip.write_all(&manifest_json).map_err(|e| e.to_string())?;
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Writes every note and asset of the vault to a zip archive with a
+/// `manifest.json` listing each file's size and blake3 hash.
+pub(crate) fn export_archive_at(
+    root: &Path,
+    output_path: &str,
+    include_settings: bool,
+) -> Result<ArchiveExportResult, String> {
+    let archive = resolve_archive_path(root, output_path)?;
+    let files = collect_archive_files(root, include_settings)?;
+    let mut manifest = ArchiveManifest {
+        format_version: ARCHIVE_FORMAT_VERSION,
+        vault_name: root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        created_at: storage::now_ms(),
+        include_settings,
+        files: Vec::with_capacity(files.len()),
+    };
+
+    // Write next to the target first so a failed export never clobbers an
+    // earlier backup.
+    let tmp = archive.with_extension("zip.tmp");
+    if let Err(error) = write_archive(root, &tmp, &mut manifest, &files) {
+        let _ = fs::remove_file(&tmp);
+        return Err(error);
+    }
+    fs::rename(&tmp, &archive).map_err(|e| e.to_string())?;
+
+    Ok(ArchiveExportResult {
+        archive_path: archive.to_string_lossy().to_string(),
+        files: manifest.files.len(),
+        total_bytes: manifest.files.iter().map(|f| f.size).sum(),
+    })
+}
+
+fn read_manifest(archive: &mut ZipArchive<File>) -> Result<ArchiveManifest, String> {
+    let mut entry = archive
+        .by_name(MANIFEST_FILE)
+        .map_err(|_| "archive has no manifest".to_string())?;
+    let mut raw = Vec::new();
+    entry.read_to_end(&mut raw).map_err(|e| e.to_string())?;
+    let manifest: ArchiveManifest =
+        serde_json::from_slice(&raw).map_err(|e| format!("invalid manifest: {}", e))?;
+    if manifest.format_version > ARCHIVE_FORMAT_VERSION {
+        return Err(format!(
+            "unsupported archive format version {}",
+            manifest.format_version
+        ));
+    }
+    Ok(manifest)
+}
+
+/// Checks every manifest entry against the archive before anything is
+/// written, so a corrupt archive leaves the destination untouched.
+fn verify_archive(
+    archive: &mut ZipArchive<File>,
+    manifest: &ArchiveManifest,
+) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for file in &manifest.files {
+        if !is_safe_entry_path(&file.path) {
+            return Err(format!("unsafe path in manifest: {}", file.path));
+        }
+        if !seen.insert(file.path.to_lowercase()) {
+            return Err(format!("duplicate path in manifest: {}", file.path));
+        }
+        let mut entry = archive
+            .by_name(&format!("{}{}", FILES_PREFIX, file.path))
+            .map_err(|_| format!("archive is missing {}", file.path))?;
+        let (hash, size) = hash_reader(&mut entry)?;
+        if size != file.size || hash != file.blake3 {
+            return Err(format!("hash mismatch for {}", file.path));
+        }
+    }
+    Ok(())
+}
+
+/// Passes writes through to `inner` and hashes exactly the bytes it accepted.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: blake3::Hasher,
+    size: u64,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn check_entry(file: &ArchiveEntry, hash: &str, size: u64) -> Result<(), String> {
+    if size != file.size || hash != file.blake3 {
+        return Err(format!("hash mismatch for {}", file.path));
+    }
+    Ok(())
+}
+
+/// Restored settings keep everything except `git_identity.signing`, so an
+/// archive can't choose how commits in the restored vault are signed.
+fn sanitize_restored_settings(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut settings = match parse_vault_settings(bytes) {
+        Ok(settings) => settings,
+        Err(error) => {
+            log::warn!("Skipping unreadable vault settings in archive: {}", error);
+            return None;
+        }
+    };
+    if let Some(identity) = settings
+        .get_mut(IDENTITY_SETTING_KEY)
+        .and_then(|value| value.as_object_mut())
+    {
+        identity.remove("signing");
+    }
+    serde_json::to_vec_pretty(&settings).ok()
+}
+
+/// Writes one manifest entry to `target`, hashing the bytes as they are
+/// written. A mismatch removes the file.
+fn restore_entry(
+    archive: &mut ZipArchive<File>,
+    file: &ArchiveEntry,
+    target: &Path,
+) -> Result<(), String> {
+    let mut entry = archive
+        .by_name(&format!("{}{}", FILES_PREFIX, file.path))
+        .map_err(|_| format!("archive is missing {}", file.path))?;
+    let write_error = |e: io::Error| format!("failed to write {}: {}", file.path, e);
+
+    if file.path == VAULT_SETTINGS_PATH {
+        let mut bytes = Vec::new();
+        entry
+            .by_ref()
+            .take(file.size.saturating_add(1))
+            .read_to_end(&mut bytes)
+            .map_err(|e| e.to_string())?;
+        check_entry(file, &blake3::hash(&bytes).to_hex(), bytes.len() as u64)?;
+        if let Some(clean) = sanitize_restored_settings(&bytes) {
+            fs::write(target, clean).map_err(write_error)?;
+        }
+        return Ok(());
+    }
+
+    let mut out = HashingWriter {
+        inner: File::create(target).map_err(write_error)?,
+        hasher: blake3::Hasher::new(),
+        size: 0,
+    };
+    let copied = io::copy(&mut entry, &mut out).map_err(write_error);
+    let hash = out.hasher.finalize().to_hex().to_string();
+    let checked = copied.and_then(|_| check_entry(file, &hash, out.size));
+    if checked.is_err() {
+        let _ = fs::remove_file(target);
+    }
+    checked
+}
+
+fn prepare_destination(dest_path: &str) -> Result<PathBuf, String> {
+    let dest = PathBuf::from(dest_path.trim());
+    if !dest.is_absolute() {
+        return Err("destination must be an absolute path".to_string());
+    }
+    if dest.exists() {
+        let mut entries = fs::read_dir(&dest).map_err(|_| "destination is not a folder")?;
+        if entries.next().is_some() {
+            return Err("destination folder must be empty".to_string());
+        }
+    } else {
+        fs::create_dir_all(&dest).map_err(|e| e.to_string())?;
+    }
+    dest.canonicalize().map_err(|e| e.to_string())
+}
+
+fn clear_destination(dest: &Path) {
+    let Ok(entries) = fs::read_dir(dest) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let _ = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+    }
+}
+
+/// Verifies the archive's hashes, then restores its files into `dest_path`,
+/// which must be empty or not exist yet. Each file is hashed again as it is
+/// written, and a restore that fails midway leaves the destination empty.
+pub(crate) fn import_archive_at(
+    archive_path: &Path,
+    dest_path: &str,
+) -> Result<ArchiveImportResult, String> {
+    let file = File::open(archive_path).map_err(|e| format!("failed to open archive: {}", e))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| format!("failed to read archive: {}", e))?;
+    let manifest = read_manifest(&mut archive)?;
+    verify_archive(&mut archive, &manifest)?;
+
+    let dest = prepare_destination(dest_path)?;
+    let restored = manifest.files.iter().try_for_each(|file| {
+        let target = dest.join(&file.path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        restore_entry(&mut archive, file, &target)
+    });
+    if let Err(error) = restored {
+        // The destination was empty, so nothing of the user's is lost.
+        clear_destination(&dest);
+        return Err(error);
+    }
+
+    Ok(ArchiveImportResult {
+        vault_path: dest.to_string_lossy().to_string(),
+        files: manifest.files.len(),
+        vault: None,
+    })
+}
+
+#[tauri::command]
+pub fn vault_export_archive(
+    args: ArchiveExportArgs,
+    app: AppHandle,
+) -> Result<ArchiveExportResult, String> {
+    log::info!(
+        "Exporting vault archive vault_id={} output_path={} include_settings={}",
+        args.vault_id,
+        args.output_path,
+        args.include_settings
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    export_archive_at(&root, &args.output_path, args.include_settings)
+}
+
+#[tauri::command]
+pub fn vault_import_archive(
+    args: ArchiveImportArgs,
+    app: AppHandle,
+) -> Result<ArchiveImportResult, String> {
+    log::info!(
+        "Importing vault archive archive_path={} dest_path={} register={}",
+        args.archive_path,
+        args.dest_path,
+        args.register
+    );
+    let mut result = import_archive_at(&PathBuf::from(&args.archive_path), &args.dest_path)?;
+    if args.register {
+        result.vault = Some(open_vault(
+            app,
+            OpenVaultArgs {
+                vault_path: result.vault_path.clone(),
+            },
+        )?);
+    }
+    Ok(result)
+}
@@ -0,0 +1,277 @@
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::storage::VaultStore;
+use crate::shared::vault_context::VaultRegistry;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+use url::Url;
+
+const SCHEME: &str = "otterly";
+const EVENT: &str = "deep_link";
+/// Links kept while the frontend is not listening; older ones are dropped.
+const MAX_PENDING: usize = 16;
+
+/// A parsed `otterly://` link, before the vault and path are checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    Open {
+        vault: Option<String>,
+        path: String,
+        heading: Option<String>,
+    },
+    New {
+        vault: Option<String>,
+        path: String,
+        content: String,
+    },
+    Search {
+        vault: Option<String>,
+        query: String,
+    },
+}
+
+/// What the frontend should do for a link.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeepLinkEvent {
+    OpenNote {
+        vault_id: String,
+        note_path: String,
+        heading: Option<String>,
+    },
+    /// A `new` link. Nothing is written until the user confirms it.
+    ConfirmNew {
+        vault_id: String,
+        vault_name: String,
+        note_path: String,
+        content: String,
+    },
+    Search {
+        vault_id: Option<String>,
+        query: String,
+    },
+    Failed {
+        url: String,
+        error: AppError,
+    },
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn note_path(path: Option<String>) -> AppResult<String> {
+    let path = non_empty(path).ok_or_else(|| AppError::invalid_input("deep link needs a path"))?;
+    let path = path.trim_matches('/');
+    if path.to_ascii_lowercase().ends_with(".md") {
+        Ok(path.to_string())
+    } else {
+        Ok(format!("{}.md", path))
+    }
+}
+
+/// Parses `otterly://open`, `otterly://new` and `otterly://search` links.
+/// The action may be given as the host (`otterly://open?..`) or the path
+/// (`otterly:///open?..`).
+pub fn parse_deep_link(raw: &str) -> AppResult<DeepLink> {
+    let url = Url::parse(raw.trim())
+        .map_err(|e| AppError::invalid_input(format!("invalid deep link: {}", e)))?;
+    if url.scheme() != SCHEME {
+        return Err(AppError::invalid_input(format!(
+            "unsupported link scheme: {}",
+            url.scheme()
+        )));
+    }
+    let action = match url.host_str() {
+        Some(host) if !host.is_empty() => host.to_ascii_lowercase(),
+        _ => url.path().trim_matches('/').to_ascii_lowercase(),
+    };
+
+    let mut vault = None;
+    let mut path = None;
+    let mut content = None;
+    let mut query = None;
+    for (key, value) in url.query_pairs() {
+        let value = Some(value.into_owned());
+        match key.as_ref() {
+            "vault" => vault = value,
+            "path" => path = value,
+            "content" => content = value,
+            "q" => query = value,
+            _ => {}
+        }
+    }
+    let vault = non_empty(vault);
+
+    match action.as_str() {
+        "open" => Ok(DeepLink::Open {
+            vault,
+            path: note_path(path)?,
+            heading: url
+                .fragment()
+                .map(|f| {
+                    percent_encoding::percent_decode_str(f)
+                        .decode_utf8_lossy()
+                        .into_owned()
+                })
+                .and_then(|f| non_empty(Some(f))),
+        }),
+        "new" => Ok(DeepLink::New {
+            vault,
+            path: note_path(path)?,
+            content: content.unwrap_or_default(),
+        }),
+        "search" => Ok(DeepLink::Search {
+            vault,
+            query: non_empty(query)
+                .ok_or_else(|| AppError::invalid_input("deep link needs a search query"))?,
+        }),
+        other => Err(AppError::invalid_input(format!(
+            "unknown deep link action: {}",
+            other
+        ))),
+    }
+}
+
+/// Finds a vault by id, then by case-insensitive name. Without a key the last
+/// opened vault is used.
+fn resolve_vault_id(store: &VaultStore, key: Option<&str>) -> AppResult<String> {
+    let Some(key) = key else {
+        return store
+            .last_vault_id
+            .clone()
+            .ok_or_else(|| AppError::not_found("vault", None));
+    };
+    if let Some(entry) = store.vaults.iter().find(|e| e.vault.id == key) {
+        return Ok(entry.vault.id.clone());
+    }
+    let mut named = store
+        .vaults
+        .iter()
+        .filter(|e| e.vault.name.eq_ignore_ascii_case(key));
+    match (named.next(), named.next()) {
+        (Some(entry), None) => Ok(entry.vault.id.clone()),
+        (Some(_), Some(_)) => Err(AppError::invalid_input(format!(
+            "vault name is ambiguous: {}",
+            key
+        ))),
+        _ => Err(AppError::not_found("vault", None)),
+    }
+}
+
+/// Checks a parsed link against the registry and the vault on disk. `new`
+/// links only check that the note can be created; the frontend asks the user
+/// before creating it.
+pub fn resolve_deep_link(registry: &VaultRegistry, link: DeepLink) -> AppResult<DeepLinkEvent> {
+    let store = registry.load()?;
+    match link {
+        DeepLink::Open {
+            vault,
+            path,
+            heading,
+        } => {
+            let ctx = registry.context(&resolve_vault_id(&store, vault.as_deref())?)?;
+            if !ctx.abs(&path)?.is_file() {
+                return Err(AppError::not_found("note", Some(&path)));
+            }
+            Ok(DeepLinkEvent::OpenNote {
+                vault_id: ctx.vault_id().to_string(),
+                note_path: path,
+                heading,
+            })
+        }
+        DeepLink::New {
+            vault,
+            path,
+            content,
+        } => {
+            let ctx = registry.context(&resolve_vault_id(&store, vault.as_deref())?)?;
+            if ctx.abs_for_write(&path)?.exists() {
+                return Err(AppError::already_exists("note", &path));
+            }
+            let vault_name = store
+                .vaults
+                .iter()
+                .find(|e| e.vault.id == ctx.vault_id())
+                .map(|e| e.vault.name.clone())
+                .unwrap_or_default();
+            Ok(DeepLinkEvent::ConfirmNew {
+                vault_id: ctx.vault_id().to_string(),
+                vault_name,
+                note_path: path,
+                content,
+            })
+        }
+        DeepLink::Search { vault, query } => {
+            let vault_id = match vault {
+                Some(key) => Some(resolve_vault_id(&store, Some(&key))?),
+                None => None,
+            };
+            Ok(DeepLinkEvent::Search { vault_id, query })
+        }
+    }
+}
+
+/// Links that arrive before the frontend is listening (e.g. the one that
+/// launched the app) are queued until it asks for them.
+#[derive(Default)]
+pub struct DeepLinkState {
+    inner: Mutex<DeepLinkQueue>,
+}
+
+#[derive(Default)]
+struct DeepLinkQueue {
+    ready: bool,
+    pending: Vec<DeepLinkEvent>,
+}
+
+fn event_for(registry: &AppResult<VaultRegistry>, url: &str) -> DeepLinkEvent {
+    let result = registry
+        .clone()
+        .and_then(|registry| resolve_deep_link(&registry, parse_deep_link(url)?));
+    match result {
+        Ok(event) => event,
+        Err(error) => {
+            log::warn!("Rejected deep link {}: {}", url, error);
+            DeepLinkEvent::Failed {
+                url: url.to_string(),
+                error,
+            }
+        }
+    }
+}
+
+/// Entry point for the deep-link plugin's open-url callback.
+pub fn handle_urls(app: &AppHandle, urls: &[String]) {
+    let registry = VaultRegistry::for_app(app).map_err(AppError::from);
+    let state = app.state::<DeepLinkState>();
+    for url in urls {
+        log::info!("Handling deep link {}", url);
+        let event = event_for(&registry, url);
+        let Ok(mut queue) = state.inner.lock() else {
+            log::error!("deep link queue lock poisoned");
+            return;
+        };
+        if queue.ready {
+            let _ = app.emit(EVENT, event);
+            continue;
+        }
+        if queue.pending.len() == MAX_PENDING {
+            log::warn!("Deep link queue full, dropping the oldest link");
+            queue.pending.remove(0);
+        }
+        queue.pending.push(event);
+    }
+}
+
+#[tauri::command]
+pub fn deep_link_take_pending(state: State<'_, DeepLinkState>) -> AppResult<Vec<DeepLinkEvent>> {
+    let mut queue = state
+        .inner
+        .lock()
+        .map_err(|_| "deep link queue lock poisoned")?;
+    queue.ready = true;
+    Ok(std::mem::take(&mut queue.pending))
+}
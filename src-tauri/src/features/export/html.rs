@@ -0,0 +1,311 @@
+use crate::features::notes::service::title_from_markdown;
+use crate::features::search::link_parser::{
+    compute_line_starts, compute_relative_path, decode_percent_sequences, is_embedded_wikilink,
+    is_external_url, is_note_relative_target, markdown_options, parse_internal_markdown_target,
+    parse_wiki_link_target, resolve_relative_path, resolve_wiki_target, source_dir_from_path,
+    sourcepos_to_byte_range,
+};
+use crate::features::templates::expand::split_frontmatter;
+use comrak::nodes::NodeValue;
+use comrak::{markdown_to_html, parse_document, Arena};
+
+pub(crate) const STYLESHEET: &str = r#"
+:root { color-scheme: light dark; --fg: #1f2328; --muted: #59636e; --bg: #ffffff; --accent: #0969da; --border: #d1d9e0; --code-bg: #f6f8fa; }
+@media (prefers-color-scheme: dark) { :root { --fg: #e6edf3; --muted: #9198a1; --bg: #0d1117; --accent: #4493f8; --border: #3d444d; --code-bg: #151b23; } }
+* { box-sizing: border-box; }
+body { margin: 0; background: var(--bg); color: var(--fg); font: 16px/1.6 -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; }
+nav, main, footer { max-width: 46rem; margin: 0 auto; padding: 0 1.25rem; }
+nav { padding-top: 1.5rem; font-size: 0.9rem; }
+main { padding-bottom: 3rem; }
+footer { padding-bottom: 2rem; color: var(--muted); font-size: 0.9rem; }
+a { color: var(--accent); text-decoration: none; }
+a:hover { text-decoration: underline; }
+h1, h2, h3, h4, h5, h6 { line-height: 1.25; margin: 1.75rem 0 0.75rem; }
+img { max-width: 100%; height: auto; }
+pre, code { font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; font-size: 0.9em; }
+code { background: var(--code-bg); padding: 0.15em 0.35em; border-radius: 4px; }
+pre { background: var(--code-bg); padding: 1rem; border-radius: 6px; overflow-x: auto; }
+pre code { background: none; padding: 0; }
+blockquote { margin: 0; padding: 0 1rem; color: var(--muted); border-left: 4px solid var(--border); }
+table { border-collapse: collapse; display: block; overflow-x: auto; }
+th, td { border: 1px solid var(--border); padding: 0.4rem 0.75rem; }
+hr { border: 0; border-top: 1px solid var(--border); }
+ul.contains-task-list { list-style: none; padding-left: 1.25rem; }
+.muted { color: var(--muted); font-size: 0.9rem; }
+.backlinks { margin-top: 3rem; padding-top: 1rem; border-top: 1px solid var(--border); }
+.backlinks h2 { font-size: 1rem; margin-top: 0; }
+"#;
+
+const HTML_EXTENSION: &str = "html";
+
+pub(crate) struct RenderedNote {
+    pub title: String,
+    pub body_html: String,
+    /// Vault-relative paths of local images the note references.
+    pub assets: Vec<String>,
+    /// Vault-relative note paths the note links to but that are not exported.
+    pub unresolved_links: Vec<String>,
+}
+
+/// `folder/note.md` becomes `folder/note.html`.
+pub(crate) fn html_page_path(note_path: &str) -> String {
+    let stem = if note_path.to_ascii_lowercase().ends_with(".md") {
+        &note_path[..note_path.len() - 3]
+    } else {
+        note_path
+    };
+    format!("{}.{}", stem, HTML_EXTENSION)
+}
+
+/// Path from the page for `from_path` to `to_path`, both vault-relative.
+pub(crate) fn relative_href(from_path: &str, to_path: &str) -> String {
+    compute_relative_path(source_dir_from_path(from_path), to_path)
+}
+
+pub(crate) fn escape_html(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn markdown_destination(href: &str) -> String {
+    if href
+        .chars()
+        .any(|c| c.is_whitespace() || c == '(' || c == ')')
+    {
+        format!("<{}>", href)
+    } else {
+        href.to_string()
+    }
+}
+
+fn is_local_asset_url(url: &str) -> bool {
+    let lower = url.trim().to_ascii_lowercase();
+    !(lower.is_empty()
+        || is_external_url(&lower)
+        || lower.starts_with("data:")
+        || lower.starts_with("//")
+        || lower.starts_with('#')
+        || lower.contains("://"))
+}
+
+fn resolve_asset(source_path: &str, url: &str) -> Option<String> {
+    let mut decoded = decode_percent_sequences(url.trim());
+    if let Some(idx) = decoded.find(['#', '?']) {
+        decoded.truncate(idx);
+    }
+    resolve_relative_path(source_dir_from_path(source_path), &decoded)
+}
+
+fn resolve_embed(source_path: &str, raw_target: &str) -> Option<String> {
+    let base_dir = if is_note_relative_target(raw_target) {
+        source_dir_from_path(source_path)
+    } else {
+        ""
+    };
+    resolve_relative_path(base_dir, raw_target.trim_start_matches('/'))
+}
+
+fn is_note_target(path: &str) -> bool {
+    path.to_ascii_lowercase().ends_with(".md")
+}
+
+struct Rewriter<'a, F: Fn(&str) -> bool> {
+    source_path: &'a str,
+    is_exported: F,
+    assets: Vec<String>,
+    unresolved_links: Vec<String>,
+}
+
+impl<F: Fn(&str) -> bool> Rewriter<'_, F> {
+    fn note_link(&mut self, label: &str, target: &str) -> String {
+        if (self.is_exported)(target) {
+            let href = relative_href(self.source_path, &html_page_path(target));
+            return format!("[{}]({})", label, markdown_destination(&href));
+        }
+        if !self.unresolved_links.iter().any(|t| t == target) {
+            self.unresolved_links.push(target.to_string());
+        }
+        label.to_string()
+    }
+
+    fn asset(&mut self, asset_path: String) -> String {
+        let href = relative_href(self.source_path, &asset_path);
+        if !self.assets.contains(&asset_path) {
+            self.assets.push(asset_path);
+        }
+        markdown_destination(&href)
+    }
+
+    fn markdown_link(&mut self, span: &str, url: &str) -> Option<String> {
+        let target = parse_internal_markdown_target(url)
+            .and_then(|parsed| resolve_wiki_target(self.source_path, &parsed))?;
+        let split = span.rfind("](")?;
+        Some(self.note_link(&span[1..split], &target))
+    }
+
+    fn image(&mut self, span: &str, url: &str) -> Option<String> {
+        if !is_local_asset_url(url) {
+            return None;
+        }
+        let asset_path = resolve_asset(self.source_path, url)?;
+        let split = span.rfind("](")?;
+        let alt = &span[2..split];
+        Some(format!("![{}]({})", alt, self.asset(asset_path)))
+    }
+
+    fn wiki_link(&mut self, span: &str, url: &str, embedded: bool) -> Option<String> {
+        let raw_target = parse_wiki_link_target(url)?;
+        let inner = &span[2..span.len() - 2];
+        let label = inner
+            .split_once('|')
+            .map(|(_, label)| label)
+            .unwrap_or(inner)
+            .trim();
+
+        if embedded {
+            let target = resolve_embed(self.source_path, &raw_target)?;
+            if !is_note_target(&target) {
+                // The preceding `!` turns the replacement into an image.
+                return Some(format!("[{}]({})", label, self.asset(target)));
+            }
+        }
+        let target = resolve_wiki_target(self.source_path, &raw_target)?;
+        Some(self.note_link(label, &target))
+    }
+
+    /// Some comrak versions leave `![[embed]]` as plain text because the `!`
+    /// opens an image bracket first, so embeds are also picked out of text.
+    fn text_embeds(&mut self, span: &str) -> Option<String> {
+        let mut out = String::with_capacity(span.len());
+        let mut rest = span;
+        let mut changed = false;
+        while let Some(start) = rest.find("![[") {
+            let Some(len) = rest[start..].find("]]").map(|end| end + 2) else {
+                break;
+            };
+            let embed = &rest[start + 1..start + len];
+            let url = embed[2..embed.len() - 2]
+                .split_once('|')
+                .map(|(target, _)| target)
+                .unwrap_or(&embed[2..embed.len() - 2]);
+            out.push_str(&rest[..start + 1]);
+            match self.wiki_link(embed, url, true) {
+                Some(replacement) => {
+                    out.push_str(&replacement);
+                    changed = true;
+                }
+                None => out.push_str(embed),
+            }
+            rest = &rest[start + len..];
+        }
+        out.push_str(rest);
+        changed.then_some(out)
+    }
+}
+
+/// Renders a note body to HTML. Internal links to notes for which
+/// `is_exported` returns true point at their `.html` pages; links to other
+/// notes are flattened to their label. Local images and embedded
+/// attachments keep their vault-relative location and are listed in
+/// `assets` so the caller can copy them next to the pages.
+pub(crate) fn render_note(
+    source_path: &str,
+    markdown: &str,
+    is_exported: impl Fn(&str) -> bool,
+) -> RenderedNote {
+    let body = split_frontmatter(markdown)
+        .map(|(_, body)| body)
+        .unwrap_or(markdown);
+    let title = title_from_markdown(body).unwrap_or_else(|| {
+        let file_name = source_path.rsplit('/').next().unwrap_or(source_path);
+        file_name
+            .strip_suffix(".md")
+            .unwrap_or(file_name)
+            .to_string()
+    });
+
+    let arena = Arena::new();
+    let options = markdown_options();
+    let root = parse_document(&arena, body, &options);
+    let line_starts = compute_line_starts(body);
+    let mut rewriter = Rewriter {
+        source_path,
+        is_exported,
+        assets: Vec::new(),
+        unresolved_links: Vec::new(),
+    };
+    let mut replacements: Vec<(usize, usize, String)> = Vec::new();
+
+    for node in root.descendants() {
+        let data = node.data.borrow();
+        let Some((start, end)) = sourcepos_to_byte_range(&line_starts, data.sourcepos) else {
+            continue;
+        };
+        if end > body.len()
+            || !body.is_char_boundary(start)
+            || !body.is_char_boundary(end)
+            || replacements.iter().any(|(s, e, _)| start < *e && *s < end)
+        {
+            continue;
+        }
+        let span = &body[start..end];
+        let replacement = match &data.value {
+            NodeValue::Link(link) if span.starts_with('[') && span.ends_with(')') => {
+                rewriter.markdown_link(span, &link.url)
+            }
+            NodeValue::Image(link) if span.starts_with("![") && span.ends_with(')') => {
+                rewriter.image(span, &link.url)
+            }
+            NodeValue::WikiLink(link)
+                if span.starts_with("[[") && span.ends_with("]]") && span.len() >= 5 =>
+            {
+                rewriter.wiki_link(span, &link.url, is_embedded_wikilink(node))
+            }
+            NodeValue::Text(text) if &**text == span && span.contains("![[") => {
+                rewriter.text_embeds(span)
+            }
+            _ => None,
+        };
+        if let Some(replacement) = replacement {
+            replacements.push((start, end, replacement));
+        }
+    }
+
+    replacements.sort_by_key(|r| std::cmp::Reverse(r.0));
+    let mut rewritten = body.to_string();
+    for (start, end, replacement) in replacements {
+        rewritten.replace_range(start..end, &replacement);
+    }
+
+    RenderedNote {
+        title,
+        body_html: markdown_to_html(&rewritten, &options),
+        assets: rewriter.assets,
+        unresolved_links: rewriter.unresolved_links,
+    }
+}
+
+/// Wraps rendered content in a standalone document with the stylesheet
+/// inlined. `index_href` is relative to the page.
+pub(crate) fn wrap_page(title: &str, body_html: &str, index_href: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+<title>{title}</title>\n<style>{css}</style>\n</head>\n<body>\n\
+<nav><a href=\"{index}\">Index</a></nav>\n<main>\n{body}</main>\n</body>\n</html>\n",
+        title = escape_html(title),
+        css = STYLESHEET,
+        index = escape_html(index_href),
+        body = body_html,
+    )
+}
@@ -0,0 +1,254 @@
+use crate::features::export::html::{self, escape_html, html_page_path, relative_href};
+use crate::features::notes::service::safe_vault_abs;
+use crate::features::search::link_parser::source_dir_from_path;
+use crate::shared::storage;
+use crate::shared::vault_ignore::VaultIgnore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use walkdir::WalkDir;
+
+pub(crate) const INDEX_PAGE: &str = "index.html";
+
+#[derive(Debug, Deserialize)]
+pub struct HtmlExportArgs {
+    pub vault_id: String,
+    /// A note path, a folder path, or an empty string for the whole vault.
+    #[serde(default)]
+    pub source_path: String,
+    pub output_dir: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HtmlExportResult {
+    pub output_dir: String,
+    pub index_path: String,
+    pub pages: Vec<String>,
+    pub assets: Vec<String>,
+    pub missing_assets: Vec<String>,
+}
+
+/// Lists the notes under `source_path`, which may name a single note, a
+/// folder, or the vault root when empty. Notes hidden by the vault's ignore
+/// rules are left out, as they are from the file tree and search.
+pub(crate) fn collect_export_notes(root: &Path, source_path: &str) -> Result<Vec<String>, String> {
+    let source_path = source_path.trim().trim_matches('/');
+    let source_abs = if source_path.is_empty() {
+        root.to_path_buf()
+    } else {
+        safe_vault_abs(root, source_path)?
+    };
+
+    if source_abs.is_file() {
+        if !source_path.to_ascii_lowercase().ends_with(".md") {
+            return Err("only markdown notes can be exported".to_string());
+        }
+        return Ok(vec![source_path.to_string()]);
+    }
+    if !source_abs.is_dir() {
+        return Err("export source does not exist".to_string());
+    }
+
+    let ignore = VaultIgnore::load(root);
+    let mut notes = Vec::new();
+    for entry in WalkDir::new(&source_abs)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir()))
+        .filter_map(|e| e.ok())
+    {
+        let p = entry.path();
+        if !entry.file_type().is_file() || p.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Ok(rel) = p.strip_prefix(&source_abs) else {
+            continue;
+        };
+        let rel = storage::normalize_relative_path(rel);
+        notes.push(if source_path.is_empty() {
+            rel
+        } else {
+            format!("{}/{}", source_path, rel)
+        });
+    }
+    notes.sort();
+    Ok(notes)
+}
+
+/// Resolves the output directory and refuses locations inside the vault,
+/// where exported pages and copied assets would end up as vault content.
+pub(crate) fn resolve_output_dir(root: &Path, output_dir: &str) -> Result<PathBuf, String> {
+    let output = PathBuf::from(output_dir.trim());
+    if !output.is_absolute() {
+        return Err("output directory must be an absolute path".to_string());
+    }
+
+    let mut nearest_existing = output.as_path();
+    while !nearest_existing.exists() {
+        nearest_existing = nearest_existing
+            .parent()
+            .ok_or("output directory has no existing parent".to_string())?;
+    }
+    let suffix = output
+        .strip_prefix(nearest_existing)
+        .map_err(|_| "invalid output directory".to_string())?;
+    let resolved = nearest_existing
+        .canonicalize()
+        .map_err(|e| e.to_string())?
+        .join(suffix);
+
+    let root = root.canonicalize().map_err(|e| e.to_string())?;
+    if resolved.starts_with(&root) {
+        return Err("output directory must be outside the vault".to_string());
+    }
+    Ok(resolved)
+}
+
+pub(crate) fn write_output(out_dir: &Path, rel: &str, content: &str) -> Result<(), String> {
+    let target = out_dir.join(rel);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&target, content).map_err(|e| format!("failed to write {}: {}", rel, e))
+}
+
+fn index_title(root: &Path, source_path: &str) -> String {
+    let source_path = source_path.trim().trim_matches('/');
+    let name = if source_path.is_empty() {
+        root.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    } else {
+        source_path
+            .rsplit('/')
+            .next()
+            .unwrap_or(source_path)
+            .to_string()
+    };
+    let name = name.strip_suffix(".md").unwrap_or(&name).to_string();
+    if name.is_empty() {
+        "Index".to_string()
+    } else {
+        name
+    }
+}
+
+/// Copies vault assets to the same relative location under `out_dir`.
+/// Returns the copied and the missing asset paths.
+pub(crate) fn copy_assets(
+    root: &Path,
+    out_dir: &Path,
+    assets: Vec<String>,
+) -> Result<(Vec<String>, Vec<String>), String> {
+    let mut copied = Vec::new();
+    let mut missing = Vec::new();
+    for asset in assets {
+        let source = match safe_vault_abs(root, &asset) {
+            Ok(source) if source.is_file() => source,
+            _ => {
+                missing.push(asset);
+                continue;
+            }
+        };
+        let target = out_dir.join(&asset);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::copy(&source, &target).map_err(|e| format!("failed to copy {}: {}", asset, e))?;
+        copied.push(asset);
+    }
+    Ok((copied, missing))
+}
+
+pub(crate) fn render_index(title: &str, pages: &[(String, String)]) -> String {
+    let mut body = format!("<h1>{}</h1>\n<ul>\n", escape_html(title));
+    for (page_path, page_title) in pages {
+        let folder = source_dir_from_path(page_path);
+        body.push_str(&format!(
+            "<li><a href=\"{}\">{}</a>",
+            escape_html(page_path),
+            escape_html(page_title)
+        ));
+        if !folder.is_empty() {
+            body.push_str(&format!(
+                " <span class=\"muted\">{}</span>",
+                escape_html(folder)
+            ));
+        }
+        body.push_str("</li>\n");
+    }
+    body.push_str("</ul>\n");
+    html::wrap_page(title, &body, INDEX_PAGE)
+}
+
+/// Renders every note under `source_path` into `output_dir`, mirroring the
+/// vault layout so relative links and image paths stay valid, copies the
+/// referenced assets and writes an `index.html` listing the pages.
+pub(crate) fn export_html_at(
+    root: &Path,
+    source_path: &str,
+    output_dir: &str,
+) -> Result<HtmlExportResult, String> {
+    let out_dir = resolve_output_dir(root, output_dir)?;
+    let notes = collect_export_notes(root, source_path)?;
+    if notes.is_empty() {
+        return Err("no notes to export".to_string());
+    }
+    fs::create_dir_all(&out_dir).map_err(|e| e.to_string())?;
+
+    let exported: HashSet<&str> = notes.iter().map(String::as_str).collect();
+    let mut pages = Vec::with_capacity(notes.len());
+    let mut index_entries = Vec::with_capacity(notes.len());
+    let mut assets: Vec<String> = Vec::new();
+
+    for note_path in &notes {
+        let markdown = fs::read_to_string(safe_vault_abs(root, note_path)?)
+            .map_err(|e| format!("failed to read {}: {}", note_path, e))?;
+        let rendered = html::render_note(note_path, &markdown, |target| exported.contains(target));
+        let page_path = html_page_path(note_path);
+        let page = html::wrap_page(
+            &rendered.title,
+            &rendered.body_html,
+            &relative_href(note_path, INDEX_PAGE),
+        );
+        write_output(&out_dir, &page_path, &page)?;
+
+        for asset in rendered.assets {
+            if !assets.contains(&asset) {
+                assets.push(asset);
+            }
+        }
+        index_entries.push((page_path.clone(), rendered.title));
+        pages.push(page_path);
+    }
+
+    let (copied, missing_assets) = copy_assets(root, &out_dir, assets)?;
+
+    // A root `index.md` already serves as the landing page.
+    if !pages.iter().any(|page| page == INDEX_PAGE) {
+        let index = render_index(&index_title(root, source_path), &index_entries);
+        write_output(&out_dir, INDEX_PAGE, &index)?;
+    }
+
+    Ok(HtmlExportResult {
+        output_dir: out_dir.to_string_lossy().to_string(),
+        index_path: out_dir.join(INDEX_PAGE).to_string_lossy().to_string(),
+        pages,
+        assets: copied,
+        missing_assets,
+    })
+}
+
+#[tauri::command]
+pub fn export_html(args: HtmlExportArgs, app: AppHandle) -> Result<HtmlExportResult, String> {
+    log::info!(
+        "Exporting HTML vault_id={} source_path={} output_dir={}",
+        args.vault_id,
+        args.source_path,
+        args.output_dir
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    export_html_at(&root, &args.source_path, &args.output_dir)
+}
@@ -0,0 +1,143 @@
+use crate::shared::error::{AppError, AppResult};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHunkSelection {
+    pub hunk_index: usize,
+    #[serde(default)]
+    pub lines: Option<Vec<usize>>,
+}
+
+pub(crate) struct PatchLine {
+    pub origin: char,
+    pub content: Vec<u8>,
+}
+
+pub(crate) struct PatchHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub lines: Vec<PatchLine>,
+}
+
+/// Collects the hunks of a single-file patch. Only context, addition and
+/// deletion lines are kept, so line indices match `GitDiffHunk::lines`.
+pub(crate) fn patch_hunks(patch: &git2::Patch<'_>) -> AppResult<Vec<PatchHunk>> {
+    let mut hunks = Vec::with_capacity(patch.num_hunks());
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch
+            .hunk(hunk_idx)
+            .map_err(|e| AppError::git(format!("failed to read hunk: {}", e)))?;
+        let mut lines = Vec::with_capacity(line_count);
+        for line_idx in 0..line_count {
+            let line = patch
+                .line_in_hunk(hunk_idx, line_idx)
+                .map_err(|e| AppError::git(format!("failed to read hunk line: {}", e)))?;
+            if !matches!(line.origin(), ' ' | '+' | '-') {
+                continue;
+            }
+            lines.push(PatchLine {
+                origin: line.origin(),
+                content: line.content().to_vec(),
+            });
+        }
+        hunks.push(PatchHunk {
+            old_start: hunk.old_start() as usize,
+            old_lines: hunk.old_lines() as usize,
+            lines,
+        });
+    }
+    Ok(hunks)
+}
+
+pub(crate) fn validate_selections(
+    hunks: &[PatchHunk],
+    selections: &[GitHunkSelection],
+) -> AppResult<()> {
+    if selections.is_empty() {
+        return Err(AppError::invalid_input("no hunks selected"));
+    }
+    for selection in selections {
+        let Some(hunk) = hunks.get(selection.hunk_index) else {
+            return Err(AppError::invalid_input(format!(
+                "hunk {} does not exist",
+                selection.hunk_index
+            )));
+        };
+        if let Some(lines) = &selection.lines {
+            if let Some(line) = lines.iter().find(|l| **l >= hunk.lines.len()) {
+                return Err(AppError::invalid_input(format!(
+                    "line {} does not exist in hunk {}",
+                    line, selection.hunk_index
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn is_selected(selections: &[GitHunkSelection], hunk: usize, line: usize) -> bool {
+    selections.iter().any(|s| {
+        s.hunk_index == hunk
+            && s.lines
+                .as_ref()
+                .map(|lines| lines.contains(&line))
+                .unwrap_or(true)
+    })
+}
+
+fn push_line(out: &mut Vec<u8>, line: &[u8]) {
+    if out.last().is_some_and(|b| *b != b'\n') {
+        out.push(b'\n');
+    }
+    out.extend_from_slice(line);
+}
+
+/// Rebuilds `old` with only the changed lines for which `keep(hunk, line)`
+/// returns true applied. Unkept deletions stay and unkept additions are
+/// dropped, so applying nothing returns `old` unchanged.
+pub(crate) fn apply_hunks(
+    old: &[u8],
+    hunks: &[PatchHunk],
+    keep: impl Fn(usize, usize) -> bool,
+) -> Vec<u8> {
+    let old_lines: Vec<&[u8]> = old.split_inclusive(|b| *b == b'\n').collect();
+    let mut out = Vec::with_capacity(old.len());
+    let mut old_pos = 0usize;
+
+    for (hunk_idx, hunk) in hunks.iter().enumerate() {
+        // A pure insertion names the line it follows rather than the first
+        // line it replaces.
+        let hunk_begin = if hunk.old_lines == 0 {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        while old_pos < hunk_begin.min(old_lines.len()) {
+            push_line(&mut out, old_lines[old_pos]);
+            old_pos += 1;
+        }
+
+        for (line_idx, line) in hunk.lines.iter().enumerate() {
+            match line.origin {
+                ' ' => {
+                    push_line(&mut out, &line.content);
+                    old_pos += 1;
+                }
+                '-' => {
+                    if !keep(hunk_idx, line_idx) {
+                        push_line(&mut out, &line.content);
+                    }
+                    old_pos += 1;
+                }
+                '+' if keep(hunk_idx, line_idx) => push_line(&mut out, &line.content),
+                _ => {}
+            }
+        }
+    }
+
+    while old_pos < old_lines.len() {
+        push_line(&mut out, old_lines[old_pos]);
+        old_pos += 1;
+    }
+    out
+}
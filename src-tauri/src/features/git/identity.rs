@@ -0,0 +1,266 @@
+use crate::features::vault_settings::service::load_vault_settings_at;
+use crate::shared::error::{AppError, AppResult};
+use git2::{Repository, Signature};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+pub(crate) const IDENTITY_SETTING_KEY: &str = "git_identity";
+
+const FALLBACK_NAME: &str = "Otterly";
+const FALLBACK_EMAIL: &str = "otterly@local";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitSigningFormat {
+    Openpgp,
+    Ssh,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitSigningSettings {
+    pub format: GitSigningFormat,
+    /// Only ever taken from git config. Vault settings travel with the vault
+    /// (sync, shared repos, archives), so they must not pick what we run.
+    #[serde(skip_deserializing)]
+    pub program: Option<String>,
+    #[serde(default)]
+    pub key: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GitIdentitySettings {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub signing: Option<GitSigningSettings>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitIdentitySource {
+    VaultSettings,
+    GitConfig,
+    Default,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitIdentity {
+    pub name: String,
+    pub email: String,
+    pub source: GitIdentitySource,
+    pub signing_format: Option<GitSigningFormat>,
+}
+
+pub(crate) struct ResolvedIdentity {
+    pub identity: GitIdentity,
+    pub signing: Option<GitSigningSettings>,
+}
+
+impl ResolvedIdentity {
+    pub fn signature(&self) -> AppResult<Signature<'static>> {
+        Signature::now(&self.identity.name, &self.identity.email)
+            .map_err(|e| AppError::git(format!("failed to create signature: {}", e)))
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn load_identity_settings(vault_path: &Path) -> GitIdentitySettings {
+    let settings = match load_vault_settings_at(vault_path) {
+        Ok(settings) => settings,
+        Err(error) => {
+            log::warn!(
+                "Ignoring unreadable vault settings for git identity: {}",
+                error
+            );
+            return GitIdentitySettings::default();
+        }
+    };
+    let Some(value) = settings.get(IDENTITY_SETTING_KEY) else {
+        return GitIdentitySettings::default();
+    };
+    serde_json::from_value(value.clone()).unwrap_or_else(|error| {
+        log::warn!(
+            "Ignoring malformed {} setting: {}",
+            IDENTITY_SETTING_KEY,
+            error
+        );
+        GitIdentitySettings::default()
+    })
+}
+
+fn signer_program_from_git_config(repo: &Repository, format: GitSigningFormat) -> Option<String> {
+    let config = repo.config().ok()?;
+    let program_key = match format {
+        GitSigningFormat::Openpgp => "gpg.program",
+        GitSigningFormat::Ssh => "gpg.ssh.program",
+    };
+    non_empty(config.get_string(program_key).ok())
+}
+
+fn signing_from_git_config(repo: &Repository) -> Option<GitSigningSettings> {
+    let config = repo.config().ok()?;
+    if !config.get_bool("commit.gpgsign").unwrap_or(false) {
+        return None;
+    }
+
+    let format = match config.get_string("gpg.format").ok().as_deref() {
+        Some("ssh") => GitSigningFormat::Ssh,
+        _ => GitSigningFormat::Openpgp,
+    };
+
+    Some(GitSigningSettings {
+        format,
+        program: signer_program_from_git_config(repo, format),
+        key: non_empty(config.get_string("user.signingkey").ok()),
+    })
+}
+
+/// Resolves the commit identity for a vault: the per-vault `git_identity`
+/// setting wins, then the repository/global git config, then the built-in
+/// fallback. Signing follows the same precedence, except that the signer
+/// program always comes from git config.
+pub(crate) fn resolve_identity(repo: &Repository, vault_path: &Path) -> ResolvedIdentity {
+    let settings = load_identity_settings(vault_path);
+    let signing = settings
+        .signing
+        .clone()
+        .map(|mut signing| {
+            signing.program = signer_program_from_git_config(repo, signing.format);
+            signing
+        })
+        .or_else(|| signing_from_git_config(repo));
+    let signing_format = signing.as_ref().map(|s| s.format);
+
+    let name = non_empty(settings.name);
+    let email = non_empty(settings.email);
+    if let (Some(name), Some(email)) = (name.clone(), email.clone()) {
+        return ResolvedIdentity {
+            identity: GitIdentity {
+                name,
+                email,
+                source: GitIdentitySource::VaultSettings,
+                signing_format,
+            },
+            signing,
+        };
+    }
+
+    let (name, email, source) = match repo.signature() {
+        Ok(sig) => (
+            name.unwrap_or_else(|| sig.name().unwrap_or(FALLBACK_NAME).to_string()),
+            email.unwrap_or_else(|| sig.email().unwrap_or(FALLBACK_EMAIL).to_string()),
+            GitIdentitySource::GitConfig,
+        ),
+        Err(_) => (
+            name.unwrap_or_else(|| FALLBACK_NAME.to_string()),
+            email.unwrap_or_else(|| FALLBACK_EMAIL.to_string()),
+            GitIdentitySource::Default,
+        ),
+    };
+
+    ResolvedIdentity {
+        identity: GitIdentity {
+            name,
+            email,
+            source,
+            signing_format,
+        },
+        signing,
+    }
+}
+
+pub(crate) fn is_valid_co_author(value: &str) -> bool {
+    let trimmed = value.trim();
+    match (trimmed.find('<'), trimmed.rfind('>')) {
+        (Some(open), Some(close)) => {
+            open > 0 && close == trimmed.len() - 1 && close > open + 1 && !trimmed.contains('\n')
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn append_co_author_trailers(message: &str, co_authors: &[String]) -> AppResult<String> {
+    if co_authors.is_empty() {
+        return Ok(message.to_string());
+    }
+
+    let mut out = message.trim_end().to_string();
+    out.push_str("\n\n");
+    for co_author in co_authors {
+        if !is_valid_co_author(co_author) {
+            return Err(AppError::invalid_input(format!(
+                "invalid co-author: {}",
+                co_author
+            )));
+        }
+        out.push_str("Co-authored-by: ");
+        out.push_str(co_author.trim());
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn signer_command(signing: &GitSigningSettings) -> AppResult<Command> {
+    match signing.format {
+        GitSigningFormat::Openpgp => {
+            let mut cmd = Command::new(signing.program.as_deref().unwrap_or("gpg"));
+            cmd.arg("--status-fd=2").arg("-bsa");
+            if let Some(key) = signing.key.as_deref() {
+                cmd.arg("-u").arg(key);
+            }
+            Ok(cmd)
+        }
+        GitSigningFormat::Ssh => {
+            let key = signing.key.as_deref().ok_or_else(|| {
+                AppError::invalid_input("ssh signing requires a signing key path")
+            })?;
+            let mut cmd = Command::new(signing.program.as_deref().unwrap_or("ssh-keygen"));
+            cmd.args(["-Y", "sign", "-n", "git", "-f"]).arg(key);
+            Ok(cmd)
+        }
+    }
+}
+
+/// Pipes the unsigned commit buffer through the configured external signer
+/// and returns the armored signature it prints.
+pub(crate) fn sign_commit_buffer(signing: &GitSigningSettings, content: &str) -> AppResult<String> {
+    let mut child = signer_command(signing)?
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::git(format!("failed to start commit signer: {}", e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(content.as_bytes())
+            .map_err(|e| AppError::git(format!("failed to send commit to signer: {}", e)))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| AppError::git(format!("commit signer failed: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::git(format!(
+            "commit signer failed: {}",
+            stderr.trim()
+        )));
+    }
+
+    let signature = String::from_utf8(output.stdout)
+        .map_err(|e| AppError::git(format!("commit signature is not valid utf-8: {}", e)))?;
+    if signature.trim().is_empty() {
+        return Err(AppError::git("commit signer produced no signature"));
+    }
+    Ok(signature)
+}
@@ -1 +1,4 @@
+pub mod hunk_stage;
+pub mod identity;
 pub mod service;
+pub mod word_diff;
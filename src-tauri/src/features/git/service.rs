@@ -1,8 +1,15 @@
+use crate::features::git::hunk_stage::{self, GitHunkSelection};
+use crate::features::git::identity;
+use crate::features::git::word_diff::{self, GitDiffSegment};
+use crate::features::notes::service as notes_service;
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::{constants, storage};
+use git2::build::CheckoutBuilder;
 use git2::{
-    DiffFormat, DiffOptions, IndexAddOption, ObjectType, Repository, Signature, Sort,
-    StatusOptions, StatusShow,
+    Delta, DiffFindOptions, DiffFormat, DiffOptions, ErrorCode, IndexAddOption, ObjectType,
+    Repository, Signature, Sort, StashApplyOptions, StashFlags, StatusOptions, StatusShow,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 
 #[derive(Debug, Clone, Serialize)]
@@ -18,6 +25,8 @@ pub struct GitStatus {
     pub ahead: usize,
     pub behind: usize,
     pub files: Vec<GitFileStatus>,
+    pub staged: Vec<GitFileStatus>,
+    pub unstaged: Vec<GitFileStatus>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -29,6 +38,38 @@ pub struct GitCommit {
     pub message: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct GitTimelineNote {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub status: String,
+    pub title: String,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitTimelineEntry {
+    pub commit: GitCommit,
+    pub additions: usize,
+    pub deletions: usize,
+    pub notes: Vec<GitTimelineNote>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitTimelinePage {
+    pub entries: Vec<GitTimelineEntry>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitStash {
+    pub index: usize,
+    pub hash: String,
+    pub message: String,
+    pub timestamp_ms: i64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct GitDiffLine {
     #[serde(rename = "type")]
@@ -36,35 +77,53 @@ pub struct GitDiffLine {
     pub content: String,
     pub old_line: Option<u32>,
     pub new_line: Option<u32>,
+    pub segments: Option<Vec<GitDiffSegment>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct GitDiffHunk {
+    pub path: String,
     pub header: String,
     pub lines: Vec<GitDiffLine>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct GitDiffFile {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub status: String,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct GitDiff {
     pub additions: usize,
     pub deletions: usize,
+    pub files: Vec<GitDiffFile>,
     pub hunks: Vec<GitDiffHunk>,
 }
 
-fn open_repo(vault_path: &str) -> Result<Repository, String> {
-    Repository::open(vault_path).map_err(|e| format!("failed to open repo: {}", e))
+#[derive(Debug, Default, Deserialize)]
+pub struct GitDiffOptions {
+    #[serde(default)]
+    pub word_diff: bool,
+    #[serde(default)]
+    pub detect_renames: bool,
+}
+
+fn open_repo(vault_path: &str) -> AppResult<Repository> {
+    Repository::open(vault_path).map_err(|e| AppError::git(format!("failed to open repo: {}", e)))
 }
 
-fn repo_index(repo: &Repository) -> Result<git2::Index, String> {
-    repo.index().map_err(|e| format!("failed to get index: {}", e))
+fn repo_index(repo: &Repository) -> AppResult<git2::Index> {
+    repo.index()
+        .map_err(|e| AppError::git(format!("failed to get index: {}", e)))
 }
 
-fn default_signature() -> Result<Signature<'static>, String> {
-    Signature::now("Otterly", "otterly@local")
-        .map_err(|e| format!("failed to create signature: {}", e))
+fn vault_signature(repo: &Repository, vault_path: &str) -> AppResult<Signature<'static>> {
+    identity::resolve_identity(repo, Path::new(vault_path)).signature()
 }
 
-fn write_default_gitignore_if_missing(vault_path: &str) -> Result<(), String> {
+fn write_default_gitignore_if_missing(vault_path: &str) -> AppResult<()> {
     let gitignore_path = Path::new(vault_path).join(".gitignore");
     if gitignore_path.exists() {
         return Ok(());
@@ -74,7 +133,7 @@ fn write_default_gitignore_if_missing(vault_path: &str) -> Result<(), String> {
         &gitignore_path,
         "node_modules/\n.DS_Store\n*.tmp\n.env\nThumbs.db\n.otterly/\n",
     )
-    .map_err(|e| format!("failed to write .gitignore: {}", e))
+    .map_err(|e| AppError::git(format!("failed to write .gitignore: {}", e)))
 }
 
 fn status_string(s: git2::Status) -> &'static str {
@@ -97,24 +156,53 @@ fn status_string(s: git2::Status) -> &'static str {
     }
 }
 
+fn index_status_string(s: git2::Status) -> Option<&'static str> {
+    if s.is_conflicted() {
+        None
+    } else if s.is_index_new() {
+        Some("added")
+    } else if s.is_index_deleted() {
+        Some("deleted")
+    } else if s.is_index_modified() || s.is_index_renamed() || s.is_index_typechange() {
+        Some("modified")
+    } else {
+        None
+    }
+}
+
+fn worktree_status_string(s: git2::Status) -> Option<&'static str> {
+    if s.is_conflicted() {
+        Some("conflicted")
+    } else if s.is_wt_new() {
+        Some("untracked")
+    } else if s.is_wt_deleted() {
+        Some("deleted")
+    } else if s.is_wt_modified() || s.is_wt_renamed() || s.is_wt_typechange() {
+        Some("modified")
+    } else {
+        None
+    }
+}
+
 #[tauri::command]
-pub fn git_has_repo(vault_path: String) -> Result<bool, String> {
+pub fn git_has_repo(vault_path: String) -> AppResult<bool> {
     Ok(Path::new(&vault_path).join(".git").exists())
 }
 
 #[tauri::command]
-pub fn git_init_repo(vault_path: String) -> Result<(), String> {
-    let repo = Repository::init(&vault_path).map_err(|e| format!("failed to init repo: {}", e))?;
+pub fn git_init_repo(vault_path: String) -> AppResult<()> {
+    let repo = Repository::init(&vault_path)
+        .map_err(|e| AppError::git(format!("failed to init repo: {}", e)))?;
     write_default_gitignore_if_missing(&vault_path)?;
     let mut index = repo_index(&repo)?;
     stage_all_files(&repo, &mut index)?;
     let (_, tree) = write_index_tree(&repo, &mut index)?;
-    commit_tree(&repo, "Initial commit", &tree, None)?;
+    commit_tree(&repo, &vault_path, "Initial commit", &tree, None)?;
     Ok(())
 }
 
 #[tauri::command]
-pub fn git_status(vault_path: String) -> Result<GitStatus, String> {
+pub fn git_status(vault_path: String) -> AppResult<GitStatus> {
     let repo = open_repo(&vault_path)?;
 
     let branch = match repo.head() {
@@ -129,22 +217,36 @@ pub fn git_status(vault_path: String) -> Result<GitStatus, String> {
 
     let statuses = repo
         .statuses(Some(&mut opts))
-        .map_err(|e| format!("failed to get status: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to get status: {}", e)))?;
 
-    let files: Vec<GitFileStatus> = statuses
-        .iter()
-        .filter_map(|entry| {
-            let path = entry.path()?.to_string();
-            let status = entry.status();
-            if status.is_ignored() {
-                return None;
-            }
-            Some(GitFileStatus {
-                path,
-                status: status_string(status).to_string(),
-            })
-        })
-        .collect();
+    let mut files = Vec::new();
+    let mut staged = Vec::new();
+    let mut unstaged = Vec::new();
+    for entry in statuses.iter() {
+        let Some(path) = entry.path() else {
+            continue;
+        };
+        let status = entry.status();
+        if status.is_ignored() {
+            continue;
+        }
+        files.push(GitFileStatus {
+            path: path.to_string(),
+            status: status_string(status).to_string(),
+        });
+        if let Some(index_status) = index_status_string(status) {
+            staged.push(GitFileStatus {
+                path: path.to_string(),
+                status: index_status.to_string(),
+            });
+        }
+        if let Some(worktree_status) = worktree_status_string(status) {
+            unstaged.push(GitFileStatus {
+                path: path.to_string(),
+                status: worktree_status.to_string(),
+            });
+        }
+    }
 
     let is_dirty = !files.is_empty();
 
@@ -156,6 +258,8 @@ pub fn git_status(vault_path: String) -> Result<GitStatus, String> {
         ahead,
         behind,
         files,
+        staged,
+        unstaged,
     })
 }
 
@@ -182,30 +286,30 @@ fn stage_selected_files(
     index: &mut git2::Index,
     vault_path: &str,
     paths: &[String],
-) -> Result<(), String> {
+) -> AppResult<()> {
     for path in paths {
         let full = Path::new(vault_path).join(path);
         if full.exists() {
             index
                 .add_path(Path::new(path))
-                .map_err(|e| format!("failed to stage {}: {}", path, e))?;
+                .map_err(|e| AppError::git(format!("failed to stage {}: {}", path, e)))?;
             continue;
         }
         index
             .remove_path(Path::new(path))
-            .map_err(|e| format!("failed to remove {}: {}", path, e))?;
+            .map_err(|e| AppError::git(format!("failed to remove {}: {}", path, e)))?;
     }
     Ok(())
 }
 
-fn stage_all_files(repo: &Repository, index: &mut git2::Index) -> Result<(), String> {
+fn stage_all_files(repo: &Repository, index: &mut git2::Index) -> AppResult<()> {
     index
         .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
-        .map_err(|e| format!("failed to stage all: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to stage all: {}", e)))?;
 
     let statuses = repo
         .statuses(None)
-        .map_err(|e| format!("failed to get status: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to get status: {}", e)))?;
     for entry in statuses.iter() {
         if entry.status().is_wt_deleted() || entry.status().is_index_deleted() {
             if let Some(path) = entry.path() {
@@ -221,7 +325,7 @@ fn stage_commit_files(
     index: &mut git2::Index,
     vault_path: &str,
     files: Option<Vec<String>>,
-) -> Result<(), String> {
+) -> AppResult<()> {
     match files {
         Some(paths) => stage_selected_files(index, vault_path, &paths),
         None => stage_all_files(repo, index),
@@ -231,16 +335,16 @@ fn stage_commit_files(
 fn write_index_tree<'repo>(
     repo: &'repo Repository,
     index: &mut git2::Index,
-) -> Result<(git2::Oid, git2::Tree<'repo>), String> {
+) -> AppResult<(git2::Oid, git2::Tree<'repo>)> {
     index
         .write()
-        .map_err(|e| format!("failed to write index: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to write index: {}", e)))?;
     let tree_oid = index
         .write_tree()
-        .map_err(|e| format!("failed to write tree: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to write tree: {}", e)))?;
     let tree = repo
         .find_tree(tree_oid)
-        .map_err(|e| format!("failed to find tree: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to find tree: {}", e)))?;
     Ok((tree_oid, tree))
 }
 
@@ -248,26 +352,69 @@ fn head_parent_commit(repo: &Repository) -> Option<git2::Commit<'_>> {
     repo.head().ok().and_then(|head| head.peel_to_commit().ok())
 }
 
-fn ensure_tree_has_changes(parent: Option<&git2::Commit<'_>>, tree_oid: git2::Oid) -> Result<(), String> {
+fn ensure_tree_has_changes(
+    parent: Option<&git2::Commit<'_>>,
+    tree_oid: git2::Oid,
+) -> AppResult<()> {
     if let Some(parent_commit) = parent {
         if parent_commit.tree_id() == tree_oid {
-            return Err("nothing to commit".to_string());
+            return Err(AppError::git("nothing to commit"));
         }
     }
     Ok(())
 }
 
+fn update_head_to(repo: &Repository, oid: git2::Oid, message: &str) -> AppResult<()> {
+    let head = repo
+        .find_reference("HEAD")
+        .map_err(|e| AppError::git(format!("failed to resolve HEAD: {}", e)))?;
+    let result = match head.symbolic_target() {
+        Some(branch_ref) => repo.reference(branch_ref, oid, true, message).map(|_| ()),
+        None => repo.set_head_detached(oid),
+    };
+    result.map_err(|e| AppError::git(format!("failed to update HEAD: {}", e)))
+}
+
+fn commit_signed_tree(
+    repo: &Repository,
+    sig: &Signature<'_>,
+    signing: &identity::GitSigningSettings,
+    message: &str,
+    tree: &git2::Tree<'_>,
+    parents: &[&git2::Commit<'_>],
+) -> AppResult<git2::Oid> {
+    let buffer = repo
+        .commit_create_buffer(sig, sig, message, tree, parents)
+        .map_err(|e| AppError::git(format!("failed to build commit: {}", e)))?;
+    let content = buffer
+        .as_str()
+        .ok_or_else(|| AppError::git("commit buffer is not valid utf-8"))?
+        .to_string();
+    let signature = identity::sign_commit_buffer(signing, &content)?;
+    let oid = repo
+        .commit_signed(&content, &signature, None)
+        .map_err(|e| AppError::git(format!("failed to write signed commit: {}", e)))?;
+    let summary = message.lines().next().unwrap_or_default();
+    update_head_to(repo, oid, &format!("commit: {}", summary))?;
+    Ok(oid)
+}
+
 fn commit_tree(
     repo: &Repository,
+    vault_path: &str,
     message: &str,
     tree: &git2::Tree<'_>,
     parent: Option<&git2::Commit<'_>>,
-) -> Result<String, String> {
-    let sig = default_signature()?;
+) -> AppResult<String> {
+    let resolved = identity::resolve_identity(repo, Path::new(vault_path));
+    let sig = resolved.signature()?;
     let parents: Vec<&git2::Commit<'_>> = parent.into_iter().collect();
-    let oid = repo
-        .commit(Some("HEAD"), &sig, &sig, message, tree, &parents)
-        .map_err(|e| format!("failed to commit: {}", e))?;
+    let oid = match resolved.signing.as_ref() {
+        Some(signing) => commit_signed_tree(repo, &sig, signing, message, tree, &parents)?,
+        None => repo
+            .commit(Some("HEAD"), &sig, &sig, message, tree, &parents)
+            .map_err(|e| AppError::git(format!("failed to commit: {}", e)))?,
+    };
     Ok(oid.to_string())
 }
 
@@ -276,28 +423,36 @@ pub fn git_stage_and_commit(
     vault_path: String,
     message: String,
     files: Option<Vec<String>>,
-) -> Result<String, String> {
+    co_authors: Option<Vec<String>>,
+) -> AppResult<String> {
+    let message = identity::append_co_author_trailers(&message, &co_authors.unwrap_or_default())?;
     let repo = open_repo(&vault_path)?;
     let mut index = repo_index(&repo)?;
     stage_commit_files(&repo, &mut index, &vault_path, files)?;
     let (tree_oid, tree) = write_index_tree(&repo, &mut index)?;
     let parent = head_parent_commit(&repo);
     ensure_tree_has_changes(parent.as_ref(), tree_oid)?;
-    commit_tree(&repo, &message, &tree, parent.as_ref())
+    commit_tree(&repo, &vault_path, &message, &tree, parent.as_ref())
+}
+
+#[tauri::command]
+pub fn git_get_identity(vault_path: String) -> AppResult<identity::GitIdentity> {
+    let repo = open_repo(&vault_path)?;
+    Ok(identity::resolve_identity(&repo, Path::new(&vault_path)).identity)
 }
 
 #[tauri::command]
-pub fn git_create_tag(vault_path: String, name: String, message: String) -> Result<(), String> {
+pub fn git_create_tag(vault_path: String, name: String, message: String) -> AppResult<()> {
     let repo = open_repo(&vault_path)?;
     let head = repo
         .head()
-        .map_err(|e| format!("failed to resolve HEAD: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to resolve HEAD: {}", e)))?;
     let target = head
         .peel(ObjectType::Commit)
-        .map_err(|e| format!("failed to peel HEAD to commit: {}", e))?;
-    let sig = default_signature()?;
+        .map_err(|e| AppError::git(format!("failed to peel HEAD to commit: {}", e)))?;
+    let sig = vault_signature(&repo, &vault_path)?;
     repo.tag(&name, &target, &sig, &message, false)
-        .map_err(|e| format!("failed to create tag: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to create tag: {}", e)))?;
     Ok(())
 }
 
@@ -306,18 +461,18 @@ pub fn git_log(
     vault_path: String,
     file_path: Option<String>,
     limit: usize,
-) -> Result<Vec<GitCommit>, String> {
+) -> AppResult<Vec<GitCommit>> {
     let repo = open_repo(&vault_path)?;
 
     let mut revwalk = repo
         .revwalk()
-        .map_err(|e| format!("failed to create revwalk: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to create revwalk: {}", e)))?;
     revwalk
         .push_head()
-        .map_err(|e| format!("failed to push HEAD: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to push HEAD: {}", e)))?;
     revwalk
         .set_sorting(Sort::TIME)
-        .map_err(|e| format!("failed to set sorting: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to set sorting: {}", e)))?;
 
     let mut commits = Vec::new();
 
@@ -326,10 +481,10 @@ pub fn git_log(
             break;
         }
 
-        let oid = oid_result.map_err(|e| format!("revwalk error: {}", e))?;
+        let oid = oid_result.map_err(|e| AppError::git(format!("revwalk error: {}", e)))?;
         let commit = repo
             .find_commit(oid)
-            .map_err(|e| format!("failed to find commit: {}", e))?;
+            .map_err(|e| AppError::git(format!("failed to find commit: {}", e)))?;
 
         if let Some(ref fp) = file_path {
             if !commit_touches_file(&repo, &commit, fp) {
@@ -392,32 +547,221 @@ fn to_git_commit(commit: git2::Commit<'_>) -> GitCommit {
     }
 }
 
+const TIMELINE_MAX_PAGE: usize = 200;
+
+fn is_note_path(path: &str) -> bool {
+    path.to_ascii_lowercase().ends_with(".md")
+}
+
+fn blob_title(repo: &Repository, file: git2::DiffFile<'_>, path: &str) -> String {
+    let from_blob = if file.id().is_zero() {
+        None
+    } else {
+        repo.find_blob(file.id()).ok().and_then(|blob| {
+            notes_service::title_from_markdown(&String::from_utf8_lossy(blob.content()))
+        })
+    };
+    from_blob.unwrap_or_else(|| {
+        Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    })
+}
+
+fn commit_note_changes(
+    repo: &Repository,
+    commit: &git2::Commit<'_>,
+) -> AppResult<Vec<GitTimelineNote>> {
+    let tree = commit
+        .tree()
+        .map_err(|e| AppError::git(format!("failed to get tree: {}", e)))?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(
+            parent
+                .tree()
+                .map_err(|e| AppError::git(format!("failed to get parent tree: {}", e)))?,
+        ),
+        Err(_) => None,
+    };
+
+    let mut diff = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+        .map_err(|e| AppError::git(format!("failed to diff: {}", e)))?;
+    let mut find_opts = DiffFindOptions::new();
+    find_opts.renames(true);
+    diff.find_similar(Some(&mut find_opts))
+        .map_err(|e| AppError::git(format!("failed to detect renames: {}", e)))?;
+
+    let mut notes = Vec::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let old_path = delta_path(delta.old_file());
+        let new_path = delta_path(delta.new_file());
+        let is_deleted = delta.status() == Delta::Deleted;
+        let (path, file) = if is_deleted {
+            (old_path.clone(), delta.old_file())
+        } else {
+            (new_path.clone(), delta.new_file())
+        };
+        let Some(path) = path else {
+            continue;
+        };
+        if !is_note_path(&path) {
+            continue;
+        }
+
+        let (additions, deletions) = git2::Patch::from_diff(&diff, idx)
+            .ok()
+            .flatten()
+            .and_then(|patch| patch.line_stats().ok())
+            .map(|(_, additions, deletions)| (additions, deletions))
+            .unwrap_or((0, 0));
+        let status = delta_status(delta.status());
+
+        notes.push(GitTimelineNote {
+            title: blob_title(repo, file, &path),
+            old_path: old_path.filter(|old| status == "renamed" && *old != path),
+            path,
+            status: status.to_string(),
+            additions,
+            deletions,
+        });
+    }
+
+    Ok(notes)
+}
+
+/// Lists commits newest first with the notes each one touched. Pages are
+/// chained through `next_cursor`, the hash of the last commit returned.
+#[tauri::command]
+pub fn git_vault_timeline(
+    vault_path: String,
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+    cursor: Option<String>,
+    limit: usize,
+) -> AppResult<GitTimelinePage> {
+    let repo = open_repo(&vault_path)?;
+    let limit = limit.clamp(1, TIMELINE_MAX_PAGE);
+    let cursor_oid = match cursor.as_deref() {
+        Some(hash) => Some(
+            git2::Oid::from_str(hash)
+                .map_err(|e| AppError::git(format!("invalid cursor: {}", e)))?,
+        ),
+        None => None,
+    };
+
+    let mut revwalk = repo
+        .revwalk()
+        .map_err(|e| AppError::git(format!("failed to create revwalk: {}", e)))?;
+    revwalk
+        .push_head()
+        .map_err(|e| AppError::git(format!("failed to push HEAD: {}", e)))?;
+    revwalk
+        .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .map_err(|e| AppError::git(format!("failed to set sorting: {}", e)))?;
+
+    let mut seen_cursor = cursor_oid.is_none();
+    let mut entries: Vec<GitTimelineEntry> = Vec::new();
+    let mut has_more = false;
+
+    for oid_result in revwalk {
+        let oid = oid_result.map_err(|e| AppError::git(format!("revwalk error: {}", e)))?;
+        if !seen_cursor {
+            seen_cursor = Some(oid) == cursor_oid;
+            continue;
+        }
+
+        let commit = repo
+            .find_commit(oid)
+            .map_err(|e| AppError::git(format!("failed to find commit: {}", e)))?;
+        let timestamp_ms = commit.time().seconds() * 1000;
+        if until_ms.is_some_and(|until| timestamp_ms > until) {
+            continue;
+        }
+        if since_ms.is_some_and(|since| timestamp_ms < since) {
+            break;
+        }
+        if entries.len() >= limit {
+            has_more = true;
+            break;
+        }
+
+        let notes = commit_note_changes(&repo, &commit)?;
+        let additions = notes.iter().map(|n| n.additions).sum();
+        let deletions = notes.iter().map(|n| n.deletions).sum();
+        entries.push(GitTimelineEntry {
+            commit: to_git_commit(commit),
+            additions,
+            deletions,
+            notes,
+        });
+    }
+
+    if !seen_cursor {
+        return Err(AppError::not_found("cursor commit", None));
+    }
+
+    let next_cursor = if has_more {
+        entries.last().map(|entry| entry.commit.hash.clone())
+    } else {
+        None
+    };
+    Ok(GitTimelinePage {
+        entries,
+        next_cursor,
+    })
+}
+
 fn resolve_tree_from_commit<'repo>(
     repo: &'repo Repository,
     commit_ref: &str,
-) -> Result<git2::Tree<'repo>, String> {
+) -> AppResult<git2::Tree<'repo>> {
     let obj = repo
         .revparse_single(commit_ref)
-        .map_err(|e| format!("failed to find commit {}: {}", commit_ref, e))?;
+        .map_err(|e| AppError::git(format!("failed to find commit {}: {}", commit_ref, e)))?;
     obj.peel(ObjectType::Tree)
-        .map_err(|e| format!("failed to peel to tree: {}", e))?
+        .map_err(|e| AppError::git(format!("failed to peel to tree: {}", e)))?
         .into_tree()
-        .map_err(|_| "not a tree".to_string())
+        .map_err(|_| AppError::git("not a tree"))
 }
 
-fn build_diff_between_trees<'repo>(
+fn build_diff<'repo>(
     repo: &'repo Repository,
-    tree_a: &'repo git2::Tree<'repo>,
-    tree_b: &'repo git2::Tree<'repo>,
+    tree_a: &git2::Tree<'repo>,
+    tree_b: Option<&git2::Tree<'repo>>,
     file_path: Option<&str>,
-) -> Result<git2::Diff<'repo>, String> {
+    detect_renames: bool,
+) -> AppResult<git2::Diff<'repo>> {
     let mut diff_opts = DiffOptions::new();
     if let Some(path) = file_path {
-        diff_opts.pathspec(path);
+        // A rename pairs two paths, so the pathspec would hide the other side.
+        // Deltas are filtered after similarity detection instead.
+        if !detect_renames {
+            diff_opts.pathspec(path);
+        }
     }
 
-    repo.diff_tree_to_tree(Some(tree_a), Some(tree_b), Some(&mut diff_opts))
-        .map_err(|e| format!("failed to diff: {}", e))
+    let mut diff = match tree_b {
+        Some(tree_b) => repo.diff_tree_to_tree(Some(tree_a), Some(tree_b), Some(&mut diff_opts)),
+        None => {
+            diff_opts
+                .include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .show_untracked_content(true);
+            repo.diff_tree_to_workdir_with_index(Some(tree_a), Some(&mut diff_opts))
+        }
+    }
+    .map_err(|e| AppError::git(format!("failed to diff: {}", e)))?;
+
+    if detect_renames {
+        let mut find_opts = DiffFindOptions::new();
+        find_opts.renames(true).for_untracked(true);
+        diff.find_similar(Some(&mut find_opts))
+            .map_err(|e| AppError::git(format!("failed to detect renames: {}", e)))?;
+    }
+
+    Ok(diff)
 }
 
 fn line_type(origin: char) -> &'static str {
@@ -428,6 +772,28 @@ fn line_type(origin: char) -> &'static str {
     }
 }
 
+fn delta_status(status: Delta) -> &'static str {
+    match status {
+        Delta::Added | Delta::Untracked => "added",
+        Delta::Deleted => "deleted",
+        Delta::Renamed => "renamed",
+        Delta::Copied => "copied",
+        _ => "modified",
+    }
+}
+
+fn delta_path(file: git2::DiffFile<'_>) -> Option<String> {
+    file.path().map(storage::normalize_relative_path)
+}
+
+fn delta_matches_path(delta: &git2::DiffDelta<'_>, file_path: Option<&str>) -> bool {
+    let Some(path) = file_path else {
+        return true;
+    };
+    delta_path(delta.old_file()).as_deref() == Some(path)
+        || delta_path(delta.new_file()).as_deref() == Some(path)
+}
+
 fn abbreviated_hash(hash: &str) -> &str {
     if hash.len() >= 7 {
         &hash[..7]
@@ -436,20 +802,48 @@ fn abbreviated_hash(hash: &str) -> &str {
     }
 }
 
-fn collect_diff_hunks(diff: &git2::Diff<'_>) -> Result<Vec<GitDiffHunk>, String> {
+fn collect_diff_files(diff: &git2::Diff<'_>, file_path: Option<&str>) -> Vec<GitDiffFile> {
+    diff.deltas()
+        .filter(|delta| delta_matches_path(delta, file_path))
+        .map(|delta| GitDiffFile {
+            old_path: delta_path(delta.old_file()),
+            new_path: delta_path(delta.new_file()),
+            status: delta_status(delta.status()).to_string(),
+        })
+        .collect()
+}
+
+fn collect_diff_hunks(
+    diff: &git2::Diff<'_>,
+    file_path: Option<&str>,
+) -> AppResult<Vec<GitDiffHunk>> {
     let mut hunks: Vec<GitDiffHunk> = Vec::new();
 
-    diff.print(DiffFormat::Patch, |_delta, hunk, line| {
+    diff.print(DiffFormat::Patch, |delta, hunk, line| {
+        if !delta_matches_path(&delta, file_path) {
+            return true;
+        }
+        let path = delta_path(delta.new_file()).unwrap_or_default();
+
         if let Some(hunk_header) = hunk {
             let header = String::from_utf8_lossy(hunk_header.header()).to_string();
-            if hunks.last().map(|h| h.header != header).unwrap_or(true) {
+            let is_new_hunk = hunks
+                .last()
+                .map(|h| h.header != header || h.path != path)
+                .unwrap_or(true);
+            if is_new_hunk {
                 hunks.push(GitDiffHunk {
+                    path,
                     header,
                     lines: Vec::new(),
                 });
             }
         }
 
+        if !matches!(line.origin(), ' ' | '+' | '-') {
+            return true;
+        }
+
         let content = String::from_utf8_lossy(line.content()).to_string();
 
         if let Some(current_hunk) = hunks.last_mut() {
@@ -458,70 +852,298 @@ fn collect_diff_hunks(diff: &git2::Diff<'_>) -> Result<Vec<GitDiffHunk>, String>
                 content,
                 old_line: line.old_lineno(),
                 new_line: line.new_lineno(),
+                segments: None,
             });
         }
 
         true
     })
-    .map_err(|e| format!("failed to print diff: {}", e))?;
+    .map_err(|e| AppError::git(format!("failed to print diff: {}", e)))?;
 
     Ok(hunks)
 }
 
+fn attach_word_segments(lines: &mut [GitDiffLine]) {
+    let mut i = 0usize;
+    while i < lines.len() {
+        if lines[i].line_type != "deletion" {
+            i += 1;
+            continue;
+        }
+
+        let deletions_start = i;
+        while i < lines.len() && lines[i].line_type == "deletion" {
+            i += 1;
+        }
+        let additions_start = i;
+        while i < lines.len() && lines[i].line_type == "addition" {
+            i += 1;
+        }
+
+        let pairs = (additions_start - deletions_start).min(i - additions_start);
+        for offset in 0..pairs {
+            let old_index = deletions_start + offset;
+            let new_index = additions_start + offset;
+            if let Some((old_segments, new_segments)) =
+                word_diff::diff_words(&lines[old_index].content, &lines[new_index].content)
+            {
+                lines[old_index].segments = Some(old_segments);
+                lines[new_index].segments = Some(new_segments);
+            }
+        }
+    }
+}
+
+fn count_diff_lines(hunks: &[GitDiffHunk]) -> (usize, usize) {
+    hunks
+        .iter()
+        .flat_map(|hunk| hunk.lines.iter())
+        .fold((0, 0), |(additions, deletions), line| {
+            match line.line_type.as_str() {
+                "addition" => (additions + 1, deletions),
+                "deletion" => (additions, deletions + 1),
+                _ => (additions, deletions),
+            }
+        })
+}
+
+/// Diffs `commit_a` against `commit_b`, or against the working tree (including
+/// staged and untracked changes) when `commit_b` is omitted.
 #[tauri::command]
 pub fn git_diff(
     vault_path: String,
     commit_a: String,
-    commit_b: String,
+    commit_b: Option<String>,
     file_path: Option<String>,
-) -> Result<GitDiff, String> {
+    options: Option<GitDiffOptions>,
+) -> AppResult<GitDiff> {
+    let options = options.unwrap_or_default();
     let repo = open_repo(&vault_path)?;
     let tree_a = resolve_tree_from_commit(&repo, &commit_a)?;
-    let tree_b = resolve_tree_from_commit(&repo, &commit_b)?;
-    let diff = build_diff_between_trees(&repo, &tree_a, &tree_b, file_path.as_deref())?;
+    let tree_b = match commit_b.as_deref() {
+        Some(commit_b) => Some(resolve_tree_from_commit(&repo, commit_b)?),
+        None => None,
+    };
+    let file_path = file_path.as_deref();
+    let diff = build_diff(
+        &repo,
+        &tree_a,
+        tree_b.as_ref(),
+        file_path,
+        options.detect_renames,
+    )?;
+
+    let files = collect_diff_files(&diff, file_path);
+    let mut hunks = collect_diff_hunks(&diff, file_path)?;
+    if options.word_diff {
+        for hunk in &mut hunks {
+            attach_word_segments(&mut hunk.lines);
+        }
+    }
+    let (additions, deletions) = count_diff_lines(&hunks);
 
-    let stats = diff
-        .stats()
-        .map_err(|e| format!("failed to get diff stats: {}", e))?;
-    let additions = stats.insertions();
-    let deletions = stats.deletions();
-    let hunks = collect_diff_hunks(&diff)?;
+    Ok(GitDiff {
+        additions,
+        deletions,
+        files,
+        hunks,
+    })
+}
+
+fn head_tree(repo: &Repository) -> Option<git2::Tree<'_>> {
+    head_parent_commit(repo).and_then(|commit| commit.tree().ok())
+}
+
+fn build_change_diff<'repo>(
+    repo: &'repo Repository,
+    index: &git2::Index,
+    staged: bool,
+    file_path: Option<&str>,
+) -> AppResult<git2::Diff<'repo>> {
+    let mut diff_opts = DiffOptions::new();
+    if let Some(path) = file_path {
+        diff_opts.pathspec(path).disable_pathspec_match(true);
+    }
+    if staged {
+        let tree = head_tree(repo);
+        repo.diff_tree_to_index(tree.as_ref(), Some(index), Some(&mut diff_opts))
+    } else {
+        diff_opts
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        repo.diff_index_to_workdir(Some(index), Some(&mut diff_opts))
+    }
+    .map_err(|e| AppError::git(format!("failed to diff: {}", e)))
+}
+
+/// Diffs HEAD against the index (`staged`) or the index against the working
+/// tree. Hunk indices in the result are the ones `git_stage_hunks` and
+/// `git_unstage_hunks` expect.
+#[tauri::command]
+pub fn git_working_diff(
+    vault_path: String,
+    staged: bool,
+    file_path: Option<String>,
+    options: Option<GitDiffOptions>,
+) -> AppResult<GitDiff> {
+    let options = options.unwrap_or_default();
+    let repo = open_repo(&vault_path)?;
+    let index = repo_index(&repo)?;
+    let file_path = file_path.as_deref();
+    let diff = build_change_diff(&repo, &index, staged, file_path)?;
+
+    let files = collect_diff_files(&diff, file_path);
+    let mut hunks = collect_diff_hunks(&diff, file_path)?;
+    if options.word_diff {
+        for hunk in &mut hunks {
+            attach_word_segments(&mut hunk.lines);
+        }
+    }
+    let (additions, deletions) = count_diff_lines(&hunks);
 
     Ok(GitDiff {
         additions,
         deletions,
+        files,
         hunks,
     })
 }
 
+fn blob_bytes(repo: &Repository, oid: git2::Oid) -> AppResult<Vec<u8>> {
+    if oid.is_zero() {
+        return Ok(Vec::new());
+    }
+    repo.find_blob(oid)
+        .map(|blob| blob.content().to_vec())
+        .map_err(|e| AppError::git(format!("failed to read blob: {}", e)))
+}
+
+fn new_index_entry(repo: &Repository, path: &str) -> git2::IndexEntry {
+    let mode = head_tree(repo)
+        .and_then(|tree| tree.get_path(Path::new(path)).ok())
+        .map(|entry| entry.filemode() as u32)
+        .unwrap_or(0o100644);
+    git2::IndexEntry {
+        ctime: git2::IndexTime::new(0, 0),
+        mtime: git2::IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id: git2::Oid::zero(),
+        flags: path.len().min(0xfff) as u16,
+        flags_extended: 0,
+        path: path.as_bytes().to_vec(),
+    }
+}
+
+/// Rewrites the index entry for `file_path` so that only the selected lines
+/// move between the working tree and the index.
+fn apply_hunk_selection(
+    vault_path: &str,
+    file_path: &str,
+    selections: &[GitHunkSelection],
+    unstage: bool,
+) -> AppResult<()> {
+    let repo = open_repo(vault_path)?;
+    let mut index = repo_index(&repo)?;
+    let diff = build_change_diff(&repo, &index, unstage, Some(file_path))?;
+    let delta_idx = diff
+        .deltas()
+        .position(|delta| delta_matches_path(&delta, Some(file_path)))
+        .ok_or_else(|| AppError::not_found("changes", Some(file_path)))?;
+    let patch = git2::Patch::from_diff(&diff, delta_idx)
+        .map_err(|e| AppError::git(format!("failed to build patch: {}", e)))?
+        .ok_or_else(|| AppError::invalid_input("binary changes cannot be staged partially"))?;
+    let hunks = hunk_stage::patch_hunks(&patch)?;
+    hunk_stage::validate_selections(&hunks, selections)?;
+
+    // Staging applies the selection on top of the index. Unstaging rebuilds
+    // the index from HEAD with every change except the selection.
+    let delta = patch.delta();
+    let old = blob_bytes(&repo, delta.old_file().id())?;
+    let content = hunk_stage::apply_hunks(&old, &hunks, |hunk, line| {
+        hunk_stage::is_selected(selections, hunk, line) != unstage
+    });
+
+    let removed_status = if unstage {
+        Delta::Added
+    } else {
+        Delta::Deleted
+    };
+    if content.is_empty() && delta.status() == removed_status {
+        index
+            .remove_path(Path::new(file_path))
+            .map_err(|e| AppError::git(format!("failed to remove {}: {}", file_path, e)))?;
+    } else {
+        let mut entry = index
+            .get_path(Path::new(file_path), 0)
+            .unwrap_or_else(|| new_index_entry(&repo, file_path));
+        // Stale stat data could make the partial blob look identical to the
+        // working tree file, so force status to compare contents.
+        entry.ctime = git2::IndexTime::new(0, 0);
+        entry.mtime = git2::IndexTime::new(0, 0);
+        index
+            .add_frombuffer(&entry, &content)
+            .map_err(|e| AppError::git(format!("failed to stage {}: {}", file_path, e)))?;
+    }
+
+    index
+        .write()
+        .map_err(|e| AppError::git(format!("failed to write index: {}", e)))
+}
+
+/// Stages the selected hunks (or lines within them) of a note's unstaged
+/// change. Committing with an empty `files` list then records only the index.
+#[tauri::command]
+pub fn git_stage_hunks(
+    vault_path: String,
+    file_path: String,
+    selections: Vec<GitHunkSelection>,
+) -> AppResult<()> {
+    apply_hunk_selection(&vault_path, &file_path, &selections, false)
+}
+
+#[tauri::command]
+pub fn git_unstage_hunks(
+    vault_path: String,
+    file_path: String,
+    selections: Vec<GitHunkSelection>,
+) -> AppResult<()> {
+    apply_hunk_selection(&vault_path, &file_path, &selections, true)
+}
+
 #[tauri::command]
 pub fn git_show_file_at_commit(
     vault_path: String,
     file_path: String,
     commit_hash: String,
-) -> Result<String, String> {
+) -> AppResult<String> {
     let repo = open_repo(&vault_path)?;
 
     let obj = repo
         .revparse_single(&commit_hash)
-        .map_err(|e| format!("failed to find commit {}: {}", commit_hash, e))?;
+        .map_err(|e| AppError::git(format!("failed to find commit {}: {}", commit_hash, e)))?;
     let commit = obj
         .peel_to_commit()
-        .map_err(|e| format!("failed to peel to commit: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to peel to commit: {}", e)))?;
     let tree = commit
         .tree()
-        .map_err(|e| format!("failed to get tree: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to get tree: {}", e)))?;
 
     let entry = tree
         .get_path(Path::new(&file_path))
-        .map_err(|e| format!("file not found at commit: {}", e))?;
+        .map_err(|e| AppError::git(format!("file not found at commit: {}", e)))?;
 
     let blob = repo
         .find_blob(entry.id())
-        .map_err(|e| format!("failed to read blob: {}", e))?;
+        .map_err(|e| AppError::git(format!("failed to read blob: {}", e)))?;
 
     String::from_utf8(blob.content().to_vec())
-        .map_err(|e| format!("file is not valid utf-8: {}", e))
+        .map_err(|e| AppError::git(format!("file is not valid utf-8: {}", e)))
 }
 
 #[tauri::command]
@@ -529,17 +1151,18 @@ pub fn git_restore_file(
     vault_path: String,
     file_path: String,
     commit_hash: String,
-) -> Result<String, String> {
+) -> AppResult<String> {
     let content =
         git_show_file_at_commit(vault_path.clone(), file_path.clone(), commit_hash.clone())?;
     let abs = Path::new(&vault_path).join(&file_path);
 
     if let Some(parent) = abs.parent() {
         std::fs::create_dir_all(parent)
-            .map_err(|e| format!("failed to create directories: {}", e))?;
+            .map_err(|e| AppError::git(format!("failed to create directories: {}", e)))?;
     }
 
-    std::fs::write(&abs, &content).map_err(|e| format!("failed to write file: {}", e))?;
+    std::fs::write(&abs, &content)
+        .map_err(|e| AppError::git(format!("failed to write file: {}", e)))?;
 
     let short_hash = abbreviated_hash(&commit_hash);
     let title = Path::new(&file_path)
@@ -548,5 +1171,167 @@ pub fn git_restore_file(
         .unwrap_or(&file_path);
     let message = format!("Restore: {} to {}", title, short_hash);
 
-    git_stage_and_commit(vault_path, message, Some(vec![file_path]))
+    git_stage_and_commit(vault_path, message, Some(vec![file_path]), None)
+}
+
+fn head_tree_contains(repo: &Repository, path: &str) -> bool {
+    head_parent_commit(repo)
+        .and_then(|commit| commit.tree().ok())
+        .map(|tree| tree.get_path(Path::new(path)).is_ok())
+        .unwrap_or(false)
+}
+
+/// Deletes an untracked file or folder. The path is resolved without
+/// following symlinks, so discarding can never reach outside the vault.
+fn remove_untracked_path(index: &mut git2::Index, vault_path: &str, path: &str) -> AppResult<()> {
+    let rel = path.trim_end_matches('/');
+    if rel.split('/').any(constants::is_excluded_folder) {
+        return Err(AppError::invalid_input(format!("cannot discard {}", path)));
+    }
+    let abs = notes_service::safe_vault_abs_for_write(Path::new(vault_path), rel)?;
+    let removed = if abs.is_dir() {
+        std::fs::remove_dir_all(&abs)
+    } else if abs.is_file() {
+        std::fs::remove_file(&abs)
+    } else {
+        Ok(())
+    };
+    removed.map_err(|e| AppError::git(format!("failed to remove {}: {}", path, e)))?;
+    let _ = index.remove_path(Path::new(rel));
+    let _ = index.remove_dir(Path::new(rel), 0);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn git_discard_changes(vault_path: String, paths: Vec<String>) -> AppResult<()> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    let repo = open_repo(&vault_path)?;
+    let mut index = repo_index(&repo)?;
+    let mut tracked: Vec<&String> = Vec::new();
+
+    for path in &paths {
+        if head_tree_contains(&repo, path) {
+            tracked.push(path);
+            continue;
+        }
+        remove_untracked_path(&mut index, &vault_path, path)?;
+    }
+    index
+        .write()
+        .map_err(|e| AppError::git(format!("failed to write index: {}", e)))?;
+
+    if tracked.is_empty() {
+        return Ok(());
+    }
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force().recreate_missing(true);
+    for path in &tracked {
+        checkout.path(path.as_str());
+    }
+    repo.checkout_head(Some(&mut checkout))
+        .map_err(|e| AppError::git(format!("failed to discard changes: {}", e)))
+}
+
+#[tauri::command]
+pub fn git_stash_save(
+    vault_path: String,
+    message: Option<String>,
+    include_untracked: bool,
+) -> AppResult<String> {
+    let mut repo = open_repo(&vault_path)?;
+    let sig = vault_signature(&repo, &vault_path)?;
+    let flags = if include_untracked {
+        StashFlags::INCLUDE_UNTRACKED
+    } else {
+        StashFlags::DEFAULT
+    };
+    let message = message.filter(|m| !m.trim().is_empty());
+    match repo.stash_save2(&sig, message.as_deref(), Some(flags)) {
+        Ok(oid) => Ok(oid.to_string()),
+        Err(e) if e.code() == ErrorCode::NotFound => Err(AppError::git("nothing to stash")),
+        Err(e) => Err(AppError::git(format!("failed to stash changes: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub fn git_stash_list(vault_path: String) -> AppResult<Vec<GitStash>> {
+    let mut repo = open_repo(&vault_path)?;
+    let mut entries: Vec<(usize, String, git2::Oid)> = Vec::new();
+    repo.stash_foreach(|index, message, oid| {
+        entries.push((index, message.to_string(), *oid));
+        true
+    })
+    .map_err(|e| AppError::git(format!("failed to list stashes: {}", e)))?;
+
+    Ok(entries
+        .into_iter()
+        .map(|(index, message, oid)| GitStash {
+            index,
+            hash: oid.to_string(),
+            message,
+            timestamp_ms: repo
+                .find_commit(oid)
+                .map(|commit| commit.time().seconds() * 1000)
+                .unwrap_or(0),
+        })
+        .collect())
+}
+
+/// Locally changed files that the stash at `index` also touches.
+fn stash_conflict_paths(repo: &Repository, index: usize) -> Vec<String> {
+    let stashed = (|| -> Result<Vec<String>, git2::Error> {
+        let reflog = repo.reflog("refs/stash")?;
+        let Some(entry) = reflog.get(index) else {
+            return Ok(Vec::new());
+        };
+        let commit = repo.find_commit(entry.id_new())?;
+        let parent_tree = commit.parent(0)?.tree()?;
+        let diff = repo.diff_tree_to_tree(Some(&parent_tree), Some(&commit.tree()?), None)?;
+        Ok(diff
+            .deltas()
+            .filter_map(|delta| delta_path(delta.new_file()))
+            .collect())
+    })()
+    .unwrap_or_default();
+
+    let Ok(statuses) = repo.statuses(None) else {
+        return Vec::new();
+    };
+    let changed: std::collections::HashSet<String> = statuses
+        .iter()
+        .filter(|entry| entry.status() != git2::Status::CURRENT)
+        .filter_map(|entry| entry.path().map(str::to_string))
+        .collect();
+    stashed
+        .into_iter()
+        .filter(|path| changed.contains(path))
+        .collect()
+}
+
+#[tauri::command]
+pub fn git_stash_apply(vault_path: String, index: usize) -> AppResult<()> {
+    let mut repo = open_repo(&vault_path)?;
+    let mut opts = StashApplyOptions::new();
+    opts.reinstantiate_index();
+    repo.stash_apply(index, Some(&mut opts)).map_err(|e| {
+        if e.code() == ErrorCode::Conflict {
+            AppError::GitConflict {
+                message: "stash conflicts with local changes".to_string(),
+                paths: stash_conflict_paths(&repo, index),
+            }
+        } else {
+            AppError::git(format!("failed to apply stash: {}", e))
+        }
+    })
+}
+
+#[tauri::command]
+pub fn git_stash_drop(vault_path: String, index: usize) -> AppResult<()> {
+    let mut repo = open_repo(&vault_path)?;
+    repo.stash_drop(index)
+        .map_err(|e| AppError::git(format!("failed to drop stash: {}", e)))
 }
@@ -0,0 +1,114 @@
+use serde::Serialize;
+
+const MAX_TOKEN_MATRIX: usize = 1_000_000;
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct GitDiffSegment {
+    #[serde(rename = "type")]
+    pub segment_type: String,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TokenClass {
+    Word,
+    Space,
+    Other,
+}
+
+fn token_class(c: char) -> TokenClass {
+    if c.is_alphanumeric() || c == '_' {
+        TokenClass::Word
+    } else if c.is_whitespace() {
+        TokenClass::Space
+    } else {
+        TokenClass::Other
+    }
+}
+
+pub(crate) fn tokenize_words(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0usize;
+    let mut current: Option<TokenClass> = None;
+
+    for (i, c) in text.char_indices() {
+        let class = token_class(c);
+        let continues =
+            matches!(current, Some(prev) if prev == class && class != TokenClass::Other);
+        if !continues && i > start {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        current = Some(class);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+fn push_segment(out: &mut Vec<GitDiffSegment>, segment_type: &str, text: &str) {
+    if let Some(last) = out.last_mut() {
+        if last.segment_type == segment_type {
+            last.text.push_str(text);
+            return;
+        }
+    }
+    out.push(GitDiffSegment {
+        segment_type: segment_type.to_string(),
+        text: text.to_string(),
+    });
+}
+
+/// Splits a replaced line pair into word-level segments. The first vector
+/// describes the old line (equal + deletion), the second the new line
+/// (equal + addition). Returns `None` when the lines are too long to diff.
+pub(crate) fn diff_words(
+    old: &str,
+    new: &str,
+) -> Option<(Vec<GitDiffSegment>, Vec<GitDiffSegment>)> {
+    let a = tokenize_words(old);
+    let b = tokenize_words(new);
+    if a.len().saturating_mul(b.len()) > MAX_TOKEN_MATRIX {
+        return None;
+    }
+
+    let n = a.len();
+    let m = b.len();
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut old_segments = Vec::new();
+    let mut new_segments = Vec::new();
+    let (mut i, mut j) = (0usize, 0usize);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            push_segment(&mut old_segments, "equal", a[i]);
+            push_segment(&mut new_segments, "equal", b[j]);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            push_segment(&mut old_segments, "deletion", a[i]);
+            i += 1;
+        } else {
+            push_segment(&mut new_segments, "addition", b[j]);
+            j += 1;
+        }
+    }
+    for token in &a[i..] {
+        push_segment(&mut old_segments, "deletion", token);
+    }
+    for token in &b[j..] {
+        push_segment(&mut new_segments, "addition", token);
+    }
+
+    Some((old_segments, new_segments))
+}
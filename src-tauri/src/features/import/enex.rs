@@ -0,0 +1,236 @@
+use crate::features::import::html_convert::{html_to_markdown, Hooks, Media};
+use crate::features::import::markup::{tokenize, Token};
+use crate::features::import::service::{attachment_markdown, sanitize_file_name, ImportWriter};
+use crate::features::templates::expand::merge_frontmatter;
+use base64::Engine;
+use chrono::NaiveDateTime;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+const ENEX_DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EnexResource {
+    pub data: String,
+    pub mime: String,
+    pub file_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EnexNote {
+    pub title: String,
+    pub content: String,
+    pub created: Option<String>,
+    pub updated: Option<String>,
+    pub tags: Vec<String>,
+    pub resources: Vec<EnexResource>,
+}
+
+/// Reads the notes of an `.enex` export. Resource data stays base64 encoded
+/// until it is written.
+pub(crate) fn parse_enex(xml: &str) -> Vec<EnexNote> {
+    let mut notes = Vec::new();
+    let mut note: Option<EnexNote> = None;
+    let mut resource: Option<EnexResource> = None;
+    let mut stack: Vec<String> = Vec::new();
+
+    for token in tokenize(xml) {
+        match token {
+            Token::Start {
+                name, self_closing, ..
+            } => {
+                match name.as_str() {
+                    "note" => note = Some(EnexNote::default()),
+                    "resource" => resource = Some(EnexResource::default()),
+                    _ => {}
+                }
+                if !self_closing {
+                    stack.push(name);
+                }
+            }
+            Token::End { name } => {
+                if let Some(pos) = stack.iter().rposition(|open| *open == name) {
+                    stack.truncate(pos);
+                }
+                match name.as_str() {
+                    "resource" => {
+                        if let (Some(note), Some(done)) = (note.as_mut(), resource.take()) {
+                            note.resources.push(done);
+                        }
+                    }
+                    "note" => notes.extend(note.take()),
+                    _ => {}
+                }
+            }
+            Token::Text(text) => {
+                let Some(current) = note.as_mut() else {
+                    continue;
+                };
+                let field = stack.last().map(String::as_str).unwrap_or_default();
+                match (field, resource.as_mut()) {
+                    ("data", Some(res)) => res.data.push_str(&text),
+                    ("mime", Some(res)) => res.mime.push_str(text.trim()),
+                    ("file-name", Some(res)) => {
+                        res.file_name = Some(text.trim().to_string()).filter(|n| !n.is_empty())
+                    }
+                    (_, Some(_)) => {}
+                    ("title", None) => current.title.push_str(&text),
+                    ("content", None) => current.content.push_str(&text),
+                    ("created", None) => current.created = Some(text.trim().to_string()),
+                    ("updated", None) => current.updated = Some(text.trim().to_string()),
+                    ("tag", None) => current.tags.push(text.trim().to_string()),
+                    _ => {}
+                }
+            }
+        }
+    }
+    notes
+}
+
+fn iso_timestamp(value: Option<&str>) -> Option<String> {
+    let parsed = NaiveDateTime::parse_from_str(value?.trim(), ENEX_DATE_FORMAT).ok()?;
+    Some(parsed.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+}
+
+fn resource_file_name(resource: &EnexResource, index: usize) -> String {
+    if let Some(name) = &resource.file_name {
+        return sanitize_file_name(name);
+    }
+    let ext = mime_guess::get_mime_extensions_str(&resource.mime)
+        .and_then(|exts| exts.first())
+        .copied()
+        .unwrap_or("bin");
+    format!("attachment-{}.{}", index + 1, ext)
+}
+
+struct WrittenResource {
+    path: String,
+    label: String,
+    mime: String,
+    referenced: bool,
+}
+
+fn import_note(
+    writer: &mut ImportWriter<'_>,
+    source: &str,
+    note: EnexNote,
+    attachment_folder: &str,
+) -> Result<(), String> {
+    let title = note.title.trim();
+    let title = if title.is_empty() { "Untitled" } else { title };
+    let note_path =
+        writer.claim_path(&writer.dest_path(&format!("{}.md", sanitize_file_name(title))))?;
+
+    let mut resources: Vec<WrittenResource> = Vec::new();
+    let mut by_hash: HashMap<String, usize> = HashMap::new();
+    for (idx, resource) in note.resources.iter().enumerate() {
+        let cleaned: String = resource
+            .data
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(cleaned) else {
+            writer.skip(
+                &format!("{} / resource {}", title, idx + 1),
+                "invalid attachment data",
+            );
+            continue;
+        };
+        let file_name = resource_file_name(resource, idx);
+        let path = writer.claim_path(&format!("{}/{}", attachment_folder, file_name))?;
+        writer.write_attachment(&path, &bytes)?;
+        by_hash.insert(format!("{:x}", md5::compute(&bytes)), resources.len());
+        resources.push(WrittenResource {
+            path,
+            label: file_name,
+            mime: resource.mime.clone(),
+            referenced: false,
+        });
+    }
+
+    let mut link = |href: &str| href.to_string();
+    let mut media = |media: Media<'_>| match media {
+        Media::EnMedia { hash, .. } => {
+            let resource = resources.get_mut(*by_hash.get(&hash.to_ascii_lowercase())?)?;
+            resource.referenced = true;
+            Some(attachment_markdown(
+                &note_path,
+                &resource.path,
+                &resource.label,
+                &resource.mime,
+            ))
+        }
+        Media::Image { src, alt } => Some(format!("![{}]({})", alt, src)),
+    };
+    let body = html_to_markdown(
+        &note.content,
+        &mut Hooks {
+            link: &mut link,
+            media: &mut media,
+        },
+    );
+
+    let mut markdown = format!("# {}\n\n{}", title, body);
+    let unreferenced: Vec<String> = resources
+        .iter()
+        .filter(|r| !r.referenced)
+        .map(|r| {
+            format!(
+                "- {}",
+                attachment_markdown(&note_path, &r.path, &r.label, "")
+            )
+        })
+        .collect();
+    if !unreferenced.is_empty() {
+        markdown.push_str(&format!(
+            "\n## Attachments\n\n{}\n",
+            unreferenced.join("\n")
+        ));
+    }
+
+    let mut frontmatter = serde_json::Map::new();
+    if let Some(created) = iso_timestamp(note.created.as_deref()) {
+        frontmatter.insert("created".to_string(), Value::String(created));
+    }
+    if let Some(updated) = iso_timestamp(note.updated.as_deref()) {
+        frontmatter.insert("updated".to_string(), Value::String(updated));
+    }
+    let tags: Vec<Value> = note
+        .tags
+        .iter()
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| Value::String(tag.replace(' ', "-")))
+        .collect();
+    if !tags.is_empty() {
+        frontmatter.insert("tags".to_string(), Value::Array(tags));
+    }
+
+    writer.write_note(
+        source,
+        &note_path,
+        &merge_frontmatter(&markdown, &frontmatter),
+    )
+}
+
+pub(crate) fn import_enex(
+    writer: &mut ImportWriter<'_>,
+    enex_path: &Path,
+    attachment_folder: &str,
+) -> Result<(), String> {
+    let xml =
+        std::fs::read_to_string(enex_path).map_err(|e| format!("failed to read export: {}", e))?;
+    let source = enex_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let notes = parse_enex(&xml);
+    if notes.is_empty() {
+        return Err("no notes found in export".to_string());
+    }
+    for note in notes {
+        let note_source = format!("{} / {}", source, note.title.trim());
+        import_note(writer, &note_source, note, attachment_folder)?;
+    }
+    Ok(())
+}
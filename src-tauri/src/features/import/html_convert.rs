@@ -0,0 +1,501 @@
+use crate::features::import::markup::{attr, tokenize, Token};
+
+/// Embedded media the converter cannot resolve on its own.
+pub(crate) enum Media<'a> {
+    Image {
+        src: &'a str,
+        alt: &'a str,
+    },
+    /// Evernote's reference to a note resource by the MD5 of its data.
+    EnMedia {
+        hash: &'a str,
+        mime: &'a str,
+    },
+}
+
+pub(crate) struct Hooks<'a> {
+    /// Maps an `href` to the destination written in markdown.
+    pub link: &'a mut dyn FnMut(&str) -> String,
+    /// Returns the markdown for an image or attachment, or `None` to drop it.
+    pub media: &'a mut dyn FnMut(Media<'_>) -> Option<String>,
+}
+
+const SKIPPED_ELEMENTS: &[&str] = &["head", "script", "style", "title", "noscript", "template"];
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "main",
+    "aside",
+    "nav",
+    "figure",
+    "figcaption",
+    "dl",
+    "dt",
+    "dd",
+    "address",
+];
+
+enum FrameKind {
+    Root,
+    Blockquote,
+    Cell,
+}
+
+struct Frame {
+    kind: FrameKind,
+    out: String,
+}
+
+struct ListState {
+    ordered: bool,
+    next: usize,
+}
+
+struct Converter<'h, 'a> {
+    hooks: &'h mut Hooks<'a>,
+    frames: Vec<Frame>,
+    lists: Vec<ListState>,
+    links: Vec<Option<String>>,
+    tables: Vec<Vec<Vec<String>>>,
+    pre_depth: usize,
+    skip_depth: usize,
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn markdown_destination(href: &str) -> String {
+    if href
+        .chars()
+        .any(|c| c.is_whitespace() || c == '(' || c == ')')
+    {
+        format!("<{}>", href)
+    } else {
+        href.to_string()
+    }
+}
+
+impl Converter<'_, '_> {
+    fn out(&mut self) -> &mut String {
+        &mut self
+            .frames
+            .last_mut()
+            .expect("root frame is never popped")
+            .out
+    }
+
+    fn at_line_start(&mut self) -> bool {
+        let out = self.out();
+        out.is_empty() || out.ends_with('\n')
+    }
+
+    fn ensure_newline(&mut self) {
+        let out = self.out();
+        let trimmed = out.trim_end_matches([' ', '\t']).len();
+        out.truncate(trimmed);
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+
+    fn ensure_blank_line(&mut self) {
+        if !self.lists.is_empty() {
+            self.ensure_newline();
+            return;
+        }
+        self.ensure_newline();
+        let out = self.out();
+        if !out.is_empty() && !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.pre_depth > 0 {
+            self.out().push_str(text);
+            return;
+        }
+        let mut collapsed = String::with_capacity(text.len());
+        let mut last_space = false;
+        for c in text.chars() {
+            if c.is_whitespace() && c != '\u{a0}' {
+                if !last_space {
+                    collapsed.push(' ');
+                }
+                last_space = true;
+            } else {
+                collapsed.push(c);
+                last_space = false;
+            }
+        }
+        let out = self.out();
+        let collapsed = if out.is_empty() || out.ends_with([' ', '\n']) {
+            collapsed.trim_start()
+        } else {
+            collapsed.as_str()
+        };
+        let escaped = escape_markdown(collapsed);
+        self.out().push_str(&escaped);
+    }
+
+    fn start(&mut self, name: &str, attrs: &[(String, String)], self_closing: bool) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.ensure_blank_line();
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                let marker = format!("{} ", "#".repeat(level));
+                self.out().push_str(&marker);
+            }
+            "br" => {
+                if self.pre_depth > 0 {
+                    self.out().push('\n');
+                } else if matches!(self.frames.last().map(|f| &f.kind), Some(FrameKind::Cell)) {
+                    self.out().push(' ');
+                } else if !self.at_line_start() {
+                    self.out().push_str("\\\n");
+                }
+            }
+            "hr" => {
+                self.ensure_blank_line();
+                self.out().push_str("---");
+                self.ensure_blank_line();
+            }
+            "strong" | "b" => self.out().push_str("**"),
+            "em" | "i" => self.out().push('*'),
+            "s" | "del" | "strike" => self.out().push_str("~~"),
+            "code" if self.pre_depth == 0 => self.out().push('`'),
+            "a" => {
+                let href = attr(attrs, "href")
+                    .map(str::trim)
+                    .filter(|href| !href.is_empty() && !href.starts_with('#'))
+                    .map(|href| (self.hooks.link)(href));
+                if href.is_some() && !self_closing {
+                    self.out().push('[');
+                }
+                if !self_closing {
+                    self.links.push(href);
+                }
+            }
+            "img" => {
+                let src = attr(attrs, "src").unwrap_or_default();
+                let alt = attr(attrs, "alt").unwrap_or_default();
+                if !src.is_empty() {
+                    if let Some(markdown) = (self.hooks.media)(Media::Image { src, alt }) {
+                        self.out().push_str(&markdown);
+                    }
+                }
+            }
+            "en-media" => {
+                let hash = attr(attrs, "hash").unwrap_or_default();
+                let mime = attr(attrs, "type").unwrap_or_default();
+                if let Some(markdown) = (self.hooks.media)(Media::EnMedia { hash, mime }) {
+                    self.out().push_str(&markdown);
+                }
+            }
+            "en-todo" | "input" => {
+                if name == "input" && attr(attrs, "type") != Some("checkbox") {
+                    return;
+                }
+                let checked = attr(attrs, "checked").is_some_and(|v| v != "false");
+                if self.at_line_start() && self.lists.is_empty() {
+                    self.out().push_str("- ");
+                }
+                self.out().push_str(if checked { "[x] " } else { "[ ] " });
+            }
+            "ul" | "ol" => {
+                if self.lists.is_empty() {
+                    self.ensure_blank_line();
+                } else {
+                    self.ensure_newline();
+                }
+                let start = attr(attrs, "start")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(1);
+                self.lists.push(ListState {
+                    ordered: name == "ol",
+                    next: start,
+                });
+            }
+            "li" => {
+                self.ensure_newline();
+                let depth = self.lists.len().max(1);
+                let marker = match self.lists.last_mut() {
+                    Some(list) if list.ordered => {
+                        list.next += 1;
+                        format!("{}. ", list.next - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                let line = format!("{}{}", "  ".repeat(depth - 1), marker);
+                self.out().push_str(&line);
+            }
+            "blockquote" => {
+                self.ensure_blank_line();
+                self.frames.push(Frame {
+                    kind: FrameKind::Blockquote,
+                    out: String::new(),
+                });
+            }
+            "pre" => {
+                self.ensure_blank_line();
+                self.out().push_str("```\n");
+                self.pre_depth += 1;
+            }
+            "table" => {
+                self.ensure_blank_line();
+                self.tables.push(Vec::new());
+            }
+            "tr" => {
+                if let Some(table) = self.tables.last_mut() {
+                    table.push(Vec::new());
+                }
+            }
+            "td" | "th" if !self.tables.is_empty() => self.frames.push(Frame {
+                kind: FrameKind::Cell,
+                out: String::new(),
+            }),
+            _ if BLOCK_ELEMENTS.contains(&name) => {
+                if self.lists.is_empty() {
+                    self.ensure_blank_line();
+                } else if !self.at_line_start() {
+                    self.out().push(' ');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => self.ensure_blank_line(),
+            "strong" | "b" => self.out().push_str("**"),
+            "em" | "i" => self.out().push('*'),
+            "s" | "del" | "strike" => self.out().push_str("~~"),
+            "code" if self.pre_depth == 0 => self.out().push('`'),
+            "a" => {
+                if let Some(Some(href)) = self.links.pop() {
+                    let close = format!("]({})", markdown_destination(&href));
+                    self.out().push_str(&close);
+                }
+            }
+            "ul" | "ol" => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.ensure_blank_line();
+                } else {
+                    self.ensure_newline();
+                }
+            }
+            "blockquote" => self.close_frame(|kind| matches!(kind, FrameKind::Blockquote)),
+            "pre" => {
+                self.pre_depth = self.pre_depth.saturating_sub(1);
+                self.ensure_newline();
+                self.out().push_str("```");
+                self.ensure_blank_line();
+            }
+            "td" | "th" => self.close_frame(|kind| matches!(kind, FrameKind::Cell)),
+            "table" => {
+                if let Some(rows) = self.tables.pop() {
+                    let table = render_table(rows);
+                    self.ensure_blank_line();
+                    self.out().push_str(&table);
+                    self.ensure_blank_line();
+                }
+            }
+            _ if BLOCK_ELEMENTS.contains(&name) && self.lists.is_empty() => {
+                self.ensure_blank_line()
+            }
+            _ => {}
+        }
+    }
+
+    /// Pops frames up to and including the innermost one matching `is_kind`.
+    fn close_frame(&mut self, is_kind: impl Fn(&FrameKind) -> bool) {
+        if !self.frames.iter().skip(1).any(|f| is_kind(&f.kind)) {
+            return;
+        }
+        while self.frames.len() > 1 {
+            let done = is_kind(&self.frames.last().expect("checked length").kind);
+            self.pop_frame();
+            if done {
+                break;
+            }
+        }
+    }
+
+    fn pop_frame(&mut self) {
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+        match frame.kind {
+            FrameKind::Root => self.frames.push(frame),
+            FrameKind::Blockquote => {
+                let quoted = frame
+                    .out
+                    .trim()
+                    .lines()
+                    .map(|line| {
+                        if line.trim().is_empty() {
+                            ">".to_string()
+                        } else {
+                            format!("> {}", line)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.ensure_blank_line();
+                self.out().push_str(&quoted);
+                self.ensure_blank_line();
+            }
+            FrameKind::Cell => {
+                let cell = frame
+                    .out
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .replace('|', "\\|");
+                if let Some(table) = self.tables.last_mut() {
+                    if table.is_empty() {
+                        table.push(Vec::new());
+                    }
+                    if let Some(row) = table.last_mut() {
+                        row.push(cell);
+                    }
+                }
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        while self.frames.len() > 1 {
+            self.pop_frame();
+        }
+        let raw = self.frames.pop().map(|f| f.out).unwrap_or_default();
+        let mut out = String::with_capacity(raw.len());
+        let mut blank_run = 0usize;
+        for line in raw.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                blank_run += 1;
+                if blank_run > 1 {
+                    continue;
+                }
+            } else {
+                blank_run = 0;
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        // A hard break right before a paragraph break renders as a stray `\`.
+        let out = out.replace("\\\n\n", "\n\n");
+        let trimmed = out.trim();
+        if trimmed.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n", trimmed.strip_suffix('\\').unwrap_or(trimmed))
+        }
+    }
+}
+
+fn render_table(rows: Vec<Vec<String>>) -> String {
+    let rows: Vec<Vec<String>> = rows.into_iter().filter(|row| !row.is_empty()).collect();
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+    let render_row = |row: &[String]| {
+        let cells: Vec<&str> = (0..columns)
+            .map(|idx| row.get(idx).map(String::as_str).unwrap_or(""))
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let mut lines = vec![
+        render_row(&rows[0]),
+        format!("|{}", " --- |".repeat(columns)),
+    ];
+    lines.extend(rows[1..].iter().map(|row| render_row(row)));
+    lines.join("\n")
+}
+
+/// Converts an HTML (or Evernote ENML) document to markdown.
+pub(crate) fn html_to_markdown(html: &str, hooks: &mut Hooks<'_>) -> String {
+    let mut converter = Converter {
+        hooks,
+        frames: vec![Frame {
+            kind: FrameKind::Root,
+            out: String::new(),
+        }],
+        lists: Vec::new(),
+        links: Vec::new(),
+        tables: Vec::new(),
+        pre_depth: 0,
+        skip_depth: 0,
+    };
+
+    for token in tokenize(html) {
+        match token {
+            Token::Start {
+                name, self_closing, ..
+            } if converter.skip_depth > 0 => {
+                if SKIPPED_ELEMENTS.contains(&name.as_str()) && !self_closing {
+                    converter.skip_depth += 1;
+                }
+            }
+            Token::End { name } if converter.skip_depth > 0 => {
+                if SKIPPED_ELEMENTS.contains(&name.as_str()) {
+                    converter.skip_depth -= 1;
+                }
+            }
+            _ if converter.skip_depth > 0 => {}
+            Token::Start {
+                name, self_closing, ..
+            } if SKIPPED_ELEMENTS.contains(&name.as_str()) => {
+                if !self_closing {
+                    converter.skip_depth = 1;
+                }
+            }
+            Token::Start {
+                name,
+                attrs,
+                self_closing,
+            } => converter.start(&name, &attrs, self_closing),
+            Token::End { name } => converter.end(&name),
+            Token::Text(text) => converter.push_text(&text),
+        }
+    }
+
+    converter.finish()
+}
+
+/// The document's `<title>`, falling back to its first `<h1>`.
+pub(crate) fn html_title(html: &str) -> Option<String> {
+    let tokens = tokenize(html);
+    let text_of = |element: &str| {
+        let start = tokens.iter().position(
+            |t| matches!(t, Token::Start { name, self_closing: false, .. } if name == element),
+        )?;
+        let mut text = String::new();
+        for token in &tokens[start + 1..] {
+            match token {
+                Token::End { name } if name == element => break,
+                Token::Text(value) => text.push_str(value),
+                _ => {}
+            }
+        }
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        (!text.is_empty()).then_some(text)
+    };
+    text_of("title").or_else(|| text_of("h1"))
+}
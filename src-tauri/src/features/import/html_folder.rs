@@ -0,0 +1,174 @@
+use crate::features::import::html_convert::{html_title, html_to_markdown, Hooks, Media};
+use crate::features::import::service::{attachment_markdown, ImportWriter};
+use crate::features::search::link_parser::{
+    compute_relative_path, decode_percent_sequences, resolve_relative_path, source_dir_from_path,
+};
+use crate::shared::storage;
+use std::collections::HashMap;
+use std::path::Path;
+use walkdir::WalkDir;
+
+fn is_html_file(path: &Path) -> bool {
+    matches!(
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref(),
+        Some("html" | "htm")
+    )
+}
+
+fn is_relative_url(url: &str) -> bool {
+    let lower = url.trim().to_ascii_lowercase();
+    !(lower.is_empty()
+        || lower.starts_with('#')
+        || lower.starts_with('/')
+        || lower.starts_with("data:")
+        || lower.starts_with("mailto:")
+        || lower.contains("://"))
+}
+
+/// Resolves a relative `href`/`src` against the page, returning the
+/// source-relative target and any `#fragment`.
+fn resolve_url(page: &str, url: &str) -> Option<(String, String)> {
+    if !is_relative_url(url) {
+        return None;
+    }
+    let mut decoded = decode_percent_sequences(url.trim());
+    let fragment = decoded.find('#').map(|idx| decoded.split_off(idx));
+    if let Some(query) = decoded.find('?') {
+        decoded.truncate(query);
+    }
+    let target = resolve_relative_path(source_dir_from_path(page), &decoded)?;
+    Some((target, fragment.unwrap_or_default()))
+}
+
+fn with_md_extension(path: &str) -> String {
+    let stem = match path.rfind('.') {
+        Some(dot) if dot > path.rfind('/').map(|s| s + 1).unwrap_or(0) => &path[..dot],
+        _ => path,
+    };
+    format!("{}.md", stem)
+}
+
+pub(crate) fn import_html_folder(
+    writer: &mut ImportWriter<'_>,
+    source_dir: &Path,
+) -> Result<(), String> {
+    if !source_dir.is_dir() {
+        return Err("import source must be a folder".to_string());
+    }
+
+    let mut pages: Vec<String> = Vec::new();
+    for entry in WalkDir::new(source_dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if !entry.file_type().is_file() || !is_html_file(entry.path()) {
+            continue;
+        }
+        if let Ok(rel) = entry.path().strip_prefix(source_dir) {
+            pages.push(storage::normalize_relative_path(rel));
+        }
+    }
+    pages.sort();
+    if pages.is_empty() {
+        return Err("no html files found".to_string());
+    }
+
+    let mut mapping: HashMap<String, String> = HashMap::new();
+    for page in &pages {
+        let path = writer.claim_path(&writer.dest_path(&with_md_extension(page)))?;
+        mapping.insert(page.clone(), path);
+    }
+
+    let mut assets: HashMap<String, String> = HashMap::new();
+    for page in &pages {
+        let note_path = mapping[page].clone();
+        let html = match std::fs::read(source_dir.join(page)) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => {
+                writer.skip(page, &format!("failed to read: {}", e));
+                continue;
+            }
+        };
+
+        let mut rewritten = 0usize;
+        let mut pending_assets: Vec<(String, String)> = Vec::new();
+        let mut skipped: Vec<(String, &'static str)> = Vec::new();
+        let mut link = |href: &str| {
+            let Some((target, fragment)) = resolve_url(page, href) else {
+                return href.to_string();
+            };
+            match mapping.get(&target) {
+                Some(mapped) => {
+                    rewritten += 1;
+                    let mut out = compute_relative_path(source_dir_from_path(&note_path), mapped);
+                    out.push_str(&fragment);
+                    out
+                }
+                None => href.to_string(),
+            }
+        };
+        let mut media = |media: Media<'_>| {
+            let Media::Image { src, alt } = media else {
+                return None;
+            };
+            let original = format!("![{}]({})", alt, src);
+            let Some((target, _)) = resolve_url(page, src) else {
+                return Some(original);
+            };
+            let asset_path = match assets.get(&target) {
+                Some(path) => path.clone(),
+                None if source_dir.join(&target).is_file() => {
+                    let Ok(path) = writer.claim_path(&writer.dest_path(&target)) else {
+                        skipped.push((target, "invalid asset path"));
+                        return Some(original);
+                    };
+                    assets.insert(target.clone(), path.clone());
+                    pending_assets.push((target, path.clone()));
+                    path
+                }
+                None => {
+                    skipped.push((target, "missing image"));
+                    return Some(original);
+                }
+            };
+            Some(attachment_markdown(&note_path, &asset_path, alt, "image/"))
+        };
+        let body = html_to_markdown(
+            &html,
+            &mut Hooks {
+                link: &mut link,
+                media: &mut media,
+            },
+        );
+
+        for (target, path) in pending_assets {
+            match std::fs::read(source_dir.join(&target)) {
+                Ok(bytes) => writer.write_attachment(&path, &bytes)?,
+                Err(_) => writer.skip(&target, "failed to read image"),
+            }
+        }
+        for (target, reason) in skipped {
+            writer.skip(&target, reason);
+        }
+        writer.report.rewritten_links += rewritten;
+
+        let markdown = if body.starts_with("# ") {
+            body
+        } else {
+            let title = html_title(&html).unwrap_or_else(|| {
+                let file = page.rsplit('/').next().unwrap_or(page);
+                file.rsplit_once('.')
+                    .map(|(stem, _)| stem)
+                    .unwrap_or(file)
+                    .to_string()
+            });
+            format!("# {}\n\n{}", title, body)
+        };
+        writer.write_note(page, &note_path, &markdown)?;
+    }
+    Ok(())
+}
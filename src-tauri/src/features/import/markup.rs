@@ -0,0 +1,220 @@
+/// A forgiving tokenizer for the HTML and XML found in exports. It does not
+/// build a tree or validate nesting; converters track the structure they
+/// care about themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token {
+    Start {
+        name: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    End {
+        name: String,
+    },
+    Text(String),
+}
+
+// Elements whose content is never markup.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea"];
+
+pub(crate) fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn named_entity(name: &str) -> Option<char> {
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "bull" => '•',
+        "middot" => '·',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "times" => '×',
+        "deg" => '°',
+        "euro" => '€',
+        _ => return None,
+    })
+}
+
+/// Decodes named and numeric character references. Unknown references are
+/// kept as written.
+pub(crate) fn decode_entities(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let decoded = after.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let name = &after[..end];
+            let c = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = name.strip_prefix('#') {
+                dec.parse::<u32>().ok().and_then(char::from_u32)
+            } else {
+                named_entity(name)
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn parse_attrs(raw: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = raw.trim();
+    while !rest.is_empty() {
+        let name_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let name = rest[..name_len].to_ascii_lowercase();
+        rest = rest[name_len..].trim_start();
+        let mut value = String::new();
+        if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            let (raw_value, remaining) = match after_eq.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let body = &after_eq[1..];
+                    match body.find(quote) {
+                        Some(end) => (&body[..end], &body[end + 1..]),
+                        None => (body, ""),
+                    }
+                }
+                _ => {
+                    let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    (&after_eq[..end], &after_eq[end..])
+                }
+            };
+            value = decode_entities(raw_value);
+            rest = remaining.trim_start();
+        }
+        if !name.is_empty() {
+            attrs.push((name, value));
+        }
+    }
+    attrs
+}
+
+/// Finds the `>` closing a tag, ignoring any inside quoted attribute values.
+fn tag_end(input: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (idx, c) in input.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+pub(crate) fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = input;
+    let mut text = String::new();
+
+    let flush = |text: &mut String, tokens: &mut Vec<Token>| {
+        if !text.is_empty() {
+            tokens.push(Token::Text(decode_entities(text)));
+            text.clear();
+        }
+    };
+
+    while let Some(lt) = rest.find('<') {
+        text.push_str(&rest[..lt]);
+        let tail = &rest[lt..];
+
+        if let Some(body) = tail.strip_prefix("<!--") {
+            rest = body.find("-->").map(|end| &body[end + 3..]).unwrap_or("");
+            continue;
+        }
+        if let Some(body) = tail.strip_prefix("<![CDATA[") {
+            flush(&mut text, &mut tokens);
+            let end = body.find("]]>").unwrap_or(body.len());
+            tokens.push(Token::Text(body[..end].to_string()));
+            rest = body.get(end + 3..).unwrap_or("");
+            continue;
+        }
+        if tail.starts_with("<!") || tail.starts_with("<?") {
+            rest = tail.find('>').map(|end| &tail[end + 1..]).unwrap_or("");
+            continue;
+        }
+
+        let is_end = tail.starts_with("</");
+        let name_start = if is_end { 2 } else { 1 };
+        let starts_name = tail[name_start..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic());
+        let Some(end) = tag_end(tail).filter(|_| starts_name) else {
+            // A stray `<` is text.
+            text.push('<');
+            rest = &tail[1..];
+            continue;
+        };
+
+        flush(&mut text, &mut tokens);
+        let inner = &tail[name_start..end];
+        let self_closing = inner.ends_with('/');
+        let inner = inner.trim_end_matches('/');
+        let name_len = inner
+            .find(|c: char| c.is_whitespace())
+            .unwrap_or(inner.len());
+        let name = inner[..name_len].to_ascii_lowercase();
+        rest = &tail[end + 1..];
+
+        if is_end {
+            tokens.push(Token::End { name });
+            continue;
+        }
+
+        let attrs = parse_attrs(&inner[name_len..]);
+        let raw_text = RAW_TEXT_ELEMENTS.contains(&name.as_str()) && !self_closing;
+        tokens.push(Token::Start {
+            name: name.clone(),
+            attrs,
+            self_closing,
+        });
+        if raw_text {
+            let closing = format!("</{}", name);
+            let end = rest
+                .to_ascii_lowercase()
+                .find(&closing)
+                .unwrap_or(rest.len());
+            if end > 0 {
+                tokens.push(Token::Text(rest[..end].to_string()));
+            }
+            rest = &rest[end..];
+        }
+    }
+
+    text.push_str(rest);
+    flush(&mut text, &mut tokens);
+    tokens
+}
@@ -0,0 +1,6 @@
+pub mod enex;
+pub mod html_convert;
+pub mod html_folder;
+pub mod markup;
+pub mod notion;
+pub mod service;
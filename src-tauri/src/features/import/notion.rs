@@ -0,0 +1,335 @@
+use crate::features::import::service::ImportWriter;
+use crate::features::search::link_parser::{
+    compute_line_starts, compute_relative_path, decode_percent_sequences, is_external_url,
+    markdown_options, resolve_relative_path, source_dir_from_path, sourcepos_to_byte_range,
+};
+use comrak::nodes::NodeValue;
+use comrak::{parse_document, Arena};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+const NOTION_ID_LEN: usize = 32;
+
+/// Removes the ` 0123456789abcdef0123456789abcdef` suffix Notion appends to
+/// page and folder names, keeping any file extension.
+pub(crate) fn strip_notion_id(name: &str) -> String {
+    let (stem, ext) = match name.rfind('.') {
+        Some(dot) if dot > 0 => name.split_at(dot),
+        _ => (name, ""),
+    };
+    let Some(split) = stem.len().checked_sub(NOTION_ID_LEN + 1) else {
+        return name.to_string();
+    };
+    let (base, suffix) = stem.split_at(split);
+    let is_id = suffix.starts_with(' ')
+        && suffix[1..].chars().all(|c| c.is_ascii_hexdigit())
+        && !base.trim().is_empty();
+    if is_id {
+        format!("{}{}", base.trim_end(), ext)
+    } else {
+        name.to_string()
+    }
+}
+
+fn clean_entry_path(entry: &str) -> String {
+    entry
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(strip_notion_id)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn markdown_destination(href: &str) -> String {
+    if href.chars().any(char::is_whitespace) {
+        format!("<{}>", href)
+    } else {
+        href.to_string()
+    }
+}
+
+/// Rewrites link and image destinations that point at other entries of the
+/// export. Returns the new markdown and the number of rewritten links.
+pub(crate) fn rewrite_export_links(
+    markdown: &str,
+    entry_path: &str,
+    new_path: &str,
+    mapping: &HashMap<String, String>,
+) -> (String, usize) {
+    let arena = Arena::new();
+    let options = markdown_options();
+    let root = parse_document(&arena, markdown, &options);
+    let line_starts = compute_line_starts(markdown);
+    let mut replacements: Vec<(usize, usize, String)> = Vec::new();
+
+    for node in root.descendants() {
+        let data = node.data.borrow();
+        let (url, prefix) = match &data.value {
+            NodeValue::Link(link) => (link.url.as_str(), "["),
+            NodeValue::Image(link) => (link.url.as_str(), "!["),
+            _ => continue,
+        };
+        if url.is_empty() || is_external_url(url) || url.contains("://") || url.starts_with('#') {
+            continue;
+        }
+        let mut decoded = decode_percent_sequences(url);
+        let fragment = decoded.find('#').map(|idx| decoded.split_off(idx));
+        let Some(target) = resolve_relative_path(source_dir_from_path(entry_path), &decoded)
+            .and_then(|resolved| mapping.get(&resolved))
+        else {
+            continue;
+        };
+        let Some((start, end)) = sourcepos_to_byte_range(&line_starts, data.sourcepos) else {
+            continue;
+        };
+        if end > markdown.len() || replacements.iter().any(|(s, e, _)| start < *e && *s < end) {
+            continue;
+        }
+        let span = &markdown[start..end];
+        let Some(split) = span.rfind("](") else {
+            continue;
+        };
+        if !span.starts_with(prefix) || !span.ends_with(')') {
+            continue;
+        }
+        let mut href = compute_relative_path(source_dir_from_path(new_path), target);
+        href.push_str(fragment.as_deref().unwrap_or_default());
+        replacements.push((
+            start,
+            end,
+            format!("{}]({})", &span[..split], markdown_destination(&href)),
+        ));
+    }
+
+    let count = replacements.len();
+    replacements.sort_by_key(|r| std::cmp::Reverse(r.0));
+    let mut out = markdown.to_string();
+    for (start, end, replacement) in replacements {
+        out.replace_range(start..end, &replacement);
+    }
+    (out, count)
+}
+
+/// Parses RFC 4180 style CSV, tolerating a missing trailing newline.
+pub(crate) fn parse_csv(input: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = input.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (in_quotes, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            (true, '"') => in_quotes = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => in_quotes = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+fn table_cell(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+/// Renders a Notion database CSV as a note with a markdown table.
+pub(crate) fn csv_to_markdown(title: &str, csv: &str) -> String {
+    let rows = parse_csv(csv);
+    let mut out = format!("# {}\n", title);
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return out;
+    }
+    out.push('\n');
+    for (idx, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = (0..columns)
+            .map(|col| row.get(col).map(|v| table_cell(v)).unwrap_or_default())
+            .collect();
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+        if idx == 0 {
+            out.push_str(&format!("|{}\n", " --- |".repeat(columns)));
+        }
+    }
+    out
+}
+
+fn entry_extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default()
+}
+
+/// Caps on what a Notion export may expand to. Entry sizes in the zip header
+/// are not trusted; every read goes through these limits.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ZipLimits {
+    /// Pages and databases are buffered, so they get a tighter cap.
+    pub text_entry_bytes: u64,
+    /// Attachments are streamed to disk.
+    pub entry_bytes: u64,
+    pub total_bytes: u64,
+}
+
+impl Default for ZipLimits {
+    fn default() -> Self {
+        Self {
+            text_entry_bytes: 64 * 1024 * 1024,
+            entry_bytes: 2 * 1024 * 1024 * 1024,
+            total_bytes: 8 * 1024 * 1024 * 1024,
+        }
+    }
+}
+
+fn is_text_entry(name: &str) -> bool {
+    matches!(entry_extension(name).as_str(), "md" | "csv")
+}
+
+/// Reads at most `limit` bytes; `None` when the entry is larger.
+fn read_limited(reader: impl Read, limit: u64) -> std::io::Result<Option<Vec<u8>>> {
+    let mut bytes = Vec::new();
+    reader
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut bytes)?;
+    Ok((bytes.len() as u64 <= limit).then_some(bytes))
+}
+
+pub(crate) fn import_notion_zip(
+    writer: &mut ImportWriter<'_>,
+    zip_path: &Path,
+) -> Result<(), String> {
+    import_notion_zip_with_limits(writer, zip_path, ZipLimits::default())
+}
+
+pub(crate) fn import_notion_zip_with_limits(
+    writer: &mut ImportWriter<'_>,
+    zip_path: &Path,
+    limits: ZipLimits,
+) -> Result<(), String> {
+    let file = File::open(zip_path).map_err(|e| format!("failed to open archive: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("failed to read archive: {}", e))?;
+
+    let mut entries: Vec<(String, usize)> = Vec::new();
+    for idx in 0..archive.len() {
+        let entry = archive
+            .by_index(idx)
+            .map_err(|e| format!("failed to read archive entry: {}", e))?;
+        let name = entry.name().replace('\\', "/");
+        if entry.is_dir() {
+            continue;
+        }
+        let file_name = name.rsplit('/').next().unwrap_or(&name);
+        if name.starts_with("__MACOSX/") || file_name.starts_with('.') {
+            continue;
+        }
+        if entry.enclosed_name().is_none() {
+            writer.skip(&name, "unsafe path");
+            continue;
+        }
+        if entry_extension(&name) == "zip" {
+            writer.skip(&name, "nested archives are not imported");
+            continue;
+        }
+        let cap = if is_text_entry(&name) {
+            limits.text_entry_bytes
+        } else {
+            limits.entry_bytes
+        };
+        if entry.size() > cap {
+            writer.skip(&name, "file is too large");
+            continue;
+        }
+        entries.push((name, idx));
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut mapping: HashMap<String, String> = HashMap::new();
+    let mut planned: Vec<(String, String, usize)> = Vec::new();
+    for (name, idx) in entries {
+        let clean = clean_entry_path(&name);
+        let ext = entry_extension(&name);
+        let desired = if ext == "csv" {
+            let stem = clean.strip_suffix(".csv").unwrap_or(&clean);
+            if stem.ends_with("_all") {
+                writer.skip(&name, "duplicate database view");
+                continue;
+            }
+            format!("{}.md", stem)
+        } else {
+            clean
+        };
+        let path = writer.claim_path(&writer.dest_path(&desired))?;
+        mapping.insert(name.clone(), path.clone());
+        planned.push((name, path, idx));
+    }
+
+    let mut total: u64 = 0;
+    for (name, path, idx) in planned {
+        let entry = archive
+            .by_index(idx)
+            .map_err(|e| format!("failed to read archive entry: {}", e))?;
+        let remaining = limits.total_bytes.saturating_sub(total);
+        let too_big = || format!("archive expands to more than {} bytes", limits.total_bytes);
+        if !is_text_entry(&name) {
+            let limit = limits.entry_bytes.min(remaining);
+            match writer.copy_attachment(&path, entry, limit)? {
+                Some(copied) => total += copied,
+                None if limit < limits.entry_bytes => return Err(too_big()),
+                None => writer.skip(&name, "file is too large"),
+            }
+            continue;
+        }
+
+        let limit = limits.text_entry_bytes.min(remaining);
+        let bytes = match read_limited(entry, limit)
+            .map_err(|e| format!("failed to read {}: {}", name, e))?
+        {
+            Some(bytes) => bytes,
+            None if limit < limits.text_entry_bytes => return Err(too_big()),
+            None => {
+                writer.skip(&name, "file is too large");
+                continue;
+            }
+        };
+        total += bytes.len() as u64;
+        let text = String::from_utf8_lossy(&bytes);
+        if entry_extension(&name) == "md" {
+            let (markdown, rewritten) = rewrite_export_links(&text, &name, &path, &mapping);
+            writer.report.rewritten_links += rewritten;
+            writer.write_note(&name, &path, &markdown)?;
+        } else {
+            let title = path
+                .rsplit('/')
+                .next()
+                .and_then(|file| file.strip_suffix(".md"))
+                .unwrap_or(&path)
+                .to_string();
+            let markdown = csv_to_markdown(&title, &text);
+            writer.write_note(&name, &path, &markdown)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use crate::features::import::{enex, html_folder, notion};
+use crate::features::notes::service::{
+    self as notes_service, create_note_file, safe_vault_abs_for_write,
+};
+use crate::features::search::link_parser::{compute_relative_path, source_dir_from_path};
+use crate::shared::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const DEFAULT_ATTACHMENT_FOLDER: &str = ".assets";
+const UNSAFE_FILE_NAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind {
+    NotionZip,
+    Enex,
+    HtmlFolder,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportArgs {
+    pub vault_id: String,
+    pub kind: ImportKind,
+    pub source_path: String,
+    /// Vault folder the imported notes are written under.
+    #[serde(default)]
+    pub dest_folder: String,
+    #[serde(default)]
+    pub attachment_folder: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedNote {
+    pub source: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedItem {
+    pub source: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub notes: Vec<ImportedNote>,
+    pub attachments: Vec<String>,
+    pub skipped: Vec<SkippedItem>,
+    pub rewritten_links: usize,
+}
+
+/// Writes imported files into the vault, never overwriting existing files.
+pub(crate) struct ImportWriter<'a> {
+    root: &'a Path,
+    dest_folder: String,
+    claimed: HashSet<String>,
+    pub report: ImportReport,
+}
+
+/// Replaces characters that are invalid in file names and falls back to
+/// `Untitled` for names that end up empty.
+pub(crate) fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if UNSAFE_FILE_NAME_CHARS.contains(&c) || c.is_control() {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('.').trim();
+    if cleaned.is_empty() {
+        "Untitled".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Percent-encodes the characters that would break a markdown link
+/// destination, matching how the editor writes attachment links.
+pub(crate) fn encode_link_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            ' ' => out.push_str("%20"),
+            '%' => out.push_str("%25"),
+            '(' => out.push_str("%28"),
+            ')' => out.push_str("%29"),
+            '#' => out.push_str("%23"),
+            '?' => out.push_str("%3F"),
+            '<' => out.push_str("%3C"),
+            '>' => out.push_str("%3E"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Markdown for an attachment at `asset_path` referenced from `note_path`.
+pub(crate) fn attachment_markdown(
+    note_path: &str,
+    asset_path: &str,
+    label: &str,
+    mime: &str,
+) -> String {
+    let href = encode_link_path(&compute_relative_path(
+        source_dir_from_path(note_path),
+        asset_path,
+    ));
+    let label = label.replace(['[', ']'], "");
+    if mime.starts_with("image/") {
+        format!("![{}]({})", label, href)
+    } else {
+        format!("[{}]({})", label, href)
+    }
+}
+
+fn split_extension(path: &str) -> (&str, &str) {
+    let file_start = path.rfind('/').map(|idx| idx + 1).unwrap_or(0);
+    match path[file_start..].rfind('.') {
+        Some(dot) if dot > 0 => path.split_at(file_start + dot),
+        _ => (path, ""),
+    }
+}
+
+impl<'a> ImportWriter<'a> {
+    pub(crate) fn new(root: &'a Path, dest_folder: &str) -> Self {
+        Self {
+            root,
+            dest_folder: dest_folder.trim().trim_matches('/').to_string(),
+            claimed: HashSet::new(),
+            report: ImportReport::default(),
+        }
+    }
+
+    /// `rel` placed under the destination folder.
+    pub(crate) fn dest_path(&self, rel: &str) -> String {
+        let rel = rel.trim_matches('/');
+        if self.dest_folder.is_empty() {
+            rel.to_string()
+        } else {
+            format!("{}/{}", self.dest_folder, rel)
+        }
+    }
+
+    /// Reserves `desired`, or `name 2.ext`, `name 3.ext`, ... when the path is
+    /// already taken in the vault or by an earlier item of this import.
+    pub(crate) fn claim_path(&mut self, desired: &str) -> Result<String, String> {
+        let (stem, ext) = split_extension(desired);
+        let mut candidate = desired.to_string();
+        let mut counter = 2;
+        loop {
+            let abs = safe_vault_abs_for_write(self.root, &candidate)?;
+            if !abs.exists() && !self.claimed.contains(&candidate.to_lowercase()) {
+                self.claimed.insert(candidate.to_lowercase());
+                return Ok(candidate);
+            }
+            candidate = format!("{} {}{}", stem, counter, ext);
+            counter += 1;
+        }
+    }
+
+    pub(crate) fn write_note(
+        &mut self,
+        source: &str,
+        path: &str,
+        markdown: &str,
+    ) -> Result<(), String> {
+        create_note_file(self.root, path, markdown)?;
+        self.report.notes.push(ImportedNote {
+            source: source.to_string(),
+            path: path.to_string(),
+        });
+        Ok(())
+    }
+
+    pub(crate) fn write_attachment(&mut self, path: &str, bytes: &[u8]) -> Result<(), String> {
+        let abs = safe_vault_abs_for_write(self.root, path)?;
+        if let Some(dir) = abs.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&abs, bytes).map_err(|e| format!("failed to write {}: {}", path, e))?;
+        self.report.attachments.push(path.to_string());
+        Ok(())
+    }
+
+    /// Streams an attachment to disk, reading at most `limit` bytes. Returns
+    /// the size written, or `None` when the source is larger than `limit`, in
+    /// which case the partial file is removed.
+    pub(crate) fn copy_attachment(
+        &mut self,
+        path: &str,
+        reader: impl Read,
+        limit: u64,
+    ) -> Result<Option<u64>, String> {
+        let abs = safe_vault_abs_for_write(self.root, path)?;
+        if let Some(dir) = abs.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let mut file =
+            File::create(&abs).map_err(|e| format!("failed to write {}: {}", path, e))?;
+        let copied = std::io::copy(&mut reader.take(limit.saturating_add(1)), &mut file);
+        drop(file);
+        let copied = match copied {
+            Ok(copied) if copied <= limit => copied,
+            other => {
+                let _ = std::fs::remove_file(&abs);
+                return match other {
+                    Ok(_) => Ok(None),
+                    Err(e) => Err(format!("failed to write {}: {}", path, e)),
+                };
+            }
+        };
+        self.report.attachments.push(path.to_string());
+        Ok(Some(copied))
+    }
+
+    pub(crate) fn skip(&mut self, source: &str, reason: &str) {
+        self.report.skipped.push(SkippedItem {
+            source: source.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+}
+
+fn attachment_folder(value: Option<&str>) -> String {
+    value
+        .map(|folder| folder.trim().trim_matches('/'))
+        .filter(|folder| !folder.is_empty())
+        .unwrap_or(DEFAULT_ATTACHMENT_FOLDER)
+        .to_string()
+}
+
+pub(crate) fn import_at(
+    root: &Path,
+    kind: ImportKind,
+    source: &Path,
+    dest_folder: &str,
+    attachment_folder_setting: Option<&str>,
+) -> Result<ImportReport, String> {
+    if !source.exists() {
+        return Err("import source does not exist".to_string());
+    }
+    let mut writer = ImportWriter::new(root, dest_folder);
+    match kind {
+        ImportKind::NotionZip => notion::import_notion_zip(&mut writer, source)?,
+        ImportKind::Enex => enex::import_enex(
+            &mut writer,
+            source,
+            &attachment_folder(attachment_folder_setting),
+        )?,
+        ImportKind::HtmlFolder => html_folder::import_html_folder(&mut writer, source)?,
+    }
+    Ok(writer.report)
+}
+
+#[tauri::command]
+pub fn import_notes(args: ImportArgs, app: AppHandle) -> Result<ImportReport, String> {
+    log::info!(
+        "Importing notes vault_id={} kind={:?} source_path={}",
+        args.vault_id,
+        args.kind,
+        args.source_path
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    let report = import_at(
+        &root,
+        args.kind,
+        &PathBuf::from(&args.source_path),
+        &args.dest_folder,
+        args.attachment_folder.as_deref(),
+    )?;
+    for path in report
+        .notes
+        .iter()
+        .map(|note| &note.path)
+        .chain(&report.attachments)
+    {
+        notes_service::invalidate_note_parent_folder_cache(&args.vault_id, path);
+    }
+    Ok(report)
+}
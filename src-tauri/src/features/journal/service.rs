@@ -0,0 +1,680 @@
+use crate::features::notes::service::{self as notes_service, atomic_write};
+use crate::features::search::service as search_service;
+use crate::shared::{constants, storage};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::AppHandle;
+use walkdir::WalkDir;
+
+const JOURNAL_DIR: &str = "journal";
+const JOURNAL_FILE: &str = "journal.json";
+const BLOBS_DIR: &str = "blobs";
+const MAX_UNDO_ENTRIES: usize = 50;
+/// Deletions of more than this many bytes are not snapshotted, so removing a
+/// large attachments folder neither doubles its disk use nor stalls.
+pub(crate) const MAX_REMOVAL_SNAPSHOT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// One reversible change. Content is referenced by the blake3 hash of a blob
+/// in the journal; `None` means the file does not exist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub(crate) enum JournalOp {
+    Move {
+        from: String,
+        to: String,
+    },
+    Write {
+        path: String,
+        before: Option<String>,
+        after: Option<String>,
+    },
+    Folder {
+        path: String,
+        before: bool,
+        after: bool,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct JournalEntry {
+    pub id: String,
+    pub kind: String,
+    pub created_at: i64,
+    pub ops: Vec<JournalOp>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Journal {
+    undo: Vec<JournalEntry>,
+    redo: Vec<JournalEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JournalEntrySummary {
+    pub id: String,
+    pub kind: String,
+    pub created_at: i64,
+    pub changes: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JournalSummary {
+    /// Most recent first.
+    pub undo: Vec<JournalEntrySummary>,
+    pub redo: Vec<JournalEntrySummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MovedPath {
+    pub from: String,
+    pub to: String,
+    pub is_folder: bool,
+}
+
+/// What an undo or redo changed on disk, so callers can update the index.
+#[derive(Debug, Clone, Serialize)]
+pub struct JournalChange {
+    pub id: String,
+    pub kind: String,
+    pub moved: Vec<MovedPath>,
+    pub written: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UndoOperationArgs {
+    pub vault_id: String,
+    /// Undoes this entry instead of the most recent one.
+    #[serde(default)]
+    pub entry_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathState {
+    Missing,
+    File(String),
+    Dir,
+}
+
+/// An op oriented in the direction it is being applied.
+enum Step<'a> {
+    Move {
+        from: &'a str,
+        to: &'a str,
+    },
+    Write {
+        path: &'a str,
+        expect: Option<&'a str>,
+        target: Option<&'a str>,
+    },
+    Folder {
+        path: &'a str,
+        exists: bool,
+    },
+}
+
+/// Absolute paths a step touches, checked against the vault root. `to` is set
+/// for moves only.
+struct StepPaths {
+    abs: PathBuf,
+    to: Option<PathBuf>,
+}
+
+static JOURNAL_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+fn journal_lock() -> &'static Mutex<()> {
+    JOURNAL_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn journal_dir(root: &Path) -> PathBuf {
+    root.join(constants::APP_DIR).join(JOURNAL_DIR)
+}
+
+fn blob_path(root: &Path, hash: &str) -> Result<PathBuf, String> {
+    if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("invalid journal blob".to_string());
+    }
+    Ok(journal_dir(root).join(BLOBS_DIR).join(hash))
+}
+
+fn load_journal(root: &Path) -> Result<Journal, String> {
+    match fs::read(journal_dir(root).join(JOURNAL_FILE)) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| e.to_string()),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Journal::default()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+fn entry_blobs<'a>(entries: impl Iterator<Item = &'a JournalEntry>) -> HashSet<&'a str> {
+    entries
+        .flat_map(|entry| &entry.ops)
+        .flat_map(|op| match op {
+            JournalOp::Write { before, after, .. } => vec![before.as_deref(), after.as_deref()],
+            _ => Vec::new(),
+        })
+        .flatten()
+        .collect()
+}
+
+/// Saves the journal and deletes the blobs only `dropped` entries used.
+fn save_journal(root: &Path, journal: &Journal, dropped: &[JournalEntry]) -> Result<(), String> {
+    let json = serde_json::to_string(journal).map_err(|e| e.to_string())?;
+    atomic_write(&journal_dir(root).join(JOURNAL_FILE), &json)?;
+
+    let kept = entry_blobs(journal.undo.iter().chain(&journal.redo));
+    for hash in entry_blobs(dropped.iter()) {
+        if !kept.contains(hash) {
+            let _ = fs::remove_file(blob_path(root, hash)?);
+        }
+    }
+    Ok(())
+}
+
+/// Stores `bytes` by content hash and returns the hash.
+pub(crate) fn store_blob(root: &Path, bytes: &[u8]) -> Result<String, String> {
+    let hash = blake3::hash(bytes).to_hex().to_string();
+    let path = blob_path(root, &hash)?;
+    if !path.exists() {
+        let dir = path.parent().ok_or("invalid journal path")?;
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let tmp = dir.join(format!("{}.tmp", hash));
+        fs::write(&tmp, bytes).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, &path).map_err(|e| e.to_string())?;
+    }
+    Ok(hash)
+}
+
+/// A `Write` op for a file whose content goes from `before` to `after`.
+pub(crate) fn write_op(
+    root: &Path,
+    path: &str,
+    before: Option<&[u8]>,
+    after: Option<&[u8]>,
+) -> Result<JournalOp, String> {
+    Ok(JournalOp::Write {
+        path: path.to_string(),
+        before: before.map(|bytes| store_blob(root, bytes)).transpose()?,
+        after: after.map(|bytes| store_blob(root, bytes)).transpose()?,
+    })
+}
+
+struct RemovalContents {
+    files: Vec<String>,
+    folders: Vec<String>,
+}
+
+/// The files under `rel` (or `rel` itself) and the folders at and below it.
+/// Stops early with `None` once the files' sizes add up to more than `limit`.
+fn removal_contents(root: &Path, rel: &str, limit: u64) -> Result<Option<RemovalContents>, String> {
+    let abs = root.join(rel);
+    let mut files = Vec::new();
+    let mut folders = Vec::new();
+    let mut total: u64 = 0;
+    for entry in WalkDir::new(&abs)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        let child = entry.path().strip_prefix(root).map_err(|e| e.to_string())?;
+        let child = storage::normalize_relative_path(child);
+        if entry.file_type().is_dir() {
+            folders.push(child);
+        } else if entry.file_type().is_file() {
+            total += entry.metadata().map_err(|e| e.to_string())?.len();
+            if total > limit {
+                return Ok(None);
+            }
+            files.push(child);
+        }
+    }
+    Ok(Some(RemovalContents { files, folders }))
+}
+
+/// Captures a file or folder about to be deleted: every file's content, then
+/// its folders deepest first. Returns `None`, storing nothing, when the files
+/// add up to more than [`MAX_REMOVAL_SNAPSHOT_BYTES`]; the delete then can't
+/// be undone.
+pub(crate) fn snapshot_removal(root: &Path, rel: &str) -> Result<Option<Vec<JournalOp>>, String> {
+    snapshot_removal_within(root, rel, MAX_REMOVAL_SNAPSHOT_BYTES)
+}
+
+/// [`snapshot_removal`] with an explicit byte limit.
+pub(crate) fn snapshot_removal_within(
+    root: &Path,
+    rel: &str,
+    limit: u64,
+) -> Result<Option<Vec<JournalOp>>, String> {
+    let Some(RemovalContents { files, mut folders }) = removal_contents(root, rel, limit)? else {
+        log::info!("Not journaling removal of {}: too large to snapshot", rel);
+        return Ok(None);
+    };
+    let mut ops = Vec::with_capacity(files.len() + folders.len());
+    for file in files {
+        let bytes = fs::read(root.join(&file)).map_err(|e| e.to_string())?;
+        ops.push(write_op(root, &file, Some(&bytes), None)?);
+    }
+    folders.sort_by_key(|folder| std::cmp::Reverse(folder.matches('/').count()));
+    ops.extend(folders.into_iter().map(|path| JournalOp::Folder {
+        path,
+        before: true,
+        after: false,
+    }));
+    Ok(Some(ops))
+}
+
+/// Adds an entry to the undo stack and clears the redo stack. Returns the
+/// entry id, or `None` when there was nothing to record.
+pub(crate) fn record_operation(
+    root: &Path,
+    kind: &str,
+    ops: Vec<JournalOp>,
+) -> Result<Option<String>, String> {
+    if ops.is_empty() {
+        return Ok(None);
+    }
+    let _guard = journal_lock().lock().map_err(|e| e.to_string())?;
+    let mut journal = load_journal(root)?;
+    push_entry(root, &mut journal, kind, ops).map(Some)
+}
+
+/// Records `ops` as part of the latest entry when that entry moved something
+/// to one of `moved_to` (or a folder above it), so undoing the move also
+/// undoes the follow-up edits. Otherwise records a new entry of `kind`.
+pub(crate) fn record_after_move(
+    root: &Path,
+    kind: &str,
+    moved_to: &[&str],
+    ops: Vec<JournalOp>,
+) -> Result<Option<String>, String> {
+    if ops.is_empty() {
+        return Ok(None);
+    }
+    let _guard = journal_lock().lock().map_err(|e| e.to_string())?;
+    let mut journal = load_journal(root)?;
+    let moved_here = |op: &JournalOp| match op {
+        JournalOp::Move { to, .. } => moved_to
+            .iter()
+            .any(|path| path == to || path.starts_with(&format!("{}/", to))),
+        _ => false,
+    };
+    if let Some(entry) = journal
+        .undo
+        .last_mut()
+        .filter(|entry| entry.ops.iter().any(moved_here))
+    {
+        entry.ops.extend(ops);
+        let id = entry.id.clone();
+        save_journal(root, &journal, &[])?;
+        return Ok(Some(id));
+    }
+    push_entry(root, &mut journal, kind, ops).map(Some)
+}
+
+fn push_entry(
+    root: &Path,
+    journal: &mut Journal,
+    kind: &str,
+    ops: Vec<JournalOp>,
+) -> Result<String, String> {
+    let created_at = storage::now_ms();
+    let mut id = created_at.to_string();
+    let mut counter = 2;
+    while journal.undo.iter().chain(&journal.redo).any(|e| e.id == id) {
+        id = format!("{}-{}", created_at, counter);
+        counter += 1;
+    }
+    journal.undo.push(JournalEntry {
+        id: id.clone(),
+        kind: kind.to_string(),
+        created_at,
+        ops,
+    });
+    let mut dropped: Vec<JournalEntry> = journal.redo.drain(..).collect();
+    if journal.undo.len() > MAX_UNDO_ENTRIES {
+        let excess = journal.undo.len() - MAX_UNDO_ENTRIES;
+        dropped.extend(journal.undo.drain(..excess));
+    }
+    save_journal(root, journal, &dropped)?;
+    Ok(id)
+}
+
+/// Logs instead of failing: a mutation that already happened should not
+/// report an error because its undo record could not be saved.
+pub(crate) fn record_operation_or_warn(root: &Path, kind: &str, ops: Vec<JournalOp>) {
+    if let Err(error) = record_operation(root, kind, ops) {
+        log::warn!("Failed to record {} in journal: {}", kind, error);
+    }
+}
+
+fn disk_state(abs: &Path) -> Result<PathState, String> {
+    let Ok(meta) = fs::symlink_metadata(abs) else {
+        return Ok(PathState::Missing);
+    };
+    if meta.is_dir() {
+        return Ok(PathState::Dir);
+    }
+    let bytes = fs::read(abs).map_err(|e| e.to_string())?;
+    Ok(PathState::File(blake3::hash(&bytes).to_hex().to_string()))
+}
+
+fn orient(op: &JournalOp, undo: bool) -> Step<'_> {
+    match op {
+        JournalOp::Move { from, to } if undo => Step::Move { from: to, to: from },
+        JournalOp::Move { from, to } => Step::Move { from, to },
+        JournalOp::Write {
+            path,
+            before,
+            after,
+        } => {
+            let (expect, target) = if undo {
+                (after, before)
+            } else {
+                (before, after)
+            };
+            Step::Write {
+                path,
+                expect: expect.as_deref(),
+                target: target.as_deref(),
+            }
+        }
+        JournalOp::Folder {
+            path,
+            before,
+            after,
+        } => Step::Folder {
+            path,
+            exists: if undo { *before } else { *after },
+        },
+    }
+}
+
+fn steps(entry: &JournalEntry, undo: bool) -> Vec<Step<'_>> {
+    if undo {
+        entry.ops.iter().rev().map(|op| orient(op, true)).collect()
+    } else {
+        entry.ops.iter().map(|op| orient(op, false)).collect()
+    }
+}
+
+/// Resolves a step's paths inside the vault. A journal file edited to point
+/// outside the vault, or through a symlink, is refused here.
+fn resolve_step(root: &Path, step: &Step<'_>) -> Result<StepPaths, String> {
+    Ok(match *step {
+        Step::Move { from, to } => StepPaths {
+            abs: notes_service::safe_vault_abs(root, from)?,
+            to: Some(notes_service::safe_vault_abs_for_write(root, to)?),
+        },
+        Step::Write { path, .. } | Step::Folder { path, .. } => StepPaths {
+            abs: notes_service::safe_vault_abs_for_write(root, path)?,
+            to: None,
+        },
+    })
+}
+
+/// Resolves every step's paths, then walks the steps against the current
+/// disk state, tracking the effect of earlier steps, and refuses when a path
+/// no longer looks the way the entry left it.
+fn verify_steps(root: &Path, steps: &[Step<'_>]) -> Result<Vec<StepPaths>, String> {
+    let paths = steps
+        .iter()
+        .map(|step| resolve_step(root, step))
+        .collect::<Result<Vec<_>, String>>()?;
+    let mut overlay: HashMap<&str, PathState> = HashMap::new();
+    let state = |path: &str, abs: &Path, overlay: &HashMap<&str, PathState>| match overlay.get(path)
+    {
+        Some(state) => Ok(state.clone()),
+        None => disk_state(abs),
+    };
+    for (step, resolved) in steps.iter().zip(&paths) {
+        match step {
+            Step::Move { from, to } => {
+                let to_abs = resolved.to.as_deref().ok_or("move is missing its target")?;
+                let source = state(from, &resolved.abs, &overlay)?;
+                if source == PathState::Missing {
+                    return Err(format!("conflict:missing:{}", from));
+                }
+                if state(to, to_abs, &overlay)? != PathState::Missing {
+                    return Err(format!("conflict:exists:{}", to));
+                }
+                overlay.insert(from, PathState::Missing);
+                overlay.insert(to, source);
+            }
+            Step::Write {
+                path,
+                expect,
+                target,
+            } => {
+                let expected = match expect {
+                    Some(hash) => PathState::File(hash.to_string()),
+                    None => PathState::Missing,
+                };
+                if state(path, &resolved.abs, &overlay)? != expected {
+                    return Err(format!("conflict:changed:{}", path));
+                }
+                overlay.insert(
+                    path,
+                    match target {
+                        Some(hash) => PathState::File(hash.to_string()),
+                        None => PathState::Missing,
+                    },
+                );
+            }
+            Step::Folder { .. } => {}
+        }
+    }
+    Ok(paths)
+}
+
+/// Applies verified steps, touching only the paths `verify_steps` resolved.
+fn apply_steps(
+    root: &Path,
+    steps: &[Step<'_>],
+    paths: &[StepPaths],
+    change: &mut JournalChange,
+) -> Result<(), String> {
+    for (step, resolved) in steps.iter().zip(paths) {
+        let abs = &resolved.abs;
+        match *step {
+            Step::Move { from, to } => {
+                let from_abs = abs;
+                let to_abs = resolved.to.as_ref().ok_or("move is missing its target")?;
+                let is_folder = from_abs.is_dir();
+                if let Some(parent) = to_abs.parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                notes_service::rename_with_temp_path(from_abs, to_abs)?;
+                change.moved.push(MovedPath {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    is_folder,
+                });
+            }
+            Step::Write { path, target, .. } => match target {
+                Some(hash) => {
+                    let bytes = fs::read(blob_path(root, hash)?)
+                        .map_err(|_| format!("journal content missing for {}", path))?;
+                    if let Some(parent) = abs.parent() {
+                        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                    }
+                    let tmp = abs.with_extension("journal.tmp");
+                    fs::write(&tmp, bytes).map_err(|e| e.to_string())?;
+                    fs::rename(&tmp, abs).map_err(|e| e.to_string())?;
+                    change.written.push(path.to_string());
+                }
+                None => {
+                    fs::remove_file(abs).map_err(|e| e.to_string())?;
+                    change.removed.push(path.to_string());
+                }
+            },
+            Step::Folder { exists, .. } => {
+                if exists {
+                    fs::create_dir_all(abs).map_err(|e| e.to_string())?;
+                } else {
+                    // Leaves folders that gained new content in place.
+                    let _ = fs::remove_dir(abs);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn replay_entry(root: &Path, entry: &JournalEntry, undo: bool) -> Result<JournalChange, String> {
+    let steps = steps(entry, undo);
+    let paths = verify_steps(root, &steps)?;
+    let mut change = JournalChange {
+        id: entry.id.clone(),
+        kind: entry.kind.clone(),
+        moved: Vec::new(),
+        written: Vec::new(),
+        removed: Vec::new(),
+    };
+    apply_steps(root, &steps, &paths, &mut change)?;
+    Ok(change)
+}
+
+/// Reverts the most recent entry, or `entry_id` when given, and moves it to
+/// the redo stack.
+pub(crate) fn undo_at(root: &Path, entry_id: Option<&str>) -> Result<JournalChange, String> {
+    let _guard = journal_lock().lock().map_err(|e| e.to_string())?;
+    let mut journal = load_journal(root)?;
+    let idx = match entry_id {
+        Some(id) => journal.undo.iter().position(|entry| entry.id == id),
+        None => journal.undo.len().checked_sub(1),
+    }
+    .ok_or_else(|| "nothing to undo".to_string())?;
+    let change = replay_entry(root, &journal.undo[idx], true)?;
+    let entry = journal.undo.remove(idx);
+    journal.redo.push(entry);
+    save_journal(root, &journal, &[])?;
+    Ok(change)
+}
+
+/// Re-applies the most recently undone entry.
+pub(crate) fn redo_at(root: &Path) -> Result<JournalChange, String> {
+    let _guard = journal_lock().lock().map_err(|e| e.to_string())?;
+    let mut journal = load_journal(root)?;
+    let entry = journal
+        .redo
+        .last()
+        .ok_or_else(|| "nothing to redo".to_string())?;
+    let change = replay_entry(root, entry, false)?;
+    if let Some(entry) = journal.redo.pop() {
+        journal.undo.push(entry);
+    }
+    save_journal(root, &journal, &[])?;
+    Ok(change)
+}
+
+fn summarize(entries: &[JournalEntry]) -> Vec<JournalEntrySummary> {
+    entries
+        .iter()
+        .rev()
+        .map(|entry| JournalEntrySummary {
+            id: entry.id.clone(),
+            kind: entry.kind.clone(),
+            created_at: entry.created_at,
+            changes: entry.ops.len(),
+        })
+        .collect()
+}
+
+pub(crate) fn journal_summary_at(root: &Path) -> Result<JournalSummary, String> {
+    let journal = load_journal(root)?;
+    Ok(JournalSummary {
+        undo: summarize(&journal.undo),
+        redo: summarize(&journal.redo),
+    })
+}
+
+fn is_note_path(path: &str) -> bool {
+    path.to_ascii_lowercase().ends_with(".md")
+}
+
+/// Brings the search index and folder listings in line with an undo or redo.
+pub(crate) fn sync_after_change(app: &AppHandle, vault_id: &str, change: &JournalChange) {
+    let mut result = Ok(());
+    for moved in &change.moved {
+        notes_service::invalidate_note_parent_folder_cache(vault_id, &moved.from);
+        notes_service::invalidate_note_parent_folder_cache(vault_id, &moved.to);
+        if moved.is_folder {
+            result = result.and(
+                search_service::index_rename_folder(
+                    app.clone(),
+                    vault_id.to_string(),
+                    format!("{}/", moved.from),
+                    format!("{}/", moved.to),
+                )
+                .map(|_| ()),
+            );
+        } else if is_note_path(&moved.from) {
+            result = result.and(search_service::index_rename_note(
+                app.clone(),
+                vault_id.to_string(),
+                moved.from.clone(),
+                moved.to.clone(),
+            ));
+        }
+    }
+    for path in &change.written {
+        notes_service::invalidate_note_parent_folder_cache(vault_id, path);
+        if is_note_path(path) {
+            result = result.and(search_service::index_upsert_note(
+                app.clone(),
+                vault_id.to_string(),
+                path.clone(),
+            ));
+        }
+    }
+    let removed: Vec<String> = change
+        .removed
+        .iter()
+        .inspect(|path| notes_service::invalidate_note_parent_folder_cache(vault_id, path))
+        .filter(|path| is_note_path(path))
+        .cloned()
+        .collect();
+    if !removed.is_empty() {
+        result = result.and(search_service::index_remove_notes(
+            app.clone(),
+            vault_id.to_string(),
+            removed,
+        ));
+    }
+    if let Err(error) = result {
+        log::warn!("Failed to update index after {}: {}", change.kind, error);
+    }
+}
+
+#[tauri::command]
+pub fn undo_last_operation(
+    args: UndoOperationArgs,
+    app: AppHandle,
+) -> Result<JournalChange, String> {
+    log::info!(
+        "Undoing operation vault_id={} entry_id={:?}",
+        args.vault_id,
+        args.entry_id
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    let change = undo_at(&root, args.entry_id.as_deref())?;
+    sync_after_change(&app, &args.vault_id, &change);
+    Ok(change)
+}
+
+#[tauri::command]
+pub fn redo_last_operation(app: AppHandle, vault_id: String) -> Result<JournalChange, String> {
+    log::info!("Redoing operation vault_id={}", vault_id);
+    let root = storage::vault_path(&app, &vault_id)?;
+    let change = redo_at(&root)?;
+    sync_after_change(&app, &vault_id, &change);
+    Ok(change)
+}
+
+#[tauri::command]
+pub fn journal_list(app: AppHandle, vault_id: String) -> Result<JournalSummary, String> {
+    let root = storage::vault_path(&app, &vault_id)?;
+    journal_summary_at(&root)
+}
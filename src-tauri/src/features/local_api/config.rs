@@ -0,0 +1,178 @@
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::storage;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const CONFIG_FILE: &str = "local_api.json";
+const TOKEN_PREFIX: &str = "otk_";
+pub(crate) const DEFAULT_PORT: u16 = 27183;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiAccess {
+    Read,
+    Write,
+}
+
+/// A stored token. Only the blake3 hash of the secret is kept on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub vault_id: String,
+    pub access: ApiAccess,
+    /// Folders the token may touch; empty means the whole vault.
+    #[serde(default)]
+    pub folders: Vec<String>,
+    pub token_hash: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiTokenInfo {
+    pub id: String,
+    pub name: String,
+    pub vault_id: String,
+    pub access: ApiAccess,
+    pub folders: Vec<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalApiConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub tokens: Vec<ApiToken>,
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+impl Default for LocalApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            tokens: Vec::new(),
+        }
+    }
+}
+
+impl ApiToken {
+    pub fn info(&self) -> ApiTokenInfo {
+        ApiTokenInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            vault_id: self.vault_id.clone(),
+            access: self.access,
+            folders: self.folders.clone(),
+            created_at: self.created_at,
+        }
+    }
+
+    pub fn can_write(&self) -> bool {
+        self.access == ApiAccess::Write
+    }
+
+    /// Whether `path` (vault-relative) lies inside the token's folder scope.
+    pub fn allows_path(&self, path: &str) -> bool {
+        storage::path_in_folders(&self.folders, path)
+    }
+}
+
+pub(crate) fn hash_token(token: &str) -> String {
+    blake3::hash(token.as_bytes()).to_hex().to_string()
+}
+
+fn random_hex(bytes: usize) -> AppResult<String> {
+    let mut buf = vec![0u8; bytes];
+    getrandom::getrandom(&mut buf).map_err(|e| e.to_string())?;
+    Ok(buf.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// `local_api.json` in a config directory, next to the vault registry.
+#[derive(Debug, Clone)]
+pub struct LocalApiStore {
+    dir: PathBuf,
+}
+
+impl LocalApiStore {
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.join(CONFIG_FILE)
+    }
+
+    pub fn load(&self) -> AppResult<LocalApiConfig> {
+        match std::fs::read(self.path()) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes).map_err(|e| e.to_string())?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(LocalApiConfig::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, config: &LocalApiConfig) -> AppResult<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.path();
+        let tmp = path.with_extension("json.tmp");
+        let bytes = serde_json::to_vec_pretty(config).map_err(|e| e.to_string())?;
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Adds a token and returns its secret, which is not stored and cannot be
+    /// shown again.
+    pub fn create_token(
+        &self,
+        name: &str,
+        vault_id: &str,
+        access: ApiAccess,
+        folders: Vec<String>,
+    ) -> AppResult<(String, ApiToken)> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::invalid_input("token name is empty"));
+        }
+        let secret = format!("{}{}", TOKEN_PREFIX, random_hex(32)?);
+        let token = ApiToken {
+            id: random_hex(8)?,
+            name: name.to_string(),
+            vault_id: vault_id.to_string(),
+            access,
+            folders: storage::normalize_folders(folders),
+            token_hash: hash_token(&secret),
+            created_at: storage::now_ms(),
+        };
+        let mut config = self.load()?;
+        config.tokens.push(token.clone());
+        self.save(&config)?;
+        Ok((secret, token))
+    }
+
+    pub fn revoke_token(&self, token_id: &str) -> AppResult<()> {
+        let mut config = self.load()?;
+        let before = config.tokens.len();
+        config.tokens.retain(|token| token.id != token_id);
+        if config.tokens.len() == before {
+            return Err(AppError::not_found("token", None));
+        }
+        self.save(&config)
+    }
+
+    /// The token matching a presented secret, if any.
+    pub fn authenticate(&self, secret: &str) -> AppResult<Option<ApiToken>> {
+        let hash = hash_token(secret.trim());
+        Ok(self
+            .load()?
+            .tokens
+            .into_iter()
+            .find(|token| token.token_hash == hash))
+    }
+}
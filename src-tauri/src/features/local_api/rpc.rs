@@ -0,0 +1,315 @@
+use crate::features::local_api::config::ApiToken;
+use crate::features::notes::service::{self as notes_service, NoteMeta};
+use crate::features::periodic_notes::service::{
+    open_periodic_note_at, parse_date, periodic_note_path_at, Period,
+};
+use crate::features::search::db as search_db;
+use crate::features::search::model::{SearchHit, SearchScope};
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::vault_context::VaultContext;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const APP_ERROR: i64 = -32000;
+const FORBIDDEN: i64 = -32003;
+
+const MAX_SEARCH_LIMIT: usize = 200;
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(FORBIDDEN, message)
+    }
+}
+
+impl From<AppError> for RpcError {
+    fn from(error: AppError) -> Self {
+        Self {
+            code: APP_ERROR,
+            message: error.to_string(),
+            data: serde_json::to_value(&error).ok(),
+        }
+    }
+}
+
+/// The reply to one call plus the notes it wrote, so the caller can refresh
+/// the index and folder caches.
+#[derive(Debug)]
+pub struct RpcOutcome {
+    pub response: Value,
+    pub written: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ListParams {
+    #[serde(default)]
+    prefix: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PathParams {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct CreateParams {
+    path: String,
+    #[serde(default)]
+    markdown: String,
+}
+
+#[derive(Deserialize)]
+struct AppendParams {
+    path: String,
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct DailyAppendParams {
+    text: String,
+    #[serde(default)]
+    date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    query: String,
+    #[serde(default)]
+    scope: Option<SearchScope>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct DailyAppendResult {
+    note: NoteMeta,
+    created: bool,
+}
+
+fn params<T: DeserializeOwned>(value: Value) -> Result<T, RpcError> {
+    let value = if value.is_null() { json!({}) } else { value };
+    serde_json::from_value(value).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn note_path(path: &str) -> String {
+    let path = path.trim().trim_matches('/');
+    if path.to_ascii_lowercase().ends_with(".md") {
+        path.to_string()
+    } else {
+        format!("{}.md", path)
+    }
+}
+
+fn require_write(token: &ApiToken) -> Result<(), RpcError> {
+    if token.can_write() {
+        Ok(())
+    } else {
+        Err(RpcError::forbidden("token is read-only"))
+    }
+}
+
+fn require_scope(token: &ApiToken, path: &str) -> Result<(), RpcError> {
+    if token.allows_path(path) {
+        Ok(())
+    } else {
+        Err(RpcError::forbidden(format!(
+            "path is outside the token's folders: {}",
+            path
+        )))
+    }
+}
+
+/// Appends `text` as its own block, keeping a single trailing newline.
+fn append_block(existing: &str, text: &str) -> String {
+    let mut out = existing.to_string();
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(text.trim_end_matches('\n'));
+    out.push('\n');
+    out
+}
+
+fn append_to_note(ctx: &VaultContext, path: &str, text: &str) -> AppResult<NoteMeta> {
+    let abs = ctx.abs_for_write(path)?;
+    let existing = std::fs::read_to_string(&abs).map_err(|e| AppError::io(e, path))?;
+    notes_service::atomic_write(&abs, &append_block(&existing, text))?;
+    notes_service::build_note_meta(ctx.root(), path)
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(APP_ERROR, e.to_string()))
+}
+
+fn call(
+    ctx: &VaultContext,
+    token: &ApiToken,
+    method: &str,
+    raw: Value,
+    written: &mut Vec<String>,
+) -> Result<Value, RpcError> {
+    match method {
+        "notes.list" => {
+            let p: ListParams = params(raw)?;
+            let prefix = p
+                .prefix
+                .map(|prefix| prefix.trim().trim_matches('/').to_string())
+                .filter(|prefix| !prefix.is_empty())
+                .map(|prefix| format!("{}/", prefix));
+            let notes: Vec<NoteMeta> = notes_service::list_notes_in(ctx)?
+                .into_iter()
+                .filter(|note| token.allows_path(&note.path))
+                .filter(|note| {
+                    prefix
+                        .as_deref()
+                        .is_none_or(|prefix| note.path.starts_with(prefix))
+                })
+                .collect();
+            to_value(notes)
+        }
+        "notes.read" => {
+            let p: PathParams = params(raw)?;
+            let path = note_path(&p.path);
+            require_scope(token, &path)?;
+            to_value(notes_service::read_note_in(ctx, &path)?)
+        }
+        "notes.create" => {
+            let p: CreateParams = params(raw)?;
+            let path = note_path(&p.path);
+            require_write(token)?;
+            require_scope(token, &path)?;
+            let meta = notes_service::create_note_file(ctx.root(), &path, &p.markdown)?;
+            written.push(meta.path.clone());
+            to_value(meta)
+        }
+        "notes.append" => {
+            let p: AppendParams = params(raw)?;
+            let path = note_path(&p.path);
+            require_write(token)?;
+            require_scope(token, &path)?;
+            let meta = append_to_note(ctx, &path, &p.text)?;
+            written.push(meta.path.clone());
+            to_value(meta)
+        }
+        "daily.append" => {
+            let p: DailyAppendParams = params(raw)?;
+            require_write(token)?;
+            let date = match p.date.as_deref() {
+                Some(date) => parse_date(date).map_err(|e| RpcError::new(INVALID_PARAMS, e))?,
+                None => chrono::Local::now().date_naive(),
+            };
+            require_scope(
+                token,
+                &periodic_note_path_at(ctx.root(), Period::Daily, date),
+            )?;
+            let daily =
+                open_periodic_note_at(ctx.root(), Period::Daily, date).map_err(AppError::from)?;
+            let note = append_to_note(ctx, &daily.note.path, &p.text)?;
+            written.push(note.path.clone());
+            to_value(DailyAppendResult {
+                note,
+                created: daily.created,
+            })
+        }
+        "search.query" => {
+            let p: SearchParams = params(raw)?;
+            let limit = p.limit.unwrap_or(20).clamp(1, MAX_SEARCH_LIMIT);
+            let conn = ctx.open_search_db().map_err(AppError::from_index)?;
+            // Fetch a full page before scoping so folder-limited tokens still
+            // get up to `limit` hits.
+            let hits: Vec<SearchHit> = search_db::search(
+                &conn,
+                &p.query,
+                p.scope.unwrap_or(SearchScope::All),
+                MAX_SEARCH_LIMIT,
+            )
+            .map_err(AppError::from_index)?
+            .into_iter()
+            .filter(|hit| token.allows_path(&hit.note.path))
+            .take(limit)
+            .collect();
+            to_value(hits)
+        }
+        other => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method: {}", other),
+        )),
+    }
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => {
+            let mut body = json!({ "code": error.code, "message": error.message });
+            if let Some(data) = error.data {
+                body["data"] = data;
+            }
+            json!({ "jsonrpc": "2.0", "id": id, "error": body })
+        }
+    }
+}
+
+/// Handles one JSON-RPC 2.0 request body for an authenticated token.
+pub fn handle_rpc(ctx: &VaultContext, token: &ApiToken, body: &[u8]) -> RpcOutcome {
+    let mut written = Vec::new();
+    let value: Value = match serde_json::from_slice(body) {
+        Ok(value) => value,
+        Err(e) => {
+            return RpcOutcome {
+                response: response(Value::Null, Err(RpcError::new(PARSE_ERROR, e.to_string()))),
+                written,
+            }
+        }
+    };
+    let request: RpcRequest = match serde_json::from_value(value) {
+        Ok(request) => request,
+        Err(e) => {
+            return RpcOutcome {
+                response: response(
+                    Value::Null,
+                    Err(RpcError::new(INVALID_REQUEST, e.to_string())),
+                ),
+                written,
+            }
+        }
+    };
+    log::debug!(
+        "Local API call token={} method={}",
+        token.id,
+        request.method
+    );
+    let result = call(ctx, token, &request.method, request.params, &mut written);
+    RpcOutcome {
+        response: response(request.id, result),
+        written,
+    }
+}
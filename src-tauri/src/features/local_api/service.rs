@@ -0,0 +1,312 @@
+use crate::features::local_api::config::{ApiAccess, ApiTokenInfo, LocalApiStore};
+use crate::features::local_api::rpc;
+use crate::features::notes::service as notes_service;
+use crate::features::search::service as search_service;
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::vault_context::VaultRegistry;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use tauri::{AppHandle, Manager, State};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// Called with the vault id and the notes an API call wrote.
+pub type WriteHook = Arc<dyn Fn(&str, &[String]) + Send + Sync>;
+
+/// A running localhost server. Dropping it does not stop the thread; call
+/// [`LocalApiServer::stop`].
+pub struct LocalApiServer {
+    server: Arc<Server>,
+    port: u16,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl LocalApiServer {
+    /// Binds `127.0.0.1:port` (0 picks a free port) and serves requests on a
+    /// background thread, reading tokens and vaults from `config_dir`.
+    pub fn start(config_dir: PathBuf, port: u16, on_write: WriteHook) -> AppResult<Self> {
+        let server = Server::http(("127.0.0.1", port)).map_err(|e| {
+            AppError::invalid_input(format!("failed to bind 127.0.0.1:{}: {}", port, e))
+        })?;
+        let port = server
+            .server_addr()
+            .to_ip()
+            .map(|addr| addr.port())
+            .unwrap_or(port);
+        let server = Arc::new(server);
+        let worker = Arc::clone(&server);
+        let thread = std::thread::spawn(move || {
+            let store = LocalApiStore::at(&config_dir);
+            let registry = VaultRegistry::at(&config_dir);
+            for request in worker.incoming_requests() {
+                handle_request(&store, &registry, port, request, &on_write);
+            }
+        });
+        log::info!("Local API listening on 127.0.0.1:{}", port);
+        Ok(Self {
+            server,
+            port,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn stop(mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        log::info!("Local API stopped");
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
+
+fn header_value<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+fn respond(request: Request, status: u16, body: Option<serde_json::Value>) {
+    let body = body.map(|b| b.to_string()).unwrap_or_default();
+    let response = Response::from_data(body)
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+        .with_header(header("Access-Control-Allow-Origin", "*"))
+        .with_header(header(
+            "Access-Control-Allow-Headers",
+            "Authorization, Content-Type",
+        ))
+        .with_header(header("Access-Control-Allow-Methods", "GET, POST, OPTIONS"));
+    if let Err(error) = request.respond(response) {
+        log::warn!("Local API failed to respond: {}", error);
+    }
+}
+
+fn respond_error(request: Request, status: u16, error: &AppError) {
+    respond(request, status, Some(serde_json::json!({ "error": error })));
+}
+
+/// Rejects requests addressed to another host name, which is how DNS
+/// rebinding pages would reach the loopback port.
+fn is_local_host(request: &Request, port: u16) -> bool {
+    match header_value(request, "Host") {
+        Some(host) => {
+            host == format!("127.0.0.1:{}", port) || host == format!("localhost:{}", port)
+        }
+        None => false,
+    }
+}
+
+fn bearer_token(request: &Request) -> Option<&str> {
+    header_value(request, "Authorization")?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+fn handle_request(
+    store: &LocalApiStore,
+    registry: &VaultRegistry,
+    port: u16,
+    mut request: Request,
+    on_write: &WriteHook,
+) {
+    if !is_local_host(&request, port) {
+        return respond_error(request, 403, &AppError::invalid_input("invalid host"));
+    }
+    if *request.method() == Method::Options {
+        return respond(request, 204, None);
+    }
+    let path = request.url().split('?').next().unwrap_or("").to_string();
+    match (request.method(), path.as_str()) {
+        (Method::Get, "/health") => respond(request, 200, Some(serde_json::json!({ "ok": true }))),
+        (Method::Post, "/rpc") => {
+            let token = match bearer_token(&request).map(|secret| store.authenticate(secret)) {
+                Some(Ok(Some(token))) => token,
+                Some(Err(error)) => return respond_error(request, 500, &error),
+                _ => return respond_error(request, 401, &AppError::invalid_input("unauthorized")),
+            };
+            let ctx = match registry.context(&token.vault_id) {
+                Ok(ctx) => ctx,
+                Err(error) => return respond_error(request, 404, &error),
+            };
+            let mut body = Vec::new();
+            if let Err(error) = request
+                .as_reader()
+                .take(MAX_BODY_BYTES + 1)
+                .read_to_end(&mut body)
+            {
+                return respond_error(request, 400, &error.into());
+            }
+            if body.len() as u64 > MAX_BODY_BYTES {
+                return respond_error(
+                    request,
+                    413,
+                    &AppError::invalid_input("request body too large"),
+                );
+            }
+            let outcome = rpc::handle_rpc(&ctx, &token, &body);
+            if !outcome.written.is_empty() {
+                on_write(ctx.vault_id(), &outcome.written);
+            }
+            respond(request, 200, Some(outcome.response));
+        }
+        _ => respond_error(request, 404, &AppError::not_found("route", Some(&path))),
+    }
+}
+
+#[derive(Default)]
+pub struct LocalApiState {
+    inner: Mutex<Option<LocalApiServer>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LocalApiStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    pub tokens: Vec<ApiTokenInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiTokenCreateArgs {
+    pub name: String,
+    pub vault_id: String,
+    pub access: ApiAccess,
+    #[serde(default)]
+    pub folders: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedApiToken {
+    /// The secret; shown once and never stored.
+    pub token: String,
+    pub info: ApiTokenInfo,
+}
+
+fn config_dir(app: &AppHandle) -> AppResult<PathBuf> {
+    Ok(VaultRegistry::for_app(app)?.dir().to_path_buf())
+}
+
+/// Keeps the app's index and folder caches in step with notes the API wrote.
+fn app_write_hook(app: AppHandle) -> WriteHook {
+    Arc::new(move |vault_id, paths| {
+        for path in paths {
+            notes_service::invalidate_note_parent_folder_cache(vault_id, path);
+            if let Err(error) =
+                search_service::index_upsert_note(app.clone(), vault_id.to_string(), path.clone())
+            {
+                log::warn!("Local API failed to index {}: {}", path, error);
+            }
+        }
+    })
+}
+
+fn status(store: &LocalApiStore, running: Option<&LocalApiServer>) -> AppResult<LocalApiStatus> {
+    let config = store.load()?;
+    Ok(LocalApiStatus {
+        enabled: config.enabled,
+        running: running.is_some(),
+        port: running.map(LocalApiServer::port).unwrap_or(config.port),
+        tokens: config.tokens.iter().map(|token| token.info()).collect(),
+    })
+}
+
+/// Starts the server at launch when it was left enabled.
+pub fn start_if_enabled(app: &AppHandle) {
+    let result = config_dir(app).and_then(|dir| {
+        let config = LocalApiStore::at(&dir).load()?;
+        if !config.enabled {
+            return Ok(());
+        }
+        let server = LocalApiServer::start(dir, config.port, app_write_hook(app.clone()))?;
+        let state = app.state::<LocalApiState>();
+        let mut slot = state.inner.lock().map_err(|e| e.to_string())?;
+        *slot = Some(server);
+        Ok(())
+    });
+    if let Err(error) = result {
+        log::error!("Failed to start local API: {}", error);
+    }
+}
+
+#[tauri::command]
+pub fn local_api_status(
+    app: AppHandle,
+    state: State<'_, LocalApiState>,
+) -> AppResult<LocalApiStatus> {
+    let store = LocalApiStore::at(config_dir(&app)?);
+    let slot = state.inner.lock().map_err(|e| e.to_string())?;
+    status(&store, slot.as_ref())
+}
+
+#[tauri::command]
+pub fn local_api_set_enabled(
+    app: AppHandle,
+    state: State<'_, LocalApiState>,
+    enabled: bool,
+    port: Option<u16>,
+) -> AppResult<LocalApiStatus> {
+    log::info!("Setting local API enabled={} port={:?}", enabled, port);
+    let dir = config_dir(&app)?;
+    let store = LocalApiStore::at(&dir);
+    let mut config = store.load()?;
+    if let Some(port) = port {
+        config.port = port;
+    }
+
+    let mut slot = state.inner.lock().map_err(|e| e.to_string())?;
+    if let Some(server) = slot.take() {
+        server.stop();
+    }
+    if enabled {
+        *slot = Some(LocalApiServer::start(
+            dir,
+            config.port,
+            app_write_hook(app.clone()),
+        )?);
+    }
+    config.enabled = enabled;
+    store.save(&config)?;
+    status(&store, slot.as_ref())
+}
+
+#[tauri::command]
+pub fn local_api_create_token(
+    app: AppHandle,
+    args: ApiTokenCreateArgs,
+) -> AppResult<CreatedApiToken> {
+    log::info!(
+        "Creating local API token name={} vault_id={} access={:?}",
+        args.name,
+        args.vault_id,
+        args.access
+    );
+    let registry = VaultRegistry::for_app(&app)?;
+    registry.vault_path(&args.vault_id)?;
+    let store = LocalApiStore::at(registry.dir());
+    let (token, stored) =
+        store.create_token(&args.name, &args.vault_id, args.access, args.folders)?;
+    Ok(CreatedApiToken {
+        token,
+        info: stored.info(),
+    })
+}
+
+#[tauri::command]
+pub fn local_api_revoke_token(app: AppHandle, token_id: String) -> AppResult<()> {
+    log::info!("Revoking local API token id={}", token_id);
+    LocalApiStore::at(config_dir(&app)?).revoke_token(&token_id)
+}
@@ -1,7 +1,20 @@
+pub mod archive;
+pub mod deep_link;
+pub mod export;
 pub mod git;
+pub mod import;
+pub mod journal;
+pub mod local_api;
 pub mod notes;
+pub mod periodic_notes;
+pub mod plugins;
+pub mod publish;
+pub mod refactor;
+pub mod replace;
 pub mod search;
 pub mod settings;
+pub mod tasks;
+pub mod templates;
 pub mod vault;
 pub mod vault_session;
 pub mod vault_settings;
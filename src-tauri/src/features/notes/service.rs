@@ -1,5 +1,9 @@
-use crate::shared::constants;
+use crate::features::journal::service::{self as journal, JournalOp};
+use crate::features::plugins::host as plugins;
+use crate::shared::error::{AppError, AppResult, ConflictReason};
 use crate::shared::storage;
+use crate::shared::vault_context::VaultContext;
+use crate::shared::vault_ignore::VaultIgnore;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs::{File, OpenOptions};
@@ -35,10 +39,13 @@ pub struct NoteWriteArgs {
     pub expected_mtime_ms: Option<i64>,
 }
 
-fn parse_safe_relative_path(path: &str) -> Result<PathBuf, String> {
+fn parse_safe_relative_path(path: &str) -> AppResult<PathBuf> {
     let rel = PathBuf::from(path);
     if rel.is_absolute() {
-        return Err("note path must be relative".to_string());
+        return Err(AppError::InvalidPath {
+            path: path.to_string(),
+            reason: "note path must be relative",
+        });
     }
     if rel.components().any(|c| {
         matches!(
@@ -46,34 +53,43 @@ fn parse_safe_relative_path(path: &str) -> Result<PathBuf, String> {
             Component::ParentDir | Component::CurDir | Component::Prefix(_) | Component::RootDir
         )
     }) {
-        return Err("note path contains invalid segments".to_string());
+        return Err(AppError::InvalidPath {
+            path: path.to_string(),
+            reason: "note path contains invalid segments",
+        });
     }
     Ok(rel)
 }
 
-fn canonical_vault_root(vault_root: &Path) -> Result<PathBuf, String> {
-    vault_root.canonicalize().map_err(|e| e.to_string())
+fn canonical_vault_root(vault_root: &Path) -> AppResult<PathBuf> {
+    vault_root
+        .canonicalize()
+        .map_err(|e| AppError::io(e, &vault_root.to_string_lossy()))
 }
 
-fn resolve_under_vault_root(vault_root: &Path, rel: &Path) -> Result<PathBuf, String> {
+fn escapes_vault(rel: &Path) -> AppError {
+    AppError::PathEscapesVault {
+        path: storage::normalize_relative_path(rel),
+    }
+}
+
+fn resolve_under_vault_root(vault_root: &Path, rel: &Path) -> AppResult<PathBuf> {
     let base = canonical_vault_root(vault_root)?;
     let candidate = base.join(rel);
 
     let mut nearest_existing = candidate.as_path();
     while !nearest_existing.exists() {
-        nearest_existing = nearest_existing
-            .parent()
-            .ok_or("note path escapes vault".to_string())?;
+        nearest_existing = nearest_existing.parent().ok_or_else(|| escapes_vault(rel))?;
     }
 
-    let nearest_existing_canon = nearest_existing.canonicalize().map_err(|e| e.to_string())?;
+    let nearest_existing_canon = nearest_existing.canonicalize()?;
     if !nearest_existing_canon.starts_with(&base) {
-        return Err("note path escapes vault".to_string());
+        return Err(escapes_vault(rel));
     }
 
     let suffix = candidate
         .strip_prefix(nearest_existing)
-        .map_err(|_| "note path escapes vault".to_string())?;
+        .map_err(|_| escapes_vault(rel))?;
     let resolved = if suffix.as_os_str().is_empty() {
         nearest_existing_canon
     } else {
@@ -81,32 +97,34 @@ fn resolve_under_vault_root(vault_root: &Path, rel: &Path) -> Result<PathBuf, St
     };
 
     if !resolved.starts_with(&base) {
-        return Err("note path escapes vault".to_string());
+        return Err(escapes_vault(rel));
     }
     Ok(resolved)
 }
 
-fn reject_symlink_components(vault_root: &Path, rel: &Path) -> Result<(), String> {
+fn reject_symlink_components(vault_root: &Path, rel: &Path) -> AppResult<()> {
     let mut current = vault_root.to_path_buf();
     for component in rel.components() {
         current.push(component.as_os_str());
         if !current.exists() {
             break;
         }
-        let metadata = std::fs::symlink_metadata(&current).map_err(|e| e.to_string())?;
+        let metadata = std::fs::symlink_metadata(&current)?;
         if metadata.file_type().is_symlink() {
-            return Err("note path contains symlink component".to_string());
+            return Err(AppError::SymlinkRejected {
+                path: storage::normalize_relative_path(rel),
+            });
         }
     }
     Ok(())
 }
 
-pub(crate) fn safe_vault_abs(vault_root: &Path, note_rel: &str) -> Result<PathBuf, String> {
+pub(crate) fn safe_vault_abs(vault_root: &Path, note_rel: &str) -> AppResult<PathBuf> {
     let rel = parse_safe_relative_path(note_rel)?;
     resolve_under_vault_root(vault_root, &rel)
 }
 
-pub(crate) fn safe_vault_abs_for_write(vault_root: &Path, note_rel: &str) -> Result<PathBuf, String> {
+pub(crate) fn safe_vault_abs_for_write(vault_root: &Path, note_rel: &str) -> AppResult<PathBuf> {
     let rel = parse_safe_relative_path(note_rel)?;
     let base = canonical_vault_root(vault_root)?;
     reject_symlink_components(&base, &rel)?;
@@ -116,11 +134,12 @@ pub(crate) fn safe_vault_abs_for_write(vault_root: &Path, note_rel: &str) -> Res
 pub(crate) fn safe_vault_rename_target_abs(
     vault_root: &Path,
     target_rel: &str,
-) -> Result<PathBuf, String> {
+) -> AppResult<PathBuf> {
     let rel = parse_safe_relative_path(target_rel)?;
-    let leaf = rel
-        .file_name()
-        .ok_or("note path must include a leaf name")?;
+    let leaf = rel.file_name().ok_or_else(|| AppError::InvalidPath {
+        path: target_rel.to_string(),
+        reason: "note path must include a leaf name",
+    })?;
     let parent_rel = rel.parent().unwrap_or_else(|| Path::new(""));
 
     let base = vault_root
@@ -136,7 +155,7 @@ pub(crate) fn safe_vault_rename_target_abs(
 
     let target_abs = parent_abs.join(leaf);
     if !target_abs.starts_with(&base) {
-        return Err("note path escapes vault".to_string());
+        return Err(escapes_vault(&rel));
     }
 
     Ok(target_abs)
@@ -147,7 +166,7 @@ fn name_from_rel_path(rel_path: &str) -> String {
     leaf.strip_suffix(".md").unwrap_or(leaf).to_string()
 }
 
-fn resolve_folder_abs(root: &Path, folder_path: &str) -> Result<PathBuf, String> {
+fn resolve_folder_abs(root: &Path, folder_path: &str) -> AppResult<PathBuf> {
     if folder_path.is_empty() {
         Ok(root.to_path_buf())
     } else {
@@ -183,7 +202,18 @@ pub(crate) fn extract_title(path: &Path) -> String {
     buf.truncate(n);
 
     let prefix = String::from_utf8_lossy(&buf);
-    for line in prefix.lines() {
+    if let Some(title) = title_from_markdown(&prefix) {
+        return title;
+    }
+
+    path.file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+pub(crate) fn title_from_markdown(markdown: &str) -> Option<String> {
+    for line in markdown.lines() {
         let l = line.trim();
         if l.is_empty() {
             continue;
@@ -191,16 +221,12 @@ pub(crate) fn extract_title(path: &Path) -> String {
         if let Some(rest) = l.strip_prefix("# ") {
             let t = rest.trim();
             if !t.is_empty() {
-                return t.to_string();
+                return Some(t.to_string());
             }
         }
         break;
     }
-
-    path.file_stem()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string()
+    None
 }
 
 pub(crate) fn file_meta(path: &Path) -> Result<(i64, i64), String> {
@@ -215,7 +241,7 @@ pub(crate) fn file_meta(path: &Path) -> Result<(i64, i64), String> {
     Ok((mtime, size))
 }
 
-fn build_note_meta(root: &Path, rel_path: &str) -> Result<NoteMeta, String> {
+pub(crate) fn build_note_meta(root: &Path, rel_path: &str) -> AppResult<NoteMeta> {
     let abs = safe_vault_abs(root, rel_path)?;
     let title = extract_title(&abs);
     let (mtime_ms, size_bytes) = file_meta(&abs)?;
@@ -230,22 +256,16 @@ fn build_note_meta(root: &Path, rel_path: &str) -> Result<NoteMeta, String> {
     })
 }
 
-#[tauri::command]
-pub fn list_notes(app: AppHandle, vault_id: String) -> Result<Vec<NoteMeta>, String> {
-    log::info!("Listing notes vault_id={}", vault_id);
-    let root = storage::vault_path(&app, &vault_id).map_err(|e| {
-        log::error!("Failed to resolve vault path for {}: {}", vault_id, e);
-        e
-    })?;
+/// Every markdown note in the vault, sorted by path.
+pub(crate) fn list_notes_in(ctx: &VaultContext) -> AppResult<Vec<NoteMeta>> {
+    let root = ctx.root();
+    let ignore = VaultIgnore::load(root);
     let mut out = Vec::new();
 
-    for entry in WalkDir::new(&root)
+    for entry in WalkDir::new(root)
         .follow_links(false)
         .into_iter()
-        .filter_entry(|e| {
-            let name = e.file_name().to_string_lossy();
-            !constants::is_excluded_folder(&name)
-        })
+        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir()))
         .filter_map(|e| e.ok())
     {
         let p = entry.path();
@@ -256,41 +276,94 @@ pub fn list_notes(app: AppHandle, vault_id: String) -> Result<Vec<NoteMeta>, Str
             continue;
         }
 
-        let rel = p.strip_prefix(&root).map_err(|e| e.to_string())?;
+        let rel = p.strip_prefix(root).map_err(|e| e.to_string())?;
         let rel = storage::normalize_relative_path(rel);
-        out.push(build_note_meta(&root, &rel)?);
+        out.push(build_note_meta(root, &rel)?);
     }
 
     out.sort_by(|a, b| a.path.cmp(&b.path));
     Ok(out)
 }
 
-#[tauri::command]
-pub fn read_note(app: AppHandle, vault_id: String, note_id: String) -> Result<NoteDoc, String> {
-    log::debug!("Reading note vault_id={} note_id={}", vault_id, note_id);
-    let root = storage::vault_path(&app, &vault_id)?;
-    let abs = safe_vault_abs(&root, &note_id)?;
+pub(crate) fn read_note_in(ctx: &VaultContext, note_id: &str) -> AppResult<NoteDoc> {
+    let abs = ctx.abs(note_id)?;
     let markdown = std::fs::read_to_string(&abs).map_err(|e| {
         log::error!("Failed to read note {}: {}", note_id, e);
-        e.to_string()
+        AppError::io(e, note_id)
     })?;
     Ok(NoteDoc {
-        meta: build_note_meta(&root, &note_id)?,
+        meta: build_note_meta(ctx.root(), note_id)?,
         markdown,
     })
 }
 
+pub(crate) fn read_note_meta_in(ctx: &VaultContext, note_id: &str) -> AppResult<NoteMeta> {
+    // abs fails if the file doesn't exist or escapes the vault
+    ctx.abs(note_id)?;
+    build_note_meta(ctx.root(), note_id)
+}
+
+/// Writes the note, refusing with a `Conflict` error that carries the disk
+/// mtime when `expected_mtime_ms` no longer matches the file. Returns the new
+/// mtime.
+pub(crate) fn write_note_in(
+    ctx: &VaultContext,
+    note_id: &str,
+    markdown: &str,
+    expected_mtime_ms: Option<i64>,
+) -> AppResult<i64> {
+    let abs = ctx.abs_for_write(note_id)?;
+
+    if let Some(expected) = expected_mtime_ms {
+        match file_meta(&abs) {
+            Ok((disk_mtime, _)) if disk_mtime != expected => {
+                return Err(AppError::Conflict {
+                    path: note_id.to_string(),
+                    reason: ConflictReason::MtimeMismatch,
+                    current_mtime_ms: Some(disk_mtime),
+                });
+            }
+            Err(_) => {
+                return Err(AppError::Conflict {
+                    path: note_id.to_string(),
+                    reason: ConflictReason::FileMissing,
+                    current_mtime_ms: None,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    atomic_write(&abs, markdown)?;
+    let (new_mtime, _) = file_meta(&abs)?;
+    Ok(new_mtime)
+}
+
+#[tauri::command]
+pub fn list_notes(app: AppHandle, vault_id: String) -> AppResult<Vec<NoteMeta>> {
+    log::info!("Listing notes vault_id={}", vault_id);
+    let ctx = VaultContext::from_app(&app, &vault_id).map_err(|e| {
+        log::error!("Failed to resolve vault path for {}: {}", vault_id, e);
+        e
+    })?;
+    list_notes_in(&ctx)
+}
+
+#[tauri::command]
+pub fn read_note(app: AppHandle, vault_id: String, note_id: String) -> AppResult<NoteDoc> {
+    log::debug!("Reading note vault_id={} note_id={}", vault_id, note_id);
+    let ctx = VaultContext::from_app(&app, &vault_id)?;
+    read_note_in(&ctx, &note_id)
+}
+
 #[tauri::command]
-pub fn read_note_meta(app: AppHandle, vault_id: String, note_id: String) -> Result<NoteMeta, String> {
+pub fn read_note_meta(app: AppHandle, vault_id: String, note_id: String) -> AppResult<NoteMeta> {
     log::debug!("Reading note meta vault_id={} note_id={}", vault_id, note_id);
-    let root = storage::vault_path(&app, &vault_id)?;
-    // safe_vault_abs will fail if the file doesn't exist or escapes the vault
-    let _abs = safe_vault_abs(&root, &note_id)?;
-    
-    build_note_meta(&root, &note_id)
+    let ctx = VaultContext::from_app(&app, &vault_id)?;
+    read_note_meta_in(&ctx, &note_id)
 }
 
-fn atomic_write(path: &Path, content: &str) -> Result<(), String> {
+pub(crate) fn atomic_write(path: &Path, content: &str) -> Result<(), String> {
     let dir = path.parent().ok_or("invalid note path")?;
     std::fs::create_dir_all(dir).map_err(|e| {
         log::error!("Failed to create directory {}: {}", dir.display(), e);
@@ -315,30 +388,16 @@ fn atomic_write(path: &Path, content: &str) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn write_note(args: NoteWriteArgs, app: AppHandle) -> Result<i64, String> {
+pub fn write_note(args: NoteWriteArgs, app: AppHandle) -> AppResult<i64> {
     log::debug!(
         "Writing note vault_id={} note_id={}",
         args.vault_id,
         args.note_id
     );
-    let root = storage::vault_path(&app, &args.vault_id)?;
-    let abs = safe_vault_abs_for_write(&root, &args.note_id)?;
-
-    if let Some(expected) = args.expected_mtime_ms {
-        match file_meta(&abs) {
-            Ok((disk_mtime, _)) if disk_mtime != expected => {
-                return Err("conflict:mtime_mismatch".to_string());
-            }
-            Err(_) => {
-                return Err("conflict:file_missing".to_string());
-            }
-            _ => {}
-        }
-    }
-
-    atomic_write(&abs, &args.markdown)?;
-    let (new_mtime, _) = file_meta(&abs)?;
-    Ok(new_mtime)
+    let ctx = VaultContext::from_app(&app, &args.vault_id)?;
+    let mtime_ms = write_note_in(&ctx, &args.note_id, &args.markdown, args.expected_mtime_ms)?;
+    plugins::active().note_saved(&ctx, &args.note_id, &args.markdown);
+    Ok(mtime_ms)
 }
 
 #[derive(Debug, Deserialize)]
@@ -349,32 +408,39 @@ pub struct NoteCreateArgs {
 }
 
 #[tauri::command]
-pub fn create_note(args: NoteCreateArgs, app: AppHandle) -> Result<NoteMeta, String> {
+pub fn create_note(args: NoteCreateArgs, app: AppHandle) -> AppResult<NoteMeta> {
     log::info!(
         "Creating note vault_id={} note_path={}",
         args.vault_id,
         args.note_path
     );
-    let root = storage::vault_path(&app, &args.vault_id)?;
-    let abs = safe_vault_abs_for_write(&root, &args.note_path)?;
+    let ctx = VaultContext::from_app(&app, &args.vault_id)?;
+    let note = create_note_file(ctx.root(), &args.note_path, &args.initial_markdown)?;
+    invalidate_note_parent_folder_cache(&args.vault_id, &note.path);
+    Ok(note)
+}
+
+pub(crate) fn create_note_file(
+    root: &Path,
+    note_path: &str,
+    markdown: &str,
+) -> AppResult<NoteMeta> {
+    let abs = safe_vault_abs_for_write(root, note_path)?;
     let dir = abs.parent().ok_or("invalid note path")?;
-    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(dir)?;
     let mut file = OpenOptions::new()
         .write(true)
         .create_new(true)
         .open(&abs)
         .map_err(|e| {
             if e.kind() == std::io::ErrorKind::AlreadyExists {
-                "note already exists".to_string()
+                AppError::already_exists("note", note_path)
             } else {
-                e.to_string()
+                AppError::io(e, note_path)
             }
         })?;
-    file.write_all(args.initial_markdown.as_bytes())
-        .map_err(|e| e.to_string())?;
-    let note = build_note_meta(&root, &args.note_path)?;
-    invalidate_note_parent_folder_cache(&args.vault_id, &note.path);
-    Ok(note)
+    file.write_all(markdown.as_bytes())?;
+    build_note_meta(root, note_path)
 }
 
 #[derive(Debug, Deserialize)]
@@ -433,7 +499,7 @@ fn sanitize_stem(value: &str) -> String {
 }
 
 #[tauri::command]
-pub fn write_image_asset(args: WriteImageAssetArgs, app: AppHandle) -> Result<String, String> {
+pub fn write_image_asset(args: WriteImageAssetArgs, app: AppHandle) -> AppResult<String> {
     log::debug!(
         "Writing image asset vault_id={} note_path={}",
         args.vault_id,
@@ -453,14 +519,14 @@ pub fn write_image_asset(args: WriteImageAssetArgs, app: AppHandle) -> Result<St
         || attachment_folder.contains('\\')
         || attachment_folder.starts_with("..")
     {
-        return Err("invalid attachment folder name".to_string());
+        return Err(AppError::invalid_input("invalid attachment folder name"));
     }
 
     let filename = if let Some(custom_filename) = args.custom_filename {
         let sanitized = custom_filename.replace('/', "").replace('\\', "");
         let sanitized = sanitized.trim_start_matches('.');
         if sanitized.is_empty() {
-            return Err("invalid custom filename".to_string());
+            return Err(AppError::invalid_input("invalid custom filename"));
         }
         let has_extension = Path::new(sanitized)
             .extension()
@@ -547,8 +613,24 @@ pub(crate) fn rename_with_temp_path(from_abs: &Path, to_abs: &Path) -> Result<()
     Ok(())
 }
 
+/// Moves a note on disk without touching the journal or caches.
+pub(crate) fn rename_note_at(root: &Path, from: &str, to: &str) -> AppResult<()> {
+    let from_abs = safe_vault_abs(root, from)?;
+    let to_abs = safe_vault_rename_target_abs(root, to)?;
+    if to_abs.exists() {
+        let from_canon = from_abs.canonicalize()?;
+        let to_canon = to_abs.canonicalize()?;
+        if from_canon != to_canon {
+            return Err(AppError::already_exists("note", to));
+        }
+    }
+    let dir = to_abs.parent().ok_or("invalid destination path")?;
+    std::fs::create_dir_all(dir)?;
+    Ok(rename_with_temp_path(&from_abs, &to_abs)?)
+}
+
 #[tauri::command]
-pub fn rename_note(args: NoteRenameArgs, app: AppHandle) -> Result<(), String> {
+pub fn rename_note(args: NoteRenameArgs, app: AppHandle) -> AppResult<()> {
     log::info!(
         "Renaming note vault_id={} from={} to={}",
         args.vault_id,
@@ -556,18 +638,15 @@ pub fn rename_note(args: NoteRenameArgs, app: AppHandle) -> Result<(), String> {
         args.to
     );
     let root = storage::vault_path(&app, &args.vault_id)?;
-    let from_abs = safe_vault_abs(&root, &args.from)?;
-    let to_abs = safe_vault_rename_target_abs(&root, &args.to)?;
-    if to_abs.exists() {
-        let from_canon = from_abs.canonicalize().map_err(|e| e.to_string())?;
-        let to_canon = to_abs.canonicalize().map_err(|e| e.to_string())?;
-        if from_canon != to_canon {
-            return Err("note already exists".to_string());
-        }
-    }
-    let dir = to_abs.parent().ok_or("invalid destination path")?;
-    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
-    rename_with_temp_path(&from_abs, &to_abs)?;
+    rename_note_at(&root, &args.from, &args.to)?;
+    journal::record_operation_or_warn(
+        &root,
+        "rename_note",
+        vec![JournalOp::Move {
+            from: args.from.clone(),
+            to: args.to.clone(),
+        }],
+    );
     invalidate_note_parent_folder_cache(&args.vault_id, &args.from);
     let from_parent = parent_folder_path(&args.from);
     let to_parent = parent_folder_path(&args.to);
@@ -640,7 +719,7 @@ fn parent_folder_path(path: &str) -> String {
         .unwrap_or_default()
 }
 
-fn invalidate_note_parent_folder_cache(vault_id: &str, note_path: &str) {
+pub(crate) fn invalidate_note_parent_folder_cache(vault_id: &str, note_path: &str) {
     let parent = parent_folder_path(note_path);
     invalidate_folder_cache(vault_id, &parent);
 }
@@ -650,18 +729,20 @@ fn invalidate_folder_parent_cache(vault_id: &str, folder_path: &str) {
     invalidate_folder_cache(vault_id, &parent);
 }
 
-pub(crate) fn scan_folder_entries(target: &Path) -> Result<Vec<FolderEntry>, String> {
+pub(crate) fn scan_folder_entries(
+    target: &Path,
+    ignore: &VaultIgnore,
+) -> Result<Vec<FolderEntry>, String> {
     let mut items = Vec::new();
 
     for entry in std::fs::read_dir(target).map_err(|e| e.to_string())? {
         let entry = entry.map_err(|e| e.to_string())?;
         let name = entry.file_name().to_string_lossy().to_string();
-        if constants::is_excluded_folder(&name) {
-            continue;
-        }
-
         let file_type = entry.file_type().map_err(|e| e.to_string())?;
         let is_dir = file_type.is_dir();
+        if ignore.is_ignored(&entry.path(), is_dir) {
+            continue;
+        }
         if !is_dir && !name.ends_with(".md") {
             continue;
         }
@@ -685,6 +766,7 @@ pub(crate) fn scan_folder_entries(target: &Path) -> Result<Vec<FolderEntry>, Str
 pub(crate) fn get_or_scan_folder_entries(
     cache_key: &str,
     target: &Path,
+    ignore: &VaultIgnore,
 ) -> Result<Arc<[FolderEntry]>, String> {
     {
         let mut cache = folder_cache().lock().map_err(|e| e.to_string())?;
@@ -695,7 +777,7 @@ pub(crate) fn get_or_scan_folder_entries(
         }
     }
 
-    let items = scan_folder_entries(target)?;
+    let items = scan_folder_entries(target, ignore)?;
     let items = Arc::<[FolderEntry]>::from(items);
     let now = Instant::now();
 
@@ -728,8 +810,24 @@ pub struct FolderStats {
     pub folder_count: usize,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteResult {
+    /// False when the deleted files were too large to keep for undo.
+    pub undoable: bool,
+}
+
+/// Records a deletion's snapshot, if one was taken, and reports whether the
+/// deletion can be undone.
+fn record_removal(root: &Path, kind: &str, snapshot: Option<Vec<JournalOp>>) -> DeleteResult {
+    let undoable = snapshot.is_some();
+    if let Some(ops) = snapshot {
+        journal::record_operation_or_warn(root, kind, ops);
+    }
+    DeleteResult { undoable }
+}
+
 #[tauri::command]
-pub fn delete_note(args: NoteDeleteArgs, app: AppHandle) -> Result<(), String> {
+pub fn delete_note(args: NoteDeleteArgs, app: AppHandle) -> AppResult<DeleteResult> {
     log::info!(
         "Deleting note vault_id={} note_id={}",
         args.vault_id,
@@ -737,24 +835,24 @@ pub fn delete_note(args: NoteDeleteArgs, app: AppHandle) -> Result<(), String> {
     );
     let root = storage::vault_path(&app, &args.vault_id)?;
     let abs = safe_vault_abs(&root, &args.note_id)?;
+    let snapshot = journal::snapshot_removal(&root, &args.note_id)?;
     std::fs::remove_file(&abs).map_err(|e| e.to_string())?;
+    let result = record_removal(&root, "delete_note", snapshot);
     invalidate_note_parent_folder_cache(&args.vault_id, &args.note_id);
-    Ok(())
+    Ok(result)
 }
 
 #[tauri::command]
-pub fn list_folders(app: AppHandle, vault_id: String) -> Result<Vec<String>, String> {
+pub fn list_folders(app: AppHandle, vault_id: String) -> AppResult<Vec<String>> {
     log::debug!("Listing folders vault_id={}", vault_id);
     let root = storage::vault_path(&app, &vault_id)?;
+    let ignore = VaultIgnore::load(&root);
     let mut out = Vec::new();
 
     for entry in WalkDir::new(&root)
         .follow_links(false)
         .into_iter()
-        .filter_entry(|e| {
-            let name = e.file_name().to_string_lossy();
-            !constants::is_excluded_folder(&name)
-        })
+        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir()))
         .filter_map(|e| e.ok())
     {
         if !entry.file_type().is_dir() || entry.path() == root.as_path() {
@@ -779,7 +877,7 @@ pub struct FolderCreateArgs {
 }
 
 #[tauri::command]
-pub fn create_folder(args: FolderCreateArgs, app: AppHandle) -> Result<(), String> {
+pub fn create_folder(args: FolderCreateArgs, app: AppHandle) -> AppResult<()> {
     log::debug!(
         "Creating folder vault_id={} parent_path={} folder_name={}",
         args.vault_id,
@@ -793,7 +891,7 @@ pub fn create_folder(args: FolderCreateArgs, app: AppHandle) -> Result<(), Strin
         || args.folder_name.contains('\\')
         || args.folder_name.starts_with('.')
     {
-        return Err("invalid folder name".to_string());
+        return Err(AppError::invalid_input("invalid folder name"));
     }
     let target = parent.join(&args.folder_name);
     std::fs::create_dir_all(&target).map_err(|e| e.to_string())?;
@@ -1024,6 +1122,7 @@ fn execute_pending_moves(
     args: &MoveItemsArgs,
     pending: Vec<PendingMove>,
     invalidate_paths: &mut HashSet<String>,
+    ops: &mut Vec<JournalOp>,
 ) -> Result<Vec<MoveItemResult>, String> {
     let mut results = Vec::with_capacity(pending.len());
 
@@ -1045,16 +1144,36 @@ fn execute_pending_moves(
                 ));
                 continue;
             }
+            let snapshot = match journal::snapshot_removal(root, &destination_path) {
+                Ok(snapshot) => snapshot,
+                Err(error) => {
+                    results.push(move_failure(&item.path, destination_path.clone(), error));
+                    continue;
+                }
+            };
             if let Err(error) =
                 remove_existing_move_target(item.is_folder, &source_abs, &destination_abs)
             {
                 results.push(move_failure(&item.path, destination_path.clone(), error));
                 continue;
             }
+            if !destination_abs.exists() {
+                match snapshot {
+                    Some(snapshot) => ops.extend(snapshot),
+                    None => log::warn!(
+                        "Overwritten {} was too large to keep for undo",
+                        destination_path
+                    ),
+                }
+            }
         }
 
         match rename_with_temp_path(&source_abs, &destination_abs) {
             Ok(()) => {
+                ops.push(JournalOp::Move {
+                    from: item.path.clone(),
+                    to: destination_path.clone(),
+                });
                 let from_parent = parent_folder_path(&item.path);
                 let to_parent = parent_folder_path(&destination_path);
                 invalidate_paths.insert(from_parent);
@@ -1071,7 +1190,7 @@ fn execute_pending_moves(
 }
 
 #[tauri::command]
-pub fn move_items(args: MoveItemsArgs, app: AppHandle) -> Result<Vec<MoveItemResult>, String> {
+pub fn move_items(args: MoveItemsArgs, app: AppHandle) -> AppResult<Vec<MoveItemResult>> {
     log::info!(
         "Moving items vault_id={} target_folder={} item_count={}",
         args.vault_id,
@@ -1084,8 +1203,11 @@ pub fn move_items(args: MoveItemsArgs, app: AppHandle) -> Result<Vec<MoveItemRes
 
     let (mut results, pending) = collect_pending_moves(&root, &args)?;
     let mut invalidate_paths: HashSet<String> = HashSet::new();
-    let executed_results = execute_pending_moves(&root, &args, pending, &mut invalidate_paths)?;
+    let mut ops = Vec::new();
+    let executed_results =
+        execute_pending_moves(&root, &args, pending, &mut invalidate_paths, &mut ops)?;
     results.extend(executed_results);
+    journal::record_operation_or_warn(&root, "move_items", ops);
 
     for path in invalidate_paths {
         invalidate_folder_cache(&args.vault_id, &path);
@@ -1095,7 +1217,7 @@ pub fn move_items(args: MoveItemsArgs, app: AppHandle) -> Result<Vec<MoveItemRes
 }
 
 #[tauri::command]
-pub fn rename_folder(args: FolderRenameArgs, app: AppHandle) -> Result<(), String> {
+pub fn rename_folder(args: FolderRenameArgs, app: AppHandle) -> AppResult<()> {
     log::debug!(
         "Renaming folder vault_id={} from_path={} to_path={}",
         args.vault_id,
@@ -1104,17 +1226,25 @@ pub fn rename_folder(args: FolderRenameArgs, app: AppHandle) -> Result<(), Strin
     );
     let root = storage::vault_path(&app, &args.vault_id)?;
     if args.from_path.is_empty() || args.to_path.is_empty() {
-        return Err("cannot rename vault root".to_string());
+        return Err(AppError::invalid_input("cannot rename vault root"));
     }
     let from_abs = safe_vault_abs(&root, &args.from_path)?;
     let to_abs = safe_vault_rename_target_abs(&root, &args.to_path)?;
     if !from_abs.is_dir() {
-        return Err("source is not a directory".to_string());
+        return Err(AppError::invalid_input("source is not a directory"));
     }
     if let Some(dir) = to_abs.parent() {
         std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
     }
     rename_with_temp_path(&from_abs, &to_abs)?;
+    journal::record_operation_or_warn(
+        &root,
+        "rename_folder",
+        vec![JournalOp::Move {
+            from: args.from_path.clone(),
+            to: args.to_path.clone(),
+        }],
+    );
     invalidate_folder_parent_cache(&args.vault_id, &args.from_path);
     let from_parent = parent_folder_path(&args.from_path);
     let to_parent = parent_folder_path(&args.to_path);
@@ -1131,7 +1261,7 @@ pub struct FolderDeleteArgs {
 }
 
 #[tauri::command]
-pub fn delete_folder(args: FolderDeleteArgs, app: AppHandle) -> Result<(), String> {
+pub fn delete_folder(args: FolderDeleteArgs, app: AppHandle) -> AppResult<DeleteResult> {
     log::debug!(
         "Deleting folder vault_id={} folder_path={}",
         args.vault_id,
@@ -1139,16 +1269,18 @@ pub fn delete_folder(args: FolderDeleteArgs, app: AppHandle) -> Result<(), Strin
     );
     let root = storage::vault_path(&app, &args.vault_id)?;
     if args.folder_path.is_empty() {
-        return Err("cannot delete vault root".to_string());
+        return Err(AppError::invalid_input("cannot delete vault root"));
     }
     let abs = safe_vault_abs(&root, &args.folder_path)?;
     if !abs.is_dir() {
-        return Err("path is not a directory".to_string());
+        return Err(AppError::invalid_input("path is not a directory"));
     }
 
+    let snapshot = journal::snapshot_removal(&root, &args.folder_path)?;
     std::fs::remove_dir_all(&abs).map_err(|e| e.to_string())?;
+    let result = record_removal(&root, "delete_folder", snapshot);
     invalidate_folder_parent_cache(&args.vault_id, &args.folder_path);
-    Ok(())
+    Ok(result)
 }
 
 #[tauri::command]
@@ -1158,7 +1290,7 @@ pub fn list_folder_contents(
     folder_path: String,
     offset: usize,
     limit: usize,
-) -> Result<FolderContents, String> {
+) -> AppResult<FolderContents> {
     log::debug!(
         "Listing folder contents vault_id={} folder_path={}",
         vault_id,
@@ -1169,7 +1301,8 @@ pub fn list_folder_contents(
     ensure_directory(&target, "not a directory")?;
 
     let key = folder_cache_key(&vault_id, &folder_path);
-    let items = get_or_scan_folder_entries(&key, &target)?;
+    let ignore = VaultIgnore::load(&root);
+    let items = get_or_scan_folder_entries(&key, &target, &ignore)?;
     let total_count = items.len();
     let start = offset.min(total_count);
     let end = start.saturating_add(limit).min(total_count);
@@ -1204,7 +1337,7 @@ pub fn get_folder_stats(
     app: AppHandle,
     vault_id: String,
     folder_path: String,
-) -> Result<FolderStats, String> {
+) -> AppResult<FolderStats> {
     log::debug!(
         "Getting folder stats vault_id={} folder_path={}",
         vault_id,
@@ -1214,16 +1347,14 @@ pub fn get_folder_stats(
     let target = resolve_folder_abs(&root, &folder_path)?;
     ensure_directory(&target, "not a directory")?;
 
+    let ignore = VaultIgnore::load(&root);
     let mut note_count = 0usize;
     let mut folder_count = 0usize;
 
     for entry in WalkDir::new(&target)
         .follow_links(false)
         .into_iter()
-        .filter_entry(|e| {
-            let name = e.file_name().to_string_lossy();
-            !constants::is_excluded_folder(&name)
-        })
+        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir()))
         .filter_map(|e| e.ok())
     {
         if entry.path() == target.as_path() {
@@ -0,0 +1,357 @@
+use crate::features::notes::service::{self as notes_service, safe_vault_abs, NoteMeta};
+use crate::features::search::db as search_db;
+use crate::features::search::service as search_service;
+use crate::features::templates::expand::{self, TemplateContext};
+use crate::features::templates::service::{create_from_template_at, TemplateRequest};
+use crate::features::vault_settings::service::load_vault_settings_at;
+use crate::shared::storage;
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::AppHandle;
+
+pub(crate) const PERIODIC_NOTES_SETTING_KEY: &str = "periodic_notes";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+// Bounds navigation and range listing to roughly ten years of periods.
+const MAX_PERIOD_SPAN_DAYS: i64 = 3660;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Period {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeriodDirection {
+    Previous,
+    Next,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PeriodConfig {
+    #[serde(default)]
+    pub path_pattern: Option<String>,
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PeriodicNotesSettings {
+    #[serde(default)]
+    pub daily: PeriodConfig,
+    #[serde(default)]
+    pub weekly: PeriodConfig,
+    #[serde(default)]
+    pub monthly: PeriodConfig,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodicNote {
+    pub period: Period,
+    pub period_start: String,
+    pub note: NoteMeta,
+    pub created: bool,
+    pub cursor_offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodicNoteRef {
+    pub period: Period,
+    pub period_start: String,
+    pub path: String,
+    pub title: String,
+}
+
+impl Period {
+    fn default_pattern(self) -> &'static str {
+        match self {
+            Period::Daily => "daily/{{date:YYYY-MM-DD}}.md",
+            Period::Weekly => "weekly/{{date:GGGG-[W]WW}}.md",
+            Period::Monthly => "monthly/{{date:YYYY-MM}}.md",
+        }
+    }
+
+    fn config(self, settings: &PeriodicNotesSettings) -> &PeriodConfig {
+        match self {
+            Period::Daily => &settings.daily,
+            Period::Weekly => &settings.weekly,
+            Period::Monthly => &settings.monthly,
+        }
+    }
+
+    /// First day of the period containing `date`. Weeks start on Monday.
+    pub(crate) fn start_of(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Daily => date,
+            Period::Weekly => {
+                date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            Period::Monthly => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    pub(crate) fn step(self, start: NaiveDate, direction: PeriodDirection) -> Option<NaiveDate> {
+        match (self, direction) {
+            (Period::Daily, PeriodDirection::Previous) => start.pred_opt(),
+            (Period::Daily, PeriodDirection::Next) => start.succ_opt(),
+            (Period::Weekly, PeriodDirection::Previous) => {
+                start.checked_sub_signed(chrono::Duration::days(7))
+            }
+            (Period::Weekly, PeriodDirection::Next) => {
+                start.checked_add_signed(chrono::Duration::days(7))
+            }
+            (Period::Monthly, PeriodDirection::Previous) => {
+                start.checked_sub_months(Months::new(1))
+            }
+            (Period::Monthly, PeriodDirection::Next) => start.checked_add_months(Months::new(1)),
+        }
+    }
+}
+
+pub(crate) fn load_periodic_settings(root: &Path) -> PeriodicNotesSettings {
+    let Some(value) = load_vault_settings_at(root)
+        .ok()
+        .and_then(|settings| settings.get(PERIODIC_NOTES_SETTING_KEY).cloned())
+    else {
+        return PeriodicNotesSettings::default();
+    };
+    serde_json::from_value(value).unwrap_or_else(|error| {
+        log::warn!(
+            "Ignoring malformed {} setting: {}",
+            PERIODIC_NOTES_SETTING_KEY,
+            error
+        );
+        PeriodicNotesSettings::default()
+    })
+}
+
+pub(crate) fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), DATE_FORMAT)
+        .map_err(|e| format!("invalid date {}: {}", value, e))
+}
+
+fn start_of_day(date: NaiveDate) -> NaiveDateTime {
+    date.and_hms_opt(0, 0, 0).unwrap_or_default()
+}
+
+fn path_pattern(period: Period, settings: &PeriodicNotesSettings) -> String {
+    period
+        .config(settings)
+        .path_pattern
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .unwrap_or(period.default_pattern())
+        .to_string()
+}
+
+pub(crate) fn periodic_note_path(pattern: &str, start: NaiveDate) -> String {
+    let variables = HashMap::new();
+    let ctx = TemplateContext {
+        title: "",
+        now: start_of_day(start),
+        variables: &variables,
+    };
+    expand::expand_note_path(pattern, &ctx)
+}
+
+/// The folder every note of this pattern lives under, used to narrow the
+/// `notes` table lookup.
+fn static_prefix(pattern: &str) -> &str {
+    let literal = pattern.split("{{").next().unwrap_or_default();
+    match literal.rfind('/') {
+        Some(idx) => &pattern[..idx + 1],
+        None => "",
+    }
+}
+
+fn indexed_titles(conn: &Connection, pattern: &str) -> Result<HashMap<String, String>, String> {
+    Ok(
+        search_db::list_note_titles_by_prefix(conn, static_prefix(pattern))?
+            .into_iter()
+            .collect(),
+    )
+}
+
+fn to_ref(
+    period: Period,
+    start: NaiveDate,
+    path: String,
+    titles: &HashMap<String, String>,
+) -> Option<PeriodicNoteRef> {
+    let title = titles.get(&path)?.clone();
+    Some(PeriodicNoteRef {
+        period,
+        period_start: start.format(DATE_FORMAT).to_string(),
+        path,
+        title,
+    })
+}
+
+pub(crate) fn list_periodic_notes_in(
+    conn: &Connection,
+    root: &Path,
+    period: Period,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<PeriodicNoteRef>, String> {
+    if to < from {
+        return Err("range end is before range start".to_string());
+    }
+    if (to - from).num_days() > MAX_PERIOD_SPAN_DAYS {
+        return Err("date range is too large".to_string());
+    }
+
+    let pattern = path_pattern(period, &load_periodic_settings(root));
+    let titles = indexed_titles(conn, &pattern)?;
+    let mut out = Vec::new();
+    let mut current = Some(period.start_of(from));
+    while let Some(start) = current.filter(|start| *start <= to) {
+        if let Some(found) = to_ref(period, start, periodic_note_path(&pattern, start), &titles) {
+            out.push(found);
+        }
+        current = period.step(start, PeriodDirection::Next);
+    }
+    Ok(out)
+}
+
+pub(crate) fn adjacent_periodic_note_in(
+    conn: &Connection,
+    root: &Path,
+    period: Period,
+    date: NaiveDate,
+    direction: PeriodDirection,
+) -> Result<Option<PeriodicNoteRef>, String> {
+    let pattern = path_pattern(period, &load_periodic_settings(root));
+    let titles = indexed_titles(conn, &pattern)?;
+    let origin = period.start_of(date);
+    let mut current = period.step(origin, direction);
+    while let Some(start) = current {
+        if (start - origin).num_days().abs() > MAX_PERIOD_SPAN_DAYS {
+            break;
+        }
+        if let Some(found) = to_ref(period, start, periodic_note_path(&pattern, start), &titles) {
+            return Ok(Some(found));
+        }
+        current = period.step(start, direction);
+    }
+    Ok(None)
+}
+
+/// Vault-relative path of the note for the period containing `date`.
+pub(crate) fn periodic_note_path_at(root: &Path, period: Period, date: NaiveDate) -> String {
+    let settings = load_periodic_settings(root);
+    periodic_note_path(&path_pattern(period, &settings), period.start_of(date))
+}
+
+/// Returns the note for the period containing `date`, creating it from the
+/// configured template when it does not exist yet.
+pub(crate) fn open_periodic_note_at(
+    root: &Path,
+    period: Period,
+    date: NaiveDate,
+) -> Result<PeriodicNote, String> {
+    let settings = load_periodic_settings(root);
+    let start = period.start_of(date);
+    let path = periodic_note_path(&path_pattern(period, &settings), start);
+    let period_start = start.format(DATE_FORMAT).to_string();
+
+    if safe_vault_abs(root, &path)?.is_file() {
+        return Ok(PeriodicNote {
+            period,
+            period_start,
+            note: notes_service::build_note_meta(root, &path)?,
+            created: false,
+            cursor_offset: None,
+        });
+    }
+
+    let title = Path::new(&path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let variables = HashMap::new();
+    let frontmatter = serde_json::Map::new();
+    let created = create_from_template_at(
+        root,
+        &TemplateRequest {
+            template_path: period.config(&settings).template.as_deref(),
+            note_path: &path,
+            title: Some(&title),
+            variables: &variables,
+            frontmatter: &frontmatter,
+            now: start_of_day(start),
+        },
+    )?;
+
+    Ok(PeriodicNote {
+        period,
+        period_start,
+        note: created.note,
+        created: true,
+        cursor_offset: created.cursor_offset,
+    })
+}
+
+#[tauri::command]
+pub fn open_periodic_note(
+    app: AppHandle,
+    vault_id: String,
+    period: Period,
+    date: Option<String>,
+) -> Result<PeriodicNote, String> {
+    log::info!(
+        "Opening periodic note vault_id={} period={:?}",
+        vault_id,
+        period
+    );
+    let root = storage::vault_path(&app, &vault_id)?;
+    let date = match date.as_deref() {
+        Some(date) => parse_date(date)?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let opened = open_periodic_note_at(&root, period, date)?;
+    if opened.created {
+        notes_service::invalidate_note_parent_folder_cache(&vault_id, &opened.note.path);
+    }
+    Ok(opened)
+}
+
+#[tauri::command]
+pub fn navigate_periodic_note(
+    app: AppHandle,
+    vault_id: String,
+    period: Period,
+    date: String,
+    direction: PeriodDirection,
+) -> Result<Option<PeriodicNoteRef>, String> {
+    let root = storage::vault_path(&app, &vault_id)?;
+    let date = parse_date(&date)?;
+    search_service::with_read_conn(&app, &vault_id, |conn| {
+        adjacent_periodic_note_in(conn, &root, period, date, direction)
+    })
+    .map_err(String::from)
+}
+
+#[tauri::command]
+pub fn list_periodic_notes(
+    app: AppHandle,
+    vault_id: String,
+    period: Period,
+    from: String,
+    to: String,
+) -> Result<Vec<PeriodicNoteRef>, String> {
+    let root = storage::vault_path(&app, &vault_id)?;
+    let from = parse_date(&from)?;
+    let to = parse_date(&to)?;
+    search_service::with_read_conn(&app, &vault_id, |conn| {
+        list_periodic_notes_in(conn, &root, period, from, to)
+    })
+    .map_err(String::from)
+}
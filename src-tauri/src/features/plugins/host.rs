@@ -0,0 +1,416 @@
+use crate::features::notes::service as notes_service;
+use crate::features::plugins::manifest::{
+    load_manifest, PluginCapabilities, PluginCommand, PluginHook, PluginManifest, VaultAccess,
+};
+use crate::features::plugins::wasm::WasmPlugin;
+use crate::shared::constants;
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::storage;
+use crate::shared::vault_context::VaultContext;
+use crate::shared::vault_ignore::IGNORE_FILE;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+/// The vault access a plugin has while one of its calls runs. Every path goes
+/// through the vault's safe path helpers and the plugin's declared folders.
+#[derive(Debug, Clone)]
+pub struct HostApi {
+    plugin_id: String,
+    ctx: Option<VaultContext>,
+    capabilities: PluginCapabilities,
+}
+
+impl HostApi {
+    pub fn new(
+        plugin_id: impl Into<String>,
+        ctx: Option<VaultContext>,
+        capabilities: PluginCapabilities,
+    ) -> Self {
+        Self {
+            plugin_id: plugin_id.into(),
+            ctx,
+            capabilities,
+        }
+    }
+
+    pub fn plugin_id(&self) -> &str {
+        &self.plugin_id
+    }
+
+    /// Whether the plugin may `need` the vault-relative `path`. App folders
+    /// such as `.git` and `.otterly` and the `.otterlyignore` file are always
+    /// off limits.
+    pub fn allows(&self, path: &str, need: VaultAccess) -> bool {
+        self.checked(path, need).is_ok()
+    }
+
+    fn permits(&self, rel: &str, need: VaultAccess) -> bool {
+        let granted = match need {
+            VaultAccess::None => true,
+            VaultAccess::Read => self.capabilities.vault != VaultAccess::None,
+            VaultAccess::Write => self.capabilities.vault == VaultAccess::Write,
+        };
+        granted && !is_protected(rel) && storage::path_in_folders(&self.capabilities.folders, rel)
+    }
+
+    fn denied(&self, path: &str, need: VaultAccess) -> AppError {
+        AppError::permission_denied(format!(
+            "plugin {} may not {} {}",
+            self.plugin_id,
+            if need == VaultAccess::Write {
+                "write"
+            } else {
+                "read"
+            },
+            path
+        ))
+    }
+
+    /// Resolves `path` inside the vault and checks both the requested path
+    /// and where it resolves to, so symlinks cannot reach protected files.
+    fn checked(&self, path: &str, need: VaultAccess) -> AppResult<(&VaultContext, PathBuf)> {
+        let ctx = self
+            .ctx
+            .as_ref()
+            .ok_or_else(|| AppError::invalid_input("no vault is open for this plugin call"))?;
+        if !self.permits(path, need) {
+            return Err(self.denied(path, need));
+        }
+        let abs = if need == VaultAccess::Write {
+            ctx.abs_for_write(path)?
+        } else {
+            ctx.abs(path)?
+        };
+        let root = ctx
+            .root()
+            .canonicalize()
+            .map_err(|e| AppError::io(e, &ctx.root().to_string_lossy()))?;
+        let resolved = abs
+            .strip_prefix(&root)
+            .map(storage::normalize_relative_path)
+            .map_err(|_| self.denied(path, need))?;
+        if !self.permits(&resolved, need) {
+            return Err(self.denied(path, need));
+        }
+        Ok((ctx, abs))
+    }
+
+    pub fn read(&self, path: &str) -> AppResult<String> {
+        let (_, abs) = self.checked(path, VaultAccess::Read)?;
+        std::fs::read_to_string(&abs).map_err(|e| AppError::io(e, path))
+    }
+
+    pub fn write(&self, path: &str, contents: &str) -> AppResult<()> {
+        let (ctx, abs) = self.checked(path, VaultAccess::Write)?;
+        if let Some(parent) = abs.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        log::info!("Plugin {} writing {}", self.plugin_id, path);
+        notes_service::atomic_write(&abs, contents)?;
+        notes_service::invalidate_note_parent_folder_cache(ctx.vault_id(), path);
+        Ok(())
+    }
+
+    pub fn log(&self, message: &str) {
+        log::info!("[plugin {}] {}", self.plugin_id, message);
+    }
+}
+
+/// App folders at any depth and the vault's ignore file. Names are compared
+/// case-insensitively, since `.GIT` is `.git` on most desktop file systems.
+fn is_protected(rel: &str) -> bool {
+    rel.eq_ignore_ascii_case(IGNORE_FILE)
+        || rel.split('/').any(|segment| {
+            constants::EXCLUDED_FOLDERS
+                .iter()
+                .any(|folder| segment.eq_ignore_ascii_case(folder))
+        })
+}
+
+/// A loaded plugin module. Each call passes one JSON request; the reply is
+/// `None` when the plugin has nothing to return.
+pub trait PluginInstance: Send {
+    fn call(&mut self, api: HostApi, request: &Value) -> AppResult<Option<Value>>;
+}
+
+struct LoadedPlugin {
+    manifest: PluginManifest,
+    instance: Mutex<Box<dyn PluginInstance>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginInfo {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub hooks: Vec<PluginHook>,
+    pub commands: Vec<PluginCommand>,
+    pub capabilities: PluginCapabilities,
+    /// Why the plugin failed to load, if it did.
+    pub error: Option<AppError>,
+}
+
+impl PluginInfo {
+    fn from_manifest(manifest: &PluginManifest) -> Self {
+        Self {
+            id: manifest.id.clone(),
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            description: manifest.description.clone(),
+            hooks: manifest.hooks.clone(),
+            commands: manifest.commands.clone(),
+            capabilities: manifest.capabilities.clone(),
+            error: None,
+        }
+    }
+
+    fn failed(id: &str, error: AppError) -> Self {
+        Self {
+            id: id.to_string(),
+            name: id.to_string(),
+            version: String::new(),
+            description: String::new(),
+            hooks: Vec::new(),
+            commands: Vec::new(),
+            capabilities: PluginCapabilities::default(),
+            error: Some(error),
+        }
+    }
+}
+
+/// Normalizes a link target returned by a plugin to a vault-relative note
+/// path, dropping anything that could point outside the vault.
+fn plugin_link_target(raw: &str) -> Option<String> {
+    let target = raw.trim().trim_matches('/');
+    if target.is_empty()
+        || !Path::new(target)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    if target.to_ascii_lowercase().ends_with(".md") {
+        Some(target.to_string())
+    } else {
+        Some(format!("{}.md", target))
+    }
+}
+
+/// The set of loaded plugins and the hooks they registered.
+#[derive(Default)]
+pub struct PluginHost {
+    plugins: Vec<LoadedPlugin>,
+    failed: Vec<PluginInfo>,
+}
+
+impl PluginHost {
+    /// Loads every plugin folder under `dir`. Broken plugins are recorded and
+    /// skipped so one bad module can't keep the rest from running.
+    pub fn load_dir(dir: &Path) -> Self {
+        let mut host = Self::default();
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return host;
+        };
+        let mut dirs: Vec<_> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect();
+        dirs.sort();
+        for plugin_dir in dirs {
+            let name = plugin_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let loaded = load_manifest(&plugin_dir).and_then(|(manifest, entry)| {
+                let bytes = std::fs::read(&entry).map_err(|e| AppError::io(e, &manifest.entry))?;
+                Ok((manifest, WasmPlugin::load(&bytes)?))
+            });
+            match loaded {
+                Ok((manifest, instance)) => {
+                    log::info!("Loaded plugin {} {}", manifest.id, manifest.version);
+                    host.add(manifest, Box::new(instance));
+                }
+                Err(error) => {
+                    log::warn!("Failed to load plugin {}: {}", name, error);
+                    host.failed.push(PluginInfo::failed(&name, error));
+                }
+            }
+        }
+        host
+    }
+
+    pub fn add(&mut self, manifest: PluginManifest, instance: Box<dyn PluginInstance>) {
+        self.plugins.retain(|p| p.manifest.id != manifest.id);
+        self.plugins.push(LoadedPlugin {
+            manifest,
+            instance: Mutex::new(instance),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    pub fn list(&self) -> Vec<PluginInfo> {
+        self.plugins
+            .iter()
+            .map(|p| PluginInfo::from_manifest(&p.manifest))
+            .chain(self.failed.iter().cloned())
+            .collect()
+    }
+
+    fn call(
+        &self,
+        plugin: &LoadedPlugin,
+        ctx: Option<&VaultContext>,
+        request: &Value,
+    ) -> AppResult<Option<Value>> {
+        let api = HostApi::new(
+            plugin.manifest.id.clone(),
+            ctx.cloned(),
+            plugin.manifest.capabilities.clone(),
+        );
+        let mut instance = plugin
+            .instance
+            .lock()
+            .map_err(|_| format!("plugin {} lock poisoned", plugin.manifest.id))?;
+        instance.call(api, request)
+    }
+
+    /// Sends a note hook to every plugin that registered it and may see the
+    /// note. Markdown is only included for plugins allowed to read it.
+    fn note_hook(
+        &self,
+        hook: PluginHook,
+        ctx: &VaultContext,
+        path: &str,
+        markdown: &str,
+    ) -> Vec<(String, Value)> {
+        let mut replies = Vec::new();
+        for plugin in self.plugins.iter().filter(|p| p.manifest.handles(hook)) {
+            let api = HostApi::new(
+                plugin.manifest.id.clone(),
+                Some(ctx.clone()),
+                plugin.manifest.capabilities.clone(),
+            );
+            if !storage::path_in_folders(&plugin.manifest.capabilities.folders, path) {
+                continue;
+            }
+            let mut request = json!({
+                "hook": hook.as_str(),
+                "vault_id": ctx.vault_id(),
+                "path": path,
+            });
+            if api.allows(path, VaultAccess::Read) {
+                request["markdown"] = json!(markdown);
+            }
+            match self.call(plugin, Some(ctx), &request) {
+                Ok(Some(reply)) => replies.push((plugin.manifest.id.clone(), reply)),
+                Ok(None) => {}
+                Err(error) => log::warn!(
+                    "Plugin {} failed in {}: {}",
+                    plugin.manifest.id,
+                    hook.as_str(),
+                    error
+                ),
+            }
+        }
+        replies
+    }
+
+    pub fn note_saved(&self, ctx: &VaultContext, path: &str, markdown: &str) {
+        self.note_hook(PluginHook::NoteSave, ctx, path, markdown);
+    }
+
+    pub fn note_indexed(&self, ctx: &VaultContext, path: &str, markdown: &str) {
+        self.note_hook(PluginHook::IndexUpsert, ctx, path, markdown);
+    }
+
+    /// Extra link targets from `link_extract` plugins, each reply shaped as
+    /// `{ "links": ["path/to/note", ...] }`.
+    pub fn link_targets(&self, ctx: &VaultContext, path: &str, markdown: &str) -> Vec<String> {
+        let mut targets = Vec::new();
+        for (plugin_id, reply) in self.note_hook(PluginHook::LinkExtract, ctx, path, markdown) {
+            let links = reply["links"].as_array().cloned().unwrap_or_default();
+            for link in links {
+                match link.as_str().and_then(plugin_link_target) {
+                    Some(target) => targets.push(target),
+                    None => log::warn!("Plugin {} returned invalid link {}", plugin_id, link),
+                }
+            }
+        }
+        targets
+    }
+
+    /// Runs a command a plugin declared. Plugins with the `command_invoke` hook
+    /// are told first.
+    pub fn run_command(
+        &self,
+        ctx: Option<&VaultContext>,
+        plugin_id: &str,
+        command: &str,
+        args: Value,
+    ) -> AppResult<Value> {
+        let plugin = self
+            .plugins
+            .iter()
+            .find(|p| p.manifest.id == plugin_id)
+            .ok_or_else(|| AppError::not_found("plugin", None))?;
+        if !plugin.manifest.has_command(command) {
+            return Err(AppError::not_found("plugin command", None));
+        }
+        let vault_id = ctx.map(|c| c.vault_id().to_string());
+
+        let invoked = json!({
+            "hook": PluginHook::CommandInvoke.as_str(),
+            "vault_id": vault_id,
+            "plugin_id": plugin_id,
+            "command": command,
+        });
+        for observer in self
+            .plugins
+            .iter()
+            .filter(|p| p.manifest.handles(PluginHook::CommandInvoke))
+        {
+            if let Err(error) = self.call(observer, ctx, &invoked) {
+                log::warn!(
+                    "Plugin {} failed in command_invoke: {}",
+                    observer.manifest.id,
+                    error
+                );
+            }
+        }
+
+        log::info!("Running plugin command {}:{}", plugin_id, command);
+        let request = json!({
+            "hook": "command",
+            "vault_id": vault_id,
+            "command": command,
+            "args": args,
+        });
+        Ok(self.call(plugin, ctx, &request)?.unwrap_or(Value::Null))
+    }
+}
+
+fn active_slot() -> &'static RwLock<Arc<PluginHost>> {
+    static ACTIVE: OnceLock<RwLock<Arc<PluginHost>>> = OnceLock::new();
+    ACTIVE.get_or_init(|| RwLock::new(Arc::new(PluginHost::default())))
+}
+
+/// The plugins that hooks currently dispatch to.
+pub fn active() -> Arc<PluginHost> {
+    active_slot()
+        .read()
+        .map(|host| Arc::clone(&host))
+        .unwrap_or_default()
+}
+
+pub fn install(host: PluginHost) {
+    if let Ok(mut slot) = active_slot().write() {
+        *slot = Arc::new(host);
+    }
+}
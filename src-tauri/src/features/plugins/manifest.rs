@@ -0,0 +1,145 @@
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::storage;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "plugin.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginHook {
+    /// After a note is saved from the editor.
+    NoteSave,
+    /// After a single note is re-indexed; full rebuilds don't fire it.
+    IndexUpsert,
+    /// While indexing, to add link targets the markdown parser can't see.
+    LinkExtract,
+    /// Before any plugin command runs.
+    CommandInvoke,
+}
+
+impl PluginHook {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluginHook::NoteSave => "note_save",
+            PluginHook::IndexUpsert => "index_upsert",
+            PluginHook::LinkExtract => "link_extract",
+            PluginHook::CommandInvoke => "command_invoke",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultAccess {
+    #[default]
+    None,
+    Read,
+    Write,
+}
+
+/// What a plugin may touch. Everything is denied unless declared.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginCapabilities {
+    #[serde(default)]
+    pub vault: VaultAccess,
+    /// Folders the plugin may read or write; empty means the whole vault.
+    #[serde(default)]
+    pub folders: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginCommand {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// Wasm module, relative to the plugin folder.
+    #[serde(default = "default_entry")]
+    pub entry: String,
+    #[serde(default)]
+    pub hooks: Vec<PluginHook>,
+    #[serde(default)]
+    pub commands: Vec<PluginCommand>,
+    #[serde(default)]
+    pub capabilities: PluginCapabilities,
+}
+
+fn default_entry() -> String {
+    "plugin.wasm".to_string()
+}
+
+fn is_slug(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+impl PluginManifest {
+    pub fn handles(&self, hook: PluginHook) -> bool {
+        self.hooks.contains(&hook)
+    }
+
+    pub fn has_command(&self, command: &str) -> bool {
+        self.commands.iter().any(|c| c.id == command)
+    }
+
+    fn validate(mut self, dir_name: &str) -> AppResult<Self> {
+        if !is_slug(&self.id) {
+            return Err(AppError::invalid_input(format!(
+                "plugin id must be lowercase letters, digits, '-' or '_': {}",
+                self.id
+            )));
+        }
+        if self.id != dir_name {
+            return Err(AppError::invalid_input(format!(
+                "plugin id {} does not match its folder {}",
+                self.id, dir_name
+            )));
+        }
+        let entry = Path::new(&self.entry);
+        if !entry
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(AppError::InvalidPath {
+                path: self.entry.clone(),
+                reason: "plugin entry must be a relative path inside the plugin folder",
+            });
+        }
+        if let Some(command) = self.commands.iter().find(|c| !is_slug(&c.id)) {
+            return Err(AppError::invalid_input(format!(
+                "invalid command id in plugin {}: {}",
+                self.id, command.id
+            )));
+        }
+        self.capabilities.folders = storage::normalize_folders(self.capabilities.folders);
+        Ok(self)
+    }
+}
+
+/// Reads and validates `plugin.json` in a plugin folder. Returns the manifest
+/// and the absolute path of its wasm module.
+pub fn load_manifest(dir: &Path) -> AppResult<(PluginManifest, PathBuf)> {
+    let dir_name = dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let path = dir.join(MANIFEST_FILE);
+    let bytes = std::fs::read(&path).map_err(|e| AppError::io(e, &path.to_string_lossy()))?;
+    let manifest: PluginManifest = serde_json::from_slice(&bytes).map_err(|e| {
+        AppError::invalid_input(format!("invalid {} in {}: {}", MANIFEST_FILE, dir_name, e))
+    })?;
+    let manifest = manifest.validate(&dir_name)?;
+    let entry = dir.join(&manifest.entry);
+    Ok((manifest, entry))
+}
@@ -0,0 +1,4 @@
+pub mod host;
+pub mod manifest;
+pub mod service;
+pub mod wasm;
@@ -0,0 +1,56 @@
+use crate::features::plugins::host::{self, PluginHost, PluginInfo};
+use crate::shared::error::AppResult;
+use crate::shared::vault_context::{VaultContext, VaultRegistry};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const PLUGINS_DIR: &str = "plugins";
+
+/// Plugins live in the app config dir, one folder per plugin, so opening a
+/// vault never runs code that came with it.
+pub fn plugins_dir(registry: &VaultRegistry) -> PathBuf {
+    registry.dir().join(PLUGINS_DIR)
+}
+
+fn reload(app: &AppHandle) -> AppResult<Vec<PluginInfo>> {
+    let dir = plugins_dir(&VaultRegistry::for_app(app)?);
+    log::info!("Loading plugins from {}", dir.display());
+    host::install(PluginHost::load_dir(&dir));
+    Ok(host::active().list())
+}
+
+pub fn load_on_startup(app: &AppHandle) {
+    if let Err(error) = reload(app) {
+        log::error!("Failed to load plugins: {}", error);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PluginCommandArgs {
+    pub vault_id: Option<String>,
+    pub plugin_id: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Value,
+}
+
+#[tauri::command]
+pub fn plugins_list() -> AppResult<Vec<PluginInfo>> {
+    Ok(host::active().list())
+}
+
+#[tauri::command]
+pub fn plugins_reload(app: AppHandle) -> AppResult<Vec<PluginInfo>> {
+    reload(&app)
+}
+
+#[tauri::command]
+pub fn plugin_run_command(app: AppHandle, args: PluginCommandArgs) -> AppResult<Value> {
+    let ctx = match &args.vault_id {
+        Some(vault_id) => Some(VaultContext::from_app(&app, vault_id)?),
+        None => None,
+    };
+    host::active().run_command(ctx.as_ref(), &args.plugin_id, &args.command, args.args)
+}
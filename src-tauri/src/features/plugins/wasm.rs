@@ -0,0 +1,234 @@
+//! Wasm runtime for plugins, built on the `wasmi` interpreter.
+//!
+//! A module exports `memory`, `otterly_alloc(len) -> ptr` and
+//! `otterly_call(ptr, len) -> i64`. Each call passes a UTF-8 JSON request;
+//! the result packs `(ptr << 32) | len` of a JSON reply, or is 0 for no reply.
+//!
+//! The host imports, all in the `otterly` module:
+//! - `log(ptr, len)`
+//! - `vault_read(path_ptr, path_len) -> i64`: packed file contents, or -1
+//! - `vault_write(path_ptr, path_len, data_ptr, data_len) -> i32`: 0, or -1
+//!
+//! Nothing else is linked, so a plugin has no clock, network or filesystem
+//! beyond what its capabilities let [`HostApi`] do.
+
+use crate::features::plugins::host::{HostApi, PluginInstance};
+use crate::shared::error::{AppError, AppResult};
+use serde_json::Value;
+use wasmi::{
+    Caller, Config, Engine, Extern, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder,
+    TypedFunc,
+};
+
+const HOST_MODULE: &str = "otterly";
+/// Instruction budget for one call, so a looping plugin can't hang a save.
+const FUEL_PER_CALL: u64 = 100_000_000;
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+struct HostState {
+    api: Option<HostApi>,
+    limits: StoreLimits,
+}
+
+pub struct WasmPlugin {
+    store: Store<HostState>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    entry: TypedFunc<(i32, i32), i64>,
+}
+
+fn runtime_error(error: impl std::fmt::Display) -> AppError {
+    AppError::from(format!("plugin runtime error: {}", error))
+}
+
+fn pack(ptr: i32, len: i32) -> i64 {
+    ((ptr as u32 as i64) << 32) | len as u32 as i64
+}
+
+fn unpack(packed: i64) -> (usize, usize) {
+    ((packed >> 32) as u32 as usize, packed as u32 as usize)
+}
+
+fn read_bytes(
+    memory: &Memory,
+    ctx: impl wasmi::AsContext,
+    ptr: usize,
+    len: usize,
+) -> Option<Vec<u8>> {
+    if len > MAX_MESSAGE_BYTES {
+        return None;
+    }
+    let mut buf = vec![0u8; len];
+    memory.read(ctx, ptr, &mut buf).ok()?;
+    Some(buf)
+}
+
+fn guest_string(caller: &Caller<'_, HostState>, ptr: i32, len: i32) -> Option<String> {
+    let memory = caller.get_export("memory").and_then(Extern::into_memory)?;
+    let bytes = read_bytes(&memory, caller, ptr as u32 as usize, len as u32 as usize)?;
+    String::from_utf8(bytes).ok()
+}
+
+/// Copies `bytes` into a buffer the guest allocates and returns it packed.
+fn write_guest(caller: &mut Caller<'_, HostState>, bytes: &[u8]) -> Option<i64> {
+    let len = i32::try_from(bytes.len()).ok()?;
+    let memory = caller.get_export("memory").and_then(Extern::into_memory)?;
+    let alloc = caller
+        .get_export("otterly_alloc")
+        .and_then(Extern::into_func)?
+        .typed::<i32, i32>(&*caller)
+        .ok()?;
+    let ptr = alloc.call(&mut *caller, len).ok()?;
+    memory
+        .write(&mut *caller, ptr as u32 as usize, bytes)
+        .ok()?;
+    Some(pack(ptr, len))
+}
+
+fn link_host(linker: &mut Linker<HostState>) -> AppResult<()> {
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "log",
+            |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                if let (Some(text), Some(api)) =
+                    (guest_string(&caller, ptr, len), &caller.data().api)
+                {
+                    api.log(&text);
+                }
+            },
+        )
+        .map_err(runtime_error)?;
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "vault_read",
+            |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i64 {
+                let (Some(path), Some(api)) =
+                    (guest_string(&caller, ptr, len), caller.data().api.clone())
+                else {
+                    return -1;
+                };
+                match api.read(&path) {
+                    Ok(text) => write_guest(&mut caller, text.as_bytes()).unwrap_or(-1),
+                    Err(error) => {
+                        log::warn!("Plugin {} read {} failed: {}", api.plugin_id(), path, error);
+                        -1
+                    }
+                }
+            },
+        )
+        .map_err(runtime_error)?;
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "vault_write",
+            |caller: Caller<'_, HostState>,
+             path_ptr: i32,
+             path_len: i32,
+             data_ptr: i32,
+             data_len: i32|
+             -> i32 {
+                let (Some(path), Some(data), Some(api)) = (
+                    guest_string(&caller, path_ptr, path_len),
+                    guest_string(&caller, data_ptr, data_len),
+                    caller.data().api.as_ref(),
+                ) else {
+                    return -1;
+                };
+                match api.write(&path, &data) {
+                    Ok(()) => 0,
+                    Err(error) => {
+                        log::warn!(
+                            "Plugin {} write {} failed: {}",
+                            api.plugin_id(),
+                            path,
+                            error
+                        );
+                        -1
+                    }
+                }
+            },
+        )
+        .map_err(runtime_error)?;
+    Ok(())
+}
+
+impl WasmPlugin {
+    pub fn load(bytes: &[u8]) -> AppResult<Self> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, bytes).map_err(runtime_error)?;
+        let mut store = Store::new(
+            &engine,
+            HostState {
+                api: None,
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(MAX_MEMORY_BYTES)
+                    .build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(FUEL_PER_CALL).map_err(runtime_error)?;
+
+        let mut linker = Linker::<HostState>::new(&engine);
+        link_host(&mut linker)?;
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(runtime_error)?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| AppError::invalid_input("plugin does not export memory"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "otterly_alloc")
+            .map_err(runtime_error)?;
+        let entry = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "otterly_call")
+            .map_err(runtime_error)?;
+        Ok(Self {
+            store,
+            memory,
+            alloc,
+            entry,
+        })
+    }
+
+    fn invoke(&mut self, input: &[u8]) -> AppResult<Option<Value>> {
+        let len = i32::try_from(input.len())
+            .map_err(|_| AppError::invalid_input("plugin request is too large"))?;
+        let ptr = self
+            .alloc
+            .call(&mut self.store, len)
+            .map_err(runtime_error)?;
+        self.memory
+            .write(&mut self.store, ptr as u32 as usize, input)
+            .map_err(runtime_error)?;
+        let packed = self
+            .entry
+            .call(&mut self.store, (ptr, len))
+            .map_err(runtime_error)?;
+        if packed == 0 {
+            return Ok(None);
+        }
+        let (out_ptr, out_len) = unpack(packed);
+        let reply = read_bytes(&self.memory, &self.store, out_ptr, out_len)
+            .ok_or_else(|| runtime_error("invalid reply buffer"))?;
+        serde_json::from_slice(&reply)
+            .map(Some)
+            .map_err(|e| runtime_error(format!("reply is not JSON: {}", e)))
+    }
+}
+
+impl PluginInstance for WasmPlugin {
+    fn call(&mut self, api: HostApi, request: &Value) -> AppResult<Option<Value>> {
+        let input = serde_json::to_vec(request).map_err(|e| e.to_string())?;
+        self.store.set_fuel(FUEL_PER_CALL).map_err(runtime_error)?;
+        self.store.data_mut().api = Some(api);
+        let result = self.invoke(&input);
+        self.store.data_mut().api = None;
+        result
+    }
+}
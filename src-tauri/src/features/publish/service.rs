@@ -0,0 +1,510 @@
+use crate::features::export::html::{self, escape_html, html_page_path, relative_href};
+use crate::features::export::service::{
+    collect_export_notes, copy_assets, render_index, resolve_output_dir, write_output, INDEX_PAGE,
+};
+use crate::features::notes::service::{file_meta, safe_vault_abs};
+use crate::features::search::db as search_db;
+use crate::features::search::link_parser::{collect_plain_text, markdown_options};
+use crate::features::search::service as search_service;
+use crate::features::tasks::parser::parse_tags;
+use crate::features::templates::expand::split_frontmatter;
+use crate::shared::storage;
+use chrono::{DateTime, Utc};
+use comrak::nodes::NodeValue;
+use comrak::{parse_document, Arena};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path};
+use tauri::AppHandle;
+
+/// Lists the files written by the previous publish so stale pages can be
+/// removed when notes stop being published.
+pub(crate) const PUBLISH_MANIFEST: &str = ".otterly-publish.json";
+const SEARCH_INDEX: &str = "search-index.json";
+const SITEMAP: &str = "sitemap.xml";
+const RSS_FEED: &str = "feed.xml";
+const RSS_ITEM_LIMIT: usize = 20;
+const SEARCH_TEXT_LIMIT: usize = 10_000;
+const DEFAULT_SITE_TITLE: &str = "Notes";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PublishSelection {
+    /// Notes under any of these folders are published.
+    #[serde(default)]
+    pub folders: Vec<String>,
+    /// Notes carrying any of these tags (or a nested tag) are published.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PublishArgs {
+    pub vault_id: String,
+    pub output_dir: String,
+    #[serde(default)]
+    pub selection: PublishSelection,
+    #[serde(default)]
+    pub site_title: Option<String>,
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+pub(crate) struct PublishOptions<'a> {
+    pub output_dir: &'a str,
+    pub selection: &'a PublishSelection,
+    pub site_title: Option<&'a str>,
+    pub base_url: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PublishWarningKind {
+    UnpublishedLink,
+    BrokenLink,
+    MissingAsset,
+    MissingBaseUrl,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishWarning {
+    pub kind: PublishWarningKind,
+    pub path: Option<String>,
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishResult {
+    pub output_dir: String,
+    pub pages: Vec<String>,
+    pub assets: Vec<String>,
+    pub removed: Vec<String>,
+    pub warnings: Vec<PublishWarning>,
+}
+
+#[derive(Serialize)]
+struct SearchEntry<'a> {
+    url: &'a str,
+    title: &'a str,
+    tags: &'a [String],
+    text: &'a str,
+}
+
+struct PublishedNote {
+    path: String,
+    page_path: String,
+    markdown: String,
+    mtime_ms: i64,
+    tags: Vec<String>,
+    text: String,
+}
+
+fn frontmatter_lines(markdown: &str) -> Vec<&str> {
+    split_frontmatter(markdown)
+        .map(|(frontmatter, _)| frontmatter.lines().collect())
+        .unwrap_or_default()
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches(|c| c == '"' || c == '\'').trim()
+}
+
+/// `Some(true)` for `publish: true`, `Some(false)` for `publish: false`.
+fn frontmatter_publish(lines: &[&str]) -> Option<bool> {
+    let value = lines
+        .iter()
+        .find_map(|line| line.strip_prefix("publish:"))?;
+    match unquote(value).to_ascii_lowercase().as_str() {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Reads `tags: [a, b]`, `tags: a, b` and block lists of `- a` entries.
+fn frontmatter_tags(lines: &[&str]) -> Vec<String> {
+    let Some(idx) = lines.iter().position(|line| line.starts_with("tags:")) else {
+        return Vec::new();
+    };
+    let inline = lines[idx]["tags:".len()..].trim();
+    let raw: Vec<&str> = if inline.is_empty() {
+        lines[idx + 1..]
+            .iter()
+            .map(|line| line.trim())
+            .take_while(|line| line.starts_with('-'))
+            .map(|line| &line[1..])
+            .collect()
+    } else {
+        inline
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split([',', ' '])
+            .collect()
+    };
+    raw.into_iter()
+        .map(|tag| unquote(tag).trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+pub(crate) fn has_tag(tags: &[String], wanted: &str) -> bool {
+    let wanted = wanted.trim().trim_start_matches('#').to_lowercase();
+    tags.iter().any(|tag| {
+        let tag = tag.to_lowercase();
+        tag == wanted || tag.starts_with(&format!("{}/", wanted))
+    })
+}
+
+fn in_folder(path: &str, folder: &str) -> bool {
+    let folder = folder.trim().trim_matches('/');
+    folder.is_empty() || path.starts_with(&format!("{}/", folder))
+}
+
+/// Plain text of the note body for the search index, plus the inline
+/// `#tags` written in its prose.
+fn body_text_and_tags(body: &str) -> (String, Vec<String>) {
+    let arena = Arena::new();
+    let options = markdown_options();
+    let root = parse_document(&arena, body, &options);
+    let mut text = String::new();
+    let mut tags = Vec::new();
+    for node in root.descendants() {
+        let block = match &node.data.borrow().value {
+            NodeValue::Paragraph | NodeValue::TableCell => {
+                let block = collect_plain_text(node);
+                tags.extend(parse_tags(&block));
+                block
+            }
+            NodeValue::Heading(_) => collect_plain_text(node),
+            NodeValue::CodeBlock(code) => code.literal.trim().to_string(),
+            _ => continue,
+        };
+        if !block.is_empty() {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&block);
+        }
+    }
+    if let Some((idx, _)) = text.char_indices().nth(SEARCH_TEXT_LIMIT) {
+        text.truncate(idx);
+    }
+    (text, tags)
+}
+
+fn merge_tags(mut tags: Vec<String>, inline_tags: Vec<String>) -> Vec<String> {
+    for tag in inline_tags {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Frontmatter tags followed by the inline `#tags` of the body.
+pub(crate) fn note_tags(markdown: &str) -> Vec<String> {
+    let body = split_frontmatter(markdown)
+        .map(|(_, body)| body)
+        .unwrap_or(markdown);
+    let (_, inline_tags) = body_text_and_tags(body);
+    merge_tags(frontmatter_tags(&frontmatter_lines(markdown)), inline_tags)
+}
+
+/// Notes are published when they sit under a selected folder, carry a
+/// selected tag, or set `publish: true`. `publish: false` always wins.
+fn select_published_notes(
+    root: &Path,
+    selection: &PublishSelection,
+) -> Result<Vec<PublishedNote>, String> {
+    let mut out = Vec::new();
+    for path in collect_export_notes(root, "")? {
+        let abs = safe_vault_abs(root, &path)?;
+        let markdown =
+            fs::read_to_string(&abs).map_err(|e| format!("failed to read {}: {}", path, e))?;
+        let lines = frontmatter_lines(&markdown);
+        let flag = frontmatter_publish(&lines);
+        if flag == Some(false) {
+            continue;
+        }
+
+        let body = split_frontmatter(&markdown)
+            .map(|(_, body)| body)
+            .unwrap_or(&markdown);
+        let (text, inline_tags) = body_text_and_tags(body);
+        let tags = merge_tags(frontmatter_tags(&lines), inline_tags);
+
+        let selected = flag == Some(true)
+            || selection
+                .folders
+                .iter()
+                .any(|folder| in_folder(&path, folder))
+            || selection.tags.iter().any(|tag| has_tag(&tags, tag));
+        if !selected {
+            continue;
+        }
+
+        let (mtime_ms, _) = file_meta(&abs)?;
+        out.push(PublishedNote {
+            page_path: html_page_path(&path),
+            path,
+            markdown,
+            mtime_ms,
+            tags,
+            text,
+        });
+    }
+    Ok(out)
+}
+
+fn backlinks_section(
+    conn: &Connection,
+    note: &PublishedNote,
+    titles: &HashMap<&str, String>,
+) -> Result<String, String> {
+    let mut items = String::new();
+    for backlink in search_db::get_backlinks(conn, &note.path)? {
+        let Some(title) = titles.get(backlink.path.as_str()) else {
+            continue;
+        };
+        let href = relative_href(&note.path, &html_page_path(&backlink.path));
+        items.push_str(&format!(
+            "<li><a href=\"{}\">{}</a></li>\n",
+            escape_html(&href),
+            escape_html(title)
+        ));
+    }
+    if items.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(format!(
+        "<section class=\"backlinks\">\n<h2>Backlinks</h2>\n<ul>\n{}</ul>\n</section>\n",
+        items
+    ))
+}
+
+fn page_url(base_url: &str, page_path: &str) -> String {
+    format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        page_path.replace(' ', "%20")
+    )
+}
+
+fn timestamp(mtime_ms: i64) -> DateTime<Utc> {
+    DateTime::<Utc>::from_timestamp_millis(mtime_ms).unwrap_or_default()
+}
+
+fn render_sitemap(base_url: &str, pages: &[(&PublishedNote, &str)]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for (note, _) in pages {
+        out.push_str(&format!(
+            "<url><loc>{}</loc><lastmod>{}</lastmod></url>\n",
+            escape_html(&page_url(base_url, &note.page_path)),
+            timestamp(note.mtime_ms).format("%Y-%m-%d")
+        ));
+    }
+    out.push_str("</urlset>\n");
+    out
+}
+
+fn render_rss(site_title: &str, base_url: &str, pages: &[(&PublishedNote, &str)]) -> String {
+    let mut recent: Vec<&(&PublishedNote, &str)> = pages.iter().collect();
+    recent.sort_by_key(|(note, _)| std::cmp::Reverse(note.mtime_ms));
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n\
+<title>{title}</title>\n<link>{link}</link>\n<description>{title}</description>\n",
+        title = escape_html(site_title),
+        link = escape_html(&page_url(base_url, "")),
+    );
+    for (note, title) in recent.into_iter().take(RSS_ITEM_LIMIT) {
+        let url = escape_html(&page_url(base_url, &note.page_path));
+        out.push_str(&format!(
+            "<item><title>{}</title><link>{url}</link><guid>{url}</guid><pubDate>{}</pubDate></item>\n",
+            escape_html(title),
+            timestamp(note.mtime_ms).to_rfc2822(),
+        ));
+    }
+    out.push_str("</channel>\n</rss>\n");
+    out
+}
+
+fn is_safe_output_entry(rel: &str) -> bool {
+    let path = Path::new(rel);
+    !rel.is_empty() && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Deletes files listed in the previous manifest that this publish did not
+/// write, then records the new file list.
+fn sync_manifest(out_dir: &Path, written: &[String]) -> Result<Vec<String>, String> {
+    let manifest_path = out_dir.join(PUBLISH_MANIFEST);
+    let previous: Vec<String> = fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+    let current: HashSet<&str> = written.iter().map(String::as_str).collect();
+
+    let mut removed = Vec::new();
+    for stale in previous {
+        if current.contains(stale.as_str()) || !is_safe_output_entry(&stale) {
+            continue;
+        }
+        let abs = out_dir.join(&stale);
+        if abs.is_file() && fs::remove_file(&abs).is_ok() {
+            removed.push(stale);
+        }
+    }
+
+    let manifest = serde_json::to_string_pretty(written).map_err(|e| e.to_string())?;
+    write_output(out_dir, PUBLISH_MANIFEST, &manifest)?;
+    Ok(removed)
+}
+
+/// Builds a static site from the selected notes: one page per note with a
+/// backlinks section, an index, `search-index.json`, and when a base URL is
+/// given, `sitemap.xml` and an RSS `feed.xml`.
+pub(crate) fn publish_site_in(
+    conn: &Connection,
+    root: &Path,
+    options: &PublishOptions<'_>,
+) -> Result<PublishResult, String> {
+    let out_dir = resolve_output_dir(root, options.output_dir)?;
+    let notes = select_published_notes(root, options.selection)?;
+    if notes.is_empty() {
+        return Err("no notes are selected for publishing".to_string());
+    }
+    fs::create_dir_all(&out_dir).map_err(|e| e.to_string())?;
+
+    let published: HashSet<&str> = notes.iter().map(|n| n.path.as_str()).collect();
+    let mut warnings = Vec::new();
+    let mut assets: Vec<String> = Vec::new();
+    let mut rendered = Vec::with_capacity(notes.len());
+
+    for note in &notes {
+        let page = html::render_note(&note.path, &note.markdown, |target| {
+            published.contains(target)
+        });
+        for target in page.unresolved_links {
+            let exists = safe_vault_abs(root, &target).is_ok_and(|abs| abs.is_file());
+            warnings.push(PublishWarning {
+                kind: if exists {
+                    PublishWarningKind::UnpublishedLink
+                } else {
+                    PublishWarningKind::BrokenLink
+                },
+                path: Some(note.path.clone()),
+                target: Some(target),
+            });
+        }
+        for asset in page.assets {
+            if !assets.contains(&asset) {
+                assets.push(asset);
+            }
+        }
+        rendered.push((page.title, page.body_html));
+    }
+
+    let titles: HashMap<&str, String> = notes
+        .iter()
+        .zip(&rendered)
+        .map(|(note, (title, _))| (note.path.as_str(), title.clone()))
+        .collect();
+    let mut written = Vec::new();
+    for (note, (title, body_html)) in notes.iter().zip(&rendered) {
+        let body = format!("{}{}", body_html, backlinks_section(conn, note, &titles)?);
+        let page = html::wrap_page(title, &body, &relative_href(&note.path, INDEX_PAGE));
+        write_output(&out_dir, &note.page_path, &page)?;
+        written.push(note.page_path.clone());
+    }
+
+    let (copied, missing) = copy_assets(root, &out_dir, assets)?;
+    warnings.extend(missing.into_iter().map(|asset| PublishWarning {
+        kind: PublishWarningKind::MissingAsset,
+        path: None,
+        target: Some(asset),
+    }));
+    written.extend(copied.iter().cloned());
+
+    let site_title = options
+        .site_title
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(DEFAULT_SITE_TITLE);
+    let pages: Vec<(&PublishedNote, &str)> = notes
+        .iter()
+        .zip(&rendered)
+        .map(|(note, (title, _))| (note, title.as_str()))
+        .collect();
+
+    if !written.iter().any(|page| page == INDEX_PAGE) {
+        let entries: Vec<(String, String)> = pages
+            .iter()
+            .map(|(note, title)| (note.page_path.clone(), title.to_string()))
+            .collect();
+        write_output(&out_dir, INDEX_PAGE, &render_index(site_title, &entries))?;
+        written.push(INDEX_PAGE.to_string());
+    }
+
+    let search_entries: Vec<SearchEntry<'_>> = pages
+        .iter()
+        .map(|(note, title)| SearchEntry {
+            url: &note.page_path,
+            title,
+            tags: &note.tags,
+            text: &note.text,
+        })
+        .collect();
+    let search_index = serde_json::to_string(&search_entries).map_err(|e| e.to_string())?;
+    write_output(&out_dir, SEARCH_INDEX, &search_index)?;
+    written.push(SEARCH_INDEX.to_string());
+
+    match options.base_url.map(str::trim).filter(|u| !u.is_empty()) {
+        Some(base_url) => {
+            write_output(&out_dir, SITEMAP, &render_sitemap(base_url, &pages))?;
+            write_output(
+                &out_dir,
+                RSS_FEED,
+                &render_rss(site_title, base_url, &pages),
+            )?;
+            written.push(SITEMAP.to_string());
+            written.push(RSS_FEED.to_string());
+        }
+        None => warnings.push(PublishWarning {
+            kind: PublishWarningKind::MissingBaseUrl,
+            path: None,
+            target: None,
+        }),
+    }
+
+    let removed = sync_manifest(&out_dir, &written)?;
+    Ok(PublishResult {
+        output_dir: out_dir.to_string_lossy().to_string(),
+        pages: notes.into_iter().map(|note| note.page_path).collect(),
+        assets: copied,
+        removed,
+        warnings,
+    })
+}
+
+#[tauri::command]
+pub fn publish_site(args: PublishArgs, app: AppHandle) -> Result<PublishResult, String> {
+    log::info!(
+        "Publishing site vault_id={} output_dir={}",
+        args.vault_id,
+        args.output_dir
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    let options = PublishOptions {
+        output_dir: &args.output_dir,
+        selection: &args.selection,
+        site_title: args.site_title.as_deref(),
+        base_url: args.base_url.as_deref(),
+    };
+    search_service::with_read_conn(&app, &args.vault_id, |conn| {
+        publish_site_in(conn, &root, &options)
+    })
+    .map_err(String::from)
+}
@@ -0,0 +1,432 @@
+use crate::features::import::service::sanitize_file_name;
+use crate::features::journal::service as journal;
+use crate::features::notes::service::{
+    atomic_write, create_note_file, invalidate_note_parent_folder_cache, safe_vault_abs,
+    safe_vault_abs_for_write,
+};
+use crate::features::search::db as search_db;
+use crate::features::search::link_parser::{
+    self, collect_plain_text, compute_line_starts, decode_percent_sequences, format_wiki_target,
+    markdown_options, source_dir_from_path, LinkRetarget,
+};
+use crate::features::search::service as search_service;
+use crate::features::templates::expand::split_frontmatter;
+use crate::shared::storage;
+use comrak::nodes::NodeValue;
+use comrak::{parse_document, Arena};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+#[derive(Debug, Deserialize)]
+pub struct NoteMergeArgs {
+    pub vault_id: String,
+    /// The note that is kept and receives the other note's content.
+    pub target_path: String,
+    /// The note that is appended and then deleted.
+    pub source_path: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitReplacement {
+    #[default]
+    Link,
+    Embed,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NoteSplitArgs {
+    pub vault_id: String,
+    pub note_path: String,
+    pub heading: String,
+    /// Defaults to the heading text next to the original note.
+    #[serde(default)]
+    pub new_path: Option<String>,
+    #[serde(default)]
+    pub replace_with: SplitReplacement,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeResult {
+    pub target_path: String,
+    pub removed_path: String,
+    /// Other notes whose links were pointed at the target.
+    pub rewritten: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitResult {
+    pub note_path: String,
+    pub new_path: String,
+    /// Other notes whose heading links were pointed at the new note.
+    pub rewritten: Vec<String>,
+}
+
+struct Section {
+    title: String,
+    /// Byte range of the whole section, heading included.
+    start: usize,
+    end: usize,
+    /// Byte offset of the first line after the heading.
+    body_start: usize,
+    /// Slugs of the headings nested inside the section.
+    nested: HashSet<String>,
+}
+
+fn heading_slug(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.trim().chars() {
+        if c.is_alphanumeric() {
+            out.extend(c.to_lowercase());
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    out.trim_matches('-').to_string()
+}
+
+fn fragment_slug(fragment: &str) -> String {
+    heading_slug(&decode_percent_sequences(fragment))
+}
+
+fn ensure_note_path(path: &str) -> String {
+    let path = path.trim().trim_matches('/');
+    if path.to_ascii_lowercase().ends_with(".md") {
+        path.to_string()
+    } else {
+        format!("{}.md", path)
+    }
+}
+
+fn frontmatter_len(markdown: &str) -> usize {
+    split_frontmatter(markdown)
+        .map(|(_, body)| markdown.len() - body.len())
+        .unwrap_or(0)
+}
+
+/// Finds the top-level heading matching `heading` and the range it owns: up
+/// to the next heading of the same or a higher level.
+fn find_section(markdown: &str, heading: &str) -> Result<Section, String> {
+    let wanted = heading_slug(heading.trim().trim_start_matches('#'));
+    if wanted.is_empty() {
+        return Err("heading is empty".to_string());
+    }
+    let offset = frontmatter_len(markdown);
+    let body = &markdown[offset..];
+    let line_starts = compute_line_starts(body);
+    let line_offset = |line: usize| {
+        line_starts
+            .get(line.saturating_sub(1))
+            .copied()
+            .unwrap_or(body.len())
+    };
+
+    let arena = Arena::new();
+    let options = markdown_options();
+    let root = parse_document(&arena, body, &options);
+    let mut headings = Vec::new();
+    for node in root.children() {
+        let data = node.data.borrow();
+        if let NodeValue::Heading(ref h) = data.value {
+            headings.push((
+                h.level,
+                data.sourcepos.start.line,
+                data.sourcepos.end.line,
+                collect_plain_text(node),
+            ));
+        }
+    }
+
+    let index = headings
+        .iter()
+        .position(|(_, _, _, title)| heading_slug(title) == wanted)
+        .ok_or("heading not found")?;
+    let (level, start_line, end_line, ref title) = headings[index];
+    let following = &headings[index + 1..];
+    let close = following
+        .iter()
+        .position(|(other, _, _, _)| *other <= level)
+        .unwrap_or(following.len());
+    let end = following
+        .get(close)
+        .map(|(_, line, _, _)| line_offset(*line))
+        .unwrap_or(body.len());
+
+    let outside: HashSet<String> = headings[..index]
+        .iter()
+        .chain(&following[close..])
+        .map(|(_, _, _, title)| heading_slug(title))
+        .collect();
+    let nested = following[..close]
+        .iter()
+        .map(|(_, _, _, title)| heading_slug(title))
+        .filter(|slug| !outside.contains(slug))
+        .collect();
+
+    Ok(Section {
+        title: title.clone(),
+        start: offset + line_offset(start_line),
+        end: offset + end,
+        body_start: offset + line_offset(end_line + 1),
+        nested,
+    })
+}
+
+/// Rewrites links in `paths` with `retarget`, writing the changed notes and
+/// appending a journal op for each one written.
+fn rewrite_backlinks(
+    root: &Path,
+    paths: &[String],
+    retarget: &LinkRetarget<'_>,
+    ops: &mut Vec<journal::JournalOp>,
+) -> Result<Vec<String>, String> {
+    let mut changed = Vec::new();
+    for path in paths {
+        let abs = safe_vault_abs_for_write(root, path)?;
+        let Ok(markdown) = fs::read_to_string(&abs) else {
+            continue;
+        };
+        let result = link_parser::rewrite_links_with(&markdown, path, path, retarget);
+        if !result.changed {
+            continue;
+        }
+        let op = journal::write_op(
+            root,
+            path,
+            Some(markdown.as_bytes()),
+            Some(result.markdown.as_bytes()),
+        )?;
+        atomic_write(&abs, &result.markdown)?;
+        ops.push(op);
+        changed.push(path.clone());
+    }
+    Ok(changed)
+}
+
+fn backlink_sources(conn: &Connection, path: &str, skip: &[&str]) -> Result<Vec<String>, String> {
+    Ok(search_db::get_backlinks(conn, path)?
+        .into_iter()
+        .map(|note| note.path)
+        .filter(|source| !skip.contains(&source.as_str()))
+        .collect())
+}
+
+/// Appends `source_path` to `target_path`, deletes the source and points
+/// every link to it (found through the `outlinks` table) at the target,
+/// keeping heading fragments since the headings move along. The source's
+/// frontmatter is dropped. Recorded as one journal entry, which also covers
+/// the files already written when a later step fails.
+pub(crate) fn merge_notes_in(
+    conn: &Connection,
+    root: &Path,
+    target_path: &str,
+    source_path: &str,
+) -> Result<MergeResult, String> {
+    if target_path == source_path {
+        return Err("cannot merge a note into itself".to_string());
+    }
+    let target_abs = safe_vault_abs(root, target_path)?;
+    let source_abs = safe_vault_abs(root, source_path)?;
+    let target_md = fs::read_to_string(&target_abs).map_err(|e| e.to_string())?;
+    let source_md = fs::read_to_string(&source_abs).map_err(|e| e.to_string())?;
+
+    let retarget = |resolved: &str, fragment: Option<&str>| {
+        (resolved == source_path).then(|| (target_path.to_string(), fragment.map(str::to_string)))
+    };
+    let kept = link_parser::rewrite_links_with(&target_md, target_path, target_path, &retarget);
+    let appended = link_parser::rewrite_links_with(
+        &source_md[frontmatter_len(&source_md)..],
+        source_path,
+        target_path,
+        &retarget,
+    );
+    let merged = format!(
+        "{}\n\n{}\n",
+        kept.markdown.trim_end(),
+        appended.markdown.trim()
+    );
+
+    let sources = backlink_sources(conn, source_path, &[target_path, source_path])?;
+    let mut ops = Vec::new();
+    let outcome = (|| {
+        let op = journal::write_op(
+            root,
+            target_path,
+            Some(target_md.as_bytes()),
+            Some(merged.as_bytes()),
+        )?;
+        atomic_write(&target_abs, &merged)?;
+        ops.push(op);
+        let rewritten = rewrite_backlinks(root, &sources, &retarget, &mut ops)?;
+        let op = journal::write_op(root, source_path, Some(source_md.as_bytes()), None)?;
+        fs::remove_file(&source_abs).map_err(|e| e.to_string())?;
+        ops.push(op);
+        Ok::<_, String>(rewritten)
+    })();
+    journal::record_operation_or_warn(root, "merge_notes", ops);
+    let rewritten = outcome?;
+
+    Ok(MergeResult {
+        target_path: target_path.to_string(),
+        removed_path: source_path.to_string(),
+        rewritten,
+    })
+}
+
+/// Moves the section under `heading` into a new note, leaves a link or embed
+/// in its place and retargets `[[note#Heading]]` links (and links to headings
+/// only found inside the section) to the new note. Recorded as one journal
+/// entry, which also covers the files already written when a later step
+/// fails.
+pub(crate) fn split_note_in(
+    conn: &Connection,
+    root: &Path,
+    note_path: &str,
+    heading: &str,
+    new_path: Option<&str>,
+    replace_with: SplitReplacement,
+) -> Result<SplitResult, String> {
+    let abs = safe_vault_abs(root, note_path)?;
+    let markdown = fs::read_to_string(&abs).map_err(|e| e.to_string())?;
+    let section = find_section(&markdown, heading)?;
+
+    let new_path = match new_path {
+        Some(path) if !path.trim().is_empty() => ensure_note_path(path),
+        _ => {
+            let dir = source_dir_from_path(note_path);
+            let name = format!("{}.md", sanitize_file_name(&section.title));
+            if dir.is_empty() {
+                name
+            } else {
+                format!("{}/{}", dir, name)
+            }
+        }
+    };
+    if new_path == note_path {
+        return Err("new note must differ from the original".to_string());
+    }
+
+    let split_slug = heading_slug(&section.title);
+    let retarget = |resolved: &str, fragment: Option<&str>| {
+        if resolved != note_path {
+            return None;
+        }
+        let slug = fragment_slug(fragment?);
+        if slug == split_slug {
+            Some((new_path.clone(), None))
+        } else if section.nested.contains(&slug) {
+            Some((new_path.clone(), fragment.map(str::to_string)))
+        } else {
+            None
+        }
+    };
+
+    let extracted = format!(
+        "# {}\n{}",
+        section.title,
+        &markdown[section.body_start.min(section.end)..section.end]
+    );
+    let extracted = link_parser::rewrite_links_with(&extracted, note_path, &new_path, &retarget);
+    let new_markdown = format!("{}\n", extracted.markdown.trim_end());
+
+    let link = format!("[[{}]]", format_wiki_target(note_path, &new_path, false));
+    let placeholder = match replace_with {
+        SplitReplacement::Link => link,
+        SplitReplacement::Embed => format!("!{}", link),
+    };
+    let separator = if section.end < markdown.len() {
+        "\n\n"
+    } else {
+        "\n"
+    };
+    let remaining = format!(
+        "{}{}{}{}",
+        &markdown[..section.start],
+        placeholder,
+        separator,
+        &markdown[section.end..]
+    );
+    let remaining = link_parser::rewrite_links_with(&remaining, note_path, note_path, &retarget);
+
+    let sources = backlink_sources(conn, note_path, &[note_path, &new_path])?;
+    let mut ops = Vec::new();
+    let outcome = (|| {
+        let op = journal::write_op(root, &new_path, None, Some(new_markdown.as_bytes()))?;
+        create_note_file(root, &new_path, &new_markdown)?;
+        ops.push(op);
+        let op = journal::write_op(
+            root,
+            note_path,
+            Some(markdown.as_bytes()),
+            Some(remaining.markdown.as_bytes()),
+        )?;
+        atomic_write(&abs, &remaining.markdown)?;
+        ops.push(op);
+        rewrite_backlinks(root, &sources, &retarget, &mut ops)
+    })();
+    journal::record_operation_or_warn(root, "split_note", ops);
+    let rewritten = outcome?;
+
+    Ok(SplitResult {
+        note_path: note_path.to_string(),
+        new_path,
+        rewritten,
+    })
+}
+
+#[tauri::command]
+pub fn merge_notes(args: NoteMergeArgs, app: AppHandle) -> Result<MergeResult, String> {
+    log::info!(
+        "Merging notes vault_id={} source={} target={}",
+        args.vault_id,
+        args.source_path,
+        args.target_path
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    let result = search_service::with_read_conn(&app, &args.vault_id, |conn| {
+        merge_notes_in(conn, &root, &args.target_path, &args.source_path)
+    })?;
+    search_service::index_remove_note(
+        app.clone(),
+        args.vault_id.clone(),
+        result.removed_path.clone(),
+    )?;
+    for path in std::iter::once(&result.target_path).chain(&result.rewritten) {
+        search_service::index_upsert_note(app.clone(), args.vault_id.clone(), path.clone())?;
+    }
+    invalidate_note_parent_folder_cache(&args.vault_id, &result.removed_path);
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn split_note(args: NoteSplitArgs, app: AppHandle) -> Result<SplitResult, String> {
+    log::info!(
+        "Splitting note vault_id={} note={} heading={}",
+        args.vault_id,
+        args.note_path,
+        args.heading
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    let result = search_service::with_read_conn(&app, &args.vault_id, |conn| {
+        split_note_in(
+            conn,
+            &root,
+            &args.note_path,
+            &args.heading,
+            args.new_path.as_deref(),
+            args.replace_with,
+        )
+    })?;
+    for path in [&result.new_path, &result.note_path]
+        .into_iter()
+        .chain(&result.rewritten)
+    {
+        search_service::index_upsert_note(app.clone(), args.vault_id.clone(), path.clone())?;
+    }
+    invalidate_note_parent_folder_cache(&args.vault_id, &result.new_path);
+    Ok(result)
+}
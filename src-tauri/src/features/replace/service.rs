@@ -0,0 +1,434 @@
+use crate::features::journal::service::{self as journal, JournalChange};
+use crate::features::notes::service::{atomic_write, file_meta, safe_vault_abs_for_write};
+use crate::features::publish::service::{has_tag, note_tags};
+use crate::features::search::db as search_db;
+use crate::features::search::service as search_service;
+use crate::shared::storage;
+use regex::{Captures, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Bytes of surrounding line kept on each side of a match in previews.
+const CONTEXT_RADIUS: usize = 80;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReplaceQuery {
+    pub pattern: String,
+    #[serde(default)]
+    pub replacement: String,
+    /// Treats `pattern` as a regular expression; `$1`/`${name}` in the
+    /// replacement expand to capture groups.
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub whole_word: bool,
+    /// Notes or folders to search; empty searches the whole vault.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Only notes carrying one of these tags (or a nested tag).
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplacePreviewArgs {
+    pub vault_id: String,
+    pub query: ReplaceQuery,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplaceFileGuard {
+    pub path: String,
+    pub expected_mtime_ms: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplaceApplyArgs {
+    pub vault_id: String,
+    pub query: ReplaceQuery,
+    /// The previewed files to change, with the mtime seen in the preview.
+    pub files: Vec<ReplaceFileGuard>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplaceUndoArgs {
+    pub vault_id: String,
+    pub batch_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplaceMatch {
+    /// Byte range of the match in the note.
+    pub start: usize,
+    pub end: usize,
+    /// 1-based line the match starts on.
+    pub line: usize,
+    /// The part of the line around the match.
+    pub context: String,
+    /// Byte range of the match within `context`.
+    pub context_start: usize,
+    pub context_end: usize,
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplaceFilePreview {
+    pub path: String,
+    pub mtime_ms: i64,
+    pub matches: Vec<ReplaceMatch>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReplacePreview {
+    pub files: Vec<ReplaceFilePreview>,
+    pub total_matches: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplacedFile {
+    pub path: String,
+    pub replacements: usize,
+    pub mtime_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplaceConflict {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplaceApplyResult {
+    /// Journal entry for `vault_replace_undo`; `None` when nothing was
+    /// written.
+    pub batch_id: Option<String>,
+    pub files: Vec<ReplacedFile>,
+    pub conflicts: Vec<ReplaceConflict>,
+    /// Set when the batch stopped at a failed write or could not be
+    /// journaled; `files` still lists everything that was written.
+    pub error: Option<String>,
+}
+
+fn build_regex(query: &ReplaceQuery) -> Result<Regex, String> {
+    if query.pattern.is_empty() {
+        return Err("search pattern is empty".to_string());
+    }
+    let pattern = if query.regex {
+        query.pattern.clone()
+    } else {
+        regex::escape(&query.pattern)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!query.case_sensitive)
+        .multi_line(true)
+        .build()
+        .map_err(|e| format!("invalid pattern: {}", e))
+}
+
+fn replacement_for(query: &ReplaceQuery, caps: &Captures<'_>) -> String {
+    if query.regex {
+        let mut out = String::new();
+        caps.expand(&query.replacement, &mut out);
+        out
+    } else {
+        query.replacement.clone()
+    }
+}
+
+fn in_scope(path: &str, scopes: &[String]) -> bool {
+    let scopes: Vec<&str> = scopes
+        .iter()
+        .map(|scope| scope.trim().trim_matches('/'))
+        .filter(|scope| !scope.is_empty())
+        .collect();
+    scopes.is_empty()
+        || scopes
+            .iter()
+            .any(|scope| path == *scope || path.starts_with(&format!("{}/", scope)))
+}
+
+fn floor_char_boundary(text: &str, mut idx: usize) -> usize {
+    while !text.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+fn ceil_char_boundary(text: &str, mut idx: usize) -> usize {
+    while !text.is_char_boundary(idx) {
+        idx += 1;
+    }
+    idx
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether `start..end` is bounded by the edge of the text or a non-word
+/// character on both sides. Checked outside the regex because `\b` never
+/// matches next to punctuation, so `c++` or `-foo` could not match at all.
+fn is_whole_word(text: &str, start: usize, end: usize) -> bool {
+    !text[..start].chars().next_back().is_some_and(is_word_char)
+        && !text[end..].chars().next().is_some_and(is_word_char)
+}
+
+/// Non-empty matches in order, limited to whole words when the query asks.
+/// A rejected match resumes the search one character in rather than after
+/// the whole match, so a shorter whole-word match inside it is not lost.
+fn query_matches<'t>(regex: &Regex, query: &ReplaceQuery, text: &'t str) -> Vec<Captures<'t>> {
+    let mut out = Vec::new();
+    let mut pos = 0;
+    while pos <= text.len() {
+        let Some(caps) = regex.captures_at(text, pos) else {
+            break;
+        };
+        let Some(found) = caps.get(0) else {
+            break;
+        };
+        let rejected = found.is_empty()
+            || (query.whole_word && !is_whole_word(text, found.start(), found.end()));
+        if rejected {
+            if found.start() == text.len() {
+                break;
+            }
+            pos = ceil_char_boundary(text, found.start() + 1);
+            continue;
+        }
+        pos = found.end();
+        out.push(caps);
+    }
+    out
+}
+
+fn find_matches(regex: &Regex, query: &ReplaceQuery, markdown: &str) -> Vec<ReplaceMatch> {
+    let mut matches = Vec::new();
+    for caps in query_matches(regex, query, markdown) {
+        let Some(found) = caps.get(0) else {
+            continue;
+        };
+        let line_start = markdown[..found.start()]
+            .rfind('\n')
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+        let line_end = markdown[found.start()..]
+            .find('\n')
+            .map(|idx| found.start() + idx)
+            .unwrap_or(markdown.len());
+        let line_text = &markdown[line_start..line_end];
+        let match_start = found.start() - line_start;
+        let match_end = found.end().min(line_end) - line_start;
+        let window_start =
+            floor_char_boundary(line_text, match_start.saturating_sub(CONTEXT_RADIUS));
+        let window_end =
+            ceil_char_boundary(line_text, (match_end + CONTEXT_RADIUS).min(line_text.len()));
+        matches.push(ReplaceMatch {
+            start: found.start(),
+            end: found.end(),
+            line: markdown[..found.start()].matches('\n').count() + 1,
+            context: line_text[window_start..window_end].to_string(),
+            context_start: match_start - window_start,
+            context_end: match_end - window_start,
+            replacement: replacement_for(query, &caps),
+        });
+    }
+    matches
+}
+
+/// Applies every non-empty match, returning the new text and the count.
+fn replace_all(regex: &Regex, query: &ReplaceQuery, markdown: &str) -> (String, usize) {
+    let mut out = String::with_capacity(markdown.len());
+    let mut last = 0;
+    let mut count = 0;
+    for caps in query_matches(regex, query, markdown) {
+        let Some(found) = caps.get(0) else {
+            continue;
+        };
+        out.push_str(&markdown[last..found.start()]);
+        out.push_str(&replacement_for(query, &caps));
+        last = found.end();
+        count += 1;
+    }
+    out.push_str(&markdown[last..]);
+    (out, count)
+}
+
+fn scoped_notes(root: &Path, query: &ReplaceQuery) -> Result<Vec<(String, String)>, String> {
+    let mut out = Vec::new();
+    for abs in search_db::list_markdown_files(root) {
+        let Ok(rel) = abs.strip_prefix(root) else {
+            continue;
+        };
+        let path = storage::normalize_relative_path(rel);
+        if !in_scope(&path, &query.paths) {
+            continue;
+        }
+        let Ok(markdown) = fs::read_to_string(&abs) else {
+            continue;
+        };
+        if !query.tags.is_empty() {
+            let tags = note_tags(&markdown);
+            if !query.tags.iter().any(|tag| has_tag(&tags, tag)) {
+                continue;
+            }
+        }
+        out.push((path, markdown));
+    }
+    Ok(out)
+}
+
+/// Lists every match the replace would touch without writing anything.
+pub(crate) fn preview_replace_at(
+    root: &Path,
+    query: &ReplaceQuery,
+) -> Result<ReplacePreview, String> {
+    let regex = build_regex(query)?;
+    let mut preview = ReplacePreview::default();
+    for (path, markdown) in scoped_notes(root, query)? {
+        let matches = find_matches(&regex, query, &markdown);
+        if matches.is_empty() {
+            continue;
+        }
+        let (mtime_ms, _) = file_meta(&root.join(&path))?;
+        preview.total_matches += matches.len();
+        preview.files.push(ReplaceFilePreview {
+            path,
+            mtime_ms,
+            matches,
+        });
+    }
+    Ok(preview)
+}
+
+/// Replaces in the guarded files only. Files changed since the preview are
+/// reported as conflicts and left alone. Every file's before and after
+/// content goes into the journal before anything is written, and the files
+/// written are recorded as one entry so the whole batch can be undone. A
+/// failed write stops the batch but still returns what was written, with
+/// `error` set, so the caller can reindex those files.
+pub(crate) fn apply_replace_at(
+    root: &Path,
+    query: &ReplaceQuery,
+    guards: &[ReplaceFileGuard],
+) -> Result<ReplaceApplyResult, String> {
+    let regex = build_regex(query)?;
+    let mut planned = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for guard in guards {
+        let abs = safe_vault_abs_for_write(root, &guard.path)?;
+        let conflict = match file_meta(&abs) {
+            Ok((disk_mtime, _)) if disk_mtime != guard.expected_mtime_ms => {
+                Some("conflict:mtime_mismatch")
+            }
+            Err(_) => Some("conflict:file_missing"),
+            _ => None,
+        };
+        if let Some(reason) = conflict {
+            conflicts.push(ReplaceConflict {
+                path: guard.path.clone(),
+                reason: reason.to_string(),
+            });
+            continue;
+        }
+
+        let before = fs::read_to_string(&abs).map_err(|e| e.to_string())?;
+        let (after, replacements) = replace_all(&regex, query, &before);
+        if replacements == 0 {
+            continue;
+        }
+        let op = journal::write_op(
+            root,
+            &guard.path,
+            Some(before.as_bytes()),
+            Some(after.as_bytes()),
+        )?;
+        planned.push((guard, abs, after, replacements, op));
+    }
+
+    let mut files = Vec::new();
+    let mut ops = Vec::new();
+    let mut error = None;
+    for (guard, abs, after, replacements, op) in planned {
+        if let Err(write_error) = atomic_write(&abs, &after) {
+            error = Some(format!("failed to write {}: {}", guard.path, write_error));
+            break;
+        }
+        ops.push(op);
+        let mtime_ms = file_meta(&abs).map(|(mtime, _)| mtime).unwrap_or_default();
+        files.push(ReplacedFile {
+            path: guard.path.clone(),
+            replacements,
+            mtime_ms,
+        });
+    }
+
+    let batch_id = match journal::record_operation(root, "replace", ops) {
+        Ok(batch_id) => batch_id,
+        Err(journal_error) => {
+            let message = format!("failed to record replace in journal: {}", journal_error);
+            error = Some(match error {
+                Some(error) => format!("{}; {}", error, message),
+                None => message,
+            });
+            None
+        }
+    };
+    Ok(ReplaceApplyResult {
+        batch_id,
+        files,
+        conflicts,
+        error,
+    })
+}
+
+#[tauri::command]
+pub fn vault_replace_preview(
+    args: ReplacePreviewArgs,
+    app: AppHandle,
+) -> Result<ReplacePreview, String> {
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    preview_replace_at(&root, &args.query)
+}
+
+#[tauri::command]
+pub fn vault_replace_apply(
+    args: ReplaceApplyArgs,
+    app: AppHandle,
+) -> Result<ReplaceApplyResult, String> {
+    log::info!(
+        "Replacing across vault vault_id={} files={}",
+        args.vault_id,
+        args.files.len()
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    let result = apply_replace_at(&root, &args.query, &args.files)?;
+    if let Some(error) = &result.error {
+        log::warn!("Replace stopped early: {}", error);
+    }
+    for file in &result.files {
+        if let Err(error) =
+            search_service::index_upsert_note(app.clone(), args.vault_id.clone(), file.path.clone())
+        {
+            log::warn!("Failed to reindex {} after replace: {}", file.path, error);
+        }
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn vault_replace_undo(args: ReplaceUndoArgs, app: AppHandle) -> Result<JournalChange, String> {
+    log::info!(
+        "Undoing replace batch vault_id={} batch_id={}",
+        args.vault_id,
+        args.batch_id
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    let change = journal::undo_at(&root, Some(&args.batch_id))?;
+    journal::sync_after_change(&app, &args.vault_id, &change);
+    Ok(change)
+}
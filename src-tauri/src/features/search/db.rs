@@ -1,9 +1,13 @@
 use crate::features::notes::service as notes_service;
+use crate::features::plugins::host as plugins;
 use crate::features::search::link_parser;
-use crate::features::search::model::{IndexNoteMeta, SearchHit, SearchScope};
+use crate::features::search::model::{IndexNoteMeta, SearchHit, SearchScope, TaskHit, TaskQuery};
+use crate::features::tasks::parser::{self as task_parser, ParsedTask, TaskPriority};
 use crate::shared::constants;
 use crate::shared::storage;
-use rusqlite::{params, Connection};
+use crate::shared::vault_context::VaultContext;
+use crate::shared::vault_ignore::VaultIgnore;
+use rusqlite::{params, Connection, OpenFlags};
 use serde::Serialize;
 use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
@@ -42,6 +46,21 @@ pub(crate) fn internal_link_targets(markdown: &str, source_path: &str) -> Vec<St
     link_parser::internal_link_targets(markdown, source_path)
 }
 
+/// Link targets for a note: the parsed markdown links plus any returned by
+/// `link_extract` plugins.
+pub(crate) fn note_link_targets(
+    ctx: &VaultContext,
+    markdown: &str,
+    source_path: &str,
+) -> Vec<String> {
+    let mut targets = internal_link_targets(markdown, source_path);
+    let plugins = plugins::active();
+    if !plugins.is_empty() {
+        targets.extend(plugins.link_targets(ctx, source_path, markdown));
+    }
+    targets
+}
+
 pub type LocalLinksSnapshot = link_parser::LocalLinksSnapshot;
 
 pub fn extract_local_links_snapshot(markdown: &str, source_path: &str) -> LocalLinksSnapshot {
@@ -49,13 +68,11 @@ pub fn extract_local_links_snapshot(markdown: &str, source_path: &str) -> LocalL
 }
 
 pub(crate) fn list_markdown_files(root: &Path) -> Vec<PathBuf> {
+    let ignore = VaultIgnore::load(root);
     let mut files: Vec<PathBuf> = WalkDir::new(root)
         .follow_links(false)
         .into_iter()
-        .filter_entry(|e| {
-            let name = e.file_name().to_string_lossy();
-            !constants::is_excluded_folder(&name)
-        })
+        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir()))
         .filter_map(|e| e.ok())
         .filter(|e| e.file_type().is_file())
         .filter(|e| e.path().extension().and_then(|x| x.to_str()) == Some("md"))
@@ -88,7 +105,7 @@ pub(crate) fn extract_meta(abs: &Path, vault_root: &Path) -> Result<IndexNoteMet
     })
 }
 
-fn db_path(vault_root: &Path) -> PathBuf {
+pub(crate) fn db_path(vault_root: &Path) -> PathBuf {
     vault_root.join(constants::APP_DIR).join("search.db")
 }
 
@@ -102,7 +119,23 @@ fn fts_schema_needs_migration(conn: &Connection) -> bool {
     }
 }
 
+fn table_exists(conn: &Connection, name: &str) -> bool {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type='table' AND name=?1",
+        params![name],
+        |_| Ok(()),
+    )
+    .is_ok()
+}
+
 fn init_schema(conn: &Connection) -> Result<(), String> {
+    // Notes indexed before the tasks table existed have no task rows; clearing
+    // the manifest makes the next sync re-read every note.
+    if table_exists(conn, "notes") && !table_exists(conn, "tasks") {
+        conn.execute_batch("DELETE FROM notes;")
+            .map_err(|e| e.to_string())?;
+    }
+
     if fts_schema_needs_migration(conn) {
         conn.execute_batch(
             "DROP TABLE IF EXISTS notes_fts;
@@ -131,7 +164,21 @@ fn init_schema(conn: &Connection) -> Result<(), String> {
             PRIMARY KEY (source_path, target_path)
         );
 
-        CREATE INDEX IF NOT EXISTS idx_outlinks_target ON outlinks(target_path);"
+        CREATE INDEX IF NOT EXISTS idx_outlinks_target ON outlinks(target_path);
+
+        CREATE TABLE IF NOT EXISTS tasks (
+            path TEXT NOT NULL,
+            line INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            completed INTEGER NOT NULL,
+            heading TEXT,
+            due TEXT,
+            priority INTEGER,
+            tags TEXT NOT NULL,
+            PRIMARY KEY (path, line)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_tasks_open_due ON tasks(completed, due);"
     ))
     .map_err(|e| e.to_string())
 }
@@ -150,6 +197,23 @@ pub fn open_search_db(vault_root: &Path) -> Result<Connection, String> {
     Ok(conn)
 }
 
+/// Opens an existing index read-only, without creating or migrating it.
+/// `None` when the vault has not been indexed.
+pub fn open_search_db_read_only(vault_root: &Path) -> Result<Option<Connection>, String> {
+    let path = db_path(vault_root);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let conn = Connection::open_with_flags(
+        &path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| e.to_string())?;
+    conn.busy_timeout(std::time::Duration::from_millis(5000))
+        .map_err(|e| e.to_string())?;
+    Ok(Some(conn))
+}
+
 pub fn upsert_note(conn: &Connection, meta: &IndexNoteMeta, body: &str) -> Result<(), String> {
     conn.execute(
         "REPLACE INTO notes (path, title, mtime_ms, size_bytes) VALUES (?1, ?2, ?3, ?4)",
@@ -166,6 +230,32 @@ pub fn upsert_note(conn: &Connection, meta: &IndexNoteMeta, body: &str) -> Resul
     )
     .map_err(|e| e.to_string())?;
 
+    set_tasks(conn, &meta.path, &task_parser::extract_tasks(body))
+}
+
+pub fn set_tasks(conn: &Connection, path: &str, tasks: &[ParsedTask]) -> Result<(), String> {
+    conn.execute("DELETE FROM tasks WHERE path = ?1", params![path])
+        .map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare_cached(
+            "INSERT OR REPLACE INTO tasks (path, line, text, completed, heading, due, priority, tags)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )
+        .map_err(|e| e.to_string())?;
+    for task in tasks {
+        let tags = serde_json::to_string(&task.tags).map_err(|e| e.to_string())?;
+        stmt.execute(params![
+            path,
+            task.line as i64,
+            task.text,
+            task.completed,
+            task.heading,
+            task.due,
+            task.priority.map(|p| p.rank()),
+            tags
+        ])
+        .map_err(|e| e.to_string())?;
+    }
     Ok(())
 }
 
@@ -176,6 +266,8 @@ pub fn remove_note(conn: &Connection, path: &str) -> Result<(), String> {
         .map_err(|e| e.to_string())?;
     conn.execute("DELETE FROM outlinks WHERE source_path = ?1", params![path])
         .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM tasks WHERE path = ?1", params![path])
+        .map_err(|e| e.to_string())?;
     Ok(())
 }
 
@@ -209,6 +301,12 @@ pub fn remove_notes_by_prefix(conn: &Connection, prefix: &str) -> Result<(), Str
                 params![like_pattern],
             )
         })
+        .and_then(|_| {
+            conn.execute(
+                "DELETE FROM tasks WHERE path LIKE ?1 ESCAPE '\\'",
+                params![like_pattern],
+            )
+        })
         .and_then(|_| {
             conn.execute(
                 "DELETE FROM notes WHERE path LIKE ?1 ESCAPE '\\'",
@@ -242,6 +340,28 @@ pub fn list_note_paths_by_prefix(conn: &Connection, prefix: &str) -> Result<Vec<
         .map_err(|e| e.to_string())
 }
 
+pub fn list_note_titles_by_prefix(
+    conn: &Connection,
+    prefix: &str,
+) -> Result<Vec<(String, String)>, String> {
+    let like_pattern = like_prefix_pattern(prefix);
+    let mut stmt = conn
+        .prepare(
+            "SELECT path, title
+             FROM notes
+             WHERE path LIKE ?1 ESCAPE '\\'
+             ORDER BY path",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![like_pattern], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
 #[derive(Debug, Serialize)]
 pub struct IndexResult {
     pub total: usize,
@@ -324,6 +444,18 @@ pub fn compute_sync_plan(
     }
 }
 
+/// What a sync would change, without applying it. `None` when the vault
+/// has no index yet.
+pub fn pending_sync_plan(vault_root: &Path) -> Result<Option<SyncPlan>, String> {
+    if !db_path(vault_root).is_file() {
+        return Ok(None);
+    }
+    let conn = open_search_db(vault_root)?;
+    let manifest = get_manifest(&conn)?;
+    let disk_files = list_markdown_files(vault_root);
+    Ok(Some(compute_sync_plan(vault_root, &manifest, &disk_files)))
+}
+
 const BATCH_SIZE: usize = 100;
 
 fn resolve_batch_outlinks(
@@ -349,17 +481,20 @@ fn resolve_batch_outlinks(
 
 pub fn rebuild_index(
     conn: &Connection,
-    vault_root: &Path,
+    ctx: &VaultContext,
     cancel: &AtomicBool,
     on_progress: &dyn Fn(usize, usize),
     yield_fn: &mut dyn FnMut(),
 ) -> Result<IndexResult, String> {
+    let vault_root = ctx.root();
     conn.execute("DELETE FROM notes", [])
         .map_err(|e| e.to_string())?;
     conn.execute("DELETE FROM notes_fts", [])
         .map_err(|e| e.to_string())?;
     conn.execute("DELETE FROM outlinks", [])
         .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM tasks", [])
+        .map_err(|e| e.to_string())?;
 
     let paths = list_markdown_files(vault_root);
     let total = paths.len();
@@ -392,7 +527,7 @@ pub fn rebuild_index(
                 }
             };
             upsert_note(conn, &meta, &markdown)?;
-            let targets = internal_link_targets(&markdown, &meta.path);
+            let targets = note_link_targets(ctx, &markdown, &meta.path);
             if !targets.is_empty() {
                 pending_links.push((meta.path.clone(), targets));
             }
@@ -409,11 +544,12 @@ pub fn rebuild_index(
 
 pub fn sync_index(
     conn: &Connection,
-    vault_root: &Path,
+    ctx: &VaultContext,
     cancel: &AtomicBool,
     on_progress: &dyn Fn(usize, usize),
     yield_fn: &mut dyn FnMut(),
 ) -> Result<IndexResult, String> {
+    let vault_root = ctx.root();
     let manifest = get_manifest(conn).unwrap_or_default();
     let disk_files = list_markdown_files(vault_root);
     let plan = compute_sync_plan(vault_root, &manifest, &disk_files);
@@ -492,7 +628,7 @@ pub fn sync_index(
                 }
             };
             upsert_note(conn, &meta, &markdown)?;
-            let targets = internal_link_targets(&markdown, &meta.path);
+            let targets = note_link_targets(ctx, &markdown, &meta.path);
             if !targets.is_empty() {
                 pending_links.push((meta.path.clone(), targets));
             }
@@ -798,6 +934,10 @@ pub fn rename_folder_paths(
          WHERE target_path LIKE ?3 ESCAPE '\\'",
         params![new_prefix, old_len, like_pattern],
     ))
+    .and_then(|_| conn.execute(
+        "UPDATE tasks SET path = ?1 || substr(path, ?2 + 1) WHERE path LIKE ?3 ESCAPE '\\'",
+        params![new_prefix, old_len, like_pattern],
+    ))
     .map_err(|e| e.to_string());
 
     match result {
@@ -833,6 +973,12 @@ pub fn rename_note_path(conn: &Connection, old_path: &str, new_path: &str) -> Re
                 params![new_path, old_path],
             )
         })
+        .and_then(|_| {
+            conn.execute(
+                "UPDATE tasks SET path = ?1 WHERE path = ?2",
+                params![new_path, old_path],
+            )
+        })
         .map(|_| ())
         .map_err(|e| e.to_string());
 
@@ -845,6 +991,77 @@ pub fn rename_note_path(conn: &Connection, old_path: &str, new_path: &str) -> Re
     }
 }
 
+const DEFAULT_TASK_LIMIT: usize = 500;
+
+fn task_has_tag(tags: &[String], wanted: &str) -> bool {
+    let wanted = wanted.trim_start_matches('#').to_lowercase();
+    tags.iter().any(|tag| {
+        let tag = tag.to_lowercase();
+        tag == wanted || tag.starts_with(&format!("{}/", wanted))
+    })
+}
+
+/// Lists indexed tasks, open ones only unless `include_completed` is set.
+/// Tasks with a due date come first (earliest first), then by priority.
+pub fn query_tasks(conn: &Connection, query: &TaskQuery) -> Result<Vec<TaskHit>, String> {
+    let like_pattern = query.path_prefix.as_deref().map(like_prefix_pattern);
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.path, COALESCE(n.title, ''), t.line, t.text, t.completed, t.heading,
+                    t.due, t.priority, t.tags
+             FROM tasks t
+             LEFT JOIN notes n ON n.path = t.path
+             WHERE (?1 OR t.completed = 0)
+               AND (?2 IS NULL OR t.path LIKE ?2 ESCAPE '\\')
+               AND (?3 IS NULL OR (t.due IS NOT NULL AND t.due <= ?3))
+               AND (?4 IS NULL OR (t.priority IS NOT NULL AND t.priority <= ?4))
+             ORDER BY t.due IS NULL, t.due, t.priority IS NULL, t.priority, t.path, t.line",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(
+            params![
+                query.include_completed,
+                like_pattern,
+                query.due_before,
+                query.min_priority.map(|p| p.rank())
+            ],
+            |row| {
+                let tags: String = row.get(8)?;
+                Ok(TaskHit {
+                    path: row.get(0)?,
+                    note_title: row.get(1)?,
+                    line: row.get::<_, i64>(2)? as usize,
+                    text: row.get(3)?,
+                    completed: row.get(4)?,
+                    heading: row.get(5)?,
+                    due: row.get(6)?,
+                    priority: row
+                        .get::<_, Option<i64>>(7)?
+                        .and_then(TaskPriority::from_rank),
+                    tags: serde_json::from_str(&tags).unwrap_or_default(),
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_TASK_LIMIT);
+    let mut out = Vec::new();
+    for row in rows {
+        let hit = row.map_err(|e| e.to_string())?;
+        if let Some(tag) = query.tag.as_deref() {
+            if !task_has_tag(&hit.tags, tag) {
+                continue;
+            }
+        }
+        out.push(hit);
+        if out.len() >= limit {
+            break;
+        }
+    }
+    Ok(out)
+}
+
 pub fn get_backlinks(conn: &Connection, path: &str) -> Result<Vec<IndexNoteMeta>, String> {
     let sql = "SELECT n.path, n.title, n.mtime_ms, n.size_bytes
                FROM outlinks o
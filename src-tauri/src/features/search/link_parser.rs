@@ -1,5 +1,5 @@
 use comrak::nodes::{AstNode, NodeCode, NodeLink, NodeValue, NodeWikiLink, Sourcepos};
-use comrak::{Arena, Options, parse_document};
+use comrak::{parse_document, Arena, Options};
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 
@@ -22,7 +22,7 @@ struct ParsedLinks {
     external_links: Vec<ExternalLink>,
 }
 
-fn markdown_options() -> Options<'static> {
+pub(crate) fn markdown_options() -> Options<'static> {
     let mut options = Options::default();
     options.extension.autolink = true;
     options.extension.table = true;
@@ -41,7 +41,7 @@ fn hex_nibble(byte: u8) -> Option<u8> {
     }
 }
 
-fn decode_percent_sequences(value: &str) -> String {
+pub(crate) fn decode_percent_sequences(value: &str) -> String {
     let bytes = value.as_bytes();
     let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
     let mut i = 0usize;
@@ -61,7 +61,7 @@ fn decode_percent_sequences(value: &str) -> String {
     String::from_utf8_lossy(&out).into_owned()
 }
 
-fn is_external_url(value: &str) -> bool {
+pub(crate) fn is_external_url(value: &str) -> bool {
     let lower = value.to_ascii_lowercase();
     lower.starts_with("http://") || lower.starts_with("https://")
 }
@@ -94,14 +94,14 @@ pub(crate) fn resolve_relative_path(source_dir: &str, target: &str) -> Option<St
     Some(joined)
 }
 
-fn source_dir_from_path(source_path: &str) -> &str {
+pub(crate) fn source_dir_from_path(source_path: &str) -> &str {
     match source_path.rfind('/') {
         Some(i) => &source_path[..i],
         None => "",
     }
 }
 
-fn parse_internal_markdown_target(raw_href: &str) -> Option<String> {
+pub(crate) fn parse_internal_markdown_target(raw_href: &str) -> Option<String> {
     let trimmed = raw_href.trim();
     if trimmed.is_empty() || is_external_url(trimmed) {
         return None;
@@ -126,7 +126,7 @@ fn markdown_destination_needs_angle_brackets(value: &str) -> bool {
     value.chars().any(char::is_whitespace)
 }
 
-fn parse_wiki_link_target(raw_target: &str) -> Option<String> {
+pub(crate) fn parse_wiki_link_target(raw_target: &str) -> Option<String> {
     let trimmed = raw_target.trim();
     if trimmed.is_empty() {
         return None;
@@ -155,7 +155,7 @@ fn ensure_md_extension(value: &str) -> String {
     format!("{value}.md")
 }
 
-fn is_note_relative_target(raw_target: &str) -> bool {
+pub(crate) fn is_note_relative_target(raw_target: &str) -> bool {
     raw_target.starts_with("./") || raw_target.starts_with("../")
 }
 
@@ -175,7 +175,7 @@ pub(crate) fn resolve_wiki_target(source_path: &str, raw_target: &str) -> Option
     resolve_relative_path(base_dir, &candidate)
 }
 
-fn collect_plain_text<'a>(node: &'a AstNode<'a>) -> String {
+pub(crate) fn collect_plain_text<'a>(node: &'a AstNode<'a>) -> String {
     let mut out = String::new();
     for descendant in node.descendants().skip(1) {
         match &descendant.data.borrow().value {
@@ -203,7 +203,7 @@ fn ends_with_unescaped_bang(value: &str) -> bool {
     slash_count % 2 == 0
 }
 
-fn is_embedded_wikilink(node: &AstNode<'_>) -> bool {
+pub(crate) fn is_embedded_wikilink(node: &AstNode<'_>) -> bool {
     let Some(prev) = node.previous_sibling() else {
         return false;
     };
@@ -305,7 +305,10 @@ pub(crate) fn internal_link_targets(markdown: &str, source_path: &str) -> Vec<St
     out
 }
 
-pub(crate) fn extract_local_links_snapshot(markdown: &str, source_path: &str) -> LocalLinksSnapshot {
+pub(crate) fn extract_local_links_snapshot(
+    markdown: &str,
+    source_path: &str,
+) -> LocalLinksSnapshot {
     let parsed = parse_all_links(markdown, source_path);
     let mut combined = parsed.markdown_targets;
     combined.extend(parsed.wiki_targets);
@@ -328,7 +331,7 @@ pub struct RewriteResult {
     pub changed: bool,
 }
 
-fn compute_line_starts(text: &str) -> Vec<usize> {
+pub(crate) fn compute_line_starts(text: &str) -> Vec<usize> {
     let mut starts = vec![0usize];
     for (i, byte) in text.bytes().enumerate() {
         if byte == b'\n' {
@@ -338,13 +341,19 @@ fn compute_line_starts(text: &str) -> Vec<usize> {
     starts
 }
 
-fn sourcepos_to_byte_range(line_starts: &[usize], pos: Sourcepos) -> Option<(usize, usize)> {
+pub(crate) fn sourcepos_to_byte_range(
+    line_starts: &[usize],
+    pos: Sourcepos,
+) -> Option<(usize, usize)> {
     if pos.start.line == 0 || pos.end.line == 0 {
         return None;
     }
     let start_offset = *line_starts.get(pos.start.line - 1)?;
     let end_offset = *line_starts.get(pos.end.line - 1)?;
-    Some((start_offset + pos.start.column - 1, end_offset + pos.end.column))
+    Some((
+        start_offset + pos.start.column - 1,
+        end_offset + pos.end.column,
+    ))
 }
 
 pub(crate) fn compute_relative_path(from_dir: &str, to_path: &str) -> String {
@@ -428,6 +437,32 @@ pub(crate) fn rewrite_links(
     old_source_path: &str,
     new_source_path: &str,
     target_map: &HashMap<String, String>,
+) -> RewriteResult {
+    rewrite_links_with(
+        markdown,
+        old_source_path,
+        new_source_path,
+        &|resolved, _| {
+            target_map
+                .get(resolved)
+                .map(|mapped| (mapped.clone(), None))
+        },
+    )
+}
+
+/// Maps a resolved link target and its `#fragment` to a new target and
+/// fragment.
+pub(crate) type LinkRetarget<'a> =
+    dyn Fn(&str, Option<&str>) -> Option<(String, Option<String>)> + 'a;
+
+/// Like `rewrite_links`, but `retarget` sees each resolved target with its
+/// `#fragment` and returns the new target and fragment, or `None` to leave
+/// the link alone.
+pub(crate) fn rewrite_links_with(
+    markdown: &str,
+    old_source_path: &str,
+    new_source_path: &str,
+    retarget: &LinkRetarget<'_>,
 ) -> RewriteResult {
     let arena = Arena::new();
     let options = markdown_options();
@@ -473,26 +508,30 @@ pub(crate) fn rewrite_links(
                     None => continue,
                 };
 
-                let new_target = if let Some(mapped) = target_map.get(&resolved) {
-                    mapped.clone()
+                let fragment = url.split_once('#').map(|(_, fragment)| fragment);
+                let (new_target, new_fragment) = if let Some(mapped) = retarget(&resolved, fragment)
+                {
+                    mapped
                 } else if source_moved && is_relative {
-                    resolved
-                } else if source_moved && !is_relative {
-                    continue;
+                    (resolved, None)
                 } else {
                     continue;
                 };
 
-                let new_href = if is_relative {
+                let mut new_href = if is_relative {
                     format_markdown_link_href(new_source_path, &new_target)
                 } else {
                     new_target.clone()
                 };
-                let (byte_start, byte_end) =
-                    match sourcepos_to_byte_range(&line_starts, sourcepos) {
-                        Some(range) => range,
-                        None => continue,
-                    };
+                if let Some(fragment) = new_fragment {
+                    new_href.push('#');
+                    new_href.push_str(&fragment);
+                }
+                let (byte_start, byte_end) = match sourcepos_to_byte_range(&line_starts, sourcepos)
+                {
+                    Some(range) => range,
+                    None => continue,
+                };
                 if byte_end > markdown.len() {
                     continue;
                 }
@@ -511,13 +550,12 @@ pub(crate) fn rewrite_links(
                     && trimmed_destination.ends_with('>')
                     && trimmed_destination.len() >= 2;
 
-                let replacement_destination = if had_angle_wrapping
-                    || markdown_destination_needs_angle_brackets(&new_href)
-                {
-                    format!("<{new_href}>")
-                } else {
-                    new_href.clone()
-                };
+                let replacement_destination =
+                    if had_angle_wrapping || markdown_destination_needs_angle_brackets(&new_href) {
+                        format!("<{new_href}>")
+                    } else {
+                        new_href.clone()
+                    };
                 let replacement = format!("[{label}]({replacement_destination})");
                 if replacement != span {
                     replacements.push((byte_start, byte_end, replacement));
@@ -534,20 +572,26 @@ pub(crate) fn rewrite_links(
                     None => continue,
                 };
 
-                let new_target = if let Some(mapped) = target_map.get(&resolved) {
-                    mapped.clone()
+                let fragment = url.split_once('#').map(|(_, fragment)| fragment.trim());
+                let (new_target, new_fragment) = if let Some(mapped) = retarget(&resolved, fragment)
+                {
+                    mapped
                 } else if source_moved && is_relative {
-                    resolved
+                    (resolved, None)
                 } else {
                     continue;
                 };
 
-                let new_wiki = format_wiki_target(new_source_path, &new_target, is_relative);
-                let (byte_start, byte_end) =
-                    match sourcepos_to_byte_range(&line_starts, sourcepos) {
-                        Some(range) => range,
-                        None => continue,
-                    };
+                let mut new_wiki = format_wiki_target(new_source_path, &new_target, is_relative);
+                if let Some(fragment) = new_fragment {
+                    new_wiki.push('#');
+                    new_wiki.push_str(&fragment);
+                }
+                let (byte_start, byte_end) = match sourcepos_to_byte_range(&line_starts, sourcepos)
+                {
+                    Some(range) => range,
+                    None => continue,
+                };
                 if byte_end > markdown.len() {
                     continue;
                 }
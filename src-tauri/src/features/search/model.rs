@@ -1,3 +1,4 @@
+use crate::features::tasks::parser::TaskPriority;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -25,3 +26,43 @@ pub struct SearchHit {
     pub score: f32,
     pub snippet: Option<String>,
 }
+
+/// A hit from a search across every registered vault.
+#[derive(Debug, Serialize)]
+pub struct FederatedSearchHit {
+    pub vault_id: String,
+    pub vault_name: String,
+    pub note: IndexNoteMeta,
+    /// bm25 relative to the best hit in the same vault, in `(0, 1]`.
+    pub score: f32,
+    pub snippet: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TaskQuery {
+    #[serde(default)]
+    pub include_completed: bool,
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+    #[serde(default)]
+    pub due_before: Option<String>,
+    #[serde(default)]
+    pub min_priority: Option<TaskPriority>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskHit {
+    pub path: String,
+    pub note_title: String,
+    pub line: usize,
+    pub text: String,
+    pub completed: bool,
+    pub heading: Option<String>,
+    pub due: Option<String>,
+    pub priority: Option<TaskPriority>,
+    pub tags: Vec<String>,
+}
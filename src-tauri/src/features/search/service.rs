@@ -1,13 +1,17 @@
+use crate::features::journal::service as journal;
 use crate::features::notes::service as notes_service;
+use crate::features::plugins::host as plugins;
 use crate::features::search::db as search_db;
 use crate::features::search::link_parser;
-use crate::features::search::model::{IndexNoteMeta, SearchHit, SearchScope};
-use crate::shared::storage;
+use crate::features::search::model::{FederatedSearchHit, IndexNoteMeta, SearchHit, SearchScope};
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::storage::{self, Vault};
+use crate::shared::vault_context::VaultContext;
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Receiver, SyncSender};
 use std::sync::{Arc, Mutex};
@@ -55,7 +59,6 @@ pub enum IndexProgressEvent {
 #[allow(dead_code)]
 enum DbCommand {
     UpsertNote {
-        vault_root: PathBuf,
         note_id: String,
         reply: SyncSender<Result<(), String>>,
     },
@@ -72,16 +75,12 @@ enum DbCommand {
         reply: SyncSender<Result<(), String>>,
     },
     Rebuild {
-        vault_root: PathBuf,
         cancel: Arc<AtomicBool>,
         app_handle: AppHandle,
-        vault_id: String,
     },
     Sync {
-        vault_root: PathBuf,
         cancel: Arc<AtomicBool>,
         app_handle: AppHandle,
-        vault_id: String,
     },
     RenamePaths {
         old_prefix: String,
@@ -107,20 +106,19 @@ pub struct SearchDbState {
     workers: Mutex<HashMap<String, VaultWorker>>,
 }
 
-fn ensure_worker(app: &AppHandle, vault_id: &str) -> Result<(), String> {
-    let vault_root = storage::vault_path(app, vault_id)?;
+fn ensure_worker(app: &AppHandle, vault_id: &str) -> AppResult<()> {
+    let ctx = VaultContext::from_app(app, vault_id)?;
     let state = app.state::<SearchDbState>();
     let mut map = state.workers.lock().map_err(|e| e.to_string())?;
     if map.contains_key(vault_id) {
         return Ok(());
     }
 
-    let read_conn = search_db::open_search_db(&vault_root)?;
-    let write_root = vault_root.clone();
+    let read_conn = ctx.open_search_db().map_err(AppError::from_index)?;
     let (tx, rx) = mpsc::channel::<DbCommand>();
 
     std::thread::spawn(move || {
-        writer_thread_loop(rx, &write_root);
+        writer_thread_loop(rx, &ctx);
     });
 
     let worker = VaultWorker {
@@ -132,8 +130,22 @@ fn ensure_worker(app: &AppHandle, vault_id: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn writer_thread_loop(rx: Receiver<DbCommand>, vault_root: &Path) {
-    let conn = match search_db::open_search_db(vault_root) {
+/// Stops a vault's index worker so the next query reopens the index from the
+/// vault's current path.
+pub(crate) fn close_worker(app: &AppHandle, vault_id: &str) -> AppResult<()> {
+    let state = app.state::<SearchDbState>();
+    let mut map = state.workers.lock().map_err(|e| e.to_string())?;
+    if let Some(worker) = map.remove(vault_id) {
+        worker.cancel.store(true, Ordering::Relaxed);
+        let _ = worker.write_tx.send(DbCommand::Shutdown);
+    }
+    Ok(())
+}
+
+/// Runs a vault's index writes. `ctx` comes from the registry, so plugin hooks
+/// and link extraction see the vault's registered id.
+fn writer_thread_loop(rx: Receiver<DbCommand>, ctx: &VaultContext) {
+    let conn = match ctx.open_search_db() {
         Ok(c) => c,
         Err(e) => {
             log::error!("writer thread: open db failed: {e}");
@@ -151,7 +163,7 @@ fn writer_thread_loop(rx: Receiver<DbCommand>, vault_root: &Path) {
         };
 
     for cmd in &rx {
-        match dispatch_command(&conn, cmd, &mut notes_cache, &rx) {
+        match dispatch_command(&conn, ctx, cmd, &mut notes_cache, &rx) {
             LoopAction::Continue => {}
             LoopAction::Break => break,
         }
@@ -165,17 +177,14 @@ enum LoopAction {
 
 fn dispatch_command(
     conn: &Connection,
+    ctx: &VaultContext,
     cmd: DbCommand,
     notes_cache: &mut BTreeMap<String, IndexNoteMeta>,
     rx: &Receiver<DbCommand>,
 ) -> LoopAction {
     match cmd {
-        DbCommand::UpsertNote {
-            vault_root,
-            note_id,
-            reply,
-        } => {
-            let result = handle_upsert(conn, &vault_root, &note_id, notes_cache);
+        DbCommand::UpsertNote { note_id, reply } => {
+            let result = handle_upsert(conn, ctx, &note_id, notes_cache);
             if let Err(ref e) = result {
                 log::warn!("writer: upsert failed for {note_id}: {e}");
             }
@@ -258,37 +267,11 @@ fn dispatch_command(
             }
             let _ = reply.send(result);
         }
-        DbCommand::Rebuild {
-            vault_root,
-            cancel,
-            app_handle,
-            vault_id,
-        } => {
-            handle_rebuild(
-                conn,
-                &vault_root,
-                &cancel,
-                &app_handle,
-                &vault_id,
-                rx,
-                notes_cache,
-            );
+        DbCommand::Rebuild { cancel, app_handle } => {
+            handle_rebuild(conn, ctx, &cancel, &app_handle, rx, notes_cache);
         }
-        DbCommand::Sync {
-            vault_root,
-            cancel,
-            app_handle,
-            vault_id,
-        } => {
-            handle_sync(
-                conn,
-                &vault_root,
-                &cancel,
-                &app_handle,
-                &vault_id,
-                rx,
-                notes_cache,
-            );
+        DbCommand::Sync { cancel, app_handle } => {
+            handle_sync(conn, ctx, &cancel, &app_handle, rx, notes_cache);
         }
         DbCommand::Shutdown => {
             return LoopAction::Break;
@@ -299,10 +282,11 @@ fn dispatch_command(
 
 fn handle_upsert(
     conn: &Connection,
-    vault_root: &Path,
+    ctx: &VaultContext,
     note_id: &str,
     notes_cache: &mut BTreeMap<String, IndexNoteMeta>,
 ) -> Result<(), String> {
+    let vault_root = ctx.root();
     let abs = notes_service::safe_vault_abs(vault_root, note_id)?;
     let markdown = match std::fs::read_to_string(&abs) {
         Ok(content) => content,
@@ -318,7 +302,12 @@ fn handle_upsert(
     search_db::upsert_note(conn, &meta, &markdown)?;
     notes_cache.insert(meta.path.clone(), meta.clone());
 
-    let targets = search_db::internal_link_targets(&markdown, &meta.path);
+    let plugins = plugins::active();
+    if !plugins.is_empty() {
+        plugins.note_indexed(ctx, &meta.path, &markdown);
+    }
+
+    let targets = search_db::note_link_targets(ctx, &markdown, &meta.path);
     let mut resolved: BTreeSet<String> = BTreeSet::new();
     for target in targets {
         if target != meta.path {
@@ -330,7 +319,7 @@ fn handle_upsert(
 
 type IndexFn = fn(
     &Connection,
-    &Path,
+    &VaultContext,
     &AtomicBool,
     &dyn Fn(usize, usize),
     &mut dyn FnMut(),
@@ -338,16 +327,16 @@ type IndexFn = fn(
 
 fn run_index_op(
     conn: &Connection,
-    vault_root: &Path,
+    ctx: &VaultContext,
     cancel: &Arc<AtomicBool>,
     app_handle: &AppHandle,
-    vault_id: &str,
     rx: &Receiver<DbCommand>,
     notes_cache: &mut BTreeMap<String, IndexNoteMeta>,
     label: &str,
     index_fn: IndexFn,
 ) {
     let start = Instant::now();
+    let vault_id = ctx.vault_id();
     let vid = vault_id.to_string();
     let app = app_handle.clone();
     let deferred: RefCell<Vec<DbCommand>> = RefCell::new(Vec::new());
@@ -372,7 +361,7 @@ fn run_index_op(
                     | DbCommand::RenamePaths { .. }
                     | DbCommand::RenamePath { .. } => {
                         cancel.store(true, Ordering::Relaxed);
-                        dispatch_command(conn, cmd, notes_cache, rx);
+                        dispatch_command(conn, ctx, cmd, notes_cache, rx);
 
                         if queued_sync_from_mutation {
                             continue;
@@ -381,10 +370,8 @@ fn run_index_op(
                         match create_next_sync_cancel_token(app_handle, vault_id) {
                             Ok(next_cancel) => {
                                 deferred.borrow_mut().push(DbCommand::Sync {
-                                    vault_root: vault_root.to_path_buf(),
                                     cancel: next_cancel,
                                     app_handle: app_handle.clone(),
-                                    vault_id: vault_id.to_string(),
                                 });
                                 queued_sync_from_mutation = true;
                             }
@@ -401,7 +388,7 @@ fn run_index_op(
 
         index_fn(
             conn,
-            vault_root,
+            ctx,
             cancel,
             &|indexed, total| {
                 if !*started_emitted.borrow() {
@@ -459,7 +446,7 @@ fn run_index_op(
 
     for cmd in deferred.into_inner() {
         if matches!(
-            dispatch_command(conn, cmd, notes_cache, rx),
+            dispatch_command(conn, ctx, cmd, notes_cache, rx),
             LoopAction::Break
         ) {
             break;
@@ -483,19 +470,17 @@ fn create_next_sync_cancel_token(
 
 fn handle_rebuild(
     conn: &Connection,
-    vault_root: &Path,
+    ctx: &VaultContext,
     cancel: &Arc<AtomicBool>,
     app_handle: &AppHandle,
-    vault_id: &str,
     rx: &Receiver<DbCommand>,
     notes_cache: &mut BTreeMap<String, IndexNoteMeta>,
 ) {
     run_index_op(
         conn,
-        vault_root,
+        ctx,
         cancel,
         app_handle,
-        vault_id,
         rx,
         notes_cache,
         "rebuild",
@@ -505,19 +490,17 @@ fn handle_rebuild(
 
 fn handle_sync(
     conn: &Connection,
-    vault_root: &Path,
+    ctx: &VaultContext,
     cancel: &Arc<AtomicBool>,
     app_handle: &AppHandle,
-    vault_id: &str,
     rx: &Receiver<DbCommand>,
     notes_cache: &mut BTreeMap<String, IndexNoteMeta>,
 ) {
     run_index_op(
         conn,
-        vault_root,
+        ctx,
         cancel,
         app_handle,
-        vault_id,
         rx,
         notes_cache,
         "sync",
@@ -525,7 +508,7 @@ fn handle_sync(
     );
 }
 
-fn with_read_conn<F, T>(app: &AppHandle, vault_id: &str, f: F) -> Result<T, String>
+pub(crate) fn with_read_conn<F, T>(app: &AppHandle, vault_id: &str, f: F) -> AppResult<T>
 where
     F: FnOnce(&Connection) -> Result<T, String>,
 {
@@ -535,54 +518,81 @@ where
     })
 }
 
-fn send_write(app: &AppHandle, vault_id: &str, cmd: DbCommand) -> Result<(), String> {
+/// Like `with_read_conn`, but only for a worker that is already running.
+/// `None` when the vault has no worker.
+fn with_live_read_conn<F, T>(app: &AppHandle, vault_id: &str, f: F) -> Option<AppResult<T>>
+where
+    F: FnOnce(&Connection) -> Result<T, String>,
+{
+    let state = app.state::<SearchDbState>();
+    let map = match state.workers.lock() {
+        Ok(map) => map,
+        Err(error) => return Some(Err(AppError::from_index(error.to_string()))),
+    };
+    let worker = map.get(vault_id)?;
+    let result = worker
+        .read_conn
+        .lock()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| f(&conn));
+    Some(result.map_err(AppError::from_index))
+}
+
+fn send_write(app: &AppHandle, vault_id: &str, cmd: DbCommand) -> AppResult<()> {
     with_worker(app, vault_id, |worker| {
         worker.write_tx.send(cmd).map_err(|e| e.to_string())
     })
 }
 
-fn with_worker<F, T>(app: &AppHandle, vault_id: &str, operation: F) -> Result<T, String>
+fn with_worker<F, T>(app: &AppHandle, vault_id: &str, operation: F) -> AppResult<T>
 where
     F: FnOnce(&VaultWorker) -> Result<T, String>,
 {
     ensure_worker(app, vault_id)?;
     let state = app.state::<SearchDbState>();
     let map = state.workers.lock().map_err(|e| e.to_string())?;
-    let worker = map.get(vault_id).ok_or("vault worker not found")?;
-    operation(worker)
+    let worker = map
+        .get(vault_id)
+        .ok_or_else(|| AppError::not_found("vault worker", Some(vault_id)))?;
+    operation(worker).map_err(AppError::from_index)
 }
 
-fn with_worker_mut<F, T>(app: &AppHandle, vault_id: &str, operation: F) -> Result<T, String>
+fn with_worker_mut<F, T>(app: &AppHandle, vault_id: &str, operation: F) -> AppResult<T>
 where
     F: FnOnce(&mut VaultWorker) -> Result<T, String>,
 {
     ensure_worker(app, vault_id)?;
     let state = app.state::<SearchDbState>();
     let mut map = state.workers.lock().map_err(|e| e.to_string())?;
-    let worker = map.get_mut(vault_id).ok_or("vault worker not found")?;
-    operation(worker)
+    let worker = map
+        .get_mut(vault_id)
+        .ok_or_else(|| AppError::not_found("vault worker", Some(vault_id)))?;
+    operation(worker).map_err(AppError::from_index)
 }
 
 fn send_write_reply<T>(
     app: &AppHandle,
     vault_id: &str,
     make_cmd: impl FnOnce(SyncSender<Result<T, String>>) -> DbCommand,
-) -> Result<T, String> {
+) -> AppResult<T> {
     let (reply_tx, reply_rx) = mpsc::sync_channel(1);
     let cmd = make_cmd(reply_tx);
     send_write(app, vault_id, cmd)?;
-    reply_rx.recv().map_err(|e| e.to_string())?
+    reply_rx
+        .recv()
+        .map_err(|e| e.to_string())?
+        .map_err(AppError::from_index)
 }
 
 fn send_write_blocking(
     app: &AppHandle,
     vault_id: &str,
     make_cmd: impl FnOnce(SyncSender<Result<(), String>>) -> DbCommand,
-) -> Result<(), String> {
+) -> AppResult<()> {
     send_write_reply(app, vault_id, make_cmd)
 }
 
-fn replace_worker_cancel_token(app: &AppHandle, vault_id: &str) -> Result<Arc<AtomicBool>, String> {
+fn replace_worker_cancel_token(app: &AppHandle, vault_id: &str) -> AppResult<Arc<AtomicBool>> {
     let next_cancel = Arc::new(AtomicBool::new(false));
     with_worker_mut(app, vault_id, |worker| {
         worker.cancel.store(true, Ordering::Relaxed);
@@ -595,29 +605,24 @@ fn replace_worker_cancel_token(app: &AppHandle, vault_id: &str) -> Result<Arc<At
 fn enqueue_index_command(
     app: &AppHandle,
     vault_id: &str,
-    make_cmd: impl FnOnce(PathBuf, Arc<AtomicBool>, AppHandle, String) -> DbCommand,
-) -> Result<(), String> {
-    let vault_root = storage::vault_path(app, vault_id)?;
+    make_cmd: impl FnOnce(Arc<AtomicBool>, AppHandle) -> DbCommand,
+) -> AppResult<()> {
     let cancel = replace_worker_cancel_token(app, vault_id)?;
-    let cmd = make_cmd(vault_root, cancel, app.clone(), vault_id.to_string());
+    let cmd = make_cmd(cancel, app.clone());
     send_write(app, vault_id, cmd)
 }
 
 #[tauri::command]
-pub fn index_build(app: AppHandle, vault_id: String) -> Result<(), String> {
+pub fn index_build(app: AppHandle, vault_id: String) -> AppResult<()> {
     log::info!("Building index vault_id={}", vault_id);
-    enqueue_index_command(&app, &vault_id, |vault_root, cancel, app_handle, vault_id| {
-        DbCommand::Sync {
-            vault_root,
-            cancel,
-            app_handle,
-            vault_id,
-        }
+    enqueue_index_command(&app, &vault_id, |cancel, app_handle| DbCommand::Sync {
+        cancel,
+        app_handle,
     })
 }
 
 #[tauri::command]
-pub fn index_cancel(app: AppHandle, vault_id: String) -> Result<(), String> {
+pub fn index_cancel(app: AppHandle, vault_id: String) -> AppResult<()> {
     with_worker(&app, &vault_id, |worker| {
         worker.cancel.store(true, Ordering::Relaxed);
         Ok(())
@@ -625,37 +630,150 @@ pub fn index_cancel(app: AppHandle, vault_id: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn index_rebuild(app: AppHandle, vault_id: String) -> Result<(), String> {
+pub fn index_rebuild(app: AppHandle, vault_id: String) -> AppResult<()> {
     log::info!("Rebuilding index vault_id={}", vault_id);
-    enqueue_index_command(&app, &vault_id, |vault_root, cancel, app_handle, vault_id| {
-        DbCommand::Rebuild {
-            vault_root,
-            cancel,
-            app_handle,
-            vault_id,
-        }
+    enqueue_index_command(&app, &vault_id, |cancel, app_handle| DbCommand::Rebuild {
+        cancel,
+        app_handle,
     })
 }
 
+/// Runs `f` on a direct connection to the vault's index, for callers with a
+/// `VaultContext` but no app, such as the CLI and tests.
+fn with_ctx_conn<F, T>(ctx: &VaultContext, f: F) -> AppResult<T>
+where
+    F: FnOnce(&Connection) -> Result<T, String>,
+{
+    let conn = ctx.open_search_db()?;
+    f(&conn).map_err(AppError::from_index)
+}
+
+fn links_snapshot(conn: &Connection, note_id: &str) -> Result<NoteLinksSnapshot, String> {
+    Ok(NoteLinksSnapshot {
+        backlinks: search_db::get_backlinks(conn, note_id)?,
+        outlinks: search_db::get_outlinks(conn, note_id)?,
+        orphan_links: search_db::get_orphan_outlinks(conn, note_id)?,
+    })
+}
+
+pub(crate) fn search_in(
+    ctx: &VaultContext,
+    text: &str,
+    scope: SearchScope,
+    limit: usize,
+) -> AppResult<Vec<SearchHit>> {
+    with_ctx_conn(ctx, |conn| search_db::search(conn, text, scope, limit))
+}
+
+pub(crate) fn note_links_snapshot_in(
+    ctx: &VaultContext,
+    note_id: &str,
+) -> AppResult<NoteLinksSnapshot> {
+    with_ctx_conn(ctx, |conn| links_snapshot(conn, note_id))
+}
+
 #[tauri::command]
 pub fn index_search(
     app: AppHandle,
     vault_id: String,
     query: SearchQueryInput,
-) -> Result<Vec<SearchHit>, String> {
+) -> AppResult<Vec<SearchHit>> {
     log::debug!("Searching index vault_id={} query={}", vault_id, query.text);
     with_read_conn(&app, &vault_id, |conn| {
         search_db::search(conn, &query.text, query.scope, 50)
     })
 }
 
+/// Merges per-vault results into one ranking. bm25 is only comparable within
+/// one index, so each vault's ranks are scaled by its own best hit first; ties
+/// fall back to the raw rank.
+pub fn merge_federated_hits(
+    results: Vec<(Vault, Vec<SearchHit>)>,
+    limit: usize,
+) -> Vec<FederatedSearchHit> {
+    let mut merged: Vec<(f32, FederatedSearchHit)> = Vec::new();
+    for (vault, hits) in results {
+        let best = hits.iter().map(|hit| hit.score).fold(0.0_f32, f32::min);
+        for hit in hits {
+            let score = if best < 0.0 { hit.score / best } else { 1.0 };
+            merged.push((
+                hit.score,
+                FederatedSearchHit {
+                    vault_id: vault.id.clone(),
+                    vault_name: vault.name.clone(),
+                    note: hit.note,
+                    score,
+                    snippet: hit.snippet,
+                },
+            ));
+        }
+    }
+    merged.sort_by(|(raw_a, a), (raw_b, b)| {
+        b.score
+            .total_cmp(&a.score)
+            .then(raw_a.total_cmp(raw_b))
+            .then_with(|| a.vault_name.cmp(&b.vault_name))
+            .then_with(|| a.note.path.cmp(&b.note.path))
+    });
+    merged.truncate(limit);
+    merged.into_iter().map(|(_, hit)| hit).collect()
+}
+
+/// Searches a vault's index on disk without starting a worker or creating
+/// the index. `None` when the vault has not been indexed.
+pub(crate) fn search_existing_index(
+    vault_root: &Path,
+    text: &str,
+    scope: SearchScope,
+    limit: usize,
+) -> Result<Option<Vec<SearchHit>>, String> {
+    let Some(conn) = search_db::open_search_db_read_only(vault_root)? else {
+        return Ok(None);
+    };
+    search_db::search(&conn, text, scope, limit).map(Some)
+}
+
+/// Searches every registered vault. Vaults with a running worker use it;
+/// the rest are read from their existing index, and vaults that were never
+/// indexed are skipped, so this never starts workers or creates indexes.
+#[tauri::command]
+pub fn index_search_all(
+    app: AppHandle,
+    query: SearchQueryInput,
+    limit: Option<usize>,
+) -> AppResult<Vec<FederatedSearchHit>> {
+    log::debug!("Searching all vaults query={}", query.text);
+    let limit = limit.unwrap_or(50);
+    let store = storage::load_store(&app)?;
+    let mut results = Vec::new();
+    for entry in store.vaults {
+        let vault = entry.vault;
+        if !Path::new(&vault.path).is_dir() {
+            continue;
+        }
+        let hits = with_live_read_conn(&app, &vault.id, |conn| {
+            search_db::search(conn, &query.text, query.scope, limit).map(Some)
+        })
+        .unwrap_or_else(|| {
+            search_existing_index(Path::new(&vault.path), &query.text, query.scope, limit)
+                .map_err(AppError::from_index)
+        });
+        match hits {
+            Ok(Some(hits)) => results.push((vault, hits)),
+            Ok(None) => log::debug!("Skipping unindexed vault {} in search", vault.id),
+            Err(error) => log::warn!("Skipping vault {} in search: {}", vault.id, error),
+        }
+    }
+    Ok(merge_federated_hits(results, limit))
+}
+
 #[tauri::command]
 pub fn index_suggest(
     app: AppHandle,
     vault_id: String,
     query: String,
     limit: Option<usize>,
-) -> Result<Vec<search_db::SuggestionHit>, String> {
+) -> AppResult<Vec<search_db::SuggestionHit>> {
     log::debug!(
         "Suggesting from index vault_id={} query={}",
         vault_id,
@@ -672,7 +790,7 @@ pub fn index_suggest_planned(
     vault_id: String,
     query: String,
     limit: Option<usize>,
-) -> Result<Vec<search_db::PlannedSuggestionHit>, String> {
+) -> AppResult<Vec<search_db::PlannedSuggestionHit>> {
     log::debug!(
         "Suggesting planned links from index vault_id={} query={}",
         vault_id,
@@ -688,24 +806,22 @@ pub fn index_list_note_paths_by_prefix(
     app: AppHandle,
     vault_id: String,
     prefix: String,
-) -> Result<Vec<String>, String> {
+) -> AppResult<Vec<String>> {
     with_read_conn(&app, &vault_id, |conn| {
         search_db::list_note_paths_by_prefix(conn, &prefix)
     })
 }
 
 #[tauri::command]
-pub fn index_upsert_note(app: AppHandle, vault_id: String, note_id: String) -> Result<(), String> {
-    let vault_root = storage::vault_path(&app, &vault_id)?;
+pub fn index_upsert_note(app: AppHandle, vault_id: String, note_id: String) -> AppResult<()> {
     send_write_blocking(&app, &vault_id, |reply| DbCommand::UpsertNote {
-        vault_root,
         note_id,
         reply,
     })
 }
 
 #[tauri::command]
-pub fn index_remove_note(app: AppHandle, vault_id: String, note_id: String) -> Result<(), String> {
+pub fn index_remove_note(app: AppHandle, vault_id: String, note_id: String) -> AppResult<()> {
     send_write_blocking(&app, &vault_id, |reply| DbCommand::RemoveNote {
         note_id,
         reply,
@@ -717,7 +833,7 @@ pub fn index_remove_notes(
     app: AppHandle,
     vault_id: String,
     note_ids: Vec<String>,
-) -> Result<(), String> {
+) -> AppResult<()> {
     send_write_blocking(&app, &vault_id, |reply| DbCommand::RemoveNotes {
         note_ids,
         reply,
@@ -729,7 +845,7 @@ pub fn index_remove_notes_by_prefix(
     app: AppHandle,
     vault_id: String,
     prefix: String,
-) -> Result<(), String> {
+) -> AppResult<()> {
     send_write_blocking(&app, &vault_id, |reply| DbCommand::RemoveNotesByPrefix {
         prefix,
         reply,
@@ -742,7 +858,7 @@ pub fn index_rename_folder(
     vault_id: String,
     old_prefix: String,
     new_prefix: String,
-) -> Result<usize, String> {
+) -> AppResult<usize> {
     send_write_reply(&app, &vault_id, |reply| DbCommand::RenamePaths {
         old_prefix,
         new_prefix,
@@ -756,7 +872,7 @@ pub fn index_rename_note(
     vault_id: String,
     old_path: String,
     new_path: String,
-) -> Result<(), String> {
+) -> AppResult<()> {
     send_write_blocking(&app, &vault_id, |reply| DbCommand::RenamePath {
         old_path,
         new_path,
@@ -769,14 +885,8 @@ pub fn index_note_links_snapshot(
     app: AppHandle,
     vault_id: String,
     note_id: String,
-) -> Result<NoteLinksSnapshot, String> {
-    with_read_conn(&app, &vault_id, |conn| {
-        Ok(NoteLinksSnapshot {
-            backlinks: search_db::get_backlinks(conn, &note_id)?,
-            outlinks: search_db::get_outlinks(conn, &note_id)?,
-            orphan_links: search_db::get_orphan_outlinks(conn, &note_id)?,
-        })
-    })
+) -> AppResult<NoteLinksSnapshot> {
+    with_read_conn(&app, &vault_id, |conn| links_snapshot(conn, &note_id))
 }
 
 #[tauri::command]
@@ -785,7 +895,7 @@ pub fn index_extract_local_note_links(
     vault_id: String,
     note_id: String,
     markdown: String,
-) -> Result<search_db::LocalLinksSnapshot, String> {
+) -> AppResult<search_db::LocalLinksSnapshot> {
     with_read_conn(&app, &vault_id, |_conn| {
         Ok(search_db::extract_local_links_snapshot(&markdown, &note_id))
     })
@@ -801,6 +911,101 @@ pub fn rewrite_note_links(
     link_parser::rewrite_links(&markdown, &old_source_path, &new_source_path, &target_map)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct LinkRewriteApplyArgs {
+    pub vault_id: String,
+    pub note_paths: Vec<String>,
+    pub target_map: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LinkRewriteApplyResult {
+    pub rewritten: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// Retargets links in `note_paths` according to `target_map`. The edits join
+/// the journal entry of the move that produced `target_map` when it is the
+/// latest one, and otherwise form their own entry. A note that can't be read
+/// or written is reported in `failed` without stopping the others.
+pub(crate) fn rewrite_links_in_notes_at(
+    root: &Path,
+    note_paths: &[String],
+    target_map: &HashMap<String, String>,
+) -> Result<LinkRewriteApplyResult, String> {
+    let mut ops = Vec::new();
+    let mut result = LinkRewriteApplyResult::default();
+    for path in note_paths {
+        match rewrite_links_collecting_ops(root, std::slice::from_ref(path), target_map, &mut ops) {
+            Ok(changed) => result.rewritten.extend(changed),
+            Err(error) => {
+                log::warn!("Failed to rewrite links in {}: {}", path, error);
+                result.failed.push(path.clone());
+            }
+        }
+    }
+    let moved_to: Vec<&str> = target_map.values().map(String::as_str).collect();
+    journal::record_after_move(root, "rewrite_links", &moved_to, ops)?;
+    Ok(result)
+}
+
+/// Rewrites and writes the notes, appending a journal op per changed note so
+/// callers can fold the rewrite into a larger entry. A note that is itself a
+/// value in `target_map` is treated as moved from the matching key, so its
+/// relative links are rebased too.
+pub(crate) fn rewrite_links_collecting_ops(
+    root: &Path,
+    note_paths: &[String],
+    target_map: &HashMap<String, String>,
+    ops: &mut Vec<journal::JournalOp>,
+) -> Result<Vec<String>, String> {
+    let mut changed = Vec::new();
+    for path in note_paths {
+        let abs = notes_service::safe_vault_abs_for_write(root, path)?;
+        let markdown = std::fs::read_to_string(&abs).map_err(|e| e.to_string())?;
+        let old_source = target_map
+            .iter()
+            .find(|(_, to)| *to == path)
+            .map_or(path.as_str(), |(from, _)| from.as_str());
+        let result = link_parser::rewrite_links(&markdown, old_source, path, target_map);
+        if !result.changed {
+            continue;
+        }
+        // Store the journal blobs first so every rewrite that lands on disk
+        // can be undone.
+        let op = journal::write_op(
+            root,
+            path,
+            Some(markdown.as_bytes()),
+            Some(result.markdown.as_bytes()),
+        )?;
+        notes_service::atomic_write(&abs, &result.markdown)?;
+        ops.push(op);
+        changed.push(path.clone());
+    }
+    Ok(changed)
+}
+
+#[tauri::command]
+pub fn apply_note_link_rewrites(
+    args: LinkRewriteApplyArgs,
+    app: AppHandle,
+) -> AppResult<LinkRewriteApplyResult> {
+    log::info!(
+        "Rewriting links vault_id={} notes={}",
+        args.vault_id,
+        args.note_paths.len()
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    let result = rewrite_links_in_notes_at(&root, &args.note_paths, &args.target_map)?;
+    for path in &result.rewritten {
+        if let Err(error) = index_upsert_note(app.clone(), args.vault_id.clone(), path.clone()) {
+            log::warn!("Failed to reindex {} after link rewrite: {}", path, error);
+        }
+    }
+    Ok(result)
+}
+
 #[tauri::command]
 pub fn resolve_note_link(source_path: String, raw_target: String) -> Option<String> {
     link_parser::resolve_wiki_target(&source_path, &raw_target)
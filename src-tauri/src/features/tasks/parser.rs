@@ -0,0 +1,227 @@
+use crate::features::search::link_parser::{collect_plain_text, markdown_options};
+use chrono::NaiveDate;
+use comrak::nodes::NodeValue;
+use comrak::{parse_document, Arena};
+use serde::{Deserialize, Serialize};
+
+const DUE_EMOJI: &str = "📅";
+const PRIORITY_EMOJIS: &[(&str, TaskPriority)] = &[
+    ("🔺", TaskPriority::Highest),
+    ("⏫", TaskPriority::High),
+    ("🔼", TaskPriority::Medium),
+    ("🔽", TaskPriority::Low),
+    ("⏬", TaskPriority::Lowest),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPriority {
+    Highest,
+    High,
+    Medium,
+    Low,
+    Lowest,
+}
+
+impl TaskPriority {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "highest" => Some(TaskPriority::Highest),
+            "high" => Some(TaskPriority::High),
+            "medium" => Some(TaskPriority::Medium),
+            "low" => Some(TaskPriority::Low),
+            "lowest" => Some(TaskPriority::Lowest),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn from_rank(rank: i64) -> Option<Self> {
+        match rank {
+            0 => Some(TaskPriority::Highest),
+            1 => Some(TaskPriority::High),
+            2 => Some(TaskPriority::Medium),
+            3 => Some(TaskPriority::Low),
+            4 => Some(TaskPriority::Lowest),
+            _ => None,
+        }
+    }
+
+    /// Sort key where more urgent priorities come first.
+    pub(crate) fn rank(self) -> i64 {
+        match self {
+            TaskPriority::Highest => 0,
+            TaskPriority::High => 1,
+            TaskPriority::Medium => 2,
+            TaskPriority::Low => 3,
+            TaskPriority::Lowest => 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ParsedTask {
+    pub line: usize,
+    pub text: String,
+    pub completed: bool,
+    pub heading: Option<String>,
+    pub due: Option<String>,
+    pub priority: Option<TaskPriority>,
+    pub tags: Vec<String>,
+}
+
+struct TaskLine<'a> {
+    checkbox_offset: usize,
+    completed: bool,
+    text: &'a str,
+}
+
+/// Splits `- [ ] text` (any bullet or ordered marker) into its parts.
+fn parse_task_line(line: &str) -> Option<TaskLine<'_>> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    let marker_len = if rest.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 || !rest[digits..].starts_with(['.', ')']) {
+            return None;
+        }
+        digits + 1
+    };
+    let after_marker = &rest[marker_len..];
+    let spaces = after_marker.len() - after_marker.trim_start().len();
+    if spaces == 0 {
+        return None;
+    }
+    let checkbox = &after_marker[spaces..];
+    let bytes = checkbox.as_bytes();
+    if bytes.len() < 3 || bytes[0] != b'[' || bytes[2] != b']' {
+        return None;
+    }
+    Some(TaskLine {
+        checkbox_offset: indent + marker_len + spaces,
+        completed: bytes[1] != b' ',
+        text: checkbox[3..].trim(),
+    })
+}
+
+fn parse_due(text: &str) -> Option<String> {
+    let candidates = [DUE_EMOJI, "due:"];
+    for marker in candidates {
+        let mut search_from = 0usize;
+        while let Some(idx) = text[search_from..].find(marker) {
+            let start = search_from + idx + marker.len();
+            let value: String = text[start..]
+                .trim_start()
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '-')
+                .collect();
+            if NaiveDate::parse_from_str(&value, "%Y-%m-%d").is_ok() {
+                return Some(value);
+            }
+            search_from = start;
+        }
+    }
+    None
+}
+
+fn parse_priority(text: &str) -> Option<TaskPriority> {
+    if let Some((_, priority)) = PRIORITY_EMOJIS
+        .iter()
+        .find(|(emoji, _)| text.contains(emoji))
+    {
+        return Some(*priority);
+    }
+    text.split_whitespace()
+        .find_map(|word| word.strip_prefix("priority:"))
+        .and_then(TaskPriority::parse)
+}
+
+pub(crate) fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        let Some(tag) = word.strip_prefix('#') else {
+            continue;
+        };
+        let tag: String = tag
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '/'))
+            .collect();
+        if tag.chars().any(|c| !c.is_ascii_digit()) && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Extracts GFM task list items with their nearest preceding heading and the
+/// due date, priority and tags written inline in the task text.
+pub(crate) fn extract_tasks(markdown: &str) -> Vec<ParsedTask> {
+    let arena = Arena::new();
+    let options = markdown_options();
+    let root = parse_document(&arena, markdown, &options);
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut heading: Option<String> = None;
+    let mut tasks = Vec::new();
+
+    for node in root.descendants() {
+        let data = node.data.borrow();
+        match &data.value {
+            NodeValue::Heading(_) => {
+                let text = collect_plain_text(node);
+                heading = (!text.is_empty()).then_some(text);
+            }
+            NodeValue::TaskItem(..) => {
+                let line_number = data.sourcepos.start.line;
+                let Some(task) = lines
+                    .get(line_number.wrapping_sub(1))
+                    .and_then(|line| parse_task_line(line))
+                else {
+                    continue;
+                };
+                tasks.push(ParsedTask {
+                    line: line_number,
+                    text: task.text.to_string(),
+                    completed: task.completed,
+                    heading: heading.clone(),
+                    due: parse_due(task.text),
+                    priority: parse_priority(task.text),
+                    tags: parse_tags(task.text),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    tasks
+}
+
+/// Rewrites the checkbox on 1-based `line`. `completed` forces a state;
+/// otherwise the current state is flipped. Returns the new document and state.
+/// Only lines [`extract_tasks`] reports count, so code blocks are left alone.
+pub(crate) fn toggle_task_line(
+    markdown: &str,
+    line: usize,
+    completed: Option<bool>,
+) -> Result<(String, bool), String> {
+    if !extract_tasks(markdown).iter().any(|task| task.line == line) {
+        return Err("line is not a task".to_string());
+    }
+    let mut offset = 0usize;
+    for (idx, raw) in markdown.split_inclusive('\n').enumerate() {
+        if idx + 1 != line {
+            offset += raw.len();
+            continue;
+        }
+        let task = parse_task_line(raw).ok_or("line is not a task")?;
+        let next = completed.unwrap_or(!task.completed);
+        let mark = if next { "x" } else { " " };
+        let start = offset + task.checkbox_offset + 1;
+        let mut out = String::with_capacity(markdown.len());
+        out.push_str(&markdown[..start]);
+        out.push_str(mark);
+        out.push_str(&markdown[start + 1..]);
+        return Ok((out, next));
+    }
+    Err("line is out of range".to_string())
+}
@@ -0,0 +1,84 @@
+use crate::features::notes::service::{atomic_write, file_meta, safe_vault_abs_for_write};
+use crate::features::search::db as search_db;
+use crate::features::search::model::{TaskHit, TaskQuery};
+use crate::features::search::service as search_service;
+use crate::features::tasks::parser;
+use crate::shared::storage;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::AppHandle;
+
+#[derive(Debug, Deserialize)]
+pub struct TaskToggleArgs {
+    pub vault_id: String,
+    pub note_path: String,
+    pub line: usize,
+    pub expected_mtime_ms: i64,
+    #[serde(default)]
+    pub completed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskToggleResult {
+    pub completed: bool,
+    pub mtime_ms: i64,
+}
+
+/// Flips the checkbox on `line` of a note, refusing when the file changed
+/// since the caller last read it.
+pub(crate) fn toggle_task_at(
+    root: &Path,
+    note_path: &str,
+    line: usize,
+    expected_mtime_ms: i64,
+    completed: Option<bool>,
+) -> Result<TaskToggleResult, String> {
+    let abs = safe_vault_abs_for_write(root, note_path)?;
+    match file_meta(&abs) {
+        Ok((disk_mtime, _)) if disk_mtime != expected_mtime_ms => {
+            return Err("conflict:mtime_mismatch".to_string());
+        }
+        Err(_) => return Err("conflict:file_missing".to_string()),
+        _ => {}
+    }
+
+    let markdown = std::fs::read_to_string(&abs).map_err(|e| e.to_string())?;
+    let (updated, completed) = parser::toggle_task_line(&markdown, line, completed)?;
+    atomic_write(&abs, &updated)?;
+    let (mtime_ms, _) = file_meta(&abs)?;
+    Ok(TaskToggleResult {
+        completed,
+        mtime_ms,
+    })
+}
+
+#[tauri::command]
+pub fn tasks_query(
+    app: AppHandle,
+    vault_id: String,
+    query: Option<TaskQuery>,
+) -> Result<Vec<TaskHit>, String> {
+    let query = query.unwrap_or_default();
+    search_service::with_read_conn(&app, &vault_id, |conn| search_db::query_tasks(conn, &query))
+        .map_err(String::from)
+}
+
+#[tauri::command]
+pub fn tasks_toggle(args: TaskToggleArgs, app: AppHandle) -> Result<TaskToggleResult, String> {
+    log::info!(
+        "Toggling task vault_id={} note_path={} line={}",
+        args.vault_id,
+        args.note_path,
+        args.line
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    let result = toggle_task_at(
+        &root,
+        &args.note_path,
+        args.line,
+        args.expected_mtime_ms,
+        args.completed,
+    )?;
+    search_service::index_upsert_note(app, args.vault_id, args.note_path)?;
+    Ok(result)
+}
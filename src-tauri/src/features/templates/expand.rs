@@ -0,0 +1,260 @@
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(crate) const CURSOR_MARKER: &str = "{{cursor}}";
+
+const DEFAULT_DATE_FORMAT: &str = "YYYY-MM-DD";
+const DEFAULT_TIME_FORMAT: &str = "HH:mm";
+const PATH_UNSAFE_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+// Longest tokens first so `YYYY` is not read as two `YY`.
+const DATE_TOKENS: &[&str] = &[
+    "YYYY", "GGGG", "MMMM", "dddd", "MMM", "ddd", "YY", "MM", "DD", "HH", "hh", "mm", "ss", "WW",
+    "M", "D", "H", "h", "m", "s", "W", "A", "a", "Q",
+];
+
+pub(crate) struct TemplateContext<'a> {
+    pub title: &'a str,
+    pub now: NaiveDateTime,
+    pub variables: &'a HashMap<String, String>,
+}
+
+fn format_token(dt: &NaiveDateTime, token: &str) -> String {
+    let hour12 = match dt.hour() % 12 {
+        0 => 12,
+        h => h,
+    };
+    match token {
+        "YYYY" => format!("{:04}", dt.year()),
+        "YY" => format!("{:02}", dt.year().rem_euclid(100)),
+        "GGGG" => format!("{:04}", dt.iso_week().year()),
+        "MMMM" => dt.format("%B").to_string(),
+        "MMM" => dt.format("%b").to_string(),
+        "MM" => format!("{:02}", dt.month()),
+        "M" => dt.month().to_string(),
+        "DD" => format!("{:02}", dt.day()),
+        "D" => dt.day().to_string(),
+        "dddd" => dt.format("%A").to_string(),
+        "ddd" => dt.format("%a").to_string(),
+        "HH" => format!("{:02}", dt.hour()),
+        "H" => dt.hour().to_string(),
+        "hh" => format!("{:02}", hour12),
+        "h" => hour12.to_string(),
+        "mm" => format!("{:02}", dt.minute()),
+        "m" => dt.minute().to_string(),
+        "ss" => format!("{:02}", dt.second()),
+        "s" => dt.second().to_string(),
+        "WW" => format!("{:02}", dt.iso_week().week()),
+        "W" => dt.iso_week().week().to_string(),
+        "A" => (if dt.hour() < 12 { "AM" } else { "PM" }).to_string(),
+        "a" => (if dt.hour() < 12 { "am" } else { "pm" }).to_string(),
+        "Q" => ((dt.month() - 1) / 3 + 1).to_string(),
+        _ => token.to_string(),
+    }
+}
+
+/// Formats a date with moment-style tokens (`YYYY-MM-DD`, `dddd`, `GGGG-[W]WW`).
+/// Text inside square brackets is copied verbatim.
+pub(crate) fn format_date(dt: &NaiveDateTime, pattern: &str) -> String {
+    let mut out = String::new();
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        if c == '[' {
+            if let Some(end) = rest.find(']') {
+                out.push_str(&rest[1..end]);
+                rest = &rest[end + 1..];
+                continue;
+            }
+        }
+        if let Some(token) = DATE_TOKENS.iter().find(|t| rest.starts_with(**t)) {
+            out.push_str(&format_token(dt, token));
+            rest = &rest[token.len()..];
+            continue;
+        }
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+fn resolve_variable(expr: &str, ctx: &TemplateContext<'_>) -> Option<String> {
+    if let Some(value) = ctx.variables.get(expr) {
+        return Some(value.clone());
+    }
+    let (name, arg) = match expr.split_once(':') {
+        Some((name, arg)) => (name.trim(), Some(arg.trim())),
+        None => (expr, None),
+    };
+    match name {
+        "title" => Some(ctx.title.to_string()),
+        "date" => Some(format_date(&ctx.now, arg.unwrap_or(DEFAULT_DATE_FORMAT))),
+        "time" => Some(format_date(&ctx.now, arg.unwrap_or(DEFAULT_TIME_FORMAT))),
+        _ => None,
+    }
+}
+
+fn expand_with(
+    template: &str,
+    ctx: &TemplateContext<'_>,
+    escape: impl Fn(String) -> String,
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let expr = after_open[..end].trim();
+        match resolve_variable(expr, ctx) {
+            Some(value) => out.push_str(&escape(value)),
+            // Unknown placeholders (including `{{cursor}}`) are kept as written.
+            None => out.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after_open[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+pub(crate) fn expand_template(template: &str, ctx: &TemplateContext<'_>) -> String {
+    expand_with(template, ctx, |value| value)
+}
+
+/// Expands a destination pattern such as `meetings/{{date}}-{{title}}.md`.
+/// Substituted values cannot introduce folders or characters that are invalid
+/// in file names.
+pub(crate) fn expand_note_path(pattern: &str, ctx: &TemplateContext<'_>) -> String {
+    let expanded = expand_with(pattern, ctx, |value| {
+        value
+            .chars()
+            .map(|c| {
+                if PATH_UNSAFE_CHARS.contains(&c) {
+                    '-'
+                } else {
+                    c
+                }
+            })
+            .collect::<String>()
+            .trim()
+            .to_string()
+    });
+    let path = expanded.replace(CURSOR_MARKER, "").trim().to_string();
+    if path.to_ascii_lowercase().ends_with(".md") {
+        path
+    } else {
+        format!("{}.md", path)
+    }
+}
+
+/// Removes the first `{{cursor}}` marker (and any others) and returns the
+/// UTF-16 offset where it stood, which is how the editor counts positions.
+pub(crate) fn take_cursor(text: &str) -> (String, Option<usize>) {
+    let Some(byte_offset) = text.find(CURSOR_MARKER) else {
+        return (text.to_string(), None);
+    };
+    let offset = text[..byte_offset].encode_utf16().count();
+    (text.replace(CURSOR_MARKER, ""), Some(offset))
+}
+
+pub(crate) fn split_frontmatter(text: &str) -> Option<(&str, &str)> {
+    let rest = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))?;
+    let mut offset = 0usize;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn frontmatter_key(line: &str) -> Option<&str> {
+    let first = line.chars().next()?;
+    if first.is_whitespace() || first == '#' || first == '-' {
+        return None;
+    }
+    let (key, _) = line.split_once(':')?;
+    Some(key.trim())
+}
+
+fn yaml_needs_quotes(value: &str, in_flow: bool) -> bool {
+    let lowered = value.to_ascii_lowercase();
+    value.is_empty()
+        || value.trim() != value
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.contains('\n')
+        || value.starts_with(|c: char| "!&*>|%@`'\"{[-?:#,".contains(c))
+        || (in_flow && value.contains(|c: char| ",[]{}".contains(c)))
+        || matches!(
+            lowered.as_str(),
+            "true" | "false" | "null" | "yes" | "no" | "~"
+        )
+        || value.parse::<f64>().is_ok()
+}
+
+fn yaml_scalar(value: &Value, in_flow: bool) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::String(s) if !yaml_needs_quotes(s, in_flow) => s.clone(),
+        Value::Array(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(|item| yaml_scalar(item, true))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        // JSON strings and objects are valid YAML flow scalars.
+        other => other.to_string(),
+    }
+}
+
+/// Merges `overrides` into the note's YAML frontmatter. Existing top-level
+/// keys are replaced in place, new keys are appended, and a frontmatter
+/// block is created when the note has none.
+pub(crate) fn merge_frontmatter(
+    markdown: &str,
+    overrides: &serde_json::Map<String, Value>,
+) -> String {
+    if overrides.is_empty() {
+        return markdown.to_string();
+    }
+
+    let (existing, body) = split_frontmatter(markdown).unwrap_or(("", markdown));
+    let mut entries: Vec<(Option<String>, String)> = Vec::new();
+    for line in existing.lines() {
+        match frontmatter_key(line) {
+            Some(key) => entries.push((Some(key.to_string()), format!("{}\n", line))),
+            None => match entries.last_mut() {
+                Some((_, raw)) => {
+                    raw.push_str(line);
+                    raw.push('\n');
+                }
+                None => entries.push((None, format!("{}\n", line))),
+            },
+        }
+    }
+
+    for (key, value) in overrides {
+        let rendered = format!("{}: {}\n", key, yaml_scalar(value, false));
+        match entries.iter_mut().find(|(k, _)| k.as_deref() == Some(key)) {
+            Some((_, raw)) => *raw = rendered,
+            None => entries.push((Some(key.clone()), rendered)),
+        }
+    }
+
+    let mut out = String::from("---\n");
+    for (_, raw) in entries {
+        out.push_str(&raw);
+    }
+    out.push_str("---\n");
+    out.push_str(body);
+    out
+}
@@ -0,0 +1,174 @@
+use crate::features::notes::service::{
+    self as notes_service, create_note_file, extract_title, safe_vault_abs, NoteMeta,
+};
+use crate::features::templates::expand::{self, TemplateContext};
+use crate::features::vault_settings::service::load_vault_settings_at;
+use crate::shared::storage;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::AppHandle;
+use walkdir::WalkDir;
+
+pub(crate) const TEMPLATES_FOLDER_SETTING_KEY: &str = "templates_folder";
+
+const DEFAULT_TEMPLATES_FOLDER: &str = "templates";
+const DEFAULT_TITLE: &str = "Untitled";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateMeta {
+    pub path: String,
+    pub name: String,
+    pub title: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NoteFromTemplateArgs {
+    pub vault_id: String,
+    pub template_path: String,
+    pub note_path: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    #[serde(default)]
+    pub frontmatter: serde_json::Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplatedNote {
+    pub note: NoteMeta,
+    pub cursor_offset: Option<usize>,
+}
+
+pub(crate) struct TemplateRequest<'a> {
+    pub template_path: Option<&'a str>,
+    pub note_path: &'a str,
+    pub title: Option<&'a str>,
+    pub variables: &'a HashMap<String, String>,
+    pub frontmatter: &'a serde_json::Map<String, Value>,
+    pub now: NaiveDateTime,
+}
+
+/// Reads the vault's `templates_folder` setting, falling back to `templates`.
+pub(crate) fn templates_folder(root: &Path) -> String {
+    load_vault_settings_at(root)
+        .ok()
+        .and_then(|settings| settings.get(TEMPLATES_FOLDER_SETTING_KEY).cloned())
+        .and_then(|value| {
+            value
+                .as_str()
+                .map(|s| storage::normalize_relative_path(Path::new(s)))
+        })
+        .map(|folder| folder.trim_matches('/').to_string())
+        .filter(|folder| !folder.is_empty())
+        .unwrap_or_else(|| DEFAULT_TEMPLATES_FOLDER.to_string())
+}
+
+pub(crate) fn list_templates_at(root: &Path) -> Result<Vec<TemplateMeta>, String> {
+    let folder = templates_folder(root);
+    let Ok(folder_abs) = safe_vault_abs(root, &folder) else {
+        return Ok(Vec::new());
+    };
+    if !folder_abs.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut out = Vec::new();
+    for entry in WalkDir::new(&folder_abs)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        let p = entry.path();
+        if !entry.file_type().is_file() || p.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Ok(rel) = p.strip_prefix(&folder_abs) else {
+            continue;
+        };
+        let rel = storage::normalize_relative_path(rel);
+        let name = rel.strip_suffix(".md").unwrap_or(&rel).to_string();
+        out.push(TemplateMeta {
+            path: format!("{}/{}", folder, rel),
+            title: extract_title(p),
+            name,
+        });
+    }
+    out.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(out)
+}
+
+fn read_template(root: &Path, template_path: &str) -> Result<String, String> {
+    let abs = safe_vault_abs(root, template_path)?;
+    std::fs::read_to_string(&abs).map_err(|e| format!("failed to read template: {}", e))
+}
+
+/// Expands the destination pattern and template body, merges frontmatter and
+/// writes the new note. The note must not exist yet.
+pub(crate) fn create_from_template_at(
+    root: &Path,
+    request: &TemplateRequest<'_>,
+) -> Result<TemplatedNote, String> {
+    let title = request
+        .title
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(DEFAULT_TITLE);
+    let ctx = TemplateContext {
+        title,
+        now: request.now,
+        variables: request.variables,
+    };
+
+    let note_path = expand::expand_note_path(request.note_path, &ctx);
+    let template = match request.template_path {
+        Some(template_path) => read_template(root, template_path)?,
+        None => String::new(),
+    };
+    let markdown = expand::expand_template(&template, &ctx);
+    let markdown = expand::merge_frontmatter(&markdown, request.frontmatter);
+    let (markdown, cursor_offset) = expand::take_cursor(&markdown);
+
+    let note = create_note_file(root, &note_path, &markdown)?;
+    Ok(TemplatedNote {
+        note,
+        cursor_offset,
+    })
+}
+
+#[tauri::command]
+pub fn list_templates(app: AppHandle, vault_id: String) -> Result<Vec<TemplateMeta>, String> {
+    log::info!("Listing templates vault_id={}", vault_id);
+    let root = storage::vault_path(&app, &vault_id)?;
+    list_templates_at(&root)
+}
+
+#[tauri::command]
+pub fn create_note_from_template(
+    args: NoteFromTemplateArgs,
+    app: AppHandle,
+) -> Result<TemplatedNote, String> {
+    log::info!(
+        "Creating note from template vault_id={} template_path={} note_path={}",
+        args.vault_id,
+        args.template_path,
+        args.note_path
+    );
+    let root = storage::vault_path(&app, &args.vault_id)?;
+    let created = create_from_template_at(
+        &root,
+        &TemplateRequest {
+            template_path: Some(&args.template_path),
+            note_path: &args.note_path,
+            title: args.title.as_deref(),
+            variables: &args.variables,
+            frontmatter: &args.frontmatter,
+            now: chrono::Local::now().naive_local(),
+        },
+    )?;
+    notes_service::invalidate_note_parent_folder_cache(&args.vault_id, &created.note.path);
+    Ok(created)
+}
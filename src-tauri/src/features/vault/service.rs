@@ -1,8 +1,19 @@
+use crate::features::notes::service::list_notes_in;
+use crate::features::search::db as search_db;
+use crate::features::search::service as search_service;
+use crate::features::vault_settings::service::load_vault_settings_at;
+use crate::features::watcher::service::{self as watcher_service, WatcherState};
+use crate::shared::constants;
+use crate::shared::error::{AppError, AppResult};
 use crate::shared::storage;
 use crate::shared::storage::{Vault, VaultEntry, VaultStore};
-use serde::Deserialize;
-use std::path::PathBuf;
-use tauri::AppHandle;
+use crate::shared::vault_context::VaultContext;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+const WINDOW_LABEL_PREFIX: &str = "vault-";
 
 fn canonicalize_path(path: &str) -> Result<String, String> {
     let p = PathBuf::from(path);
@@ -33,7 +44,8 @@ fn mark_vault_opened(vault: &mut Vault, opened_at: i64) {
 }
 
 fn load_note_count(app: &AppHandle, vault_id: &str) -> Option<u64> {
-    match crate::features::notes::service::list_notes(app.clone(), vault_id.to_string()) {
+    let notes = VaultContext::from_app(app, vault_id).and_then(|ctx| list_notes_in(&ctx));
+    match notes {
         Ok(notes) => Some(notes.len() as u64),
         Err(error) => {
             log::warn!(
@@ -46,6 +58,18 @@ fn load_note_count(app: &AppHandle, vault_id: &str) -> Option<u64> {
     }
 }
 
+/// The id for a newly registered folder. A vault relinked away from this path
+/// may still hold its hash, so salt it until it is free.
+fn unused_vault_id(store: &VaultStore, vault_path: &str) -> String {
+    let mut id = storage::vault_id_for_path(vault_path);
+    let mut salt = 1;
+    while store.vaults.iter().any(|v| v.vault.id == id) {
+        id = storage::vault_id_for_path(&format!("{}#{}", vault_path, salt));
+        salt += 1;
+    }
+    id
+}
+
 #[derive(Debug, Deserialize)]
 pub struct OpenVaultArgs {
     pub vault_path: String,
@@ -72,7 +96,7 @@ fn upsert_vault(store: &mut VaultStore, mut vault: Vault) {
 }
 
 #[tauri::command]
-pub fn open_vault(app: AppHandle, args: OpenVaultArgs) -> Result<Vault, String> {
+pub fn open_vault(app: AppHandle, args: OpenVaultArgs) -> AppResult<Vault> {
     log::info!("Opening vault path={}", args.vault_path);
     let vault_path = canonicalize_path(&args.vault_path).map_err(|e| {
         log::error!(
@@ -87,26 +111,39 @@ pub fn open_vault(app: AppHandle, args: OpenVaultArgs) -> Result<Vault, String>
         e.to_string()
     })?;
     if !meta.is_dir() {
-        return Err("vault path is not a directory".to_string());
+        return Err(AppError::invalid_input("vault path is not a directory"));
     }
 
     let mut store = storage::load_store(&app)?;
-    let id = storage::vault_id_for_path(&vault_path);
-    let existing = store.vaults.iter().find(|v| v.vault.id == id);
+    // A relinked vault keeps its original id, so match on the path.
+    let existing = store
+        .vaults
+        .iter()
+        .find(|v| v.vault.path == vault_path)
+        .map(|v| v.vault.clone());
+    let id = existing
+        .as_ref()
+        .map(|v| v.id.clone())
+        .unwrap_or_else(|| unused_vault_id(&store, &vault_path));
     let created_at = existing
-        .map(|v| v.vault.created_at)
+        .as_ref()
+        .map(|v| v.created_at)
         .unwrap_or_else(storage::now_ms);
-    let existing_note_count = existing.and_then(|v| v.vault.note_count);
+    let existing_note_count = existing.as_ref().and_then(|v| v.note_count);
     let note_count = load_note_count(&app, &id).or(existing_note_count);
 
     let vault = Vault {
         id: id.clone(),
         path: vault_path,
-        name: vault_name(&args.vault_path),
+        name: existing
+            .as_ref()
+            .map(|v| v.name.clone())
+            .unwrap_or_else(|| vault_name(&args.vault_path)),
         created_at,
         last_opened_at: Some(storage::now_ms()),
         note_count,
         is_available: true,
+        pinned: existing.as_ref().is_some_and(|v| v.pinned),
     };
 
     upsert_vault(&mut store, vault.clone());
@@ -115,7 +152,7 @@ pub fn open_vault(app: AppHandle, args: OpenVaultArgs) -> Result<Vault, String>
 }
 
 #[tauri::command]
-pub fn open_vault_by_id(app: AppHandle, vault_id: String) -> Result<Vault, String> {
+pub fn open_vault_by_id(app: AppHandle, vault_id: String) -> AppResult<Vault> {
     log::info!("Opening vault by id vault_id={}", vault_id);
     let mut store = storage::load_store(&app)?;
     let now = storage::now_ms();
@@ -127,15 +164,17 @@ pub fn open_vault_by_id(app: AppHandle, vault_id: String) -> Result<Vault, Strin
         .find(|v| v.vault.id == vault_id)
         .ok_or_else(|| {
             log::error!("Vault not found: {}", vault_id);
-            "vault not found".to_string()
+            AppError::not_found("vault", None)
         })?;
 
     refresh_vault_availability(&mut entry.vault);
     if !entry.vault.is_available {
-        let message = format!("vault unavailable at path: {}", entry.vault.path);
-        log::warn!("Open vault by id skipped: {}", message);
+        let error = AppError::VaultUnavailable {
+            path: entry.vault.path.clone(),
+        };
+        log::warn!("Open vault by id skipped: {}", error);
         storage::save_store(&app, &store)?;
-        return Err(message);
+        return Err(error);
     }
 
     entry.last_opened_at = now;
@@ -148,13 +187,21 @@ pub fn open_vault_by_id(app: AppHandle, vault_id: String) -> Result<Vault, Strin
     Ok(vault)
 }
 
+/// Pinned vaults first, then most recently opened.
+pub fn sort_vaults(store: &mut VaultStore) {
+    store.vaults.sort_by(|a, b| {
+        b.vault
+            .pinned
+            .cmp(&a.vault.pinned)
+            .then(b.last_opened_at.cmp(&a.last_opened_at))
+    });
+}
+
 #[tauri::command]
-pub fn list_vaults(app: AppHandle) -> Result<Vec<Vault>, String> {
+pub fn list_vaults(app: AppHandle) -> AppResult<Vec<Vault>> {
     log::info!("Listing vaults");
     let mut store = storage::load_store(&app)?;
-    store
-        .vaults
-        .sort_by(|a, b| b.last_opened_at.cmp(&a.last_opened_at));
+    sort_vaults(&mut store);
     let vaults = store
         .vaults
         .iter_mut()
@@ -181,7 +228,7 @@ pub struct RemoveVaultArgs {
 }
 
 #[tauri::command]
-pub fn remember_last_vault(app: AppHandle, args: RememberLastArgs) -> Result<(), String> {
+pub fn remember_last_vault(app: AppHandle, args: RememberLastArgs) -> AppResult<()> {
     log::info!("Remembering last vault vault_id={}", args.vault_id);
     let mut store = storage::load_store(&app)?;
     store.last_vault_id = Some(args.vault_id);
@@ -190,7 +237,7 @@ pub fn remember_last_vault(app: AppHandle, args: RememberLastArgs) -> Result<(),
 }
 
 #[tauri::command]
-pub fn remove_vault_from_registry(app: AppHandle, args: RemoveVaultArgs) -> Result<(), String> {
+pub fn remove_vault_from_registry(app: AppHandle, args: RemoveVaultArgs) -> AppResult<()> {
     log::info!("Removing vault from registry vault_id={}", args.vault_id);
     let mut store = storage::load_store(&app)?;
     store.vaults.retain(|entry| entry.vault.id != args.vault_id);
@@ -202,7 +249,260 @@ pub fn remove_vault_from_registry(app: AppHandle, args: RemoveVaultArgs) -> Resu
 }
 
 #[tauri::command]
-pub fn get_last_vault_id(app: AppHandle) -> Result<Option<String>, String> {
+pub fn get_last_vault_id(app: AppHandle) -> AppResult<Option<String>> {
     let store = storage::load_store(&app)?;
     Ok(store.last_vault_id)
 }
+
+fn find_vault_mut<'a>(store: &'a mut VaultStore, vault_id: &str) -> AppResult<&'a mut Vault> {
+    store
+        .vaults
+        .iter_mut()
+        .map(|entry| &mut entry.vault)
+        .find(|vault| vault.id == vault_id)
+        .ok_or_else(|| AppError::not_found("vault", None))
+}
+
+/// Points a registered vault at a new folder, keeping its id so settings,
+/// tokens and the last-opened marker still apply. `vault_path` must already
+/// be canonical.
+pub fn relink_vault_in(
+    store: &mut VaultStore,
+    vault_id: &str,
+    vault_path: &str,
+) -> AppResult<Vault> {
+    if let Some(other) = store
+        .vaults
+        .iter()
+        .find(|entry| entry.vault.path == vault_path && entry.vault.id != vault_id)
+    {
+        return Err(AppError::already_exists("vault", &other.vault.path));
+    }
+    let vault = find_vault_mut(store, vault_id)?;
+    vault.path = vault_path.to_string();
+    refresh_vault_availability(vault);
+    Ok(vault.clone())
+}
+
+pub fn rename_vault_in(store: &mut VaultStore, vault_id: &str, name: &str) -> AppResult<Vault> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_input("vault name cannot be empty"));
+    }
+    let vault = find_vault_mut(store, vault_id)?;
+    vault.name = name.to_string();
+    Ok(vault.clone())
+}
+
+pub fn pin_vault_in(store: &mut VaultStore, vault_id: &str, pinned: bool) -> AppResult<Vault> {
+    let vault = find_vault_mut(store, vault_id)?;
+    vault.pinned = pinned;
+    Ok(vault.clone())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VaultIssue {
+    MissingFolder,
+    UnreadableAppData {
+        path: String,
+        error: String,
+    },
+    SearchIndexMissing,
+    SearchIndexUnreadable {
+        error: String,
+    },
+    StaleSearchIndex {
+        added: usize,
+        modified: usize,
+        removed: usize,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct VaultHealth {
+    pub vault_id: String,
+    pub name: String,
+    pub path: String,
+    pub issues: Vec<VaultIssue>,
+}
+
+fn check_app_data(root: &Path, issues: &mut Vec<VaultIssue>) {
+    let app_dir = root.join(constants::APP_DIR);
+    match std::fs::metadata(&app_dir) {
+        Ok(meta) if !meta.is_dir() => {
+            issues.push(VaultIssue::UnreadableAppData {
+                path: constants::APP_DIR.to_string(),
+                error: "not a directory".to_string(),
+            });
+            return;
+        }
+        Ok(_) => {}
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return,
+        Err(error) => {
+            issues.push(VaultIssue::UnreadableAppData {
+                path: constants::APP_DIR.to_string(),
+                error: error.to_string(),
+            });
+            return;
+        }
+    }
+    if let Err(error) = load_vault_settings_at(root) {
+        issues.push(VaultIssue::UnreadableAppData {
+            path: format!("{}/settings.json", constants::APP_DIR),
+            error,
+        });
+    }
+}
+
+fn check_search_index(root: &Path, issues: &mut Vec<VaultIssue>) {
+    match search_db::pending_sync_plan(root) {
+        Ok(None) => issues.push(VaultIssue::SearchIndexMissing),
+        Ok(Some(plan)) => {
+            if !plan.added.is_empty() || !plan.modified.is_empty() || !plan.removed.is_empty() {
+                issues.push(VaultIssue::StaleSearchIndex {
+                    added: plan.added.len(),
+                    modified: plan.modified.len(),
+                    removed: plan.removed.len(),
+                });
+            }
+        }
+        Err(error) => issues.push(VaultIssue::SearchIndexUnreadable { error }),
+    }
+}
+
+/// Checks one registered vault. Only reads, except that opening an existing
+/// search index may migrate its schema, as opening the vault would.
+pub fn check_vault_health(vault: &Vault) -> VaultHealth {
+    let root = Path::new(&vault.path);
+    let mut issues = Vec::new();
+    if is_vault_path_available(&vault.path) {
+        check_app_data(root, &mut issues);
+        check_search_index(root, &mut issues);
+    } else {
+        issues.push(VaultIssue::MissingFolder);
+    }
+    VaultHealth {
+        vault_id: vault.id.clone(),
+        name: vault.name.clone(),
+        path: vault.path.clone(),
+        issues,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelinkVaultArgs {
+    pub vault_id: String,
+    pub vault_path: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenameVaultArgs {
+    pub vault_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PinVaultArgs {
+    pub vault_id: String,
+    pub pinned: bool,
+}
+
+#[tauri::command]
+pub fn relink_vault(app: AppHandle, args: RelinkVaultArgs) -> AppResult<Vault> {
+    log::info!(
+        "Relinking vault vault_id={} path={}",
+        args.vault_id,
+        args.vault_path
+    );
+    let vault_path = canonicalize_path(&args.vault_path)?;
+    if !is_vault_path_available(&vault_path) {
+        return Err(AppError::invalid_input("vault path is not a directory"));
+    }
+    let mut store = storage::load_store(&app)?;
+    let vault = relink_vault_in(&mut store, &args.vault_id, &vault_path)?;
+    storage::save_store(&app, &store)?;
+    search_service::close_worker(&app, &args.vault_id)?;
+    watcher_service::restart_watcher(&app, &args.vault_id);
+    Ok(vault)
+}
+
+#[tauri::command]
+pub fn rename_vault(app: AppHandle, args: RenameVaultArgs) -> AppResult<Vault> {
+    log::info!("Renaming vault vault_id={}", args.vault_id);
+    let mut store = storage::load_store(&app)?;
+    let vault = rename_vault_in(&mut store, &args.vault_id, &args.name)?;
+    storage::save_store(&app, &store)?;
+    Ok(vault)
+}
+
+#[tauri::command]
+pub fn pin_vault(app: AppHandle, args: PinVaultArgs) -> AppResult<Vault> {
+    log::info!(
+        "Setting vault pin vault_id={} pinned={}",
+        args.vault_id,
+        args.pinned
+    );
+    let mut store = storage::load_store(&app)?;
+    let vault = pin_vault_in(&mut store, &args.vault_id, args.pinned)?;
+    storage::save_store(&app, &store)?;
+    Ok(vault)
+}
+
+#[tauri::command]
+pub fn check_vaults(app: AppHandle) -> AppResult<Vec<VaultHealth>> {
+    log::info!("Checking vault health");
+    let mut store = storage::load_store(&app)?;
+    sort_vaults(&mut store);
+    for entry in &mut store.vaults {
+        refresh_vault_availability(&mut entry.vault);
+    }
+    storage::save_store(&app, &store)?;
+    Ok(store
+        .vaults
+        .iter()
+        .map(|entry| check_vault_health(&entry.vault))
+        .collect())
+}
+
+fn next_window_label(app: &AppHandle) -> String {
+    (1..)
+        .map(|n| format!("{}{}", WINDOW_LABEL_PREFIX, n))
+        .find(|label| app.get_webview_window(label).is_none())
+        .unwrap_or_default()
+}
+
+/// Opens another app window and returns its label. With a `vault_id`, the
+/// window opens that vault and is subscribed to its watcher right away, so
+/// it gets file events before its frontend has loaded.
+pub fn open_window(app: &AppHandle, vault_id: Option<&str>) -> AppResult<String> {
+    let label = next_window_label(app);
+    let url = match vault_id {
+        Some(vault_id) => {
+            let root = storage::vault_path(app, vault_id)?;
+            app.state::<WatcherState>()
+                .watch(app, &label, vault_id, &root)?;
+            format!(
+                "/?vault_id={}",
+                utf8_percent_encode(vault_id, NON_ALPHANUMERIC)
+            )
+        }
+        None => "/".to_string(),
+    };
+    log::info!("Opening window label={} vault_id={:?}", label, vault_id);
+    let built = WebviewWindowBuilder::new(app, &label, WebviewUrl::App(PathBuf::from(url)))
+        .title("otterly")
+        .inner_size(1200.0, 820.0)
+        .disable_drag_drop_handler()
+        .build();
+    if let Err(e) = built {
+        watcher_service::release_window(app, &label);
+        return Err(AppError::from(e.to_string()));
+    }
+    Ok(label)
+}
+
+#[tauri::command]
+pub async fn open_vault_window(app: AppHandle, vault_id: String) -> AppResult<String> {
+    open_window(&app, Some(&vault_id))
+}
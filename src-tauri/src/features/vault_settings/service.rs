@@ -23,6 +23,14 @@ fn load_vault_settings(app: &AppHandle, vault_id: &str) -> Result<HashMap<String
     parse_vault_settings(&bytes)
 }
 
+pub(crate) fn load_vault_settings_at(vault_root: &Path) -> Result<HashMap<String, Value>, String> {
+    let path = vault_root.join(constants::APP_DIR).join(SETTINGS_FILE);
+    let Some(bytes) = read_vault_settings_file(&path)? else {
+        return Ok(HashMap::new());
+    };
+    parse_vault_settings(&bytes)
+}
+
 pub(crate) fn parse_vault_settings(bytes: &[u8]) -> Result<HashMap<String, Value>, String> {
     let mut stream = serde_json::Deserializer::from_slice(bytes).into_iter::<Value>();
     let first = stream
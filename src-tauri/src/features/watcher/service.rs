@@ -1,25 +1,98 @@
-use crate::shared::constants;
 use crate::shared::storage;
+use crate::shared::vault_ignore::VaultIgnore;
 use notify::{Config, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::Serialize;
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, EventTarget, Manager, State, WebviewWindow};
 
 #[derive(Default)]
 pub struct WatcherState {
-    inner: Arc<Mutex<Option<WatcherRuntime>>>,
+    inner: Arc<Mutex<Watchers>>,
+}
+
+#[derive(Default)]
+struct Watchers {
+    subscriptions: WatcherSubscriptions,
+    runtimes: HashMap<String, WatcherRuntime>,
 }
 
 struct WatcherRuntime {
     stop_tx: mpsc::Sender<()>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+/// Which windows show which vault. Each window watches at most one vault; a
+/// vault's watcher runs while at least one window shows it.
+#[derive(Debug, Default)]
+pub struct WatcherSubscriptions {
+    by_vault: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Watchers to start and stop after a subscription change.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SubscriptionChange {
+    pub start: Option<String>,
+    pub stop: Option<String>,
+}
+
+impl WatcherSubscriptions {
+    fn vault_of(&self, window: &str) -> Option<String> {
+        self.by_vault
+            .iter()
+            .find(|(_, windows)| windows.contains(window))
+            .map(|(vault_id, _)| vault_id.clone())
+    }
+
+    /// Points `window` at `vault_id`, moving it off any vault it showed before.
+    pub fn watch(&mut self, window: &str, vault_id: &str) -> SubscriptionChange {
+        let mut change = self.release(window);
+        if change.stop.as_deref() == Some(vault_id) {
+            change.stop = None;
+        } else if !self.by_vault.contains_key(vault_id) {
+            change.start = Some(vault_id.to_string());
+        }
+        self.by_vault
+            .entry(vault_id.to_string())
+            .or_default()
+            .insert(window.to_string());
+        change
+    }
+
+    /// Drops `window`'s subscription, e.g. when it unwatches or closes.
+    pub fn release(&mut self, window: &str) -> SubscriptionChange {
+        let Some(vault_id) = self.vault_of(window) else {
+            return SubscriptionChange::default();
+        };
+        let windows = self.by_vault.entry(vault_id.clone()).or_default();
+        windows.remove(window);
+        if windows.is_empty() {
+            self.by_vault.remove(&vault_id);
+            return SubscriptionChange {
+                start: None,
+                stop: Some(vault_id),
+            };
+        }
+        SubscriptionChange::default()
+    }
+
+    pub fn windows_for(&self, vault_id: &str) -> Vec<String> {
+        self.by_vault
+            .get(vault_id)
+            .map(|windows| windows.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn watched_vaults(&self) -> Vec<String> {
+        self.by_vault.keys().cloned().collect()
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 #[serde(tag = "type", rename_all = "snake_case")]
-enum VaultFsEvent {
+pub enum VaultFsEvent {
     NoteChangedExternally {
         vault_id: String,
         note_path: String,
@@ -38,50 +111,55 @@ enum VaultFsEvent {
     },
 }
 
-fn rel_path(root: &Path, abs: &Path) -> Option<String> {
+fn rel_path(root: &Path, abs: &Path, ignore: &VaultIgnore) -> Option<String> {
     let rel = abs.strip_prefix(root).ok()?;
     let rel = storage::normalize_relative_path(rel);
 
-    for excluded in constants::EXCLUDED_FOLDERS {
-        if rel == *excluded || rel.starts_with(&format!("{}/", excluded)) {
-            return None;
-        }
+    if ignore.is_ignored_rel(&rel, abs.is_dir()) {
+        return None;
     }
 
     Some(rel)
 }
 
-fn emit(app: &AppHandle, event: VaultFsEvent) {
-    let _ = app.emit("vault_fs_event", event);
+/// Where watcher events are delivered. The app emits them to webview windows
+/// by label; tests record them.
+pub trait WindowSink: Clone + Send + Sync + 'static {
+    fn send(&self, window: &str, event: &VaultFsEvent);
 }
 
-fn with_runtime_lock<T>(
-    state: &State<'_, WatcherState>,
-    update: impl FnOnce(&mut Option<WatcherRuntime>) -> T,
-) -> Result<T, String> {
-    let mut guard = state.inner.lock().map_err(|_| "watcher lock poisoned")?;
-    Ok(update(&mut guard))
+impl WindowSink for AppHandle {
+    fn send(&self, window: &str, event: &VaultFsEvent) {
+        let _ = self.emit_to(
+            EventTarget::webview_window(window),
+            "vault_fs_event",
+            event.clone(),
+        );
+    }
+}
+
+/// Sends an event only to the windows currently showing its vault.
+fn emit(sink: &impl WindowSink, watchers: &Mutex<Watchers>, vault_id: &str, event: VaultFsEvent) {
+    let windows = match watchers.lock() {
+        Ok(guard) => guard.subscriptions.windows_for(vault_id),
+        Err(_) => return,
+    };
+    for label in windows {
+        sink.send(&label, &event);
+    }
 }
 
 fn stop_runtime(runtime: WatcherRuntime) {
     let _ = runtime.stop_tx.send(());
 }
 
-fn stop_active_runtime(state: &State<'_, WatcherState>) -> Result<(), String> {
-    let runtime = with_runtime_lock(state, |slot| slot.take())?;
-    if let Some(runtime) = runtime {
-        stop_runtime(runtime);
+fn apply_stop(watchers: &mut Watchers, change: &SubscriptionChange) {
+    if let Some(vault_id) = &change.stop {
+        if let Some(runtime) = watchers.runtimes.remove(vault_id) {
+            log::info!("Stopping watcher vault_id={}", vault_id);
+            stop_runtime(runtime);
+        }
     }
-    Ok(())
-}
-
-fn set_active_runtime(
-    state: &State<'_, WatcherState>,
-    runtime: WatcherRuntime,
-) -> Result<(), String> {
-    with_runtime_lock(state, |slot| {
-        *slot = Some(runtime);
-    })
 }
 
 fn classify_event(
@@ -111,21 +189,17 @@ fn classify_event(
     }
 }
 
-#[tauri::command]
-pub fn watch_vault(
-    app: AppHandle,
-    state: State<WatcherState>,
-    vault_id: String,
-) -> Result<(), String> {
-    log::info!("Watching vault vault_id={}", vault_id);
-    stop_active_runtime(&state)?;
-
-    let root = storage::vault_path(&app, &vault_id)?;
+fn spawn_watcher(
+    sink: &impl WindowSink,
+    watchers: Arc<Mutex<Watchers>>,
+    vault_id: &str,
+    root: &Path,
+) -> Result<WatcherRuntime, String> {
     let root_canon = root.canonicalize().map_err(|e| e.to_string())?;
     let (stop_tx, stop_rx) = mpsc::channel::<()>();
 
-    let app_handle = app.clone();
-    let vault_id_clone = vault_id.clone();
+    let sink = sink.clone();
+    let vault_id = vault_id.to_string();
 
     std::thread::spawn(move || {
         let (tx, rx) = mpsc::channel::<Result<notify::Event, notify::Error>>();
@@ -147,6 +221,7 @@ pub fn watch_vault(
             log::error!("Failed to start watching {}: {}", root_canon.display(), e);
             return;
         }
+        let mut ignore = VaultIgnore::load(&root_canon);
 
         loop {
             if stop_rx.try_recv().is_ok() {
@@ -175,26 +250,132 @@ pub fn watch_vault(
                     continue;
                 }
 
-                let Some(rel) = rel_path(&root_canon, &abs) else {
+                if abs.strip_prefix(&root_canon).is_ok_and(|rel| {
+                    VaultIgnore::is_rules_source(&storage::normalize_relative_path(rel))
+                }) {
+                    ignore = VaultIgnore::load(&root_canon);
+                }
+
+                let Some(rel) = rel_path(&root_canon, &abs, &ignore) else {
                     continue;
                 };
 
                 let ext = abs.extension().and_then(|e| e.to_str()).unwrap_or_default();
                 let is_md = ext == "md";
 
-                if let Some(vault_event) = classify_event(kind, &vault_id_clone, rel, is_md) {
-                    emit(&app_handle, vault_event);
+                if let Some(vault_event) = classify_event(kind, &vault_id, rel, is_md) {
+                    emit(&sink, &watchers, &vault_id, vault_event);
                 }
             }
         }
     });
 
-    set_active_runtime(&state, WatcherRuntime { stop_tx })?;
-    Ok(())
+    Ok(WatcherRuntime { stop_tx })
+}
+
+impl WatcherState {
+    /// Points `window` at the vault in `root`, starting the vault's watcher if
+    /// no other window shows it yet.
+    pub fn watch(
+        &self,
+        sink: &impl WindowSink,
+        window: &str,
+        vault_id: &str,
+        root: &Path,
+    ) -> Result<(), String> {
+        log::info!("Watching vault vault_id={} window={}", vault_id, window);
+        let watchers = Arc::clone(&self.inner);
+        self.update(|w| {
+            let change = w.subscriptions.watch(window, vault_id);
+            apply_stop(w, &change);
+            if change.start.is_none() && w.runtimes.contains_key(vault_id) {
+                return Ok(());
+            }
+            match spawn_watcher(sink, watchers, vault_id, root) {
+                Ok(runtime) => {
+                    w.runtimes.insert(vault_id.to_string(), runtime);
+                    Ok(())
+                }
+                Err(e) => {
+                    let change = w.subscriptions.release(window);
+                    apply_stop(w, &change);
+                    Err(e)
+                }
+            }
+        })?
+    }
+
+    /// Drops `window`'s subscription, stopping its vault's watcher once no
+    /// window shows that vault.
+    pub fn release(&self, window: &str) -> Result<(), String> {
+        self.update(|w| {
+            let change = w.subscriptions.release(window);
+            apply_stop(w, &change);
+        })
+    }
+
+    /// Restarts a vault's running watcher on `root`. Vaults nobody watches are
+    /// left alone; if `root` cannot be watched, the watcher stays stopped.
+    pub fn restart(
+        &self,
+        sink: &impl WindowSink,
+        vault_id: &str,
+        root: &Path,
+    ) -> Result<(), String> {
+        let watchers = Arc::clone(&self.inner);
+        self.update(|w| {
+            let Some(runtime) = w.runtimes.remove(vault_id) else {
+                return Ok(());
+            };
+            log::info!("Restarting watcher vault_id={}", vault_id);
+            stop_runtime(runtime);
+            let runtime = spawn_watcher(sink, watchers, vault_id, root)?;
+            w.runtimes.insert(vault_id.to_string(), runtime);
+            Ok(())
+        })?
+    }
+
+    fn update<T>(&self, update: impl FnOnce(&mut Watchers) -> T) -> Result<T, String> {
+        let mut guard = self.inner.lock().map_err(|_| "watcher lock poisoned")?;
+        Ok(update(&mut guard))
+    }
+}
+
+fn watched_root(app: &AppHandle, vault_id: &str) -> Result<PathBuf, String> {
+    Ok(storage::vault_path(app, vault_id)?)
 }
 
 #[tauri::command]
-pub fn unwatch_vault(state: State<WatcherState>) -> Result<(), String> {
-    log::info!("Unwatching vault");
-    stop_active_runtime(&state)
+pub fn watch_vault(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<WatcherState>,
+    vault_id: String,
+) -> Result<(), String> {
+    let root = watched_root(&app, &vault_id)?;
+    state.watch(&app, window.label(), &vault_id, &root)
+}
+
+#[tauri::command]
+pub fn unwatch_vault(window: WebviewWindow, state: State<WatcherState>) -> Result<(), String> {
+    log::info!("Unwatching vault window={}", window.label());
+    state.release(window.label())
+}
+
+/// Restarts a vault's running watcher so it follows the vault's current path.
+/// If the new folder cannot be watched, the watcher stays stopped.
+pub fn restart_watcher(app: &AppHandle, vault_id: &str) {
+    let result = watched_root(app, vault_id)
+        .and_then(|root| app.state::<WatcherState>().restart(app, vault_id, &root));
+    if let Err(e) = result {
+        log::warn!("Failed to restart watcher for vault {}: {}", vault_id, e);
+    }
+}
+
+/// Releases a closed window's watcher so vaults no window shows stop being
+/// watched.
+pub fn release_window(app: &AppHandle, label: &str) {
+    if let Err(e) = app.state::<WatcherState>().release(label) {
+        log::warn!("Failed to release watcher for window {}: {}", label, e);
+    }
 }
@@ -1,4 +1,5 @@
 mod app;
+mod cli;
 mod features;
 mod shared;
 
@@ -9,3 +10,8 @@ mod tests;
 pub fn run() {
     app::run();
 }
+
+/// Entry point of the headless `otterly-cli` binary.
+pub fn run_cli() -> i32 {
+    cli::run(std::env::args().skip(1).collect())
+}
@@ -0,0 +1,263 @@
+use serde::ser::{SerializeMap, Serializer};
+use serde::Serialize;
+use std::fmt;
+
+pub type AppResult<T> = Result<T, AppError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictReason {
+    /// The file changed on disk since the caller read it.
+    MtimeMismatch,
+    /// The file the caller expected is gone.
+    FileMissing,
+}
+
+impl ConflictReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            ConflictReason::MtimeMismatch => "mtime_mismatch",
+            ConflictReason::FileMissing => "file_missing",
+        }
+    }
+}
+
+/// Error returned by commands. It serializes to `{ code, message, ...context }`
+/// so the frontend can branch on `code`; `message` keeps the wording the
+/// commands used before, e.g. `conflict:mtime_mismatch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    NotFound {
+        entity: &'static str,
+        path: Option<String>,
+    },
+    Conflict {
+        path: String,
+        reason: ConflictReason,
+        current_mtime_ms: Option<i64>,
+    },
+    VaultUnavailable {
+        path: String,
+    },
+    PathEscapesVault {
+        path: String,
+    },
+    InvalidPath {
+        path: String,
+        reason: &'static str,
+    },
+    SymlinkRejected {
+        path: String,
+    },
+    AlreadyExists {
+        entity: &'static str,
+        path: String,
+    },
+    InvalidInput {
+        message: String,
+    },
+    /// The caller (an API token or plugin) lacks the capability for this call.
+    PermissionDenied {
+        message: String,
+    },
+    GitConflict {
+        message: String,
+        paths: Vec<String>,
+    },
+    Git {
+        message: String,
+    },
+    IndexBusy {
+        message: String,
+    },
+    Io {
+        message: String,
+        path: Option<String>,
+    },
+    Other {
+        message: String,
+    },
+}
+
+impl AppError {
+    pub fn not_found(entity: &'static str, path: Option<&str>) -> Self {
+        AppError::NotFound {
+            entity,
+            path: path.map(str::to_string),
+        }
+    }
+
+    pub fn already_exists(entity: &'static str, path: &str) -> Self {
+        AppError::AlreadyExists {
+            entity,
+            path: path.to_string(),
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        AppError::InvalidInput {
+            message: message.into(),
+        }
+    }
+
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        AppError::PermissionDenied {
+            message: message.into(),
+        }
+    }
+
+    pub fn git(message: impl Into<String>) -> Self {
+        AppError::Git {
+            message: message.into(),
+        }
+    }
+
+    pub fn io(error: std::io::Error, path: &str) -> Self {
+        if error.kind() == std::io::ErrorKind::NotFound {
+            return AppError::not_found("file", Some(path));
+        }
+        AppError::Io {
+            message: error.to_string(),
+            path: Some(path.to_string()),
+        }
+    }
+
+    /// Classifies a stringly-typed search index error, recognising SQLite's
+    /// busy/locked failures.
+    pub fn from_index(message: String) -> Self {
+        if message.contains("database is locked") || message.contains("database is busy") {
+            AppError::IndexBusy { message }
+        } else {
+            AppError::Other { message }
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound { .. } => "not_found",
+            AppError::Conflict { .. } => "conflict",
+            AppError::VaultUnavailable { .. } => "vault_unavailable",
+            AppError::PathEscapesVault { .. } => "path_escapes_vault",
+            AppError::InvalidPath { .. } => "invalid_path",
+            AppError::SymlinkRejected { .. } => "symlink_rejected",
+            AppError::AlreadyExists { .. } => "already_exists",
+            AppError::InvalidInput { .. } => "invalid_input",
+            AppError::PermissionDenied { .. } => "permission_denied",
+            AppError::GitConflict { .. } => "git_conflict",
+            AppError::Git { .. } => "git",
+            AppError::IndexBusy { .. } => "index_busy",
+            AppError::Io { .. } => "io",
+            AppError::Other { .. } => "other",
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotFound { entity, .. } => write!(f, "{} not found", entity),
+            AppError::Conflict { reason, .. } => write!(f, "conflict:{}", reason.as_str()),
+            AppError::VaultUnavailable { path } => write!(f, "vault unavailable at path: {}", path),
+            AppError::PathEscapesVault { .. } => f.write_str("note path escapes vault"),
+            AppError::InvalidPath { reason, .. } => f.write_str(reason),
+            AppError::SymlinkRejected { .. } => f.write_str("note path contains symlink component"),
+            AppError::AlreadyExists { entity, .. } => write!(f, "{} already exists", entity),
+            AppError::InvalidInput { message }
+            | AppError::PermissionDenied { message }
+            | AppError::GitConflict { message, .. }
+            | AppError::Git { message }
+            | AppError::IndexBusy { message }
+            | AppError::Io { message, .. }
+            | AppError::Other { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            AppError::NotFound { entity, path } => {
+                map.serialize_entry("entity", entity)?;
+                map.serialize_entry("path", path)?;
+            }
+            AppError::Conflict {
+                path,
+                reason,
+                current_mtime_ms,
+            } => {
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("reason", reason.as_str())?;
+                map.serialize_entry("current_mtime_ms", current_mtime_ms)?;
+            }
+            AppError::VaultUnavailable { path }
+            | AppError::PathEscapesVault { path }
+            | AppError::InvalidPath { path, .. }
+            | AppError::SymlinkRejected { path } => {
+                map.serialize_entry("path", path)?;
+            }
+            AppError::AlreadyExists { entity, path } => {
+                map.serialize_entry("entity", entity)?;
+                map.serialize_entry("path", path)?;
+            }
+            AppError::GitConflict { paths, .. } => {
+                map.serialize_entry("paths", paths)?;
+            }
+            AppError::Io { path, .. } => {
+                map.serialize_entry("path", path)?;
+            }
+            AppError::InvalidInput { .. }
+            | AppError::PermissionDenied { .. }
+            | AppError::Git { .. }
+            | AppError::IndexBusy { .. }
+            | AppError::Other { .. } => {}
+        }
+        map.end()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other { message }
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        AppError::Io {
+            message: error.to_string(),
+            path: None,
+        }
+    }
+}
+
+impl From<git2::Error> for AppError {
+    fn from(error: git2::Error) -> Self {
+        match error.code() {
+            git2::ErrorCode::Conflict | git2::ErrorCode::MergeConflict => AppError::GitConflict {
+                message: error.message().to_string(),
+                paths: Vec::new(),
+            },
+            _ => AppError::git(error.message()),
+        }
+    }
+}
+
+/// Lets code that still returns `Result<_, String>` call typed functions
+/// with `?`.
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
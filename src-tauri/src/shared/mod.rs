@@ -1,2 +1,5 @@
 pub mod constants;
+pub mod error;
 pub mod storage;
+pub mod vault_context;
+pub mod vault_ignore;
@@ -1,8 +1,10 @@
+use crate::shared::error::AppResult;
+use crate::shared::vault_context::VaultRegistry;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::http::{Request, Response};
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 fn default_is_available() -> bool {
     true
@@ -20,6 +22,8 @@ pub struct Vault {
     pub note_count: Option<u64>,
     #[serde(default = "default_is_available")]
     pub is_available: bool,
+    #[serde(default)]
+    pub pinned: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,48 +50,15 @@ pub fn vault_id_for_path(path: &str) -> String {
 }
 
 pub fn store_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
-    let dir = dir.join("otterly");
-    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
-    Ok(dir.join("vaults.json"))
+    VaultRegistry::for_app(app)?.store_path()
 }
 
 pub fn load_store(app: &AppHandle) -> Result<VaultStore, String> {
-    log::debug!("Loading vault store");
-    let path = store_path(app)?;
-    let bytes = match std::fs::read(&path) {
-        Ok(b) => b,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(VaultStore::default()),
-        Err(e) => {
-            log::error!("Failed to read vault store at {}: {}", path.display(), e);
-            return Err(e.to_string());
-        }
-    };
-    serde_json::from_slice(&bytes).map_err(|e| {
-        log::error!("Failed to parse vault store at {}: {}", path.display(), e);
-        e.to_string()
-    })
+    VaultRegistry::for_app(app)?.load()
 }
 
 pub fn save_store(app: &AppHandle, store: &VaultStore) -> Result<(), String> {
-    log::debug!("Saving vault store");
-    let path = store_path(app)?;
-    let tmp = path.with_extension("json.tmp");
-    let bytes = serde_json::to_vec_pretty(store).map_err(|e| e.to_string())?;
-    std::fs::write(&tmp, &bytes).map_err(|e| {
-        log::error!("Failed to write vault store to {}: {}", tmp.display(), e);
-        e.to_string()
-    })?;
-    std::fs::rename(&tmp, &path).map_err(|e| {
-        log::error!(
-            "Failed to rename vault store {} -> {}: {}",
-            tmp.display(),
-            path.display(),
-            e
-        );
-        e.to_string()
-    })?;
-    Ok(())
+    VaultRegistry::for_app(app)?.save(store)
 }
 
 pub fn vault_path_by_id(store: &VaultStore, vault_id: &str) -> Option<String> {
@@ -105,10 +76,36 @@ pub fn normalize_relative_path(path: &Path) -> String {
         .join("/")
 }
 
-pub fn vault_path(app: &AppHandle, vault_id: &str) -> Result<PathBuf, String> {
-    let store = load_store(app)?;
-    let path = vault_path_by_id(&store, vault_id).ok_or("vault not found")?;
-    Ok(PathBuf::from(path))
+/// Trims and dedupes a folder scope. An empty entry means the whole vault, so
+/// it collapses the list to empty.
+pub fn normalize_folders(folders: Vec<String>) -> Vec<String> {
+    let mut out: Vec<String> = folders
+        .iter()
+        .map(|folder| folder.trim().trim_matches('/').to_string())
+        .collect();
+    if out.iter().any(String::is_empty) {
+        return Vec::new();
+    }
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Whether a vault-relative path lies inside one of `folders`; an empty scope
+/// allows everything.
+pub fn path_in_folders(folders: &[String], path: &str) -> bool {
+    let path = path.trim_matches('/');
+    folders.is_empty()
+        || folders.iter().any(|folder| {
+            path == folder
+                || path
+                    .strip_prefix(folder.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+}
+
+pub fn vault_path(app: &AppHandle, vault_id: &str) -> AppResult<PathBuf> {
+    VaultRegistry::for_app(app)?.vault_path(vault_id)
 }
 
 fn url_decode(input: &str) -> String {
@@ -0,0 +1,197 @@
+use crate::features::notes::service::{safe_vault_abs, safe_vault_abs_for_write};
+use crate::features::search::db as search_db;
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::storage::{self, VaultStore};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager};
+
+const STORE_FILE: &str = "vaults.json";
+
+#[derive(Clone)]
+struct CachedStore {
+    modified: Option<SystemTime>,
+    len: u64,
+    store: VaultStore,
+}
+
+/// Parsed registries keyed by file path. An entry is reused while the file's
+/// mtime and size match, so edits made outside the app are still picked up.
+fn store_cache() -> &'static Mutex<HashMap<PathBuf, CachedStore>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedStore>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn file_stamp(path: &Path) -> std::io::Result<(Option<SystemTime>, u64)> {
+    let meta = std::fs::metadata(path)?;
+    Ok((meta.modified().ok(), meta.len()))
+}
+
+/// The vault registry (`vaults.json`) in a config directory. The app uses its
+/// config dir; tests and the CLI can point it anywhere.
+#[derive(Debug, Clone)]
+pub struct VaultRegistry {
+    dir: PathBuf,
+}
+
+impl VaultRegistry {
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn for_app(app: &AppHandle) -> Result<Self, String> {
+        let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+        Ok(Self::at(dir.join("otterly")))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn store_path(&self) -> Result<PathBuf, String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        Ok(self.dir.join(STORE_FILE))
+    }
+
+    pub fn load(&self) -> Result<VaultStore, String> {
+        let path = self.store_path()?;
+        let (modified, len) = match file_stamp(&path) {
+            Ok(stamp) => stamp,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(VaultStore::default()),
+            Err(e) => return Err(e.to_string()),
+        };
+        let mut cache = store_cache().lock().map_err(|e| e.to_string())?;
+        if let Some(cached) = cache.get(&path) {
+            if cached.modified == modified && cached.len == len {
+                return Ok(cached.store.clone());
+            }
+        }
+
+        log::debug!("Loading vault store from {}", path.display());
+        let bytes = std::fs::read(&path).map_err(|e| {
+            log::error!("Failed to read vault store at {}: {}", path.display(), e);
+            e.to_string()
+        })?;
+        let store: VaultStore = serde_json::from_slice(&bytes).map_err(|e| {
+            log::error!("Failed to parse vault store at {}: {}", path.display(), e);
+            e.to_string()
+        })?;
+        cache.insert(
+            path,
+            CachedStore {
+                modified,
+                len,
+                store: store.clone(),
+            },
+        );
+        Ok(store)
+    }
+
+    pub fn save(&self, store: &VaultStore) -> Result<(), String> {
+        log::debug!("Saving vault store");
+        let path = self.store_path()?;
+        let tmp = path.with_extension("json.tmp");
+        let bytes = serde_json::to_vec_pretty(store).map_err(|e| e.to_string())?;
+        let mut cache = store_cache().lock().map_err(|e| e.to_string())?;
+        std::fs::write(&tmp, &bytes).map_err(|e| {
+            log::error!("Failed to write vault store to {}: {}", tmp.display(), e);
+            e.to_string()
+        })?;
+        std::fs::rename(&tmp, &path).map_err(|e| {
+            log::error!(
+                "Failed to rename vault store {} -> {}: {}",
+                tmp.display(),
+                path.display(),
+                e
+            );
+            e.to_string()
+        })?;
+        match file_stamp(&path) {
+            Ok((modified, len)) => {
+                cache.insert(
+                    path,
+                    CachedStore {
+                        modified,
+                        len,
+                        store: store.clone(),
+                    },
+                );
+            }
+            Err(_) => {
+                cache.remove(&path);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn vault_path(&self, vault_id: &str) -> AppResult<PathBuf> {
+        let store = self.load()?;
+        let path = storage::vault_path_by_id(&store, vault_id)
+            .ok_or_else(|| AppError::not_found("vault", None))?;
+        Ok(PathBuf::from(path))
+    }
+
+    pub fn context(&self, vault_id: &str) -> AppResult<VaultContext> {
+        Ok(VaultContext::new(vault_id, self.vault_path(vault_id)?))
+    }
+}
+
+/// A resolved vault: its id and root folder. The note services and the
+/// `search::service::*_in` index reads take this instead of an `AppHandle`,
+/// so they run the same from commands, tests and the CLI. Index writes still
+/// go through the app's worker; git takes the vault path directly.
+#[derive(Debug, Clone)]
+pub struct VaultContext {
+    vault_id: String,
+    root: PathBuf,
+}
+
+impl VaultContext {
+    pub fn new(vault_id: impl Into<String>, root: impl Into<PathBuf>) -> Self {
+        Self {
+            vault_id: vault_id.into(),
+            root: root.into(),
+        }
+    }
+
+    /// A context for an unregistered folder, with the id the registry would
+    /// give it.
+    pub fn for_root(root: &Path) -> AppResult<Self> {
+        let root = root
+            .canonicalize()
+            .map_err(|e| AppError::io(e, &root.to_string_lossy()))?;
+        let id = storage::vault_id_for_path(&root.to_string_lossy());
+        Ok(Self::new(id, root))
+    }
+
+    pub fn from_app(app: &AppHandle, vault_id: &str) -> AppResult<Self> {
+        VaultRegistry::for_app(app)?.context(vault_id)
+    }
+
+    pub fn vault_id(&self) -> &str {
+        &self.vault_id
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Absolute path of an existing file or folder inside the vault.
+    pub fn abs(&self, rel: &str) -> AppResult<PathBuf> {
+        safe_vault_abs(&self.root, rel)
+    }
+
+    /// Absolute path for a file that may not exist yet.
+    pub fn abs_for_write(&self, rel: &str) -> AppResult<PathBuf> {
+        safe_vault_abs_for_write(&self.root, rel)
+    }
+
+    /// Opens a direct connection to the vault's search index, bypassing the
+    /// app's index worker.
+    pub fn open_search_db(&self) -> Result<Connection, String> {
+        search_db::open_search_db(&self.root)
+    }
+}
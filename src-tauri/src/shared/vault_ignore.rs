@@ -0,0 +1,100 @@
+use crate::features::vault_settings::service::load_vault_settings_at;
+use crate::shared::constants;
+use crate::shared::storage;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
+
+/// Gitignore-syntax file at the vault root.
+pub const IGNORE_FILE: &str = ".otterlyignore";
+/// Vault setting with extra patterns, as a list of gitignore lines.
+pub(crate) const IGNORE_PATTERNS_SETTING_KEY: &str = "ignore_patterns";
+
+/// Which vault paths are hidden from listing, indexing and watching: the
+/// built-in app folders plus `.otterlyignore` and the `ignore_patterns`
+/// setting. Broken patterns are logged and skipped.
+#[derive(Debug, Clone)]
+pub struct VaultIgnore {
+    root: PathBuf,
+    canonical_root: PathBuf,
+    matcher: Gitignore,
+}
+
+fn setting_patterns(root: &Path) -> Vec<String> {
+    let Some(value) = load_vault_settings_at(root)
+        .ok()
+        .and_then(|settings| settings.get(IGNORE_PATTERNS_SETTING_KEY).cloned())
+    else {
+        return Vec::new();
+    };
+    serde_json::from_value(value).unwrap_or_else(|error| {
+        log::warn!(
+            "Ignoring malformed {} setting: {}",
+            IGNORE_PATTERNS_SETTING_KEY,
+            error
+        );
+        Vec::new()
+    })
+}
+
+impl VaultIgnore {
+    pub fn load(root: &Path) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        let file = root.join(IGNORE_FILE);
+        if file.is_file() {
+            if let Some(error) = builder.add(&file) {
+                log::warn!("Skipping invalid lines in {}: {}", file.display(), error);
+            }
+        }
+        for pattern in setting_patterns(root) {
+            if let Err(error) = builder.add_line(None, &pattern) {
+                log::warn!("Skipping ignore pattern {:?}: {}", pattern, error);
+            }
+        }
+        let matcher = builder.build().unwrap_or_else(|error| {
+            log::warn!(
+                "Failed to build ignore rules for {}: {}",
+                root.display(),
+                error
+            );
+            Gitignore::empty()
+        });
+        Self {
+            root: root.to_path_buf(),
+            canonical_root: root.canonicalize().unwrap_or_else(|_| root.to_path_buf()),
+            matcher,
+        }
+    }
+
+    /// Whether a vault-relative path, or any folder above it, is ignored.
+    pub fn is_ignored_rel(&self, rel: &str, is_dir: bool) -> bool {
+        let rel = rel.trim_matches('/');
+        if rel.is_empty() {
+            return false;
+        }
+        if rel.split('/').any(constants::is_excluded_folder) {
+            return true;
+        }
+        self.matcher
+            .matched_path_or_any_parents(rel, is_dir)
+            .is_ignore()
+    }
+
+    /// Like [`Self::is_ignored_rel`] for an absolute path. Outside the vault
+    /// only the built-in folders are checked, by name.
+    pub fn is_ignored(&self, abs: &Path, is_dir: bool) -> bool {
+        let rel = abs
+            .strip_prefix(&self.root)
+            .or_else(|_| abs.strip_prefix(&self.canonical_root));
+        match rel {
+            Ok(rel) => self.is_ignored_rel(&storage::normalize_relative_path(rel), is_dir),
+            Err(_) => abs
+                .file_name()
+                .is_some_and(|name| constants::is_excluded_folder(&name.to_string_lossy())),
+        }
+    }
+
+    /// Whether a change to this vault-relative path can change the rules.
+    pub fn is_rules_source(rel: &str) -> bool {
+        rel == IGNORE_FILE || rel == format!("{}/settings.json", constants::APP_DIR)
+    }
+}
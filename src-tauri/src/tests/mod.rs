@@ -1,3 +1,5 @@
+mod support;
+
 #[path = "../../tests/notes_service_safety.rs"]
 mod notes_service_safety;
 
@@ -12,3 +14,63 @@ mod vault_session_service_parse;
 
 #[path = "../../tests/link_rewrite.rs"]
 mod link_rewrite;
+
+#[path = "../../tests/git_service_behavior.rs"]
+mod git_service_behavior;
+
+#[path = "../../tests/templates_behavior.rs"]
+mod templates_behavior;
+
+#[path = "../../tests/periodic_notes_behavior.rs"]
+mod periodic_notes_behavior;
+
+#[path = "../../tests/tasks_behavior.rs"]
+mod tasks_behavior;
+
+#[path = "../../tests/export_behavior.rs"]
+mod export_behavior;
+
+#[path = "../../tests/publish_behavior.rs"]
+mod publish_behavior;
+
+#[path = "../../tests/import_behavior.rs"]
+mod import_behavior;
+
+#[path = "../../tests/archive_behavior.rs"]
+mod archive_behavior;
+
+#[path = "../../tests/replace_behavior.rs"]
+mod replace_behavior;
+
+#[path = "../../tests/journal_behavior.rs"]
+mod journal_behavior;
+
+#[path = "../../tests/refactor_behavior.rs"]
+mod refactor_behavior;
+
+#[path = "../../tests/cli_behavior.rs"]
+mod cli_behavior;
+
+#[path = "../../tests/vault_context_behavior.rs"]
+mod vault_context_behavior;
+
+#[path = "../../tests/local_api_behavior.rs"]
+mod local_api_behavior;
+
+#[path = "../../tests/deep_link_behavior.rs"]
+mod deep_link_behavior;
+
+#[path = "../../tests/plugins_behavior.rs"]
+mod plugins_behavior;
+
+#[path = "../../tests/watcher_behavior.rs"]
+mod watcher_behavior;
+
+#[path = "../../tests/federated_search_behavior.rs"]
+mod federated_search_behavior;
+
+#[path = "../../tests/vault_registry_behavior.rs"]
+mod vault_registry_behavior;
+
+#[path = "../../tests/vault_ignore_behavior.rs"]
+mod vault_ignore_behavior;
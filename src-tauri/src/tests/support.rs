@@ -0,0 +1,11 @@
+use std::fs;
+use std::path::Path;
+
+/// Writes `content` to `root/rel`, creating parent folders.
+pub fn write_file(root: &Path, rel: &str, content: &str) {
+    let p = root.join(rel);
+    if let Some(parent) = p.parent() {
+        fs::create_dir_all(parent).expect("parent dir should be created");
+    }
+    fs::write(&p, content).expect("file should be written");
+}
@@ -0,0 +1,139 @@
+use crate::features::archive::service::{export_archive_at, import_archive_at};
+use crate::tests::support::write_file;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn seed_vault(root: &Path) {
+    write_file(root, "notes/a.md", "# A\n");
+    write_file(root, ".assets/pic.png", "png");
+    write_file(root, ".otterly/settings.json", "{\"theme\":\"dark\"}");
+    write_file(root, ".otterly/search.db", "cache");
+    write_file(root, ".git/HEAD", "ref: refs/heads/main");
+}
+
+#[test]
+fn archive_round_trip_restores_notes_and_assets() {
+    let vault = TempDir::new().expect("vault dir");
+    let out = TempDir::new().expect("out dir");
+    seed_vault(vault.path());
+    let archive = out.path().join("backup.zip");
+
+    let exported = export_archive_at(vault.path(), &archive.to_string_lossy(), false)
+        .expect("export should succeed");
+    assert_eq!(exported.files, 2);
+    assert_eq!(exported.total_bytes, 7);
+    assert!(!out.path().join("backup.zip.tmp").exists());
+
+    let dest = out.path().join("restored");
+    let imported =
+        import_archive_at(&archive, &dest.to_string_lossy()).expect("import should succeed");
+    assert_eq!(imported.files, 2);
+    assert_eq!(
+        fs::read_to_string(dest.join("notes/a.md")).expect("note"),
+        "# A\n"
+    );
+    assert!(dest.join(".assets/pic.png").is_file());
+    assert!(!dest.join(".otterly").exists());
+    assert!(!dest.join(".git").exists());
+}
+
+#[test]
+fn archive_keeps_files_hidden_by_ignore_rules() {
+    let vault = TempDir::new().expect("vault dir");
+    let out = TempDir::new().expect("out dir");
+    write_file(vault.path(), ".otterlyignore", "drafts/\n");
+    write_file(vault.path(), "drafts/b.md", "# B\n");
+    let archive = out.path().join("backup.zip");
+
+    export_archive_at(vault.path(), &archive.to_string_lossy(), false)
+        .expect("export should succeed");
+    let dest = out.path().join("restored");
+    import_archive_at(&archive, &dest.to_string_lossy()).expect("import should succeed");
+
+    assert!(dest.join(".otterlyignore").is_file());
+    assert!(dest.join("drafts/b.md").is_file());
+}
+
+#[test]
+fn archive_includes_settings_only_when_requested() {
+    let vault = TempDir::new().expect("vault dir");
+    let out = TempDir::new().expect("out dir");
+    seed_vault(vault.path());
+    write_file(
+        vault.path(),
+        ".otterly/settings.json",
+        r#"{"theme":"dark","git_identity":{"name":"Ann","signing":{"format":"ssh","key":"k"}}}"#,
+    );
+    let archive = out.path().join("backup.zip");
+
+    let exported = export_archive_at(vault.path(), &archive.to_string_lossy(), true)
+        .expect("export should succeed");
+    assert_eq!(exported.files, 3);
+
+    let dest = out.path().join("restored");
+    import_archive_at(&archive, &dest.to_string_lossy()).expect("import should succeed");
+    assert!(!dest.join(".otterly/search.db").exists());
+
+    // Signing config is never restored from an archive.
+    let settings: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(dest.join(".otterly/settings.json")).expect("settings"),
+    )
+    .expect("settings json");
+    assert_eq!(settings["theme"], "dark");
+    assert_eq!(settings["git_identity"]["name"], "Ann");
+    assert!(settings["git_identity"].get("signing").is_none());
+}
+
+#[test]
+fn archive_export_rejects_paths_inside_the_vault() {
+    let vault = TempDir::new().expect("vault dir");
+    seed_vault(vault.path());
+    let inside = vault.path().join("backup.zip");
+
+    let err = export_archive_at(vault.path(), &inside.to_string_lossy(), false)
+        .expect_err("export inside vault should fail");
+    assert_eq!(err, "archive must be written outside the vault");
+}
+
+#[test]
+fn archive_import_rejects_tampered_files_and_non_empty_destinations() {
+    let vault = TempDir::new().expect("vault dir");
+    let out = TempDir::new().expect("out dir");
+    seed_vault(vault.path());
+    let archive = out.path().join("backup.zip");
+    export_archive_at(vault.path(), &archive.to_string_lossy(), false)
+        .expect("export should succeed");
+
+    let busy = out.path().join("busy");
+    write_file(&busy, "keep.md", "keep");
+    let err = import_archive_at(&archive, &busy.to_string_lossy())
+        .expect_err("non-empty destination should fail");
+    assert_eq!(err, "destination folder must be empty");
+
+    let mut source =
+        zip::ZipArchive::new(fs::File::open(&archive).expect("archive")).expect("zip should open");
+    let tampered = out.path().join("tampered.zip");
+    let mut writer = zip::ZipWriter::new(fs::File::create(&tampered).expect("tampered"));
+    for idx in 0..source.len() {
+        let mut entry = source.by_index(idx).expect("entry");
+        let name = entry.name().to_string();
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).expect("entry bytes");
+        if name == "vault/notes/a.md" {
+            bytes = b"# B\n".to_vec();
+        }
+        writer
+            .start_file(name, zip::write::SimpleFileOptions::default())
+            .expect("entry should start");
+        writer.write_all(&bytes).expect("entry should be written");
+    }
+    writer.finish().expect("zip should finish");
+
+    let dest = out.path().join("restored");
+    let err = import_archive_at(&tampered, &dest.to_string_lossy())
+        .expect_err("tampered archive should fail");
+    assert_eq!(err, "hash mismatch for notes/a.md");
+    assert!(!dest.exists());
+}
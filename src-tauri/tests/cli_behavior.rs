@@ -0,0 +1,121 @@
+use crate::cli::commands::{backlinks, check_links, create, list, move_note, open_synced_index};
+use crate::cli::{parse_args, CliCommand};
+use crate::features::journal::service::undo_at;
+use crate::shared::vault_context::VaultContext;
+use crate::tests::support::write_file;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+#[test]
+fn parse_args_reads_commands_and_flags() {
+    let parsed = parse_args(&args(&[
+        "--vault", "/tmp/v", "search", "rust", "notes", "--limit", "5",
+    ]))
+    .expect("search should parse");
+    assert_eq!(parsed.vault.as_deref(), Some(Path::new("/tmp/v")));
+    match parsed.command {
+        Some(CliCommand::Search { query, limit, .. }) => {
+            assert_eq!(query, "rust notes");
+            assert_eq!(limit, 5);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+
+    let parsed = parse_args(&args(&["move", "a.md", "b/a.md", "--commit", "Move a"]))
+        .expect("move should parse");
+    assert!(matches!(parsed.command, Some(CliCommand::Move { .. })));
+    assert_eq!(parsed.commit.as_deref(), Some("Move a"));
+
+    assert!(parse_args(&args(&["--help"]))
+        .expect("help should parse")
+        .command
+        .is_none());
+    assert_eq!(
+        parse_args(&args(&["list", "--commit", "x"])).expect_err("commit on list"),
+        "--commit only applies to create and move"
+    );
+    assert_eq!(
+        parse_args(&args(&["move", "a.md"])).expect_err("missing target"),
+        "move expects 2 argument(s), got 1"
+    );
+    assert!(parse_args(&args(&["frobnicate"])).is_err());
+}
+
+#[test]
+fn move_rewrites_backlinks_and_relative_links_as_one_entry() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(
+        root,
+        "a.md",
+        "# A\n\nSee [[notes/b]] and [b](notes/b.md).\n",
+    );
+    write_file(root, "notes/b.md", "# B\n\nNext: [c](./c.md).\n");
+    write_file(root, "notes/c.md", "# C\n");
+    let ctx = VaultContext::for_root(root).expect("context");
+    let conn = open_synced_index(&ctx).expect("index should sync");
+
+    let result = move_note(&conn, &ctx, "notes/b.md", "archive/b").expect("move should succeed");
+
+    assert_eq!(result.to, "archive/b.md");
+    assert_eq!(result.rewritten, vec!["archive/b.md", "a.md"]);
+    assert!(!root.join("notes/b.md").exists());
+    assert_eq!(
+        fs::read_to_string(root.join("a.md")).expect("a"),
+        "# A\n\nSee [[archive/b]] and [b](archive/b.md).\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("archive/b.md")).expect("b"),
+        "# B\n\nNext: [c](../notes/c.md).\n"
+    );
+
+    open_synced_index(&ctx).expect("index should sync");
+    let sources: Vec<String> = backlinks(&ctx, "archive/b")
+        .expect("backlinks")
+        .into_iter()
+        .map(|note| note.path)
+        .collect();
+    assert_eq!(sources, vec!["a.md"]);
+
+    let change = undo_at(root, None).expect("undo should succeed");
+    assert_eq!(change.kind, "rename_note");
+    assert!(root.join("notes/b.md").exists());
+    assert_eq!(
+        fs::read_to_string(root.join("a.md")).expect("a"),
+        "# A\n\nSee [[notes/b]] and [b](notes/b.md).\n"
+    );
+}
+
+#[test]
+fn check_links_reports_missing_targets_and_list_filters_by_prefix() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(
+        root,
+        "docs/index.md",
+        "# Index\n\n[[docs/setup]] and [[docs/gone]].\n",
+    );
+    write_file(root, "docs/setup.md", "# Setup\n");
+    let ctx = VaultContext::for_root(root).expect("context");
+    create(&ctx, "inbox/todo", "# Todo\n").expect("create should succeed");
+    let conn = open_synced_index(&ctx).expect("index should sync");
+
+    let report = check_links(&conn).expect("check should succeed");
+    assert_eq!(report.checked, 3);
+    assert_eq!(report.broken.len(), 1);
+    assert_eq!(report.broken[0].source, "docs/index.md");
+    assert_eq!(report.broken[0].target, "docs/gone.md");
+
+    let paths: Vec<String> = list(&conn, Some("docs/"))
+        .expect("list")
+        .into_iter()
+        .map(|note| note.path)
+        .collect();
+    assert_eq!(paths, vec!["docs/index.md", "docs/setup.md"]);
+    assert_eq!(list(&conn, None).expect("list").len(), 3);
+}
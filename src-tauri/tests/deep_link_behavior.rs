@@ -0,0 +1,177 @@
+use crate::features::deep_link::service::{
+    parse_deep_link, resolve_deep_link, DeepLink, DeepLinkEvent,
+};
+use crate::shared::storage::{Vault, VaultEntry, VaultStore};
+use crate::shared::vault_context::VaultRegistry;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn entry(id: &str, name: &str, path: &Path) -> VaultEntry {
+    VaultEntry {
+        vault: Vault {
+            id: id.to_string(),
+            path: path.to_string_lossy().to_string(),
+            name: name.to_string(),
+            created_at: 1,
+            last_opened_at: None,
+            note_count: None,
+            is_available: true,
+            pinned: false,
+        },
+        last_opened_at: 1,
+    }
+}
+
+fn registry_with(config: &Path, vaults: Vec<VaultEntry>, last: Option<&str>) -> VaultRegistry {
+    let registry = VaultRegistry::at(config);
+    registry
+        .save(&VaultStore {
+            vaults,
+            last_vault_id: last.map(str::to_string),
+        })
+        .expect("registry should save");
+    registry
+}
+
+fn resolve(registry: &VaultRegistry, url: &str) -> Result<DeepLinkEvent, String> {
+    parse_deep_link(url)
+        .and_then(|link| resolve_deep_link(registry, link))
+        .map_err(|e| e.code().to_string())
+}
+
+#[test]
+fn parses_actions_params_and_heading() {
+    assert_eq!(
+        parse_deep_link("otterly://open?vault=Work%20Notes&path=notes/x#Next%20steps")
+            .expect("open link"),
+        DeepLink::Open {
+            vault: Some("Work Notes".to_string()),
+            path: "notes/x.md".to_string(),
+            heading: Some("Next steps".to_string()),
+        }
+    );
+    assert_eq!(
+        parse_deep_link("otterly:///new?path=inbox/idea.md&content=%23%20Idea%0Abody")
+            .expect("new link"),
+        DeepLink::New {
+            vault: None,
+            path: "inbox/idea.md".to_string(),
+            content: "# Idea\nbody".to_string(),
+        }
+    );
+    assert_eq!(
+        parse_deep_link("otterly://search?q=rust+tips").expect("search link"),
+        DeepLink::Search {
+            vault: None,
+            query: "rust tips".to_string(),
+        }
+    );
+
+    for bad in [
+        "https://open?path=a.md",
+        "otterly://delete?path=a.md",
+        "otterly://open?vault=v",
+        "otterly://search?q=%20",
+    ] {
+        assert_eq!(
+            parse_deep_link(bad).expect_err(bad).code(),
+            "invalid_input",
+            "{}",
+            bad
+        );
+    }
+}
+
+#[test]
+fn resolves_vaults_by_id_or_name_and_keeps_paths_inside() {
+    let config = TempDir::new().expect("temp dir");
+    let work = TempDir::new().expect("temp dir");
+    let home = TempDir::new().expect("temp dir");
+    fs::create_dir_all(work.path().join("notes")).expect("notes dir");
+    fs::write(work.path().join("notes/x.md"), "# X\n").expect("note");
+    fs::write(home.path().join("outside.md"), "secret").expect("note");
+    let registry = registry_with(
+        config.path(),
+        vec![
+            entry("w1", "Work", work.path()),
+            entry("h1", "Home", home.path()),
+            entry("h2", "home", home.path()),
+        ],
+        Some("w1"),
+    );
+
+    match resolve(&registry, "otterly://open?vault=work&path=notes/x.md#Intro") {
+        Ok(DeepLinkEvent::OpenNote {
+            vault_id,
+            note_path,
+            heading,
+        }) => {
+            assert_eq!(vault_id, "w1");
+            assert_eq!(note_path, "notes/x.md");
+            assert_eq!(heading.as_deref(), Some("Intro"));
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(matches!(
+        resolve(&registry, "otterly://open?path=notes/x"),
+        Ok(DeepLinkEvent::OpenNote { vault_id, .. }) if vault_id == "w1"
+    ));
+
+    assert_eq!(
+        resolve(&registry, "otterly://open?vault=w1&path=../outside.md").unwrap_err(),
+        "invalid_path"
+    );
+    assert_eq!(
+        resolve(&registry, "otterly://open?vault=w1&path=notes/missing.md").unwrap_err(),
+        "not_found"
+    );
+    assert_eq!(
+        resolve(&registry, "otterly://open?vault=home&path=outside.md").unwrap_err(),
+        "invalid_input"
+    );
+    assert_eq!(
+        resolve(&registry, "otterly://search?vault=nope&q=x").unwrap_err(),
+        "not_found"
+    );
+    assert!(matches!(
+        resolve(&registry, "otterly://search?vault=h1&q=x"),
+        Ok(DeepLinkEvent::Search { vault_id: Some(id), query }) if id == "h1" && query == "x"
+    ));
+}
+
+#[test]
+fn new_links_wait_for_confirmation_before_writing() {
+    let config = TempDir::new().expect("temp dir");
+    let vault = TempDir::new().expect("temp dir");
+    let registry = registry_with(
+        config.path(),
+        vec![entry("v1", "Notes", vault.path())],
+        None,
+    );
+    let url = "otterly://new?vault=v1&path=inbox/idea&content=%23%20Idea%0A";
+
+    match resolve(&registry, url) {
+        Ok(DeepLinkEvent::ConfirmNew {
+            vault_id,
+            vault_name,
+            note_path,
+            content,
+        }) => {
+            assert_eq!(vault_id, "v1");
+            assert_eq!(vault_name, "Notes");
+            assert_eq!(note_path, "inbox/idea.md");
+            assert_eq!(content, "# Idea\n");
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(!vault.path().join("inbox").exists());
+
+    fs::create_dir_all(vault.path().join("inbox")).expect("inbox dir");
+    fs::write(vault.path().join("inbox/idea.md"), "# Idea\n").expect("note");
+    assert_eq!(resolve(&registry, url).unwrap_err(), "already_exists");
+    assert_eq!(
+        resolve(&registry, "otterly://new?path=a.md").unwrap_err(),
+        "not_found"
+    );
+}
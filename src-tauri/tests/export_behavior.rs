@@ -0,0 +1,121 @@
+use crate::features::export::html::render_note;
+use crate::features::export::service::export_html_at;
+use crate::tests::support::write_file;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn render_note_rewrites_links_to_exported_pages() {
+    let markdown = "---\ntags: [a]\n---\n# Plan\n\nSee [[projects/beta|Beta]], [alpha](./alpha.md) and [[missing]].\n";
+    let rendered = render_note("projects/plan.md", markdown, |target| {
+        target == "projects/beta.md" || target == "projects/alpha.md"
+    });
+
+    assert_eq!(rendered.title, "Plan");
+    assert!(!rendered.body_html.contains("tags:"));
+    assert!(rendered
+        .body_html
+        .contains("<a href=\"beta.html\">Beta</a>"));
+    assert!(rendered
+        .body_html
+        .contains("<a href=\"alpha.html\">alpha</a>"));
+    assert!(rendered.body_html.contains(" and missing."));
+    assert_eq!(rendered.unresolved_links, vec!["missing.md".to_string()]);
+}
+
+#[test]
+fn render_note_collects_images_and_embeds() {
+    let markdown = "![diagram](../.assets/my%20diagram.png)\n\n![[.assets/photo.jpg]]\n\n![remote](https://example.com/x.png)\n";
+    let rendered = render_note("notes/a.md", markdown, |_| false);
+
+    assert_eq!(
+        rendered.assets,
+        vec![
+            ".assets/my diagram.png".to_string(),
+            ".assets/photo.jpg".to_string()
+        ]
+    );
+    assert!(rendered
+        .body_html
+        .contains("src=\"../.assets/my%20diagram.png\""));
+    assert!(rendered.body_html.contains("src=\"../.assets/photo.jpg\""));
+    assert!(rendered
+        .body_html
+        .contains("src=\"https://example.com/x.png\""));
+}
+
+#[test]
+fn export_folder_writes_pages_assets_and_index() {
+    let vault = TempDir::new().expect("temp dir should be created");
+    let out = TempDir::new().expect("temp dir should be created");
+    let root = vault.path();
+    write_file(
+        root,
+        "docs/intro.md",
+        "# Intro\n\nNext: [[docs/guide/setup]]\n\n![logo](../.assets/logo.png)\n",
+    );
+    write_file(
+        root,
+        "docs/guide/setup.md",
+        "# Setup\n\nBack to [intro](../intro.md).\n",
+    );
+    write_file(root, "other.md", "# Other\n");
+    write_file(root, ".assets/logo.png", "png");
+
+    let output_dir = out.path().join("site");
+    let result =
+        export_html_at(root, "docs", &output_dir.to_string_lossy()).expect("export should succeed");
+
+    assert_eq!(
+        result.pages,
+        vec![
+            "docs/guide/setup.html".to_string(),
+            "docs/intro.html".to_string()
+        ]
+    );
+    assert_eq!(result.assets, vec![".assets/logo.png".to_string()]);
+    assert!(result.missing_assets.is_empty());
+    assert!(output_dir.join(".assets/logo.png").is_file());
+    assert!(!output_dir.join("other.html").exists());
+
+    let intro = fs::read_to_string(output_dir.join("docs/intro.html")).expect("page exists");
+    assert!(intro.contains("<title>Intro</title>"));
+    assert!(intro.contains("<style>"));
+    assert!(intro.contains("href=\"./guide/setup.html\""));
+    assert!(intro.contains("href=\"../index.html\""));
+
+    let setup = fs::read_to_string(output_dir.join("docs/guide/setup.html")).expect("page exists");
+    assert!(setup.contains("href=\"../intro.html\""));
+
+    let index = fs::read_to_string(output_dir.join("index.html")).expect("index exists");
+    assert!(index.contains("<h1>docs</h1>"));
+    assert!(index.contains("href=\"docs/intro.html\">Intro</a>"));
+}
+
+#[test]
+fn export_rejects_output_inside_vault() {
+    let vault = TempDir::new().expect("temp dir should be created");
+    write_file(vault.path(), "a.md", "# A\n");
+    let inside = vault.path().join("export");
+
+    let err = export_html_at(vault.path(), "a.md", &inside.to_string_lossy())
+        .expect_err("export into the vault should fail");
+    assert!(err.contains("outside the vault"));
+}
+
+#[test]
+fn export_leaves_out_ignored_notes() {
+    let vault = TempDir::new().expect("temp dir should be created");
+    let out = TempDir::new().expect("temp dir should be created");
+    let root = vault.path();
+    write_file(root, ".otterlyignore", "drafts/\n");
+    write_file(root, "a.md", "# A\n");
+    write_file(root, "drafts/b.md", "# B\n");
+
+    let output_dir = out.path().join("site");
+    let result =
+        export_html_at(root, "", &output_dir.to_string_lossy()).expect("export should succeed");
+
+    assert_eq!(result.pages, vec!["a.html".to_string()]);
+    assert!(!output_dir.join("drafts").exists());
+}
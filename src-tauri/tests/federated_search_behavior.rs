@@ -0,0 +1,122 @@
+use crate::features::search::db::{open_search_db, search, upsert_note};
+use crate::features::search::model::{IndexNoteMeta, SearchHit, SearchScope};
+use crate::features::search::service::{merge_federated_hits, search_existing_index};
+use crate::shared::storage::Vault;
+use tempfile::TempDir;
+
+fn vault(id: &str, name: &str) -> Vault {
+    Vault {
+        id: id.to_string(),
+        path: format!("/vaults/{}", name),
+        name: name.to_string(),
+        created_at: 0,
+        last_opened_at: None,
+        note_count: None,
+        is_available: true,
+        pinned: false,
+    }
+}
+
+fn search_vault(notes: &[(&str, &str)], query: &str) -> Vec<SearchHit> {
+    let tmp = TempDir::new().expect("temp dir should be created");
+    let conn = open_search_db(tmp.path()).expect("db should open");
+    for (path, body) in notes {
+        let meta = IndexNoteMeta {
+            id: path.to_string(),
+            path: path.to_string(),
+            title: path.trim_end_matches(".md").to_string(),
+            name: path.trim_end_matches(".md").to_string(),
+            mtime_ms: 100,
+            size_bytes: body.len() as i64,
+        };
+        upsert_note(&conn, &meta, body).expect("upsert should succeed");
+    }
+    search(&conn, query, SearchScope::All, 50).expect("search should succeed")
+}
+
+#[test]
+fn federated_hits_are_tagged_and_ranked_per_vault() {
+    let work = search_vault(
+        &[
+            ("standup.md", "meeting meeting meeting with the team"),
+            (
+                "roadmap.md",
+                "quarterly plan, one meeting mentioned among many other words",
+            ),
+            ("budget.md", "numbers only"),
+        ],
+        "meeting",
+    );
+    let home = search_vault(&[("club.md", "book club meeting on friday")], "meeting");
+    assert_eq!(work.len(), 2);
+    assert_eq!(home.len(), 1);
+
+    let merged = merge_federated_hits(
+        vec![
+            (vault("w", "Work"), work),
+            (vault("h", "Home"), home),
+            (vault("e", "Empty"), Vec::new()),
+        ],
+        10,
+    );
+
+    let tagged: Vec<(&str, &str)> = merged
+        .iter()
+        .map(|hit| (hit.vault_name.as_str(), hit.note.path.as_str()))
+        .collect();
+    assert_eq!(tagged.len(), 3);
+    assert!(tagged.contains(&("Home", "club.md")));
+    assert!(tagged.contains(&("Work", "standup.md")));
+    assert_eq!(tagged[2], ("Work", "roadmap.md"));
+    assert!(merged.iter().all(|hit| hit.score > 0.0 && hit.score <= 1.0));
+    assert_eq!(merged[0].score, 1.0);
+    assert_eq!(merged[1].score, 1.0);
+    assert!(merged[2].score < 1.0);
+    let club = merged
+        .iter()
+        .find(|hit| hit.note.path == "club.md")
+        .unwrap();
+    assert_eq!(club.vault_id, "h");
+}
+
+#[test]
+fn federated_hits_respect_the_limit() {
+    let hits = search_vault(
+        &[
+            ("a.md", "otter"),
+            ("b.md", "otter otter"),
+            ("c.md", "otter river"),
+        ],
+        "otter",
+    );
+    let merged = merge_federated_hits(vec![(vault("w", "Work"), hits)], 2);
+    assert_eq!(merged.len(), 2);
+    assert!(merged[0].score >= merged[1].score);
+}
+
+#[test]
+fn federated_search_reads_existing_indexes_without_creating_new_ones() {
+    let indexed = TempDir::new().expect("temp dir should be created");
+    {
+        let conn = open_search_db(indexed.path()).expect("db should open");
+        let meta = IndexNoteMeta {
+            id: "a.md".to_string(),
+            path: "a.md".to_string(),
+            title: "a".to_string(),
+            name: "a".to_string(),
+            mtime_ms: 100,
+            size_bytes: 5,
+        };
+        upsert_note(&conn, &meta, "otter").expect("upsert should succeed");
+    }
+    let hits = search_existing_index(indexed.path(), "otter", SearchScope::All, 10)
+        .expect("search should succeed")
+        .expect("index should exist");
+    assert_eq!(hits.len(), 1);
+
+    let fresh = TempDir::new().expect("temp dir should be created");
+    let hits = search_existing_index(fresh.path(), "otter", SearchScope::All, 10)
+        .expect("search should succeed");
+    assert!(hits.is_none());
+    assert!(!fresh.path().join(".otterly").exists());
+}
@@ -0,0 +1,575 @@
+use crate::features::git::hunk_stage::GitHunkSelection;
+use crate::features::git::identity::{append_co_author_trailers, GitIdentitySource};
+use crate::features::git::service::{
+    git_diff, git_discard_changes, git_get_identity, git_init_repo, git_log, git_stage_and_commit,
+    git_stage_hunks, git_stash_apply, git_stash_drop, git_stash_list, git_stash_save, git_status,
+    git_unstage_hunks, git_vault_timeline, git_working_diff, GitDiffOptions,
+};
+use crate::features::git::word_diff::diff_words;
+use crate::shared::error::AppError;
+use crate::tests::support::write_file;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn read_file(root: &Path, rel: &str) -> String {
+    fs::read_to_string(root.join(rel)).expect("file should be readable")
+}
+
+fn init_vault(files: &[(&str, &str)]) -> (TempDir, String) {
+    let tmp = TempDir::new().expect("temp dir should be created");
+    for (rel, content) in files {
+        write_file(tmp.path(), rel, content);
+    }
+    let vault_path = tmp.path().to_string_lossy().to_string();
+    git_init_repo(vault_path.clone()).expect("repo should be initialized");
+    (tmp, vault_path)
+}
+
+#[test]
+fn discard_changes_restores_tracked_and_removes_untracked() {
+    let (tmp, vault_path) = init_vault(&[("a.md", "original a"), ("b.md", "original b")]);
+    write_file(tmp.path(), "a.md", "edited a");
+    write_file(tmp.path(), "b.md", "edited b");
+    write_file(tmp.path(), "new.md", "scratch");
+
+    git_discard_changes(
+        vault_path.clone(),
+        vec!["a.md".to_string(), "new.md".to_string()],
+    )
+    .expect("discard should succeed");
+
+    assert_eq!(read_file(tmp.path(), "a.md"), "original a");
+    assert_eq!(read_file(tmp.path(), "b.md"), "edited b");
+    assert!(!tmp.path().join("new.md").exists());
+
+    let status = git_status(vault_path).expect("status should load");
+    let paths: Vec<&str> = status.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["b.md"]);
+}
+
+#[test]
+fn discard_changes_removes_untracked_folders_but_not_symlinks() {
+    let (tmp, vault_path) = init_vault(&[("a.md", "original a")]);
+    write_file(tmp.path(), "drafts/one.md", "scratch");
+    write_file(tmp.path(), "drafts/deep/two.md", "scratch");
+
+    git_discard_changes(vault_path.clone(), vec!["drafts/".to_string()])
+        .expect("discard should succeed");
+    assert!(!tmp.path().join("drafts").exists());
+
+    let outside = TempDir::new().expect("temp dir should be created");
+    write_file(outside.path(), "keep.md", "outside");
+    std::os::unix::fs::symlink(outside.path(), tmp.path().join("link"))
+        .expect("symlink should be created");
+
+    let err = git_discard_changes(vault_path, vec!["link/keep.md".to_string()])
+        .expect_err("symlinked path should be rejected");
+    assert!(matches!(err, AppError::SymlinkRejected { .. }));
+    assert_eq!(read_file(outside.path(), "keep.md"), "outside");
+}
+
+#[test]
+fn discard_changes_recreates_deleted_note() {
+    let (tmp, vault_path) = init_vault(&[("docs/a.md", "keep me")]);
+    fs::remove_file(tmp.path().join("docs/a.md")).expect("file should be removed");
+
+    git_discard_changes(vault_path, vec!["docs/a.md".to_string()]).expect("discard should succeed");
+
+    assert_eq!(read_file(tmp.path(), "docs/a.md"), "keep me");
+}
+
+#[test]
+fn stash_round_trip_restores_edits() {
+    let (tmp, vault_path) = init_vault(&[("a.md", "original")]);
+    write_file(tmp.path(), "a.md", "experiment");
+    write_file(tmp.path(), "draft.md", "untracked draft");
+
+    git_stash_save(vault_path.clone(), Some("try rewrite".to_string()), true)
+        .expect("stash should succeed");
+    assert_eq!(read_file(tmp.path(), "a.md"), "original");
+    assert!(!tmp.path().join("draft.md").exists());
+
+    let stashes = git_stash_list(vault_path.clone()).expect("stash list should load");
+    assert_eq!(stashes.len(), 1);
+    assert_eq!(stashes[0].index, 0);
+    assert!(stashes[0].message.contains("try rewrite"));
+
+    git_stash_apply(vault_path.clone(), 0).expect("apply should succeed");
+    assert_eq!(read_file(tmp.path(), "a.md"), "experiment");
+    assert_eq!(read_file(tmp.path(), "draft.md"), "untracked draft");
+
+    git_stash_drop(vault_path.clone(), 0).expect("drop should succeed");
+    assert!(git_stash_list(vault_path)
+        .expect("stash list should load")
+        .is_empty());
+}
+
+#[test]
+fn stash_without_changes_reports_nothing_to_stash() {
+    let (_tmp, vault_path) = init_vault(&[("a.md", "original")]);
+
+    let result = git_stash_save(vault_path, None, false);
+
+    assert_eq!(result, Err(AppError::git("nothing to stash")));
+}
+
+#[test]
+fn stash_apply_over_local_edits_reports_conflicting_paths() {
+    let (tmp, vault_path) = init_vault(&[("a.md", "original"), ("b.md", "other")]);
+    write_file(tmp.path(), "a.md", "stashed");
+    git_stash_save(vault_path.clone(), None, false).expect("stash should succeed");
+    write_file(tmp.path(), "a.md", "local edit");
+
+    let err = git_stash_apply(vault_path, 0).expect_err("apply should conflict");
+
+    assert_eq!(err.code(), "git_conflict");
+    assert_eq!(
+        err,
+        AppError::GitConflict {
+            message: "stash conflicts with local changes".to_string(),
+            paths: vec!["a.md".to_string()],
+        }
+    );
+}
+
+fn diff_options(word_diff: bool, detect_renames: bool) -> Option<GitDiffOptions> {
+    Some(GitDiffOptions {
+        word_diff,
+        detect_renames,
+    })
+}
+
+#[test]
+fn diff_words_marks_only_changed_words() {
+    let (old_segments, new_segments) =
+        diff_words("The quick brown fox.\n", "The slow brown fox!\n").expect("words should diff");
+
+    let old: Vec<(&str, &str)> = old_segments
+        .iter()
+        .map(|s| (s.segment_type.as_str(), s.text.as_str()))
+        .collect();
+    let new: Vec<(&str, &str)> = new_segments
+        .iter()
+        .map(|s| (s.segment_type.as_str(), s.text.as_str()))
+        .collect();
+
+    assert_eq!(
+        old,
+        vec![
+            ("equal", "The "),
+            ("deletion", "quick"),
+            ("equal", " brown fox"),
+            ("deletion", "."),
+            ("equal", "\n"),
+        ]
+    );
+    assert_eq!(
+        new,
+        vec![
+            ("equal", "The "),
+            ("addition", "slow"),
+            ("equal", " brown fox"),
+            ("addition", "!"),
+            ("equal", "\n"),
+        ]
+    );
+}
+
+#[test]
+fn git_diff_attaches_word_segments_to_replaced_lines() {
+    let (tmp, vault_path) = init_vault(&[("a.md", "# Title\n\nA long paragraph here.\n")]);
+    write_file(tmp.path(), "a.md", "# Title\n\nA short paragraph here.\n");
+    git_stage_and_commit(vault_path.clone(), "edit".to_string(), None, None)
+        .expect("commit should succeed");
+
+    let diff = git_diff(
+        vault_path,
+        "HEAD~1".to_string(),
+        Some("HEAD".to_string()),
+        Some("a.md".to_string()),
+        diff_options(true, false),
+    )
+    .expect("diff should succeed");
+
+    assert_eq!((diff.additions, diff.deletions), (1, 1));
+    let changed: Vec<_> = diff.hunks[0]
+        .lines
+        .iter()
+        .filter(|line| line.line_type != "context")
+        .collect();
+    let deletion = changed[0].segments.as_ref().expect("deletion has segments");
+    let addition = changed[1].segments.as_ref().expect("addition has segments");
+    assert!(deletion
+        .iter()
+        .any(|s| s.segment_type == "deletion" && s.text == "long"));
+    assert!(addition
+        .iter()
+        .any(|s| s.segment_type == "addition" && s.text == "short"));
+}
+
+#[test]
+fn git_diff_detects_renamed_note() {
+    let body = "# Meeting\n\nline one\nline two\nline three\nline four\n";
+    let (tmp, vault_path) = init_vault(&[("inbox/meeting.md", body)]);
+    fs::create_dir_all(tmp.path().join("archive")).expect("dir should be created");
+    fs::rename(
+        tmp.path().join("inbox/meeting.md"),
+        tmp.path().join("archive/meeting.md"),
+    )
+    .expect("note should be moved");
+    git_stage_and_commit(vault_path.clone(), "move".to_string(), None, None)
+        .expect("commit should succeed");
+
+    let diff = git_diff(
+        vault_path,
+        "HEAD~1".to_string(),
+        Some("HEAD".to_string()),
+        Some("archive/meeting.md".to_string()),
+        diff_options(false, true),
+    )
+    .expect("diff should succeed");
+
+    assert_eq!(diff.files.len(), 1);
+    assert_eq!(diff.files[0].status, "renamed");
+    assert_eq!(diff.files[0].old_path.as_deref(), Some("inbox/meeting.md"));
+    assert_eq!(
+        diff.files[0].new_path.as_deref(),
+        Some("archive/meeting.md")
+    );
+    assert_eq!((diff.additions, diff.deletions), (0, 0));
+}
+
+#[test]
+fn git_diff_against_working_tree_includes_uncommitted_edits() {
+    let (tmp, vault_path) = init_vault(&[("a.md", "first\n"), ("b.md", "other\n")]);
+    write_file(tmp.path(), "a.md", "first\nsecond\n");
+    write_file(tmp.path(), "b.md", "changed\n");
+
+    let diff = git_diff(
+        vault_path,
+        "HEAD".to_string(),
+        None,
+        Some("a.md".to_string()),
+        None,
+    )
+    .expect("diff should succeed");
+
+    assert_eq!((diff.additions, diff.deletions), (1, 0));
+    assert_eq!(diff.hunks.len(), 1);
+    assert_eq!(diff.hunks[0].path, "a.md");
+    assert!(diff.hunks[0]
+        .lines
+        .iter()
+        .any(|line| line.line_type == "addition" && line.content == "second\n"));
+}
+
+fn write_vault_settings(root: &Path, settings: serde_json::Value) {
+    write_file(root, ".otterly/settings.json", &settings.to_string());
+}
+
+fn set_repo_config(vault_path: &str, key: &str, value: &str) {
+    let repo = git2::Repository::open(vault_path).expect("repo should open");
+    let mut config = repo.config().expect("config should load");
+    config.set_str(key, value).expect("config should be set");
+}
+
+#[test]
+fn commit_author_comes_from_repo_git_config() {
+    let (tmp, vault_path) = init_vault(&[("a.md", "one")]);
+    set_repo_config(&vault_path, "user.name", "Ada Lovelace");
+    set_repo_config(&vault_path, "user.email", "ada@example.com");
+    write_file(tmp.path(), "a.md", "two");
+
+    git_stage_and_commit(vault_path.clone(), "edit".to_string(), None, None)
+        .expect("commit should succeed");
+
+    let log = git_log(vault_path.clone(), None, 1).expect("log should load");
+    assert_eq!(log[0].author, "Ada Lovelace");
+    let identity = git_get_identity(vault_path).expect("identity should resolve");
+    assert_eq!(identity.source, GitIdentitySource::GitConfig);
+    assert_eq!(identity.email, "ada@example.com");
+}
+
+#[test]
+fn vault_identity_setting_overrides_git_config() {
+    let (tmp, vault_path) = init_vault(&[("a.md", "one")]);
+    set_repo_config(&vault_path, "user.name", "Ada Lovelace");
+    set_repo_config(&vault_path, "user.email", "ada@example.com");
+    write_vault_settings(
+        tmp.path(),
+        serde_json::json!({
+            "git_identity": { "name": "Vault Bot", "email": "bot@example.com" }
+        }),
+    );
+    write_file(tmp.path(), "a.md", "two");
+
+    git_stage_and_commit(vault_path.clone(), "edit".to_string(), None, None)
+        .expect("commit should succeed");
+
+    let log = git_log(vault_path.clone(), None, 1).expect("log should load");
+    assert_eq!(log[0].author, "Vault Bot");
+    let identity = git_get_identity(vault_path).expect("identity should resolve");
+    assert_eq!(identity.source, GitIdentitySource::VaultSettings);
+}
+
+#[test]
+fn co_author_trailers_are_appended_and_validated() {
+    let message = append_co_author_trailers(
+        "Update notes\n",
+        &["Grace Hopper <grace@example.com>".to_string()],
+    )
+    .expect("trailer should be accepted");
+    assert_eq!(
+        message,
+        "Update notes\n\nCo-authored-by: Grace Hopper <grace@example.com>\n"
+    );
+
+    assert!(matches!(
+        append_co_author_trailers("x", &["no email".to_string()]),
+        Err(AppError::InvalidInput { .. })
+    ));
+    assert!(append_co_author_trailers("x", &["<only@email>".to_string()]).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn commits_are_signed_with_configured_external_signer() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (tmp, vault_path) = init_vault(&[("a.md", "one")]);
+    let signer = tmp.path().join(".otterly/fake-signer.sh");
+    write_file(
+        tmp.path(),
+        ".otterly/fake-signer.sh",
+        "#!/bin/sh\ncat > /dev/null\nprintf -- '-----BEGIN PGP SIGNATURE-----\\nfake\\n-----END PGP SIGNATURE-----\\n'\n",
+    );
+    fs::set_permissions(&signer, fs::Permissions::from_mode(0o755))
+        .expect("signer should be executable");
+    write_vault_settings(
+        tmp.path(),
+        serde_json::json!({
+            "git_identity": {
+                "signing": { "format": "openpgp", "program": "/bin/false" }
+            }
+        }),
+    );
+    // The vault can ask for signing, but only git config picks the program.
+    git2::Repository::open(&vault_path)
+        .and_then(|repo| repo.config())
+        .and_then(|mut config| config.set_str("gpg.program", &signer.to_string_lossy()))
+        .expect("gpg.program should be set");
+    write_file(tmp.path(), "a.md", "two");
+
+    let hash = git_stage_and_commit(vault_path.clone(), "signed".to_string(), None, None)
+        .expect("signed commit should succeed");
+
+    let repo = git2::Repository::open(&vault_path).expect("repo should open");
+    let head = repo.head().expect("head").peel_to_commit().expect("commit");
+    assert_eq!(head.id().to_string(), hash);
+    let gpgsig = head
+        .header_field_bytes("gpgsig")
+        .expect("commit should be signed");
+    assert!(String::from_utf8_lossy(&gpgsig).contains("fake"));
+}
+
+#[test]
+fn vault_timeline_lists_note_changes_per_commit() {
+    let (tmp, vault_path) = init_vault(&[("a.md", "# Alpha\n\none\n"), ("img.png", "bin")]);
+    write_file(tmp.path(), "a.md", "# Alpha\n\none\ntwo\n");
+    write_file(tmp.path(), "b.md", "no heading\n");
+    git_stage_and_commit(vault_path.clone(), "second".to_string(), None, None)
+        .expect("commit should succeed");
+    fs::rename(tmp.path().join("b.md"), tmp.path().join("c.md")).expect("rename");
+    git_stage_and_commit(vault_path.clone(), "third".to_string(), None, None)
+        .expect("commit should succeed");
+
+    let page =
+        git_vault_timeline(vault_path.clone(), None, None, None, 2).expect("timeline should load");
+    let messages: Vec<&str> = page
+        .entries
+        .iter()
+        .map(|e| e.commit.message.as_str())
+        .collect();
+    assert_eq!(messages, vec!["third", "second"]);
+
+    let renamed = &page.entries[0].notes;
+    assert_eq!(renamed.len(), 1);
+    assert_eq!(renamed[0].status, "renamed");
+    assert_eq!(renamed[0].path, "c.md");
+    assert_eq!(renamed[0].old_path.as_deref(), Some("b.md"));
+    assert_eq!(renamed[0].title, "c");
+
+    let second = &page.entries[1];
+    let alpha = second
+        .notes
+        .iter()
+        .find(|n| n.path == "a.md")
+        .expect("a.md should be listed");
+    assert_eq!(alpha.title, "Alpha");
+    assert_eq!((alpha.additions, alpha.deletions), (1, 0));
+    assert_eq!(second.additions, 2);
+
+    let cursor = page.next_cursor.expect("more commits should remain");
+    let rest =
+        git_vault_timeline(vault_path, None, None, Some(cursor), 2).expect("next page should load");
+    assert_eq!(rest.entries.len(), 1);
+    assert_eq!(rest.entries[0].commit.message, "Initial commit");
+    let initial: Vec<&str> = rest.entries[0]
+        .notes
+        .iter()
+        .map(|n| n.path.as_str())
+        .collect();
+    assert_eq!(initial, vec!["a.md"]);
+    assert!(rest.next_cursor.is_none());
+}
+
+fn numbered_lines(edits: &[(usize, &str)]) -> String {
+    (1..=20)
+        .map(|n| {
+            edits
+                .iter()
+                .find(|(line, _)| *line == n)
+                .map(|(_, text)| text.to_string())
+                .unwrap_or_else(|| format!("line {}", n))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+fn select_hunk(hunk_index: usize, lines: Option<Vec<usize>>) -> GitHunkSelection {
+    GitHunkSelection { hunk_index, lines }
+}
+
+fn committed_content(vault_path: &str, rel: &str) -> String {
+    let repo = git2::Repository::open(vault_path).expect("repo should open");
+    let tree = repo.head().expect("head").peel_to_tree().expect("tree");
+    let entry = tree
+        .get_path(Path::new(rel))
+        .expect("file should be committed");
+    let blob = repo.find_blob(entry.id()).expect("blob");
+    String::from_utf8_lossy(blob.content()).to_string()
+}
+
+#[test]
+fn staging_one_hunk_commits_only_that_edit() {
+    let (tmp, vault_path) = init_vault(&[("note.md", &numbered_lines(&[]))]);
+    write_file(
+        tmp.path(),
+        "note.md",
+        &numbered_lines(&[(2, "first edit"), (18, "second edit")]),
+    );
+
+    let unstaged = git_working_diff(vault_path.clone(), false, Some("note.md".into()), None)
+        .expect("unstaged diff should load");
+    assert_eq!(unstaged.hunks.len(), 2);
+
+    git_stage_hunks(
+        vault_path.clone(),
+        "note.md".to_string(),
+        vec![select_hunk(0, None)],
+    )
+    .expect("hunk should stage");
+
+    let staged = git_working_diff(vault_path.clone(), true, None, None).expect("staged diff");
+    assert_eq!(staged.hunks.len(), 1);
+    assert_eq!((staged.additions, staged.deletions), (1, 1));
+    let unstaged = git_working_diff(vault_path.clone(), false, None, None).expect("unstaged");
+    assert_eq!(unstaged.hunks.len(), 1);
+    assert!(unstaged.hunks[0]
+        .lines
+        .iter()
+        .any(|l| l.content.contains("second edit")));
+
+    let status = git_status(vault_path.clone()).expect("status should load");
+    assert_eq!(status.staged.len(), 1);
+    assert_eq!(status.staged[0].status, "modified");
+    assert_eq!(status.unstaged.len(), 1);
+
+    git_stage_and_commit(vault_path.clone(), "first".to_string(), Some(vec![]), None)
+        .expect("index should commit");
+    assert_eq!(
+        committed_content(&vault_path, "note.md"),
+        numbered_lines(&[(2, "first edit")])
+    );
+    assert_eq!(
+        read_file(tmp.path(), "note.md"),
+        numbered_lines(&[(2, "first edit"), (18, "second edit")])
+    );
+}
+
+#[test]
+fn staging_selected_lines_and_unstaging_hunks() {
+    let (tmp, vault_path) = init_vault(&[("note.md", &numbered_lines(&[]))]);
+    write_file(
+        tmp.path(),
+        "note.md",
+        &numbered_lines(&[(5, "five"), (6, "six")]),
+    );
+
+    let diff = git_working_diff(vault_path.clone(), false, Some("note.md".into()), None)
+        .expect("diff should load");
+    let added_six = diff.hunks[0]
+        .lines
+        .iter()
+        .position(|l| l.line_type == "addition" && l.content == "six\n")
+        .expect("addition should be listed");
+    let removed_six = diff.hunks[0]
+        .lines
+        .iter()
+        .position(|l| l.line_type == "deletion" && l.content == "line 6\n")
+        .expect("deletion should be listed");
+
+    git_stage_hunks(
+        vault_path.clone(),
+        "note.md".to_string(),
+        vec![select_hunk(0, Some(vec![removed_six, added_six]))],
+    )
+    .expect("lines should stage");
+    git_stage_and_commit(vault_path.clone(), "six".to_string(), Some(vec![]), None)
+        .expect("index should commit");
+    assert_eq!(
+        committed_content(&vault_path, "note.md"),
+        numbered_lines(&[(6, "six")])
+    );
+
+    write_file(tmp.path(), "added.md", "brand new\n");
+    git_stage_hunks(
+        vault_path.clone(),
+        "added.md".to_string(),
+        vec![select_hunk(0, None)],
+    )
+    .expect("untracked note should stage");
+    git_stage_hunks(
+        vault_path.clone(),
+        "note.md".to_string(),
+        vec![select_hunk(0, None)],
+    )
+    .expect("remaining hunk should stage");
+
+    git_unstage_hunks(
+        vault_path.clone(),
+        "added.md".to_string(),
+        vec![select_hunk(0, None)],
+    )
+    .expect("new note should unstage");
+    let status = git_status(vault_path.clone()).expect("status should load");
+    let staged: Vec<&str> = status.staged.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(staged, vec!["note.md"]);
+    let untracked = status
+        .unstaged
+        .iter()
+        .find(|f| f.path == "added.md")
+        .expect("new note should be untracked again");
+    assert_eq!(untracked.status, "untracked");
+
+    let err = git_unstage_hunks(
+        vault_path,
+        "note.md".to_string(),
+        vec![select_hunk(3, None)],
+    )
+    .expect_err("missing hunk should be rejected");
+    assert!(matches!(err, AppError::InvalidInput { .. }));
+    assert!(err.to_string().contains("hunk 3"));
+}
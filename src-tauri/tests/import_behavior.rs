@@ -0,0 +1,255 @@
+use crate::features::import::html_convert::{html_to_markdown, Hooks};
+use crate::features::import::notion::{
+    csv_to_markdown, import_notion_zip_with_limits, strip_notion_id, ZipLimits,
+};
+use crate::features::import::service::{import_at, ImportKind, ImportWriter};
+use crate::tests::support::write_file;
+use base64::Engine;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write_zip(path: &Path, entries: &[(&str, &[u8])]) {
+    let file = fs::File::create(path).expect("zip should be created");
+    let mut zip = zip::ZipWriter::new(file);
+    for (name, bytes) in entries {
+        zip.start_file(*name, zip::write::SimpleFileOptions::default())
+            .expect("entry should start");
+        zip.write_all(bytes).expect("entry should be written");
+    }
+    zip.finish().expect("zip should finish");
+}
+
+#[test]
+fn notion_names_and_databases_are_cleaned_up() {
+    assert_eq!(
+        strip_notion_id("Roadmap 0123456789abcdef0123456789abcdef.md"),
+        "Roadmap.md"
+    );
+    assert_eq!(strip_notion_id("Plain note.md"), "Plain note.md");
+
+    let markdown = csv_to_markdown("Tasks", "Name,Status\n\"Ship, soon\",Done\nDraft|x\n");
+    assert_eq!(
+        markdown,
+        "# Tasks\n\n| Name | Status |\n| --- | --- |\n| Ship, soon | Done |\n| Draft\\|x |  |\n"
+    );
+}
+
+#[test]
+fn notion_zip_import_caps_entry_and_total_sizes() {
+    let vault = TempDir::new().expect("vault dir");
+    let source = TempDir::new().expect("source dir");
+    let zip_path = source.path().join("export.zip");
+    write_zip(
+        &zip_path,
+        &[
+            ("a.md", b"# A\n\n12345"),
+            ("big.md", &[b'x'; 20]),
+            ("c.png", b"12345"),
+            ("huge.bin", &[0; 9]),
+        ],
+    );
+    let limits = ZipLimits {
+        text_entry_bytes: 16,
+        entry_bytes: 8,
+        total_bytes: 30,
+    };
+
+    let mut writer = ImportWriter::new(vault.path(), "");
+    import_notion_zip_with_limits(&mut writer, &zip_path, limits).expect("import");
+    assert_eq!(writer.report.notes.len(), 1);
+    assert_eq!(writer.report.attachments, vec!["c.png"]);
+    let skipped: Vec<&str> = writer
+        .report
+        .skipped
+        .iter()
+        .map(|s| s.source.as_str())
+        .collect();
+    assert_eq!(skipped, vec!["big.md", "huge.bin"]);
+
+    let small = TempDir::new().expect("vault dir");
+    let mut writer = ImportWriter::new(small.path(), "");
+    let error = import_notion_zip_with_limits(
+        &mut writer,
+        &zip_path,
+        ZipLimits {
+            total_bytes: 12,
+            ..limits
+        },
+    )
+    .expect_err("archive is over the total cap");
+    assert!(error.contains("more than 12 bytes"));
+    assert!(!small.path().join("c.png").exists());
+}
+
+#[test]
+fn notion_zip_import_strips_ids_and_rewrites_links() {
+    let vault = TempDir::new().expect("vault dir");
+    let source = TempDir::new().expect("source dir");
+    let zip_path = source.path().join("export.zip");
+    let id = "0123456789abcdef0123456789abcdef";
+    let home = format!("Home {}.md", id);
+    let child = format!("Home {}/Child {}.md", id, id);
+    let image = format!("Home {}/diagram.png", id);
+    let db = format!("Home {}/Tasks {}.csv", id, id);
+    let db_all = format!("Home {}/Tasks {}_all.csv", id, id);
+    let home_body = format!(
+        "# Home\n\nSee [Child](Home%20{id}/Child%20{id}.md) and [web](https://example.com).\n"
+    );
+    let child_body = "# Child\n\n![diagram](diagram.png)\n";
+    write_zip(
+        &zip_path,
+        &[
+            (&home, home_body.as_bytes()),
+            (&child, child_body.as_bytes()),
+            (&image, b"png"),
+            (&db, b"Name\nOne\n"),
+            (&db_all, b"Name\nOne\n"),
+        ],
+    );
+    write_file(vault.path(), "imported/Home.md", "existing");
+
+    let report = import_at(
+        vault.path(),
+        ImportKind::NotionZip,
+        &zip_path,
+        "imported",
+        None,
+    )
+    .expect("import should succeed");
+
+    let home_note = fs::read_to_string(vault.path().join("imported/Home 2.md")).expect("home");
+    assert!(home_note.contains("[Child](./Home/Child.md)"));
+    assert!(home_note.contains("[web](https://example.com)"));
+    assert_eq!(
+        fs::read_to_string(vault.path().join("imported/Home.md")).expect("existing"),
+        "existing"
+    );
+    let child_note =
+        fs::read_to_string(vault.path().join("imported/Home/Child.md")).expect("child");
+    assert!(child_note.contains("![diagram](diagram.png)"));
+    assert!(vault.path().join("imported/Home/diagram.png").is_file());
+    assert!(vault.path().join("imported/Home/Tasks.md").is_file());
+
+    assert_eq!(report.notes.len(), 3);
+    assert_eq!(report.attachments, vec!["imported/Home/diagram.png"]);
+    assert_eq!(report.rewritten_links, 2);
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].reason, "duplicate database view");
+}
+
+#[test]
+fn enex_import_writes_resources_and_frontmatter() {
+    let vault = TempDir::new().expect("vault dir");
+    let source = TempDir::new().expect("source dir");
+    let image = b"fake image bytes";
+    let hash = format!("{:x}", md5::compute(image));
+    let data = base64::engine::general_purpose::STANDARD.encode(image);
+    let enex = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<en-export>
+  <note>
+    <title>Trip &amp; Plans</title>
+    <content><![CDATA[<?xml version="1.0"?><en-note><div>Packing <b>list</b></div><en-media type="image/png" hash="{hash}"/><div><en-todo checked="true"/>Passport</div></en-note>]]></content>
+    <created>20240102T030405Z</created>
+    <tag>travel</tag>
+    <tag>summer trip</tag>
+    <resource>
+      <data encoding="base64">{data}</data>
+      <mime>image/png</mime>
+      <resource-attributes><file-name>map.png</file-name></resource-attributes>
+    </resource>
+  </note>
+</en-export>"#
+    );
+    write_file(source.path(), "notes.enex", &enex);
+
+    let report = import_at(
+        vault.path(),
+        ImportKind::Enex,
+        &source.path().join("notes.enex"),
+        "Evernote",
+        Some("files"),
+    )
+    .expect("import should succeed");
+
+    assert_eq!(report.notes.len(), 1);
+    assert_eq!(report.notes[0].path, "Evernote/Trip & Plans.md");
+    assert_eq!(report.attachments, vec!["files/map.png"]);
+    assert_eq!(
+        fs::read(vault.path().join("files/map.png")).expect("resource"),
+        image
+    );
+
+    let note = fs::read_to_string(vault.path().join("Evernote/Trip & Plans.md")).expect("note");
+    assert!(note.starts_with("---\n"));
+    assert!(note.contains("created: 2024-01-02T03:04:05"));
+    assert!(note.contains("summer-trip"));
+    assert!(note.contains("# Trip & Plans"));
+    assert!(note.contains("Packing **list**"));
+    assert!(note.contains("![map.png](../files/map.png)"));
+    assert!(note.contains("- [x] Passport"));
+    assert!(!note.contains("## Attachments"));
+}
+
+#[test]
+fn html_folder_import_rewrites_links_and_copies_images() {
+    let vault = TempDir::new().expect("vault dir");
+    let source = TempDir::new().expect("source dir");
+    write_file(
+        source.path(),
+        "index.html",
+        "<html><head><title>Start</title></head><body><p>Go to <a href=\"docs/guide.html#setup\">the guide</a>.</p><img src=\"img/logo one.png\" alt=\"Logo\"><img src=\"missing.png\"></body></html>",
+    );
+    write_file(
+        source.path(),
+        "docs/guide.html",
+        "<h1>Guide</h1><p>Back <a href=\"../index.html\">home</a></p>",
+    );
+    write_file(source.path(), "img/logo one.png", "png");
+
+    let report = import_at(
+        vault.path(),
+        ImportKind::HtmlFolder,
+        source.path(),
+        "site",
+        None,
+    )
+    .expect("import should succeed");
+
+    let index = fs::read_to_string(vault.path().join("site/index.md")).expect("index");
+    assert!(index.starts_with("# Start\n\n"));
+    assert!(index.contains("[the guide](./docs/guide.md#setup)"));
+    assert!(index.contains("![Logo](./img/logo%20one.png)"));
+    let guide = fs::read_to_string(vault.path().join("site/docs/guide.md")).expect("guide");
+    assert!(guide.starts_with("# Guide"));
+    assert!(guide.contains("[home](../index.md)"));
+
+    assert!(vault.path().join("site/img/logo one.png").is_file());
+    assert_eq!(report.rewritten_links, 2);
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].reason, "missing image");
+}
+
+#[test]
+fn html_to_markdown_converts_common_blocks() {
+    let html = "<h2>Title</h2><ul><li>One<ul><li>Nested</li></ul></li><li><i>Two</i></li></ul>\
+        <blockquote><p>Quoted</p></blockquote><pre><code>let x = 1;</code></pre>\
+        <table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>";
+    let mut link = |href: &str| href.to_string();
+    let mut media = |_: crate::features::import::html_convert::Media<'_>| None;
+    let markdown = html_to_markdown(
+        html,
+        &mut Hooks {
+            link: &mut link,
+            media: &mut media,
+        },
+    );
+
+    assert!(markdown.contains("## Title"));
+    assert!(markdown.contains("- One\n  - Nested\n- *Two*"));
+    assert!(markdown.contains("> Quoted"));
+    assert!(markdown.contains("```\nlet x = 1;\n```"));
+    assert!(markdown.contains("| A | B |\n| --- | --- |\n| 1 | 2 |"));
+}
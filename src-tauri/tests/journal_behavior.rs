@@ -0,0 +1,193 @@
+use crate::features::journal::service::{
+    journal_summary_at, record_operation, redo_at, snapshot_removal, snapshot_removal_within,
+    undo_at, write_op, JournalOp,
+};
+use crate::features::search::service::rewrite_links_in_notes_at;
+use crate::tests::support::write_file;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn read(root: &Path, rel: &str) -> String {
+    fs::read_to_string(root.join(rel)).expect("file should be readable")
+}
+
+#[test]
+fn deleted_folder_is_restored_by_undo_and_removed_again_by_redo() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(root, "projects/a.md", "# A\n");
+    write_file(root, "projects/deep/b.md", "# B\n");
+    fs::create_dir_all(root.join("projects/empty")).expect("empty dir");
+
+    let ops = snapshot_removal(root, "projects")
+        .expect("snapshot should succeed")
+        .expect("small folders should be snapshotted");
+    fs::remove_dir_all(root.join("projects")).expect("folder should be deleted");
+    record_operation(root, "delete_folder", ops).expect("record should succeed");
+
+    let change = undo_at(root, None).expect("undo should succeed");
+    assert_eq!(change.kind, "delete_folder");
+    assert_eq!(read(root, "projects/a.md"), "# A\n");
+    assert_eq!(read(root, "projects/deep/b.md"), "# B\n");
+    assert!(root.join("projects/empty").is_dir());
+
+    let change = redo_at(root).expect("redo should succeed");
+    let mut removed = change.removed.clone();
+    removed.sort();
+    assert_eq!(removed, vec!["projects/a.md", "projects/deep/b.md"]);
+    assert!(!root.join("projects").exists());
+    assert!(redo_at(root).is_err());
+}
+
+#[test]
+fn removals_over_the_byte_limit_are_not_snapshotted() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(root, "attachments/a.png", &"a".repeat(600));
+    write_file(root, "attachments/b.png", &"b".repeat(600));
+
+    let snapshot = snapshot_removal_within(root, "attachments", 1000).expect("snapshot");
+    assert!(snapshot.is_none());
+    assert!(!root.join(".otterly/journal").exists());
+
+    let snapshot = snapshot_removal_within(root, "attachments", 2000)
+        .expect("snapshot")
+        .expect("folders under the limit should be snapshotted");
+    assert_eq!(snapshot.len(), 3);
+}
+
+#[test]
+fn undo_reverts_moves_and_overwritten_targets_in_reverse_order() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(root, "inbox/note.md", "new\n");
+    write_file(root, "archive/note.md", "old\n");
+
+    let mut ops = snapshot_removal(root, "archive/note.md")
+        .expect("snapshot")
+        .expect("small files should be snapshotted");
+    fs::remove_file(root.join("archive/note.md")).expect("remove target");
+    fs::rename(root.join("inbox/note.md"), root.join("archive/note.md")).expect("move");
+    ops.push(JournalOp::Move {
+        from: "inbox/note.md".to_string(),
+        to: "archive/note.md".to_string(),
+    });
+    record_operation(root, "move_items", ops).expect("record should succeed");
+
+    let change = undo_at(root, None).expect("undo should succeed");
+    assert_eq!(change.moved.len(), 1);
+    assert!(!change.moved[0].is_folder);
+    assert_eq!(read(root, "inbox/note.md"), "new\n");
+    assert_eq!(read(root, "archive/note.md"), "old\n");
+}
+
+#[test]
+fn undo_refuses_when_files_changed_since_the_operation() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(root, "a.md", "after\n");
+    let op = write_op(root, "a.md", Some(b"before\n"), Some(b"after\n")).expect("op");
+    record_operation(root, "replace", vec![op]).expect("record should succeed");
+    write_file(root, "a.md", "edited later\n");
+
+    let err = undo_at(root, None).expect_err("undo should conflict");
+    assert_eq!(err, "conflict:changed:a.md");
+    assert_eq!(read(root, "a.md"), "edited later\n");
+    assert_eq!(
+        journal_summary_at(root).expect("summary").undo.len(),
+        1,
+        "entry stays undoable after a conflict"
+    );
+}
+
+#[test]
+fn undo_refuses_entries_that_point_outside_the_vault() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path().join("vault");
+    write_file(&root, "note.md", "# Note\n");
+    let ops = vec![JournalOp::Move {
+        from: "../escaped.md".to_string(),
+        to: "note.md".to_string(),
+    }];
+    record_operation(&root, "move_items", ops).expect("record should succeed");
+
+    assert!(undo_at(&root, None).is_err());
+    assert!(!tmp.path().join("escaped.md").exists());
+    assert_eq!(read(&root, "note.md"), "# Note\n");
+}
+
+#[test]
+fn link_rewrites_are_recorded_as_one_entry() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(root, "a.md", "See [[old]] and [o](old.md).\n");
+    write_file(root, "b.md", "Also [[old|Old]].\n");
+    write_file(root, "c.md", "Unrelated.\n");
+    let target_map = HashMap::from([("old.md".to_string(), "new.md".to_string())]);
+
+    let result = rewrite_links_in_notes_at(
+        root,
+        &[
+            "a.md".to_string(),
+            "b.md".to_string(),
+            "c.md".to_string(),
+            "missing.md".to_string(),
+        ],
+        &target_map,
+    )
+    .expect("rewrite should succeed");
+    assert_eq!(result.rewritten, vec!["a.md", "b.md"]);
+    assert_eq!(result.failed, vec!["missing.md"]);
+    assert!(read(root, "a.md").contains("[[new]]"));
+    assert!(read(root, "a.md").contains("(new.md)"));
+
+    let summary = journal_summary_at(root).expect("summary");
+    assert_eq!(summary.undo.len(), 1);
+    assert_eq!(summary.undo[0].kind, "rewrite_links");
+    assert_eq!(summary.undo[0].changes, 2);
+
+    undo_at(root, None).expect("undo should succeed");
+    assert_eq!(read(root, "a.md"), "See [[old]] and [o](old.md).\n");
+    assert_eq!(read(root, "b.md"), "Also [[old|Old]].\n");
+    assert_eq!(journal_summary_at(root).expect("summary").redo.len(), 1);
+}
+
+#[test]
+fn link_rewrites_join_the_move_they_follow() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(root, "a.md", "See [[old]].\n");
+    // The note already moved from old.md, so its relative link is stale.
+    write_file(root, "docs/new.md", "Up [a](./a.md).\n");
+    record_operation(
+        root,
+        "rename_note",
+        vec![JournalOp::Move {
+            from: "old.md".to_string(),
+            to: "docs/new.md".to_string(),
+        }],
+    )
+    .expect("record should succeed");
+    let target_map = HashMap::from([("old.md".to_string(), "docs/new.md".to_string())]);
+
+    let result = rewrite_links_in_notes_at(
+        root,
+        &["a.md".to_string(), "docs/new.md".to_string()],
+        &target_map,
+    )
+    .expect("rewrite should succeed");
+    assert_eq!(result.rewritten, vec!["a.md", "docs/new.md"]);
+    assert_eq!(read(root, "a.md"), "See [[docs/new]].\n");
+    assert_eq!(read(root, "docs/new.md"), "Up [a](../a.md).\n");
+
+    let summary = journal_summary_at(root).expect("summary");
+    assert_eq!(summary.undo.len(), 1);
+    assert_eq!(summary.undo[0].kind, "rename_note");
+
+    undo_at(root, None).expect("undo should succeed");
+    assert_eq!(read(root, "a.md"), "See [[old]].\n");
+    assert_eq!(read(root, "old.md"), "Up [a](./a.md).\n");
+    assert!(!root.join("docs/new.md").exists());
+}
@@ -0,0 +1,218 @@
+use crate::features::local_api::config::{ApiAccess, LocalApiStore};
+use crate::features::local_api::rpc::handle_rpc;
+use crate::features::local_api::service::LocalApiServer;
+use crate::shared::storage::{Vault, VaultEntry, VaultStore};
+use crate::shared::vault_context::{VaultContext, VaultRegistry};
+use crate::tests::support::write_file;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+fn call(
+    ctx: &VaultContext,
+    token: &crate::features::local_api::config::ApiToken,
+    body: Value,
+) -> Value {
+    handle_rpc(ctx, token, body.to_string().as_bytes()).response
+}
+
+fn error_code(response: &Value) -> Option<i64> {
+    response["error"]["code"].as_i64()
+}
+
+#[test]
+fn tokens_are_stored_hashed_and_can_be_revoked() {
+    let config = TempDir::new().expect("temp dir");
+    let store = LocalApiStore::at(config.path());
+
+    let (secret, token) = store
+        .create_token(
+            "clipper",
+            "v1",
+            ApiAccess::Write,
+            vec!["/inbox/".to_string(), "inbox".to_string()],
+        )
+        .expect("token should be created");
+    assert!(secret.starts_with("otk_"));
+    assert_eq!(token.folders, vec!["inbox"]);
+    let on_disk = fs::read_to_string(config.path().join("local_api.json")).expect("config");
+    assert!(!on_disk.contains(&secret));
+
+    let found = store.authenticate(&secret).expect("auth").expect("token");
+    assert_eq!(found.id, token.id);
+    assert!(store.authenticate("otk_wrong").expect("auth").is_none());
+
+    store.revoke_token(&token.id).expect("revoke");
+    assert!(store.authenticate(&secret).expect("auth").is_none());
+    assert_eq!(
+        store
+            .revoke_token(&token.id)
+            .expect_err("already revoked")
+            .code(),
+        "not_found"
+    );
+}
+
+#[test]
+fn rpc_enforces_access_and_folder_scope() {
+    let config = TempDir::new().expect("temp dir");
+    let vault = TempDir::new().expect("temp dir");
+    write_file(vault.path(), "inbox/todo.md", "# Todo\n- milk");
+    write_file(vault.path(), "private/diary.md", "# Diary\n");
+    let ctx = VaultContext::for_root(vault.path()).expect("context");
+    let store = LocalApiStore::at(config.path());
+    let (_, reader) = store
+        .create_token("nvim", ctx.vault_id(), ApiAccess::Read, vec![])
+        .expect("token");
+    let (_, writer) = store
+        .create_token(
+            "launcher",
+            ctx.vault_id(),
+            ApiAccess::Write,
+            vec!["inbox".to_string(), "daily".to_string()],
+        )
+        .expect("token");
+
+    let listed = call(
+        &ctx,
+        &reader,
+        json!({"jsonrpc": "2.0", "id": 1, "method": "notes.list"}),
+    );
+    assert_eq!(listed["id"], 1);
+    assert_eq!(listed["result"].as_array().map(Vec::len), Some(2));
+    let denied = call(
+        &ctx,
+        &reader,
+        json!({"id": 2, "method": "notes.create", "params": {"path": "inbox/new"}}),
+    );
+    assert_eq!(error_code(&denied), Some(-32003));
+
+    let outside = call(
+        &ctx,
+        &writer,
+        json!({"id": 3, "method": "notes.read", "params": {"path": "private/diary.md"}}),
+    );
+    assert_eq!(error_code(&outside), Some(-32003));
+    let scoped = call(&ctx, &writer, json!({"id": 4, "method": "notes.list"}));
+    assert_eq!(scoped["result"][0]["path"], "inbox/todo.md");
+
+    let appended = handle_rpc(
+        &ctx,
+        &writer,
+        json!({"id": 5, "method": "notes.append", "params": {"path": "inbox/todo", "text": "- eggs"}})
+            .to_string()
+            .as_bytes(),
+    );
+    assert_eq!(appended.written, vec!["inbox/todo.md"]);
+    assert_eq!(
+        fs::read_to_string(vault.path().join("inbox/todo.md")).expect("read"),
+        "# Todo\n- milk\n- eggs\n"
+    );
+
+    let daily = call(
+        &ctx,
+        &writer,
+        json!({"id": 6, "method": "daily.append", "params": {"text": "met Sam", "date": "2026-01-02"}}),
+    );
+    assert_eq!(daily["result"]["created"], true);
+    let daily_text =
+        fs::read_to_string(vault.path().join("daily/2026-01-02.md")).expect("daily note");
+    assert!(daily_text.ends_with("met Sam\n"));
+
+    let duplicate = call(
+        &ctx,
+        &writer,
+        json!({"id": 7, "method": "notes.create", "params": {"path": "inbox/todo.md"}}),
+    );
+    assert_eq!(error_code(&duplicate), Some(-32000));
+    assert_eq!(duplicate["error"]["data"]["code"], "already_exists");
+    assert_eq!(
+        error_code(&call(
+            &ctx,
+            &writer,
+            json!({"id": 8, "method": "notes.delete"})
+        )),
+        Some(-32601)
+    );
+}
+
+fn http(port: u16, host: &str, token: Option<&str>, body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).expect("connect");
+    let auth = token
+        .map(|token| format!("Authorization: Bearer {}\r\n", token))
+        .unwrap_or_default();
+    write!(
+        stream,
+        "POST /rpc HTTP/1.1\r\nHost: {}\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        host,
+        auth,
+        body.len(),
+        body
+    )
+    .expect("write request");
+    let mut raw = String::new();
+    stream.read_to_string(&mut raw).expect("read response");
+    let status = raw
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .expect("status line");
+    let body = raw.split("\r\n\r\n").nth(1).unwrap_or_default();
+    (status, serde_json::from_str(body).unwrap_or(Value::Null))
+}
+
+#[test]
+fn server_authenticates_requests_and_reports_writes() {
+    let config = TempDir::new().expect("temp dir");
+    let vault = TempDir::new().expect("temp dir");
+    write_file(vault.path(), "a.md", "# A\n");
+    let ctx = VaultContext::for_root(vault.path()).expect("context");
+    VaultRegistry::at(config.path())
+        .save(&VaultStore {
+            vaults: vec![VaultEntry {
+                vault: Vault {
+                    id: ctx.vault_id().to_string(),
+                    path: ctx.root().to_string_lossy().to_string(),
+                    name: "notes".to_string(),
+                    created_at: 1,
+                    last_opened_at: None,
+                    note_count: None,
+                    is_available: true,
+                    pinned: false,
+                },
+                last_opened_at: 1,
+            }],
+            last_vault_id: None,
+        })
+        .expect("save registry");
+    let (secret, _) = LocalApiStore::at(config.path())
+        .create_token("cli", ctx.vault_id(), ApiAccess::Write, vec![])
+        .expect("token");
+
+    let writes: Arc<Mutex<Vec<String>>> = Arc::default();
+    let seen = Arc::clone(&writes);
+    let server = LocalApiServer::start(
+        config.path().to_path_buf(),
+        0,
+        Arc::new(move |_, paths| seen.lock().unwrap().extend(paths.iter().cloned())),
+    )
+    .expect("server should start");
+    let port = server.port();
+    let host = format!("127.0.0.1:{}", port);
+    let create =
+        json!({"id": 1, "method": "notes.create", "params": {"path": "b", "markdown": "# B\n"}})
+            .to_string();
+
+    assert_eq!(http(port, &host, None, &create).0, 401);
+    assert_eq!(http(port, "evil.example:80", Some(&secret), &create).0, 403);
+    let (status, body) = http(port, &host, Some(&secret), &create);
+    assert_eq!(status, 200);
+    assert_eq!(body["result"]["path"], "b.md");
+    assert_eq!(*writes.lock().unwrap(), vec!["b.md"]);
+
+    server.stop();
+    assert!(TcpStream::connect(("127.0.0.1", port)).is_err());
+}
@@ -1,8 +1,11 @@
 use crate::features::notes::service::{
-    folder_cache_key, get_or_scan_folder_entries, invalidate_folder_cache, rename_with_temp_path,
-    safe_vault_abs, safe_vault_abs_for_write, safe_vault_rename_target_abs, scan_folder_entries,
+    create_note_file, folder_cache_key, get_or_scan_folder_entries, invalidate_folder_cache,
+    rename_with_temp_path, safe_vault_abs, safe_vault_abs_for_write, safe_vault_rename_target_abs,
+    scan_folder_entries,
 };
+use crate::shared::error::AppError;
 use crate::shared::storage;
+use crate::shared::vault_ignore::VaultIgnore;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -32,6 +35,26 @@ fn safe_vault_abs_rejects_traversal() {
     let _ = std::fs::remove_dir_all(&root);
 }
 
+#[test]
+fn path_errors_serialize_with_stable_codes() {
+    let root = mk_temp_dir();
+    let err = safe_vault_abs(&root, "../x.md").expect_err("traversal should fail");
+    assert_eq!(
+        serde_json::to_value(&err).expect("error should serialize"),
+        serde_json::json!({
+            "code": "invalid_path",
+            "message": "note path contains invalid segments",
+            "path": "../x.md",
+        })
+    );
+
+    create_note_file(&root, "a.md", "# A\n").expect("create should succeed");
+    let err = create_note_file(&root, "a.md", "# A\n").expect_err("duplicate should fail");
+    assert_eq!(err.code(), "already_exists");
+    assert_eq!(err.to_string(), "note already exists");
+    let _ = std::fs::remove_dir_all(&root);
+}
+
 #[cfg(unix)]
 #[test]
 fn safe_vault_abs_for_write_rejects_symlink_escape() {
@@ -41,7 +64,12 @@ fn safe_vault_abs_for_write_rejects_symlink_escape() {
     unix_fs::symlink(&outside, &link).expect("symlink should be created");
 
     let result = safe_vault_abs_for_write(&root, "notes/escape.md");
-    assert!(result.is_err());
+    assert_eq!(
+        result,
+        Err(AppError::SymlinkRejected {
+            path: "notes/escape.md".to_string()
+        })
+    );
 
     let _ = std::fs::remove_dir_all(&outside);
     let _ = std::fs::remove_dir_all(&root);
@@ -90,7 +118,8 @@ fn scan_folder_entries_filters_and_sorts() {
     std::fs::write(root.join("a.md"), "a").expect("file should be created");
     std::fs::write(root.join("readme.txt"), "ignored").expect("file should be created");
 
-    let entries = scan_folder_entries(&root).expect("scan should succeed");
+    let entries =
+        scan_folder_entries(&root, &VaultIgnore::load(&root)).expect("scan should succeed");
     let names: Vec<String> = entries.iter().map(|entry| entry.name.clone()).collect();
     let dirs_first = entries
         .iter()
@@ -118,15 +147,19 @@ fn folder_entries_cache_hit_and_invalidation() {
     let vault_id = format!("v{}", storage::now_ms());
     let folder_path = "";
     let key = folder_cache_key(&vault_id, folder_path);
-    let first = get_or_scan_folder_entries(&key, &root).expect("first scan should succeed");
+    let ignore = VaultIgnore::load(&root);
+    let first =
+        get_or_scan_folder_entries(&key, &root, &ignore).expect("first scan should succeed");
     assert_eq!(first.len(), 1);
 
     std::fs::write(root.join("b.md"), "b").expect("second file should be created");
-    let second = get_or_scan_folder_entries(&key, &root).expect("cache hit should succeed");
+    let second =
+        get_or_scan_folder_entries(&key, &root, &ignore).expect("cache hit should succeed");
     assert_eq!(second.len(), 1);
 
     invalidate_folder_cache(&vault_id, folder_path);
-    let third = get_or_scan_folder_entries(&key, &root).expect("scan after invalidate should work");
+    let third = get_or_scan_folder_entries(&key, &root, &ignore)
+        .expect("scan after invalidate should work");
     assert_eq!(third.len(), 2);
 
     let _ = std::fs::remove_dir_all(&root);
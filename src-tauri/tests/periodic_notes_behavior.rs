@@ -0,0 +1,132 @@
+use crate::features::periodic_notes::service::{
+    adjacent_periodic_note_in, list_periodic_notes_in, open_periodic_note_at, Period,
+    PeriodDirection,
+};
+use crate::features::search::db::{open_search_db, upsert_note};
+use crate::features::search::model::IndexNoteMeta;
+use crate::tests::support::write_file;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use tempfile::TempDir;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).expect("valid date")
+}
+
+fn index_paths(conn: &Connection, paths: &[&str]) {
+    for path in paths {
+        let meta = IndexNoteMeta {
+            id: path.to_string(),
+            path: path.to_string(),
+            title: format!("title of {}", path),
+            name: path.to_string(),
+            mtime_ms: 100,
+            size_bytes: 10,
+        };
+        upsert_note(conn, &meta, "body").expect("upsert should succeed");
+    }
+}
+
+#[test]
+fn period_start_aligns_weeks_and_months() {
+    assert_eq!(Period::Weekly.start_of(date(2024, 3, 7)), date(2024, 3, 4));
+    assert_eq!(Period::Weekly.start_of(date(2024, 3, 4)), date(2024, 3, 4));
+    assert_eq!(
+        Period::Monthly.start_of(date(2024, 3, 31)),
+        date(2024, 3, 1)
+    );
+    assert_eq!(
+        Period::Monthly.step(date(2024, 1, 1), PeriodDirection::Previous),
+        Some(date(2023, 12, 1))
+    );
+}
+
+#[test]
+fn open_periodic_note_creates_from_template_then_reopens() {
+    let tmp = TempDir::new().expect("temp dir should be created");
+    write_file(
+        tmp.path(),
+        ".otterly/settings.json",
+        r#"{"periodic_notes": {"daily": {"path_pattern": "journal/{{date:YYYY}}/{{date}}.md", "template": "templates/daily.md"}}}"#,
+    );
+    write_file(
+        tmp.path(),
+        "templates/daily.md",
+        "# {{date:dddd, MMMM D}}\n\n{{cursor}}",
+    );
+
+    let opened =
+        open_periodic_note_at(tmp.path(), Period::Daily, date(2024, 3, 7)).expect("should open");
+    assert!(opened.created);
+    assert_eq!(opened.note.path, "journal/2024/2024-03-07.md");
+    assert_eq!(opened.note.title, "Thursday, March 7");
+    assert_eq!(opened.cursor_offset, Some("# Thursday, March 7\n\n".len()));
+
+    let reopened =
+        open_periodic_note_at(tmp.path(), Period::Daily, date(2024, 3, 7)).expect("should open");
+    assert!(!reopened.created);
+    assert_eq!(reopened.note.path, opened.note.path);
+
+    let weekly =
+        open_periodic_note_at(tmp.path(), Period::Weekly, date(2024, 3, 7)).expect("should open");
+    assert_eq!(weekly.note.path, "weekly/2024-W10.md");
+    assert_eq!(weekly.period_start, "2024-03-04");
+}
+
+#[test]
+fn periodic_notes_are_listed_and_navigated_from_index() {
+    let tmp = TempDir::new().expect("temp dir should be created");
+    let conn = open_search_db(tmp.path()).expect("db should open");
+    index_paths(
+        &conn,
+        &[
+            "daily/2024-02-28.md",
+            "daily/2024-03-01.md",
+            "daily/2024-03-05.md",
+            "daily/notes.md",
+            "monthly/2024-01.md",
+        ],
+    );
+
+    let listed = list_periodic_notes_in(
+        &conn,
+        tmp.path(),
+        Period::Daily,
+        date(2024, 3, 1),
+        date(2024, 3, 31),
+    )
+    .expect("list should succeed");
+    let starts: Vec<&str> = listed.iter().map(|n| n.period_start.as_str()).collect();
+    assert_eq!(starts, vec!["2024-03-01", "2024-03-05"]);
+    assert_eq!(listed[0].title, "title of daily/2024-03-01.md");
+
+    let previous = adjacent_periodic_note_in(
+        &conn,
+        tmp.path(),
+        Period::Daily,
+        date(2024, 3, 5),
+        PeriodDirection::Previous,
+    )
+    .expect("navigate should succeed")
+    .expect("previous note should exist");
+    assert_eq!(previous.path, "daily/2024-03-01.md");
+
+    let next_month = adjacent_periodic_note_in(
+        &conn,
+        tmp.path(),
+        Period::Monthly,
+        date(2024, 1, 15),
+        PeriodDirection::Next,
+    )
+    .expect("navigate should succeed");
+    assert!(next_month.is_none());
+
+    let reversed = list_periodic_notes_in(
+        &conn,
+        tmp.path(),
+        Period::Daily,
+        date(2024, 3, 5),
+        date(2024, 3, 1),
+    );
+    assert!(reversed.is_err());
+}
@@ -0,0 +1,282 @@
+use crate::features::plugins::host::{HostApi, PluginHost, PluginInstance};
+use crate::features::plugins::manifest::{
+    load_manifest, PluginCapabilities, PluginManifest, VaultAccess,
+};
+use crate::shared::error::AppResult;
+use crate::shared::vault_context::VaultContext;
+use crate::tests::support::write_file;
+use serde_json::{json, Value};
+use std::fs;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+type Reply = fn(&HostApi, &Value) -> AppResult<Option<Value>>;
+
+struct FakePlugin {
+    seen: Arc<Mutex<Vec<Value>>>,
+    reply: Reply,
+}
+
+impl PluginInstance for FakePlugin {
+    fn call(&mut self, api: HostApi, request: &Value) -> AppResult<Option<Value>> {
+        self.seen.lock().unwrap().push(request.clone());
+        (self.reply)(&api, request)
+    }
+}
+
+fn manifest(value: Value) -> PluginManifest {
+    serde_json::from_value(value).expect("manifest should parse")
+}
+
+fn fake(reply: Reply) -> (Box<FakePlugin>, Arc<Mutex<Vec<Value>>>) {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    (
+        Box::new(FakePlugin {
+            seen: Arc::clone(&seen),
+            reply,
+        }),
+        seen,
+    )
+}
+
+#[test]
+fn manifests_are_validated_and_broken_plugins_are_reported() {
+    let dir = TempDir::new().expect("temp dir");
+    write_file(
+        dir.path(),
+        "word-count/plugin.json",
+        r#"{"id":"word-count","name":"Word count","version":"1.0.0",
+            "hooks":["note_save"],"commands":[{"id":"count","title":"Count words"}],
+            "capabilities":{"vault":"read","folders":["/journal/"]}}"#,
+    );
+    write_file(dir.path(), "word-count/plugin.wasm", "not wasm");
+    write_file(
+        dir.path(),
+        "renamed/plugin.json",
+        r#"{"id":"other","name":"Other","version":"1"}"#,
+    );
+    write_file(
+        dir.path(),
+        "escape/plugin.json",
+        r#"{"id":"escape","name":"Escape","version":"1","entry":"../evil.wasm"}"#,
+    );
+
+    let (parsed, entry) = load_manifest(&dir.path().join("word-count")).expect("valid manifest");
+    assert_eq!(entry, dir.path().join("word-count/plugin.wasm"));
+    assert_eq!(parsed.capabilities.folders, vec!["journal"]);
+    assert_eq!(parsed.capabilities.vault, VaultAccess::Read);
+    assert_eq!(
+        load_manifest(&dir.path().join("renamed"))
+            .expect_err("id must match folder")
+            .code(),
+        "invalid_input"
+    );
+    assert_eq!(
+        load_manifest(&dir.path().join("escape"))
+            .expect_err("entry must stay inside")
+            .code(),
+        "invalid_path"
+    );
+
+    let host = PluginHost::load_dir(dir.path());
+    assert!(host.is_empty());
+    let listed = host.list();
+    assert_eq!(listed.len(), 3);
+    assert!(listed.iter().all(|info| info.error.is_some()));
+    assert!(PluginHost::load_dir(&dir.path().join("missing"))
+        .list()
+        .is_empty());
+}
+
+#[test]
+fn host_api_enforces_declared_capabilities() {
+    let vault = TempDir::new().expect("temp dir");
+    write_file(vault.path(), "journal/today.md", "# Today\n");
+    write_file(vault.path(), "private.md", "secret");
+    write_file(vault.path(), ".git/config", "[core]");
+    let ctx = VaultContext::for_root(vault.path()).expect("context");
+    let scoped = |vault_access| PluginCapabilities {
+        vault: vault_access,
+        folders: vec!["journal".to_string()],
+    };
+
+    let reader = HostApi::new("reader", Some(ctx.clone()), scoped(VaultAccess::Read));
+    assert_eq!(reader.read("journal/today.md").expect("read"), "# Today\n");
+    assert_eq!(
+        reader
+            .read("private.md")
+            .expect_err("outside folders")
+            .code(),
+        "permission_denied"
+    );
+    assert_eq!(
+        reader
+            .write("journal/today.md", "x")
+            .expect_err("read only")
+            .code(),
+        "permission_denied"
+    );
+
+    let writer = HostApi::new("writer", Some(ctx.clone()), scoped(VaultAccess::Write));
+    writer
+        .write("journal/stats/words.md", "42")
+        .expect("write inside folders");
+    assert_eq!(
+        fs::read_to_string(vault.path().join("journal/stats/words.md")).expect("written"),
+        "42"
+    );
+    assert_eq!(
+        writer
+            .write("journal/../private.md", "x")
+            .expect_err("traversal")
+            .code(),
+        "invalid_path"
+    );
+
+    let everywhere = HostApi::new(
+        "everywhere",
+        Some(ctx),
+        PluginCapabilities {
+            vault: VaultAccess::Write,
+            folders: Vec::new(),
+        },
+    );
+    assert_eq!(
+        everywhere.read("private.md").expect("whole vault"),
+        "secret"
+    );
+    assert_eq!(
+        everywhere
+            .read(".git/config")
+            .expect_err("app folder")
+            .code(),
+        "permission_denied"
+    );
+    let none = HostApi::new("none", None, PluginCapabilities::default());
+    assert_eq!(
+        none.read("private.md").expect_err("no vault").code(),
+        "invalid_input"
+    );
+}
+
+#[test]
+fn host_api_keeps_protected_files_off_limits_however_they_are_named() {
+    let vault = TempDir::new().expect("temp dir");
+    write_file(vault.path(), ".otterly/settings.json", "{}");
+    write_file(vault.path(), ".otterlyignore", "drafts/");
+    let ctx = VaultContext::for_root(vault.path()).expect("context");
+    let api = HostApi::new(
+        "everywhere",
+        Some(ctx),
+        PluginCapabilities {
+            vault: VaultAccess::Write,
+            folders: Vec::new(),
+        },
+    );
+
+    for path in [".OTTERLY/settings.json", ".Git/config", ".otterlyignore"] {
+        assert!(!api.allows(path, VaultAccess::Read), "{path}");
+        assert_eq!(
+            api.write(path, "x").expect_err("protected").code(),
+            "permission_denied"
+        );
+    }
+    assert_eq!(
+        fs::read_to_string(vault.path().join(".otterlyignore")).expect("ignore file"),
+        "drafts/"
+    );
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(vault.path().join(".otterly"), vault.path().join("config"))
+            .expect("symlink");
+        assert_eq!(
+            api.read("config/settings.json")
+                .expect_err("resolves into .otterly")
+                .code(),
+            "permission_denied"
+        );
+    }
+}
+
+#[test]
+fn hooks_dispatch_to_registered_plugins_only() {
+    let vault = TempDir::new().expect("temp dir");
+    write_file(vault.path(), "notes/a.md", "see JIRA-1");
+    let ctx = VaultContext::for_root(vault.path()).expect("context");
+
+    let (linker, linker_seen) = fake(|_, request| {
+        Ok(match request["hook"].as_str() {
+            Some("link_extract") => Some(json!({ "links": ["tickets/JIRA-1", "../escape", 7] })),
+            _ => None,
+        })
+    });
+    let (observer, observer_seen) = fake(|_, _| Ok(None));
+    let (counter, counter_seen) = fake(|api, request| {
+        if request["hook"] == "command" {
+            let words = api.read("notes/a.md")?.split_whitespace().count();
+            return Ok(Some(json!({ "words": words, "args": request["args"] })));
+        }
+        Ok(None)
+    });
+
+    let mut host = PluginHost::default();
+    host.add(
+        manifest(json!({
+            "id": "tickets", "name": "Tickets", "version": "1",
+            "hooks": ["link_extract"],
+            "capabilities": { "vault": "read" }
+        })),
+        linker,
+    );
+    host.add(
+        manifest(json!({
+            "id": "audit", "name": "Audit", "version": "1",
+            "hooks": ["note_save", "command_invoke"]
+        })),
+        observer,
+    );
+    host.add(
+        manifest(json!({
+            "id": "count", "name": "Count", "version": "1",
+            "commands": [{ "id": "words", "title": "Count words" }],
+            "capabilities": { "vault": "read", "folders": ["notes"] }
+        })),
+        counter,
+    );
+
+    assert_eq!(
+        host.link_targets(&ctx, "notes/a.md", "see JIRA-1"),
+        vec!["tickets/JIRA-1.md"]
+    );
+    assert_eq!(linker_seen.lock().unwrap()[0]["markdown"], "see JIRA-1");
+
+    host.note_saved(&ctx, "notes/a.md", "see JIRA-1");
+    let saved = observer_seen.lock().unwrap()[0].clone();
+    assert_eq!(saved["hook"], "note_save");
+    assert_eq!(saved["path"], "notes/a.md");
+    assert!(saved.get("markdown").is_none());
+    assert_eq!(linker_seen.lock().unwrap().len(), 1);
+
+    let result = host
+        .run_command(Some(&ctx), "count", "words", json!({ "x": 1 }))
+        .expect("command should run");
+    assert_eq!(result, json!({ "words": 2, "args": { "x": 1 } }));
+    assert_eq!(counter_seen.lock().unwrap().len(), 1);
+    let invoked = observer_seen.lock().unwrap()[1].clone();
+    assert_eq!(invoked["hook"], "command_invoke");
+    assert_eq!(invoked["plugin_id"], "count");
+
+    assert_eq!(
+        host.run_command(Some(&ctx), "count", "delete-all", Value::Null)
+            .expect_err("undeclared command")
+            .code(),
+        "not_found"
+    );
+    assert_eq!(
+        host.run_command(None, "count", "words", Value::Null)
+            .expect_err("command needs a vault")
+            .code(),
+        "invalid_input"
+    );
+}
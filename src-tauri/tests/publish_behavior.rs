@@ -0,0 +1,219 @@
+use crate::features::publish::service::{
+    publish_site_in, PublishOptions, PublishSelection, PublishWarningKind, PUBLISH_MANIFEST,
+};
+use crate::features::search::db::{open_search_db, set_outlinks, upsert_note};
+use crate::features::search::model::IndexNoteMeta;
+use crate::tests::support::write_file;
+use rusqlite::Connection;
+use std::fs;
+use tempfile::TempDir;
+
+fn index_note(conn: &Connection, path: &str, title: &str, outlinks: &[&str]) {
+    let meta = IndexNoteMeta {
+        id: path.to_string(),
+        path: path.to_string(),
+        title: title.to_string(),
+        name: path.to_string(),
+        mtime_ms: 100,
+        size_bytes: 10,
+    };
+    upsert_note(conn, &meta, "body").expect("upsert should succeed");
+    let targets: Vec<String> = outlinks.iter().map(|t| t.to_string()).collect();
+    set_outlinks(conn, path, &targets).expect("outlinks should be set");
+}
+
+fn handbook_vault() -> TempDir {
+    let vault = TempDir::new().expect("temp dir should be created");
+    let root = vault.path();
+    write_file(
+        root,
+        "handbook/welcome.md",
+        "# Welcome\n\nRead [[handbook/onboarding]] and [[private/salaries]].\n",
+    );
+    write_file(
+        root,
+        "handbook/onboarding.md",
+        "# Onboarding\n\nBack to [[handbook/welcome]].\n",
+    );
+    write_file(
+        root,
+        "handbook/draft.md",
+        "---\npublish: false\n---\n# Draft\n",
+    );
+    write_file(
+        root,
+        "notes/faq.md",
+        "---\ntags: [public]\n---\n# FAQ\n\nSee [[handbook/welcome]].\n",
+    );
+    write_file(
+        root,
+        "notes/launch.md",
+        "---\npublish: true\n---\n# Launch\n\nShipping #release notes.\n",
+    );
+    write_file(root, "private/salaries.md", "# Salaries\n");
+    vault
+}
+
+#[test]
+fn publish_selects_notes_by_folder_tag_and_frontmatter() {
+    let vault = handbook_vault();
+    let out = TempDir::new().expect("temp dir should be created");
+    let conn = open_search_db(vault.path()).expect("db should open");
+    let selection = PublishSelection {
+        folders: vec!["handbook".to_string()],
+        tags: vec!["public".to_string()],
+    };
+    let output_dir = out.path().join("site");
+
+    let result = publish_site_in(
+        &conn,
+        vault.path(),
+        &PublishOptions {
+            output_dir: &output_dir.to_string_lossy(),
+            selection: &selection,
+            site_title: Some("Handbook"),
+            base_url: Some("https://docs.example.com/"),
+        },
+    )
+    .expect("publish should succeed");
+
+    assert_eq!(
+        result.pages,
+        vec![
+            "handbook/onboarding.html".to_string(),
+            "handbook/welcome.html".to_string(),
+            "notes/faq.html".to_string(),
+            "notes/launch.html".to_string(),
+        ]
+    );
+    assert!(!output_dir.join("handbook/draft.html").exists());
+    assert!(!output_dir.join("private/salaries.html").exists());
+
+    let unpublished: Vec<_> = result
+        .warnings
+        .iter()
+        .filter(|w| w.kind == PublishWarningKind::UnpublishedLink)
+        .map(|w| (w.path.as_deref(), w.target.as_deref()))
+        .collect();
+    assert_eq!(
+        unpublished,
+        vec![(Some("handbook/welcome.md"), Some("private/salaries.md"))]
+    );
+
+    let welcome =
+        fs::read_to_string(output_dir.join("handbook/welcome.html")).expect("page exists");
+    assert!(welcome.contains("href=\"onboarding.html\""));
+    assert!(!welcome.contains("salaries.html"));
+
+    let search =
+        fs::read_to_string(output_dir.join("search-index.json")).expect("search index exists");
+    let entries: serde_json::Value = serde_json::from_str(&search).expect("valid json");
+    let launch = entries
+        .as_array()
+        .and_then(|items| items.iter().find(|e| e["url"] == "notes/launch.html"))
+        .expect("launch entry exists");
+    assert_eq!(launch["title"], "Launch");
+    assert_eq!(launch["tags"], serde_json::json!(["release"]));
+    assert!(launch["text"]
+        .as_str()
+        .is_some_and(|t| t.contains("Shipping")));
+
+    let sitemap = fs::read_to_string(output_dir.join("sitemap.xml")).expect("sitemap exists");
+    assert!(sitemap.contains("<loc>https://docs.example.com/handbook/welcome.html</loc>"));
+    let feed = fs::read_to_string(output_dir.join("feed.xml")).expect("feed exists");
+    assert!(feed.contains("<title>Handbook</title>"));
+    assert_eq!(feed.matches("<item>").count(), 4);
+}
+
+#[test]
+fn publish_adds_backlinks_from_published_sources_only() {
+    let vault = handbook_vault();
+    let out = TempDir::new().expect("temp dir should be created");
+    let conn = open_search_db(vault.path()).expect("db should open");
+    index_note(
+        &conn,
+        "handbook/welcome.md",
+        "Welcome",
+        &["handbook/onboarding.md"],
+    );
+    index_note(
+        &conn,
+        "handbook/onboarding.md",
+        "Onboarding",
+        &["handbook/welcome.md"],
+    );
+    index_note(&conn, "notes/faq.md", "FAQ", &["handbook/welcome.md"]);
+    index_note(
+        &conn,
+        "private/salaries.md",
+        "Salaries",
+        &["handbook/welcome.md"],
+    );
+    let selection = PublishSelection {
+        folders: vec!["handbook".to_string()],
+        tags: Vec::new(),
+    };
+    let output_dir = out.path().join("site");
+
+    let result = publish_site_in(
+        &conn,
+        vault.path(),
+        &PublishOptions {
+            output_dir: &output_dir.to_string_lossy(),
+            selection: &selection,
+            site_title: None,
+            base_url: None,
+        },
+    )
+    .expect("publish should succeed");
+
+    let welcome =
+        fs::read_to_string(output_dir.join("handbook/welcome.html")).expect("page exists");
+    let backlinks = &welcome[welcome
+        .find("<h2>Backlinks</h2>")
+        .expect("backlinks section")..];
+    assert!(backlinks.contains("href=\"onboarding.html\">Onboarding</a>"));
+    assert!(!backlinks.contains("Salaries"));
+    assert!(!backlinks.contains("FAQ"));
+    assert!(result
+        .warnings
+        .iter()
+        .any(|w| w.kind == PublishWarningKind::MissingBaseUrl));
+    assert!(!output_dir.join("sitemap.xml").exists());
+}
+
+#[test]
+fn republish_removes_pages_that_are_no_longer_published() {
+    let vault = handbook_vault();
+    let out = TempDir::new().expect("temp dir should be created");
+    let conn = open_search_db(vault.path()).expect("db should open");
+    let output_dir = out.path().join("site");
+    let output = output_dir.to_string_lossy().to_string();
+    let selection = PublishSelection {
+        folders: vec!["handbook".to_string()],
+        tags: Vec::new(),
+    };
+    let options = PublishOptions {
+        output_dir: &output,
+        selection: &selection,
+        site_title: None,
+        base_url: None,
+    };
+
+    publish_site_in(&conn, vault.path(), &options).expect("first publish should succeed");
+    assert!(output_dir.join("handbook/onboarding.html").is_file());
+    write_file(out.path(), "site/keep.txt", "not ours");
+
+    write_file(
+        vault.path(),
+        "handbook/onboarding.md",
+        "---\npublish: false\n---\n# Onboarding\n",
+    );
+    let result =
+        publish_site_in(&conn, vault.path(), &options).expect("second publish should succeed");
+
+    assert_eq!(result.removed, vec!["handbook/onboarding.html".to_string()]);
+    assert!(!output_dir.join("handbook/onboarding.html").exists());
+    assert!(output_dir.join("keep.txt").is_file());
+    assert!(output_dir.join(PUBLISH_MANIFEST).is_file());
+}
@@ -0,0 +1,154 @@
+use crate::features::journal::service::{journal_summary_at, undo_at};
+use crate::features::refactor::service::{merge_notes_in, split_note_in, SplitReplacement};
+use crate::features::search::db::{open_search_db, rebuild_index};
+use crate::shared::vault_context::VaultContext;
+use crate::tests::support::write_file;
+use rusqlite::Connection;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use tempfile::TempDir;
+
+fn read(root: &Path, rel: &str) -> String {
+    fs::read_to_string(root.join(rel)).expect("file should be readable")
+}
+
+fn indexed(root: &Path) -> Connection {
+    let conn = open_search_db(root).expect("db should open");
+    let cancel = AtomicBool::new(false);
+    let ctx = VaultContext::new("test", root);
+    rebuild_index(&conn, &ctx, &cancel, &|_, _| {}, &mut || {}).expect("index should build");
+    conn
+}
+
+#[test]
+fn merge_appends_source_deletes_it_and_retargets_links() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(root, "a.md", "# A\n\nFirst part. See [[b]].\n");
+    write_file(
+        root,
+        "notes/b.md",
+        "---\ntags: [x]\n---\n# B\n\n## Details\n\nSee [peer](./peer.md).\n",
+    );
+    write_file(root, "notes/peer.md", "# Peer\n");
+    write_file(
+        root,
+        "c.md",
+        "Read [[notes/b#Details|details]] and [b](notes/b.md).\n",
+    );
+    let conn = indexed(root);
+
+    let result = merge_notes_in(&conn, root, "a.md", "notes/b.md").expect("merge should succeed");
+
+    assert_eq!(result.rewritten, vec!["c.md"]);
+    assert!(!root.join("notes/b.md").exists());
+    let merged = read(root, "a.md");
+    assert!(merged.starts_with("# A\n\nFirst part."));
+    assert!(merged.contains("# B\n\n## Details"));
+    assert!(merged.contains("[peer](notes/peer.md)"));
+    assert!(!merged.contains("tags:"));
+    assert_eq!(
+        read(root, "c.md"),
+        "Read [[a#Details|details]] and [b](a.md).\n"
+    );
+
+    let change = undo_at(root, None).expect("undo should succeed");
+    assert_eq!(change.kind, "merge_notes");
+    assert!(read(root, "notes/b.md").starts_with("---\ntags: [x]"));
+    assert_eq!(read(root, "a.md"), "# A\n\nFirst part. See [[b]].\n");
+}
+
+#[test]
+fn split_extracts_section_and_retargets_heading_links() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(
+        root,
+        "docs/guide.md",
+        "# Guide\n\nIntro.\n\n## Setup\n\nInstall it.\n\n### Linux\n\nUse apt.\n\n## Usage\n\nRun it.\n",
+    );
+    write_file(
+        root,
+        "index.md",
+        "[[docs/guide#Setup]], [[docs/guide#Linux]], [[docs/guide#Usage]] and [g](docs/guide.md#setup).\n",
+    );
+    let conn = indexed(root);
+
+    let result = split_note_in(
+        &conn,
+        root,
+        "docs/guide.md",
+        "Setup",
+        None,
+        SplitReplacement::Embed,
+    )
+    .expect("split should succeed");
+
+    assert_eq!(result.new_path, "docs/Setup.md");
+    assert_eq!(result.rewritten, vec!["index.md"]);
+    assert_eq!(
+        read(root, "docs/Setup.md"),
+        "# Setup\n\nInstall it.\n\n### Linux\n\nUse apt.\n"
+    );
+    assert_eq!(
+        read(root, "docs/guide.md"),
+        "# Guide\n\nIntro.\n\n![[docs/Setup]]\n\n## Usage\n\nRun it.\n"
+    );
+    assert_eq!(
+        read(root, "index.md"),
+        "[[docs/Setup]], [[docs/Setup#Linux]], [[docs/guide#Usage]] and [g](docs/Setup.md).\n"
+    );
+    assert_eq!(
+        journal_summary_at(root).expect("summary").undo[0].changes,
+        3
+    );
+}
+
+#[test]
+fn split_rejects_missing_headings_and_existing_notes() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(root, "a.md", "# A\n\n## Part\n\nText.\n");
+    write_file(root, "taken.md", "# Taken\n");
+    let conn = indexed(root);
+
+    let err = split_note_in(&conn, root, "a.md", "Nope", None, SplitReplacement::Link)
+        .expect_err("missing heading should fail");
+    assert_eq!(err, "heading not found");
+
+    let err = split_note_in(
+        &conn,
+        root,
+        "a.md",
+        "## Part",
+        Some("taken"),
+        SplitReplacement::Link,
+    )
+    .expect_err("existing note should fail");
+    assert_eq!(err, "note already exists");
+    assert_eq!(read(root, "a.md"), "# A\n\n## Part\n\nText.\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn merge_failing_midway_stays_undoable() {
+    let tmp = TempDir::new().expect("temp dir");
+    let root = tmp.path();
+    write_file(root, "a.md", "# A\n");
+    write_file(root, "b.md", "# B\n");
+    write_file(root, "sub/c.md", "See [[b]].\n");
+    let conn = indexed(root);
+    // The indexed backlink now goes through a symlink, so rewriting it fails
+    // after the target has been written.
+    fs::rename(root.join("sub"), root.join("real")).expect("rename folder");
+    std::os::unix::fs::symlink(root.join("real"), root.join("sub")).expect("symlink");
+
+    merge_notes_in(&conn, root, "a.md", "b.md").expect_err("merge should fail");
+    assert!(read(root, "a.md").contains("# B"));
+    assert!(root.join("b.md").exists());
+
+    let change = undo_at(root, None).expect("undo should succeed");
+    assert_eq!(change.kind, "merge_notes");
+    assert_eq!(read(root, "a.md"), "# A\n");
+}
@@ -0,0 +1,195 @@
+use crate::features::journal::service::undo_at;
+use crate::features::notes::service::file_meta;
+use crate::features::replace::service::{
+    apply_replace_at, preview_replace_at, ReplaceFileGuard, ReplaceQuery,
+};
+use crate::tests::support::write_file;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn query(pattern: &str, replacement: &str) -> ReplaceQuery {
+    ReplaceQuery {
+        pattern: pattern.to_string(),
+        replacement: replacement.to_string(),
+        ..ReplaceQuery::default()
+    }
+}
+
+fn guards_for(root: &Path, paths: &[&str]) -> Vec<ReplaceFileGuard> {
+    paths
+        .iter()
+        .map(|path| ReplaceFileGuard {
+            path: path.to_string(),
+            expected_mtime_ms: file_meta(&root.join(path)).expect("meta").0,
+        })
+        .collect()
+}
+
+#[test]
+fn preview_reports_matches_with_context_and_ranges() {
+    let tmp = TempDir::new().expect("temp dir");
+    write_file(
+        tmp.path(),
+        "a.md",
+        "# Cat notes\n\nThe cat sat.\nConcatenate CAT\n",
+    );
+    write_file(tmp.path(), "b.md", "no match here\n");
+
+    let mut q = query("cat", "dog");
+    q.whole_word = true;
+    let preview = preview_replace_at(tmp.path(), &q).expect("preview should succeed");
+
+    assert_eq!(preview.total_matches, 3);
+    assert_eq!(preview.files.len(), 1);
+    let matches = &preview.files[0].matches;
+    assert_eq!(matches[1].line, 3);
+    assert_eq!(matches[1].context, "The cat sat.");
+    assert_eq!(
+        &matches[1].context[matches[1].context_start..matches[1].context_end],
+        "cat"
+    );
+    assert_eq!(matches[1].start, 17);
+    assert_eq!(matches[2].context, "Concatenate CAT");
+
+    q.case_sensitive = true;
+    let preview = preview_replace_at(tmp.path(), &q).expect("preview should succeed");
+    assert_eq!(preview.total_matches, 1);
+}
+
+#[test]
+fn preview_scopes_by_path_and_tag() {
+    let tmp = TempDir::new().expect("temp dir");
+    write_file(
+        tmp.path(),
+        "work/a.md",
+        "---\ntags: [project/alpha]\n---\nfoo\n",
+    );
+    write_file(tmp.path(), "work/b.md", "foo\n");
+    write_file(tmp.path(), "home/c.md", "foo #project\n");
+
+    let mut q = query("foo", "bar");
+    q.paths = vec!["work".to_string()];
+    let preview = preview_replace_at(tmp.path(), &q).expect("preview should succeed");
+    let paths: Vec<&str> = preview.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["work/a.md", "work/b.md"]);
+
+    q.paths.clear();
+    q.tags = vec!["project".to_string()];
+    let preview = preview_replace_at(tmp.path(), &q).expect("preview should succeed");
+    let paths: Vec<&str> = preview.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["home/c.md", "work/a.md"]);
+}
+
+#[test]
+fn apply_expands_captures_and_undo_restores_the_batch() {
+    let tmp = TempDir::new().expect("temp dir");
+    write_file(tmp.path(), "a.md", "due 2024-01-05 and 2024-02-10\n");
+    write_file(tmp.path(), "b.md", "due 2023-12-31\n");
+
+    let mut q = query(r"(\d{4})-(\d{2})-(\d{2})", "$3/$2/$1");
+    q.regex = true;
+    let preview = preview_replace_at(tmp.path(), &q).expect("preview should succeed");
+    assert_eq!(preview.files[0].matches[0].replacement, "05/01/2024");
+
+    let result = apply_replace_at(tmp.path(), &q, &guards_for(tmp.path(), &["a.md", "b.md"]))
+        .expect("apply should succeed");
+    assert_eq!(result.files.len(), 2);
+    assert_eq!(result.files[0].replacements, 2);
+    assert!(result.conflicts.is_empty());
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("a.md")).expect("a"),
+        "due 05/01/2024 and 10/02/2024\n"
+    );
+
+    let batch_id = result.batch_id.expect("batch id");
+    let undo = undo_at(tmp.path(), Some(&batch_id)).expect("undo should succeed");
+    assert_eq!(undo.kind, "replace");
+    assert_eq!(undo.written, vec!["b.md", "a.md"]);
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("b.md")).expect("b"),
+        "due 2023-12-31\n"
+    );
+    assert!(undo_at(tmp.path(), Some(&batch_id)).is_err());
+}
+
+#[test]
+fn apply_skips_files_changed_since_preview() {
+    let tmp = TempDir::new().expect("temp dir");
+    write_file(tmp.path(), "a.md", "old text\n");
+    write_file(tmp.path(), "b.md", "old text\n");
+    let mut guards = guards_for(tmp.path(), &["a.md", "b.md"]);
+    guards[1].expected_mtime_ms -= 1000;
+
+    let result =
+        apply_replace_at(tmp.path(), &query("old", "new"), &guards).expect("apply should succeed");
+
+    assert_eq!(result.files.len(), 1);
+    assert_eq!(result.conflicts.len(), 1);
+    assert_eq!(result.conflicts[0].path, "b.md");
+    assert_eq!(result.conflicts[0].reason, "conflict:mtime_mismatch");
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("b.md")).expect("b"),
+        "old text\n"
+    );
+}
+
+#[test]
+fn whole_word_matches_queries_that_start_or_end_with_punctuation() {
+    let tmp = TempDir::new().expect("temp dir");
+    write_file(
+        tmp.path(),
+        "a.md",
+        "c++ beats abc++ and c++x\nuse -foo, not a-foo or -food\n",
+    );
+
+    let mut q = query("c++", "rust");
+    q.whole_word = true;
+    let preview = preview_replace_at(tmp.path(), &q).expect("preview should succeed");
+    assert_eq!(preview.total_matches, 1);
+    assert_eq!(preview.files[0].matches[0].start, 0);
+
+    q.pattern = "-foo".to_string();
+    let preview = preview_replace_at(tmp.path(), &q).expect("preview should succeed");
+    assert_eq!(preview.total_matches, 1);
+    assert_eq!(
+        preview.files[0].matches[0].context,
+        "use -foo, not a-foo or -food"
+    );
+    assert_eq!(preview.files[0].matches[0].context_start, 4);
+}
+
+#[test]
+fn replace_skips_ignored_notes() {
+    let tmp = TempDir::new().expect("temp dir");
+    write_file(tmp.path(), ".otterlyignore", "drafts/\n");
+    write_file(tmp.path(), "a.md", "foo\n");
+    write_file(tmp.path(), "drafts/b.md", "foo\n");
+
+    let preview = preview_replace_at(tmp.path(), &query("foo", "bar")).expect("preview");
+    let paths: Vec<&str> = preview.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["a.md"]);
+}
+
+#[test]
+fn apply_returns_written_files_when_the_journal_cannot_be_saved() {
+    let tmp = TempDir::new().expect("temp dir");
+    write_file(tmp.path(), "a.md", "old text\n");
+    write_file(tmp.path(), ".otterly/journal/journal.json", "not json");
+
+    let guards = guards_for(tmp.path(), &["a.md"]);
+    let result =
+        apply_replace_at(tmp.path(), &query("old", "new"), &guards).expect("apply should return");
+
+    assert_eq!(result.files.len(), 1);
+    assert_eq!(result.files[0].path, "a.md");
+    assert!(result.batch_id.is_none());
+    assert!(result
+        .error
+        .expect("error")
+        .starts_with("failed to record replace in journal"));
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("a.md")).expect("a"),
+        "new text\n"
+    );
+}
@@ -6,6 +6,7 @@ use crate::features::search::db::{
     set_outlinks, suggest_planned, sync_index, upsert_note, wiki_link_targets,
 };
 use crate::features::search::model::{IndexNoteMeta, SearchScope};
+use crate::shared::vault_context::VaultContext;
 use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::fs;
@@ -214,9 +215,10 @@ fn sync_progress_advances_when_some_files_are_unreadable() {
 
     let cancel = AtomicBool::new(false);
     let progress_points: RefCell<Vec<(usize, usize)>> = RefCell::new(Vec::new());
+    let ctx = VaultContext::new("test", root);
     let result = sync_index(
         &conn,
-        root,
+        &ctx,
         &cancel,
         &|indexed, total| progress_points.borrow_mut().push((indexed, total)),
         &mut || {},
@@ -247,7 +249,8 @@ fn rebuild_resolves_batch_outlinks_before_yield() {
 
     let cancel = AtomicBool::new(false);
     let mut first_yield_checked = false;
-    rebuild_index(&conn, root, &cancel, &|_, _| {}, &mut || {
+    let ctx = VaultContext::new("test", root);
+    rebuild_index(&conn, &ctx, &cancel, &|_, _| {}, &mut || {
         if first_yield_checked {
             return;
         }
@@ -0,0 +1,157 @@
+use crate::features::notes::service::file_meta;
+use crate::features::search::db::{
+    open_search_db, query_tasks, remove_note, rename_note_path, upsert_note,
+};
+use crate::features::search::model::{IndexNoteMeta, TaskQuery};
+use crate::features::tasks::parser::{extract_tasks, toggle_task_line, TaskPriority};
+use crate::features::tasks::service::toggle_task_at;
+use rusqlite::Connection;
+use std::fs;
+use tempfile::TempDir;
+
+const NOTE: &str = "# Project\n\
+\n\
+- [ ] write spec 📅 2026-10-20 ⏫ #work\n\
+- [x] book room due:2026-10-01\n\
+\n\
+## Later\n\
+\n\
+1. [ ] refactor priority:low #work/backend #123\n\
+\n\
+```\n\
+- [ ] not a task\n\
+```\n\
+- plain item\n";
+
+fn index(conn: &Connection, path: &str, title: &str, body: &str) {
+    let meta = IndexNoteMeta {
+        id: path.to_string(),
+        path: path.to_string(),
+        title: title.to_string(),
+        name: path.to_string(),
+        mtime_ms: 100,
+        size_bytes: body.len() as i64,
+    };
+    upsert_note(conn, &meta, body).expect("upsert should succeed");
+}
+
+#[test]
+fn extract_tasks_reads_metadata_and_heading_context() {
+    let tasks = extract_tasks(NOTE);
+    assert_eq!(tasks.len(), 3);
+
+    assert_eq!(tasks[0].line, 3);
+    assert_eq!(tasks[0].text, "write spec 📅 2026-10-20 ⏫ #work");
+    assert!(!tasks[0].completed);
+    assert_eq!(tasks[0].heading.as_deref(), Some("Project"));
+    assert_eq!(tasks[0].due.as_deref(), Some("2026-10-20"));
+    assert_eq!(tasks[0].priority, Some(TaskPriority::High));
+    assert_eq!(tasks[0].tags, vec!["work"]);
+
+    assert!(tasks[1].completed);
+    assert_eq!(tasks[1].due.as_deref(), Some("2026-10-01"));
+
+    assert_eq!(tasks[2].line, 8);
+    assert_eq!(tasks[2].heading.as_deref(), Some("Later"));
+    assert_eq!(tasks[2].priority, Some(TaskPriority::Low));
+    assert_eq!(tasks[2].tags, vec!["work/backend"]);
+}
+
+#[test]
+fn toggle_task_line_flips_only_the_checkbox() {
+    let (updated, completed) = toggle_task_line(NOTE, 3, None).expect("toggle should succeed");
+    assert!(completed);
+    assert!(updated.contains("- [x] write spec"));
+    assert_eq!(updated.len(), NOTE.len());
+
+    let (updated, completed) =
+        toggle_task_line(&updated, 8, Some(true)).expect("toggle should succeed");
+    assert!(completed);
+    assert!(updated.contains("1. [x] refactor"));
+
+    assert!(toggle_task_line(NOTE, 1, None).is_err());
+    // Line 12 sits inside a fenced code block.
+    assert!(toggle_task_line(NOTE, 12, None).is_err());
+    assert!(toggle_task_line(NOTE, 99, None).is_err());
+}
+
+#[test]
+fn query_tasks_filters_and_orders_open_tasks() {
+    let tmp = TempDir::new().expect("temp dir should be created");
+    let conn = open_search_db(tmp.path()).expect("db should open");
+    index(&conn, "project.md", "Project", NOTE);
+    index(
+        &conn,
+        "inbox.md",
+        "Inbox",
+        "- [ ] call back 🔺\n- [ ] someday\n",
+    );
+
+    let open = query_tasks(&conn, &TaskQuery::default()).expect("query should succeed");
+    let texts: Vec<&str> = open.iter().map(|t| t.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        vec![
+            "write spec 📅 2026-10-20 ⏫ #work",
+            "call back 🔺",
+            "refactor priority:low #work/backend #123",
+            "someday",
+        ]
+    );
+    assert_eq!(open[0].note_title, "Project");
+
+    let tagged = query_tasks(
+        &conn,
+        &TaskQuery {
+            tag: Some("#work".to_string()),
+            ..TaskQuery::default()
+        },
+    )
+    .expect("query should succeed");
+    assert_eq!(tagged.len(), 2);
+
+    let due = query_tasks(
+        &conn,
+        &TaskQuery {
+            include_completed: true,
+            due_before: Some("2026-10-15".to_string()),
+            ..TaskQuery::default()
+        },
+    )
+    .expect("query should succeed");
+    assert_eq!(due.len(), 1);
+    assert!(due[0].completed);
+
+    rename_note_path(&conn, "inbox.md", "archive/inbox.md").expect("rename should succeed");
+    let urgent = query_tasks(
+        &conn,
+        &TaskQuery {
+            min_priority: Some(TaskPriority::Highest),
+            ..TaskQuery::default()
+        },
+    )
+    .expect("query should succeed");
+    assert_eq!(urgent.len(), 1);
+    assert_eq!(urgent[0].path, "archive/inbox.md");
+
+    remove_note(&conn, "project.md").expect("remove should succeed");
+    let remaining = query_tasks(&conn, &TaskQuery::default()).expect("query should succeed");
+    assert_eq!(remaining.len(), 2);
+}
+
+#[test]
+fn toggle_task_at_rejects_stale_mtime() {
+    let tmp = TempDir::new().expect("temp dir should be created");
+    let abs = tmp.path().join("todo.md");
+    fs::write(&abs, "- [ ] one\n").expect("file should be written");
+    let (mtime, _) = file_meta(&abs).expect("meta should load");
+
+    let err = toggle_task_at(tmp.path(), "todo.md", 1, mtime - 1, None)
+        .expect_err("stale mtime should be rejected");
+    assert_eq!(err, "conflict:mtime_mismatch");
+
+    let result =
+        toggle_task_at(tmp.path(), "todo.md", 1, mtime, None).expect("toggle should succeed");
+    assert!(result.completed);
+    assert_eq!(fs::read_to_string(&abs).expect("read"), "- [x] one\n");
+}
@@ -0,0 +1,160 @@
+use crate::features::templates::expand::{
+    expand_note_path, expand_template, format_date, merge_frontmatter, take_cursor, TemplateContext,
+};
+use crate::features::templates::service::{
+    create_from_template_at, list_templates_at, TemplateRequest,
+};
+use crate::tests::support::write_file;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::fs;
+use tempfile::TempDir;
+
+fn fixed_now() -> chrono::NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 3, 7)
+        .and_then(|d| d.and_hms_opt(9, 5, 30))
+        .expect("valid date")
+}
+
+#[test]
+fn format_date_supports_moment_tokens_and_literals() {
+    let now = fixed_now();
+    assert_eq!(format_date(&now, "YYYY-MM-DD"), "2024-03-07");
+    assert_eq!(format_date(&now, "dddd, MMMM D"), "Thursday, March 7");
+    assert_eq!(format_date(&now, "GGGG-[W]WW"), "2024-W10");
+    assert_eq!(format_date(&now, "h:mm A"), "9:05 AM");
+    assert_eq!(format_date(&now, "[Q]Q YY"), "Q1 24");
+}
+
+#[test]
+fn expand_template_substitutes_known_variables() {
+    let mut variables = HashMap::new();
+    variables.insert("project".to_string(), "Otter".to_string());
+    let ctx = TemplateContext {
+        title: "Standup",
+        now: fixed_now(),
+        variables: &variables,
+    };
+
+    let out = expand_template(
+        "# {{title}}\n{{date}} {{time}} {{date:DD/MM}} {{ project }} {{unknown}} {{cursor}}",
+        &ctx,
+    );
+    assert_eq!(
+        out,
+        "# Standup\n2024-03-07 09:05 07/03 Otter {{unknown}} {{cursor}}"
+    );
+
+    let (text, offset) = take_cursor("ab{{cursor}}cd{{cursor}}");
+    assert_eq!(text, "abcd");
+    assert_eq!(offset, Some(2));
+
+    let (text, offset) = take_cursor("🦦 é{{cursor}}");
+    assert_eq!(text, "🦦 é");
+    assert_eq!(offset, Some(4));
+}
+
+#[test]
+fn expand_note_path_sanitizes_values_and_appends_extension() {
+    let variables = HashMap::new();
+    let ctx = TemplateContext {
+        title: "Q1: plan/review",
+        now: fixed_now(),
+        variables: &variables,
+    };
+    assert_eq!(
+        expand_note_path("meetings/{{date}}-{{title}}", &ctx),
+        "meetings/2024-03-07-Q1- plan-review.md"
+    );
+}
+
+#[test]
+fn merge_frontmatter_replaces_and_appends_keys() {
+    let mut overrides = serde_json::Map::new();
+    overrides.insert("status".to_string(), serde_json::json!("done"));
+    overrides.insert("tags".to_string(), serde_json::json!(["a", "b, c"]));
+
+    let merged = merge_frontmatter(
+        "---\nstatus: draft\naliases:\n  - x\n---\nbody\n",
+        &overrides,
+    );
+    assert_eq!(
+        merged,
+        "---\nstatus: done\naliases:\n  - x\ntags: [a, \"b, c\"]\n---\nbody\n"
+    );
+
+    let created = merge_frontmatter("body\n", &overrides);
+    assert!(created.starts_with("---\nstatus: done\n"));
+    assert!(created.ends_with("---\nbody\n"));
+}
+
+#[test]
+fn create_from_template_writes_expanded_note() {
+    let tmp = TempDir::new().expect("temp dir should be created");
+    write_file(
+        tmp.path(),
+        "templates/meeting.md",
+        "---\ntype: meeting\n---\n# {{title}}\n\nDate: {{date}}\n\n{{cursor}}\n",
+    );
+    write_file(tmp.path(), "templates/nested/daily.md", "# Daily\n");
+
+    let templates = list_templates_at(tmp.path()).expect("templates should list");
+    let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["meeting", "nested/daily"]);
+
+    let variables = HashMap::new();
+    let mut frontmatter = serde_json::Map::new();
+    frontmatter.insert("attendees".to_string(), serde_json::json!(2));
+    let created = create_from_template_at(
+        tmp.path(),
+        &TemplateRequest {
+            template_path: Some("templates/meeting.md"),
+            note_path: "meetings/{{date}}-{{title}}.md",
+            title: Some("Sync"),
+            variables: &variables,
+            frontmatter: &frontmatter,
+            now: fixed_now(),
+        },
+    )
+    .expect("note should be created");
+
+    assert_eq!(created.note.path, "meetings/2024-03-07-Sync.md");
+    let written = fs::read_to_string(tmp.path().join("meetings/2024-03-07-Sync.md"))
+        .expect("note should be readable");
+    let expected = "---\ntype: meeting\nattendees: 2\n---\n# Sync\n\nDate: 2024-03-07\n\n\n";
+    assert_eq!(written, expected);
+    assert_eq!(
+        created.cursor_offset,
+        Some(expected.encode_utf16().count() - 1)
+    );
+
+    let again = create_from_template_at(
+        tmp.path(),
+        &TemplateRequest {
+            template_path: Some("templates/meeting.md"),
+            note_path: "meetings/{{date}}-{{title}}.md",
+            title: Some("Sync"),
+            variables: &variables,
+            frontmatter: &frontmatter,
+            now: fixed_now(),
+        },
+    );
+    assert!(again.is_err());
+}
+
+#[test]
+fn templates_folder_follows_vault_setting() {
+    let tmp = TempDir::new().expect("temp dir should be created");
+    write_file(
+        tmp.path(),
+        ".otterly/settings.json",
+        r#"{"templates_folder": "_meta/tpl"}"#,
+    );
+    write_file(tmp.path(), "_meta/tpl/a.md", "# A\n");
+    write_file(tmp.path(), "templates/ignored.md", "# B\n");
+
+    let templates = list_templates_at(tmp.path()).expect("templates should list");
+    assert_eq!(templates.len(), 1);
+    assert_eq!(templates[0].path, "_meta/tpl/a.md");
+    assert_eq!(templates[0].title, "A");
+}
@@ -0,0 +1,130 @@
+use crate::features::notes::service::{
+    list_notes_in, read_note_in, read_note_meta_in, write_note_in,
+};
+use crate::features::search::db::rebuild_index;
+use crate::features::search::model::SearchScope;
+use crate::features::search::service::{note_links_snapshot_in, search_in};
+use crate::shared::error::{AppError, ConflictReason};
+use crate::shared::storage::{Vault, VaultEntry, VaultStore};
+use crate::shared::vault_context::{VaultContext, VaultRegistry};
+use crate::tests::support::write_file;
+use std::fs;
+use std::sync::atomic::AtomicBool;
+use tempfile::TempDir;
+
+fn entry(id: &str, path: &str) -> VaultEntry {
+    VaultEntry {
+        vault: Vault {
+            id: id.to_string(),
+            path: path.to_string(),
+            name: id.to_string(),
+            created_at: 1,
+            last_opened_at: None,
+            note_count: None,
+            is_available: true,
+            pinned: false,
+        },
+        last_opened_at: 1,
+    }
+}
+
+#[test]
+fn registry_round_trips_and_sees_external_edits() {
+    let config = TempDir::new().expect("temp dir");
+    let registry = VaultRegistry::at(config.path().join("otterly"));
+    assert!(registry.load().expect("empty load").vaults.is_empty());
+
+    let store = VaultStore {
+        vaults: vec![entry("v1", "/tmp/one")],
+        last_vault_id: Some("v1".to_string()),
+    };
+    registry.save(&store).expect("save should succeed");
+    let loaded = registry.load().expect("load should succeed");
+    assert_eq!(loaded.last_vault_id.as_deref(), Some("v1"));
+    assert_eq!(
+        registry.vault_path("v1").expect("path"),
+        std::path::PathBuf::from("/tmp/one")
+    );
+
+    let edited = VaultStore {
+        vaults: vec![entry("v1", "/tmp/one"), entry("v2", "/tmp/second")],
+        last_vault_id: None,
+    };
+    fs::write(
+        registry.store_path().expect("store path"),
+        serde_json::to_vec(&edited).expect("json"),
+    )
+    .expect("external write");
+    assert_eq!(registry.load().expect("reload").vaults.len(), 2);
+    assert_eq!(
+        registry.vault_path("missing").expect_err("unknown id"),
+        AppError::not_found("vault", None)
+    );
+}
+
+#[test]
+fn context_runs_note_operations_without_the_app() {
+    let config = TempDir::new().expect("temp dir");
+    let vault = TempDir::new().expect("temp dir");
+    fs::write(vault.path().join("a.md"), "# Alpha\n").expect("write");
+    fs::create_dir_all(vault.path().join("sub")).expect("dir");
+    fs::write(vault.path().join("sub/b.md"), "# Beta\n").expect("write");
+
+    let registry = VaultRegistry::at(config.path());
+    let path = vault.path().to_string_lossy().to_string();
+    registry
+        .save(&VaultStore {
+            vaults: vec![entry("v1", &path)],
+            last_vault_id: None,
+        })
+        .expect("save");
+    let ctx = registry.context("v1").expect("context");
+    assert_eq!(ctx.vault_id(), "v1");
+
+    let paths: Vec<String> = list_notes_in(&ctx)
+        .expect("list")
+        .into_iter()
+        .map(|note| note.path)
+        .collect();
+    assert_eq!(paths, vec!["a.md", "sub/b.md"]);
+
+    let doc = read_note_in(&ctx, "a.md").expect("read");
+    assert_eq!(doc.meta.title, "Alpha");
+    let mtime = write_note_in(&ctx, "a.md", "# Alpha 2\n", Some(doc.meta.mtime_ms))
+        .expect("write should succeed");
+    assert_eq!(
+        write_note_in(&ctx, "a.md", "stale\n", Some(mtime - 1000)).expect_err("stale write"),
+        AppError::Conflict {
+            path: "a.md".to_string(),
+            reason: ConflictReason::MtimeMismatch,
+            current_mtime_ms: Some(mtime),
+        }
+    );
+    assert_eq!(
+        read_note_meta_in(&ctx, "a.md").expect("meta").title,
+        "Alpha 2"
+    );
+    assert!(ctx.abs("../outside.md").is_err());
+
+    let adhoc = VaultContext::for_root(vault.path()).expect("context for root");
+    assert_eq!(adhoc.root(), vault.path().canonicalize().expect("canon"));
+}
+
+#[test]
+fn search_reads_run_against_a_context_without_the_app() {
+    let tmp = TempDir::new().expect("temp dir");
+    write_file(tmp.path(), "a.md", "# Alpha\n\nSee [[b]] about otters.\n");
+    write_file(tmp.path(), "b.md", "# Beta\n");
+    let ctx = VaultContext::for_root(tmp.path()).expect("context");
+    let conn = ctx.open_search_db().expect("db should open");
+    let cancel = AtomicBool::new(false);
+    rebuild_index(&conn, &ctx, &cancel, &|_, _| {}, &mut || {}).expect("index");
+
+    let hits = search_in(&ctx, "otters", SearchScope::All, 10).expect("search");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].note.path, "a.md");
+
+    let snapshot = note_links_snapshot_in(&ctx, "b.md").expect("snapshot");
+    let backlinks: Vec<&str> = snapshot.backlinks.iter().map(|n| n.path.as_str()).collect();
+    assert_eq!(backlinks, vec!["a.md"]);
+}
@@ -0,0 +1,100 @@
+use crate::features::notes::service::{list_notes_in, scan_folder_entries};
+use crate::features::search::db::list_markdown_files;
+use crate::shared::vault_context::VaultContext;
+use crate::shared::vault_ignore::VaultIgnore;
+use crate::tests::support::write_file;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn seed_vault() -> TempDir {
+    let dir = TempDir::new().expect("temp dir");
+    let root = dir.path();
+    for rel in [
+        "keep.md",
+        "node_modules/pkg/readme.md",
+        "build/out.md",
+        "private/diary.md",
+        "private/shared.md",
+        "drafts/wip.md",
+        "notes/scratch.tmp.md",
+        ".git/notes.md",
+    ] {
+        write_file(root, rel, "# note");
+    }
+    write_file(
+        root,
+        ".otterlyignore",
+        "# generated\nnode_modules/\n/build\nprivate/*\n!private/shared.md\n*.tmp.md\n",
+    );
+    write_file(
+        root,
+        ".otterly/settings.json",
+        r#"{"ignore_patterns":["drafts/"]}"#,
+    );
+    dir
+}
+
+#[test]
+fn ignore_rules_combine_file_settings_and_builtins() {
+    let dir = seed_vault();
+    let ignore = VaultIgnore::load(dir.path());
+
+    assert!(ignore.is_ignored_rel("node_modules/pkg/readme.md", false));
+    assert!(ignore.is_ignored_rel("build", true));
+    assert!(ignore.is_ignored_rel("private/diary.md", false));
+    assert!(!ignore.is_ignored_rel("private/shared.md", false));
+    assert!(ignore.is_ignored_rel("drafts/wip.md", false));
+    assert!(ignore.is_ignored_rel("notes/scratch.tmp.md", false));
+    assert!(ignore.is_ignored_rel(".otterly/settings.json", false));
+    assert!(ignore.is_ignored_rel("sub/.git", true));
+    assert!(!ignore.is_ignored_rel("notes/build/out.md", false));
+    assert!(!ignore.is_ignored_rel("keep.md", false));
+    assert!(ignore.is_ignored(&dir.path().join("build/out.md"), false));
+
+    assert!(VaultIgnore::is_rules_source(".otterlyignore"));
+    assert!(VaultIgnore::is_rules_source(".otterly/settings.json"));
+    assert!(!VaultIgnore::is_rules_source("notes/.otterlyignore"));
+}
+
+#[test]
+fn listing_and_indexing_skip_ignored_paths() {
+    let dir = seed_vault();
+    let root = dir.path();
+    let ctx = VaultContext::for_root(root).expect("context");
+
+    let notes: Vec<String> = list_notes_in(&ctx)
+        .expect("list notes")
+        .into_iter()
+        .map(|note| note.path)
+        .collect();
+    assert_eq!(notes, vec!["keep.md", "private/shared.md"]);
+
+    let indexed: Vec<_> = list_markdown_files(root)
+        .into_iter()
+        .map(|abs| abs.strip_prefix(root).unwrap().to_path_buf())
+        .collect();
+    assert_eq!(
+        indexed,
+        vec![Path::new("keep.md"), Path::new("private/shared.md")]
+    );
+
+    let top: Vec<String> = scan_folder_entries(root, &VaultIgnore::load(root))
+        .expect("scan")
+        .iter()
+        .map(|entry| entry.name.clone())
+        .collect();
+    assert_eq!(top, vec!["notes", "private", "keep.md"]);
+}
+
+#[test]
+fn broken_settings_fall_back_to_builtin_rules() {
+    let dir = TempDir::new().expect("temp dir");
+    write_file(
+        dir.path(),
+        ".otterly/settings.json",
+        r#"{"ignore_patterns":"drafts/"}"#,
+    );
+    let ignore = VaultIgnore::load(dir.path());
+    assert!(!ignore.is_ignored_rel("drafts/wip.md", false));
+    assert!(ignore.is_ignored_rel(".git/config", false));
+}
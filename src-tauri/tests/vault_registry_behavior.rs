@@ -0,0 +1,120 @@
+use crate::features::search::db::{open_search_db, sync_index};
+use crate::features::vault::service::{
+    check_vault_health, pin_vault_in, relink_vault_in, rename_vault_in, sort_vaults, VaultIssue,
+};
+use crate::shared::storage::{Vault, VaultEntry, VaultStore};
+use crate::shared::vault_context::VaultContext;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use tempfile::TempDir;
+
+fn entry(id: &str, path: &str, last_opened_at: i64) -> VaultEntry {
+    VaultEntry {
+        vault: Vault {
+            id: id.to_string(),
+            path: path.to_string(),
+            name: id.to_string(),
+            created_at: 1,
+            last_opened_at: None,
+            note_count: None,
+            is_available: true,
+            pinned: false,
+        },
+        last_opened_at,
+    }
+}
+
+fn index(root: &Path) {
+    let conn = open_search_db(root).expect("db should open");
+    let ctx = VaultContext::new("test", root);
+    sync_index(&conn, &ctx, &AtomicBool::new(false), &|_, _| {}, &mut || {})
+        .expect("sync should succeed");
+}
+
+#[test]
+fn relink_rename_and_pin_keep_the_vault_id() {
+    let moved = TempDir::new().expect("temp dir");
+    let moved_path = moved.path().to_string_lossy().to_string();
+    let mut store = VaultStore {
+        vaults: vec![
+            entry("work", "/gone/work", 30),
+            entry("home", "/gone/home", 20),
+            entry("old", &moved_path, 10),
+        ],
+        last_vault_id: Some("work".to_string()),
+    };
+
+    assert_eq!(
+        relink_vault_in(&mut store, "work", &moved_path)
+            .expect_err("path already registered")
+            .code(),
+        "already_exists"
+    );
+    store.vaults.retain(|e| e.vault.id != "old");
+    let relinked = relink_vault_in(&mut store, "work", &moved_path).expect("relink");
+    assert_eq!(relinked.id, "work");
+    assert_eq!(relinked.path, moved_path);
+    assert!(relinked.is_available);
+    assert_eq!(
+        relink_vault_in(&mut store, "missing", "/x")
+            .expect_err("unknown vault")
+            .code(),
+        "not_found"
+    );
+
+    assert_eq!(
+        rename_vault_in(&mut store, "home", "  Personal ")
+            .expect("rename")
+            .name,
+        "Personal"
+    );
+    assert_eq!(
+        rename_vault_in(&mut store, "home", " ")
+            .expect_err("empty name")
+            .code(),
+        "invalid_input"
+    );
+
+    assert!(pin_vault_in(&mut store, "home", true).expect("pin").pinned);
+    sort_vaults(&mut store);
+    let order: Vec<&str> = store.vaults.iter().map(|e| e.vault.id.as_str()).collect();
+    assert_eq!(order, vec!["home", "work"]);
+    assert_eq!(store.last_vault_id.as_deref(), Some("work"));
+}
+
+#[test]
+fn health_check_reports_missing_broken_and_stale_vaults() {
+    let missing = check_vault_health(&entry("gone", "/gone/vault", 1).vault);
+    assert_eq!(missing.issues, vec![VaultIssue::MissingFolder]);
+
+    let dir = TempDir::new().expect("temp dir");
+    let root = dir.path();
+    let vault = entry("v", &root.to_string_lossy(), 1).vault;
+    fs::write(root.join("a.md"), "# A").expect("note written");
+    assert_eq!(
+        check_vault_health(&vault).issues,
+        vec![VaultIssue::SearchIndexMissing]
+    );
+
+    index(root);
+    assert!(check_vault_health(&vault).issues.is_empty());
+
+    fs::write(root.join("b.md"), "# B").expect("note written");
+    fs::remove_file(root.join("a.md")).expect("note removed");
+    fs::write(root.join(".otterly/settings.json"), "{ broken").expect("settings written");
+    let issues = check_vault_health(&vault).issues;
+    assert_eq!(issues.len(), 2);
+    assert!(matches!(
+        &issues[0],
+        VaultIssue::UnreadableAppData { path, .. } if path == ".otterly/settings.json"
+    ));
+    assert_eq!(
+        issues[1],
+        VaultIssue::StaleSearchIndex {
+            added: 1,
+            modified: 0,
+            removed: 1
+        }
+    );
+}
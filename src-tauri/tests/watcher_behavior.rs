@@ -0,0 +1,135 @@
+use crate::features::watcher::service::{
+    SubscriptionChange, VaultFsEvent, WatcherState, WatcherSubscriptions, WindowSink,
+};
+use crate::tests::support::write_file;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn change(start: Option<&str>, stop: Option<&str>) -> SubscriptionChange {
+    SubscriptionChange {
+        start: start.map(str::to_string),
+        stop: stop.map(str::to_string),
+    }
+}
+
+#[test]
+fn watchers_are_shared_per_vault_and_routed_per_window() {
+    let mut subs = WatcherSubscriptions::default();
+
+    assert_eq!(subs.watch("main", "work"), change(Some("work"), None));
+    assert_eq!(subs.watch("second", "home"), change(Some("home"), None));
+    assert_eq!(subs.watch("third", "work"), change(None, None));
+    assert_eq!(subs.watched_vaults(), vec!["home", "work"]);
+    assert_eq!(subs.windows_for("work"), vec!["main", "third"]);
+    assert_eq!(subs.windows_for("home"), vec!["second"]);
+    assert!(subs.windows_for("other").is_empty());
+
+    // Re-watching the same vault keeps its watcher running.
+    assert_eq!(subs.watch("second", "home"), change(None, None));
+    assert_eq!(subs.windows_for("home"), vec!["second"]);
+}
+
+#[test]
+fn switching_or_closing_windows_stops_unused_watchers() {
+    let mut subs = WatcherSubscriptions::default();
+    subs.watch("main", "work");
+    subs.watch("second", "work");
+
+    assert_eq!(subs.watch("second", "home"), change(Some("home"), None));
+    assert_eq!(subs.windows_for("work"), vec!["main"]);
+
+    assert_eq!(subs.watch("main", "home"), change(None, Some("work")));
+    assert_eq!(subs.watched_vaults(), vec!["home"]);
+
+    assert_eq!(subs.release("main"), change(None, None));
+    assert_eq!(subs.release("second"), change(None, Some("home")));
+    assert_eq!(subs.release("second"), change(None, None));
+    assert!(subs.watched_vaults().is_empty());
+}
+
+#[derive(Clone, Default)]
+struct RecordingSink {
+    sent: Arc<Mutex<Vec<(String, VaultFsEvent)>>>,
+}
+
+impl WindowSink for RecordingSink {
+    fn send(&self, window: &str, event: &VaultFsEvent) {
+        self.sent
+            .lock()
+            .unwrap()
+            .push((window.to_string(), event.clone()));
+    }
+}
+
+impl RecordingSink {
+    fn added_notes(&self, window: &str) -> Vec<(String, String)> {
+        let mut notes: Vec<(String, String)> = self
+            .sent
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(label, _)| label == window)
+            .filter_map(|(_, event)| match event {
+                VaultFsEvent::NoteAdded {
+                    vault_id,
+                    note_path,
+                } => Some((vault_id.clone(), note_path.clone())),
+                _ => None,
+            })
+            .collect();
+        notes.sort();
+        notes.dedup();
+        notes
+    }
+
+    fn wait_for(&self, window: &str, vault_id: &str, note_path: &str) {
+        let expected = (vault_id.to_string(), note_path.to_string());
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !self.added_notes(window).contains(&expected) {
+            assert!(
+                Instant::now() < deadline,
+                "{} never saw {}",
+                window,
+                note_path
+            );
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+}
+
+#[test]
+fn windows_on_different_vaults_only_receive_their_own_events() {
+    let work = TempDir::new().expect("temp dir should be created");
+    let home = TempDir::new().expect("temp dir should be created");
+    let state = WatcherState::default();
+    let sink = RecordingSink::default();
+
+    state
+        .watch(&sink, "main", "work", work.path())
+        .expect("main should watch work");
+    state
+        .watch(&sink, "vault-1", "home", home.path())
+        .expect("vault-1 should watch home");
+    // Give the watcher threads time to register with the OS.
+    std::thread::sleep(Duration::from_millis(300));
+
+    write_file(work.path(), "standup.md", "# Standup\n");
+    write_file(home.path(), "groceries.md", "# Groceries\n");
+    sink.wait_for("main", "work", "standup.md");
+    sink.wait_for("vault-1", "home", "groceries.md");
+    // Let any late events for the other vault arrive before checking.
+    std::thread::sleep(Duration::from_millis(300));
+
+    assert_eq!(
+        sink.added_notes("main"),
+        vec![("work".to_string(), "standup.md".to_string())]
+    );
+    assert_eq!(
+        sink.added_notes("vault-1"),
+        vec![("home".to_string(), "groceries.md".to_string())]
+    );
+
+    state.release("vault-1").expect("release should succeed");
+    state.release("main").expect("release should succeed");
+}
@@ -43,6 +43,7 @@ export const ACTION_IDS = {
   vault_select_pinned_slot: "vault.select_pinned_slot",
   vault_remove_from_registry: "vault.remove_from_registry",
   vault_toggle_pin: "vault.toggle_pin",
+  vault_open_in_new_window: "vault.open_in_new_window",
   vault_sync_index: "vault.sync_index",
   vault_reindex: "vault.reindex",
 
@@ -76,6 +77,10 @@ export const ACTION_IDS = {
 
   shell_open_url: "shell.open_url",
 
+  deep_link_handle: "deep_link.handle",
+  deep_link_confirm_new: "deep_link.confirm_new",
+  deep_link_cancel_new: "deep_link.cancel_new",
+
   ui_toggle_sidebar: "ui.toggle_sidebar",
   ui_select_folder: "ui.select_folder",
   ui_set_sidebar_view: "ui.set_sidebar_view",
@@ -17,6 +17,7 @@ import { create_clipboard_tauri_adapter } from "$lib/features/clipboard";
 import { create_shell_tauri_adapter } from "$lib/features/shell";
 import { create_git_tauri_adapter } from "$lib/features/git";
 import { create_watcher_tauri_adapter } from "$lib/features/watcher";
+import { create_deep_link_tauri_adapter } from "$lib/features/deep_link";
 import type { Ports } from "$lib/app/di/app_ports";
 
 export function create_prod_ports(): Ports {
@@ -32,6 +33,7 @@ export function create_prod_ports(): Ports {
   const shell = create_shell_tauri_adapter();
   const git = create_git_tauri_adapter();
   const watcher = create_watcher_tauri_adapter();
+  const deep_link = create_deep_link_tauri_adapter();
 
   return {
     vault,
@@ -50,5 +52,6 @@ export function create_prod_ports(): Ports {
     shell,
     git,
     watcher,
+    deep_link,
   };
 }
@@ -19,6 +19,7 @@ import { HotkeyService } from "$lib/features/hotkey";
 import { ThemeService } from "$lib/features/theme";
 import { LinkRepairService, LinksService } from "$lib/features/links";
 import { WatcherService } from "$lib/features/watcher";
+import { DeepLinkService } from "$lib/features/deep_link";
 import { mount_reactors } from "$lib/reactors";
 
 export type AppContext = ReturnType<typeof create_app_context>;
@@ -73,7 +74,6 @@ export function create_app_context(input: {
   const link_repair_service = new LinkRepairService(
     input.ports.notes,
     input.ports.search,
-    input.ports.index,
     stores.editor,
     stores.tab,
     now_ms,
@@ -159,6 +159,8 @@ export function create_app_context(input: {
     now_ms,
   );
 
+  const deep_link_service = new DeepLinkService(input.ports.deep_link);
+
   const vault_service = new VaultService(
     input.ports.vault,
     input.ports.notes,
@@ -199,6 +201,7 @@ export function create_app_context(input: {
       git: git_service,
       hotkey: hotkey_service,
       theme: theme_service,
+      deep_link: deep_link_service,
     },
     default_mount_config: input.default_mount_config,
   });
@@ -232,6 +235,7 @@ export function create_app_context(input: {
       cleanup_reactors();
       editor_service.unmount();
       void watcher_service.stop();
+      deep_link_service.stop();
     },
   };
 }
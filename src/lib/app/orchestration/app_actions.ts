@@ -120,6 +120,13 @@ async function mount_ready_vault_state(
 }
 
 async function execute_app_mounted(input: ActionRegistrationInput) {
+  await mount_app_state(input);
+  void input.services.deep_link.start((event) => {
+    void input.registry.execute(ACTION_IDS.deep_link_handle, event);
+  });
+}
+
+async function mount_app_state(input: ActionRegistrationInput) {
   set_startup_loading(input);
 
   const bootstrap_data = await load_bootstrap_data(input);
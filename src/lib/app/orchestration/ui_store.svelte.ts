@@ -106,6 +106,14 @@ const INITIAL_CROSS_VAULT_OPEN_CONFIRM = {
   note_path: null,
 } as const;
 
+const INITIAL_DEEP_LINK_NEW_CONFIRM = {
+  open: false,
+  vault_id: null,
+  vault_name: "",
+  note_path: null,
+  content: "",
+} as const;
+
 const INITIAL_HELP_DIALOG = {
   open: false,
 } as const;
@@ -301,6 +309,14 @@ export class UIStore {
     note_path: NotePath | null;
   }>({ ...INITIAL_CROSS_VAULT_OPEN_CONFIRM });
 
+  deep_link_new_confirm = $state<{
+    open: boolean;
+    vault_id: VaultId | null;
+    vault_name: string;
+    note_path: NotePath | null;
+    content: string;
+  }>({ ...INITIAL_DEEP_LINK_NEW_CONFIRM });
+
   help_dialog = $state<{ open: boolean }>({ ...INITIAL_HELP_DIALOG });
 
   vault_dashboard = $state<{
@@ -471,6 +487,7 @@ export class UIStore {
       ...INITIAL_FILETREE_MOVE_CONFLICT_DIALOG,
     };
     this.cross_vault_open_confirm = { ...INITIAL_CROSS_VAULT_OPEN_CONFIRM };
+    this.deep_link_new_confirm = { ...INITIAL_DEEP_LINK_NEW_CONFIRM };
     this.help_dialog = { ...INITIAL_HELP_DIALOG };
     this.vault_dashboard = { ...INITIAL_VAULT_DASHBOARD };
     this.tab_close_confirm = { ...INITIAL_TAB_CLOSE_CONFIRM };
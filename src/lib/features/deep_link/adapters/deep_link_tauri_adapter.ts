@@ -0,0 +1,41 @@
+import type { DeepLinkPort } from "$lib/features/deep_link/ports";
+import type { DeepLinkEvent } from "$lib/features/deep_link/types/deep_link";
+import { tauri_invoke } from "$lib/shared/adapters/tauri_invoke";
+import { listen } from "@tauri-apps/api/event";
+
+function subscribe_deep_links(
+  callback: (event: DeepLinkEvent) => void,
+): () => void {
+  let unlisten_fn: (() => void) | null = null;
+  let is_disposed = false;
+
+  void listen<DeepLinkEvent>("deep_link", (event) => {
+    if (is_disposed) {
+      return;
+    }
+    callback(event.payload);
+  }).then((fn_ref) => {
+    if (is_disposed) {
+      fn_ref();
+      return;
+    }
+    unlisten_fn = fn_ref;
+  });
+
+  return () => {
+    is_disposed = true;
+    unlisten_fn?.();
+    unlisten_fn = null;
+  };
+}
+
+export function create_deep_link_tauri_adapter(): DeepLinkPort {
+  return {
+    async take_pending(): Promise<DeepLinkEvent[]> {
+      return await tauri_invoke<DeepLinkEvent[]>("deep_link_take_pending");
+    },
+    subscribe(callback: (event: DeepLinkEvent) => void): () => void {
+      return subscribe_deep_links(callback);
+    },
+  };
+}
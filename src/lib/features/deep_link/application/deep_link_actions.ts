@@ -0,0 +1,126 @@
+import { toast } from "svelte-sonner";
+import { ACTION_IDS } from "$lib/app/action_registry/action_ids";
+import type { ActionRegistrationInput } from "$lib/app/action_registry/action_registration_input";
+import type { DeepLinkEvent } from "$lib/features/deep_link/types/deep_link";
+import {
+  as_markdown_text,
+  as_note_path,
+  as_vault_id,
+  type VaultId,
+} from "$lib/shared/types/ids";
+
+function clear_new_note_confirm(input: ActionRegistrationInput) {
+  input.stores.ui.deep_link_new_confirm = {
+    open: false,
+    vault_id: null,
+    vault_name: "",
+    note_path: null,
+    content: "",
+  };
+}
+
+async function ensure_vault(
+  input: ActionRegistrationInput,
+  vault_id: VaultId,
+): Promise<boolean> {
+  if (input.stores.vault.vault?.id !== vault_id) {
+    await input.registry.execute(ACTION_IDS.vault_select, vault_id);
+  }
+  return input.stores.vault.vault?.id === vault_id;
+}
+
+async function handle_deep_link(
+  input: ActionRegistrationInput,
+  event: DeepLinkEvent,
+) {
+  const { registry, stores, services } = input;
+
+  switch (event.type) {
+    case "open_note":
+      if (!(await ensure_vault(input, as_vault_id(event.vault_id)))) {
+        return;
+      }
+      await registry.execute(ACTION_IDS.note_open, {
+        note_path: as_note_path(event.note_path),
+        cleanup_if_missing: true,
+      });
+      if (
+        event.heading &&
+        stores.editor.open_note?.meta.path === event.note_path
+      ) {
+        services.editor.scroll_to_heading(event.heading);
+      }
+      break;
+    case "confirm_new":
+      stores.ui.deep_link_new_confirm = {
+        open: true,
+        vault_id: as_vault_id(event.vault_id),
+        vault_name: event.vault_name,
+        note_path: as_note_path(event.note_path),
+        content: event.content,
+      };
+      break;
+    case "search":
+      if (event.vault_id === null) {
+        await registry.execute(ACTION_IDS.omnibar_open_all_vaults);
+      } else {
+        if (!(await ensure_vault(input, as_vault_id(event.vault_id)))) {
+          return;
+        }
+        await registry.execute(ACTION_IDS.omnibar_open);
+      }
+      await registry.execute(ACTION_IDS.omnibar_set_query, event.query);
+      break;
+    case "failed":
+      toast.error(`Could not open link: ${event.error.message}`);
+      break;
+  }
+}
+
+export function register_deep_link_actions(input: ActionRegistrationInput) {
+  const { registry, stores, services } = input;
+
+  registry.register({
+    id: ACTION_IDS.deep_link_handle,
+    label: "Handle Deep Link",
+    execute: async (event: unknown) => {
+      await handle_deep_link(input, event as DeepLinkEvent);
+    },
+  });
+
+  registry.register({
+    id: ACTION_IDS.deep_link_confirm_new,
+    label: "Confirm Deep Link Note",
+    execute: async () => {
+      const { vault_id, note_path, content } = stores.ui.deep_link_new_confirm;
+      clear_new_note_confirm(input);
+      if (!vault_id || !note_path) {
+        return;
+      }
+      if (!(await ensure_vault(input, vault_id))) {
+        return;
+      }
+
+      const meta = await services.note.create_note_with_markdown(
+        note_path,
+        as_markdown_text(content),
+      );
+      if (!meta) {
+        toast.error(`Could not create ${note_path}`);
+        return;
+      }
+      await registry.execute(ACTION_IDS.note_open, {
+        note_path: meta.path,
+        cleanup_if_missing: true,
+      });
+    },
+  });
+
+  registry.register({
+    id: ACTION_IDS.deep_link_cancel_new,
+    label: "Cancel Deep Link Note",
+    execute: () => {
+      clear_new_note_confirm(input);
+    },
+  });
+}
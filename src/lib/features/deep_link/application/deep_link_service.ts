@@ -0,0 +1,30 @@
+import type { DeepLinkPort } from "$lib/features/deep_link/ports";
+import type { DeepLinkEvent } from "$lib/features/deep_link/types/deep_link";
+import { create_logger } from "$lib/shared/utils/logger";
+
+const log = create_logger("deep_link_service");
+
+export class DeepLinkService {
+  private unsubscribe: (() => void) | null = null;
+
+  constructor(private readonly port: DeepLinkPort) {}
+
+  /** Listens for links, then replays those queued before the app was ready. */
+  async start(handler: (event: DeepLinkEvent) => void): Promise<void> {
+    if (this.unsubscribe) return;
+    this.unsubscribe = this.port.subscribe(handler);
+    try {
+      const pending = await this.port.take_pending();
+      for (const event of pending) {
+        handler(event);
+      }
+    } catch (error) {
+      log.from_error("Failed to load pending deep links", error);
+    }
+  }
+
+  stop(): void {
+    this.unsubscribe?.();
+    this.unsubscribe = null;
+  }
+}
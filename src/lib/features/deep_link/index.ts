@@ -0,0 +1,6 @@
+export type { DeepLinkPort } from "$lib/features/deep_link/ports";
+export { create_deep_link_tauri_adapter } from "$lib/features/deep_link/adapters/deep_link_tauri_adapter";
+export { DeepLinkService } from "$lib/features/deep_link/application/deep_link_service";
+export { register_deep_link_actions } from "$lib/features/deep_link/application/deep_link_actions";
+export type { DeepLinkEvent } from "$lib/features/deep_link/types/deep_link";
+export { default as ConfirmDeepLinkNoteDialog } from "$lib/features/deep_link/ui/confirm_deep_link_note_dialog.svelte";
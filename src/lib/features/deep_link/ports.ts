@@ -0,0 +1,6 @@
+import type { DeepLinkEvent } from "$lib/features/deep_link/types/deep_link";
+
+export interface DeepLinkPort {
+  take_pending(): Promise<DeepLinkEvent[]>;
+  subscribe(callback: (event: DeepLinkEvent) => void): () => void;
+}
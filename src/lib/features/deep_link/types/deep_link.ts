@@ -0,0 +1,25 @@
+/** What to do for an `otterly://` link, as resolved by the Rust backend. */
+export type DeepLinkEvent =
+  | {
+      type: "open_note";
+      vault_id: string;
+      note_path: string;
+      heading: string | null;
+    }
+  | {
+      type: "confirm_new";
+      vault_id: string;
+      vault_name: string;
+      note_path: string;
+      content: string;
+    }
+  | {
+      type: "search";
+      vault_id: string | null;
+      query: string;
+    }
+  | {
+      type: "failed";
+      url: string;
+      error: { code: string; message: string };
+    };
@@ -74,6 +74,7 @@ import { slash_command_plugin } from "./slash_command_plugin";
 import { error_message } from "$lib/shared/utils/error_message";
 import { count_words } from "$lib/shared/utils/count_words";
 import { create_logger } from "$lib/shared/utils/logger";
+import { heading_matches } from "$lib/features/editor/domain/heading_match";
 
 const log = create_logger("milkdown_adapter");
 
@@ -157,6 +158,24 @@ function line_from_pos(doc: ProseNode, pos: number): number {
   return count_newlines(doc.textBetween(0, pos, "\n")) + 1;
 }
 
+function find_heading_position(
+  doc: ProseNode,
+  heading: string,
+): number | null {
+  let position: number | null = null;
+  doc.descendants((node, pos) => {
+    if (position !== null) return false;
+    if (
+      node.type.name === "heading" &&
+      heading_matches(node.textContent, heading)
+    ) {
+      position = pos + 1;
+    }
+    return position === null;
+  });
+  return position;
+}
+
 function calculate_cursor_info(view: EditorView): CursorInfo {
   const { doc, selection } = view.state;
   const $from = selection?.$from;
@@ -762,6 +781,27 @@ export function create_milkdown_editor_port(args?: {
             set_wiki_suggestions(view, items);
           });
         },
+        scroll_to_heading(heading: string) {
+          if (!editor) return false;
+          let found = false;
+          run_editor_action((ctx) => {
+            const view = ctx.get(editorViewCtx);
+            const position = find_heading_position(view.state.doc, heading);
+            if (position === null) return;
+
+            found = true;
+            const selection = TextSelection.create(view.state.doc, position);
+            view.dispatch(view.state.tr.setSelection(selection));
+            const dom = view.domAtPos(position);
+            const element =
+              dom.node instanceof HTMLElement
+                ? dom.node
+                : dom.node.parentElement;
+            element?.scrollIntoView({ block: "start" });
+            view.focus();
+          });
+          return found;
+        },
         update_find_state(query: string, selected_index: number) {
           if (!editor) return;
           run_editor_action((ctx) => {
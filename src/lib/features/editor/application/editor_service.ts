@@ -163,6 +163,12 @@ export class EditorService {
     });
   }
 
+  scroll_to_heading(heading: string) {
+    requestAnimationFrame(() => {
+      this.session?.scroll_to_heading?.(heading);
+    });
+  }
+
   focus() {
     this.session?.focus();
   }
@@ -0,0 +1,21 @@
+function normalize_heading(value: string): string {
+  return value.trim().replace(/\s+/g, " ").toLowerCase();
+}
+
+function slugify_heading(value: string): string {
+  return normalize_heading(value)
+    .replace(/[^\p{L}\p{N}\s-]/gu, "")
+    .replace(/\s/g, "-");
+}
+
+/**
+ * Whether a heading's text matches a link target. Targets may be the
+ * heading text itself or its slug (`#my-heading`), compared without case.
+ */
+export function heading_matches(text: string, target: string): boolean {
+  if (normalize_heading(text) === normalize_heading(target)) {
+    return true;
+  }
+  const slug = slugify_heading(text);
+  return slug !== "" && slug === slugify_heading(target);
+}
@@ -31,6 +31,7 @@ export type EditorSession = {
   rename_buffer: (old_note_path: string, new_note_path: string) => void;
   close_buffer: (note_path: string) => void;
   update_find_state?: (query: string, selected_index: number) => void;
+  scroll_to_heading?: (heading: string) => boolean;
 };
 
 export type EditorEventHandlers = {
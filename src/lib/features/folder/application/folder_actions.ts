@@ -1,4 +1,5 @@
 import { SvelteMap, SvelteSet } from "svelte/reactivity";
+import { toast } from "svelte-sonner";
 import { ACTION_IDS } from "$lib/app/action_registry/action_ids";
 import type { ActionRegistrationInput } from "$lib/app/action_registry/action_registration_input";
 import {
@@ -252,6 +253,9 @@ export function register_folder_actions(input: ActionRegistrationInput) {
     if (result.status !== "success") {
       return;
     }
+    if (!result.undoable) {
+      toast.warning(`Deleted ${folder_path}, but it was too large to undo`);
+    }
 
     close_delete_dialog(input);
     const parent_path = parent_folder_path(folder_path);
@@ -5,6 +5,7 @@ import type { EditorStore } from "$lib/features/editor";
 import type { OpStore } from "$lib/app";
 import type { TabStore } from "$lib/features/tab";
 import type {
+  FolderDeleteResult,
   FolderDeleteStatsResult,
   FolderLoadResult,
   FolderMoveResult,
@@ -314,7 +315,7 @@ export class FolderService {
     }
   }
 
-  async delete_folder(folder_path: string): Promise<FolderMutationResult> {
+  async delete_folder(folder_path: string): Promise<FolderDeleteResult> {
     const vault_id = this.get_active_vault_id();
     if (!vault_id || !folder_path) {
       return { status: "skipped" };
@@ -328,7 +329,10 @@ export class FolderService {
         this.editor_store.open_note?.meta.path.startsWith(folder_prefix) ??
         false;
 
-      await this.notes_port.delete_folder(vault_id, folder_path);
+      const outcome = await this.notes_port.delete_folder(
+        vault_id,
+        folder_path,
+      );
       await this.index_port.remove_notes_by_prefix(vault_id, folder_prefix);
 
       this.notes_store.remove_folder(folder_path);
@@ -349,7 +353,7 @@ export class FolderService {
       }
 
       this.succeed_operation("folder.delete");
-      return { status: "success" };
+      return { status: "success", undoable: outcome.undoable };
     } catch (error) {
       const message = this.fail_operation(
         "folder.delete",
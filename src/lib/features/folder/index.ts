@@ -15,6 +15,7 @@ export { default as RenameFolderDialog } from "$lib/features/folder/ui/rename_fo
 export { default as FiletreeMoveConflictDialog } from "$lib/features/folder/ui/filetree_move_conflict_dialog.svelte";
 export { default as VirtualFileTree } from "$lib/features/folder/ui/virtual_file_tree.svelte";
 export type {
+  FolderDeleteResult,
   FolderMutationResult,
   FolderMoveResult,
 } from "$lib/features/folder/types/folder_service_result";
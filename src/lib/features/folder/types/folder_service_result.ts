@@ -12,6 +12,19 @@ export type FolderMutationResult =
       error: string;
     };
 
+export type FolderDeleteResult =
+  | {
+      status: "success";
+      undoable: boolean;
+    }
+  | {
+      status: "skipped";
+    }
+  | {
+      status: "failed";
+      error: string;
+    };
+
 export type FolderMoveResult =
   | {
       status: "success";
@@ -1,5 +1,5 @@
 import type { NotesPort } from "$lib/features/note";
-import type { SearchPort, WorkspaceIndexPort } from "$lib/features/search";
+import type { SearchPort } from "$lib/features/search";
 import type { EditorStore } from "$lib/features/editor";
 import type { TabStore } from "$lib/features/tab";
 import {
@@ -24,11 +24,15 @@ export type LinkRepairProgress = {
   total: number;
 };
 
+type RewriteTarget = {
+  note_path: string;
+  old_source_path: string;
+};
+
 export class LinkRepairService {
   constructor(
     private readonly notes_port: NotesPort,
     private readonly search_port: SearchPort,
-    private readonly index_port: WorkspaceIndexPort,
     private readonly editor_store: EditorStore,
     private readonly tab_store: TabStore,
     private readonly now_ms: () => number,
@@ -80,14 +84,10 @@ export class LinkRepairService {
     return null;
   }
 
-  private async persist_open_note_rewrite(input: {
-    vault_id: VaultId;
-    note_path: NotePath;
-    rewritten_markdown: ReturnType<typeof as_markdown_text>;
-    matched_open_note: NonNullable<EditorStore["open_note"]>;
-  }): Promise<void> {
-    const { vault_id, note_path, rewritten_markdown, matched_open_note } =
-      input;
+  private set_repaired_open_note(
+    matched_open_note: NonNullable<EditorStore["open_note"]>,
+    rewritten_markdown: ReturnType<typeof as_markdown_text>,
+  ) {
     const repair_buffer_id = `${matched_open_note.buffer_id}:repair-links:${String(this.now_ms())}`;
 
     this.editor_store.set_open_note({
@@ -96,36 +96,30 @@ export class LinkRepairService {
       buffer_id: repair_buffer_id,
       is_dirty: matched_open_note.is_dirty,
     });
-
-    if (matched_open_note.is_dirty) {
-      return;
-    }
-
-    await this.notes_port.write_note(vault_id, note_path, rewritten_markdown);
-    await this.index_port.upsert_note(vault_id, note_path);
   }
 
-  private async persist_closed_note_rewrite(input: {
+  private async refresh_after_disk_rewrite(input: {
     vault_id: VaultId;
-    note_path: NotePath;
-    old_source_path: string;
-    new_source_path: string;
-    rewritten_markdown: ReturnType<typeof as_markdown_text>;
+    target: RewriteTarget;
   }): Promise<void> {
-    const {
-      vault_id,
+    const { vault_id, target } = input;
+    const note_path = as_note_path(target.note_path);
+    const matched_open_note = this.find_matching_open_note(
       note_path,
-      old_source_path,
-      new_source_path,
-      rewritten_markdown,
-    } = input;
+      target.old_source_path,
+      target.note_path,
+    );
+
+    if (matched_open_note) {
+      const note = await this.notes_port.read_note(vault_id, note_path);
+      this.set_repaired_open_note(matched_open_note, note.markdown);
+      return;
+    }
 
-    await this.notes_port.write_note(vault_id, note_path, rewritten_markdown);
-    await this.index_port.upsert_note(vault_id, note_path);
     this.tab_store.invalidate_cache_by_path(note_path);
 
-    if (old_source_path !== new_source_path) {
-      const old_note_path = as_note_path(old_source_path);
+    if (target.old_source_path !== target.note_path) {
+      const old_note_path = as_note_path(target.old_source_path);
       this.tab_store.invalidate_cache_by_path(old_note_path);
       this.close_editor_buffer(old_note_path);
     }
@@ -157,102 +151,125 @@ export class LinkRepairService {
     const total = external_sources.size + path_map.size;
     on_progress?.({ processed: 0, total });
 
-    for (const source_path of external_sources) {
-      result.scanned += 1;
-      const rewrite_result = await this.rewrite_note_file(
-        vault_id,
-        as_note_path(source_path),
-        source_path,
-        source_path,
-        target_map,
+    const targets: RewriteTarget[] = [
+      ...Array.from(external_sources, (source_path) => ({
+        note_path: source_path,
+        old_source_path: source_path,
+      })),
+      ...Array.from(path_map, ([old_path, new_path]) => ({
+        note_path: new_path,
+        old_source_path: old_path,
+      })),
+    ];
+
+    // Unsaved edits stay in the editor; everything else is rewritten on disk
+    // in one backend call so the edits share the move's undo entry.
+    const on_disk: RewriteTarget[] = [];
+    for (const target of targets) {
+      const matched_open_note = this.find_matching_open_note(
+        as_note_path(target.note_path),
+        target.old_source_path,
+        target.note_path,
       );
-      if (rewrite_result.status === "rewritten") {
-        result.rewritten += 1;
-      }
-      if (rewrite_result.status === "failed") {
-        result.failed.push(source_path);
+      if (!matched_open_note?.is_dirty) {
+        on_disk.push(target);
+        continue;
       }
-      on_progress?.({ processed: result.scanned, total });
-    }
 
-    for (const [old_path, new_path] of path_map) {
       result.scanned += 1;
-      const rewrite_result = await this.rewrite_note_file(
-        vault_id,
-        as_note_path(new_path),
-        old_path,
-        new_path,
+      const status = await this.rewrite_dirty_open_note(
+        target,
+        matched_open_note,
         target_map,
       );
-      if (rewrite_result.status === "rewritten") {
+      if (status === "rewritten") {
         result.rewritten += 1;
       }
-      if (rewrite_result.status === "failed") {
-        result.failed.push(new_path);
+      if (status === "failed") {
+        result.failed.push(target.note_path);
       }
       on_progress?.({ processed: result.scanned, total });
     }
 
+    if (on_disk.length > 0) {
+      await this.rewrite_on_disk(vault_id, on_disk, target_map, result);
+      on_progress?.({ processed: result.scanned, total });
+    }
+
     return result;
   }
 
-  private async rewrite_note_file(
+  private async rewrite_on_disk(
     vault_id: VaultId,
-    note_path: NotePath,
-    old_source_path: string,
-    new_source_path: string,
+    targets: RewriteTarget[],
     target_map: Record<string, string>,
-  ): Promise<{ status: "unchanged" | "rewritten" | "failed" }> {
-    try {
-      const matched_open_note = this.find_matching_open_note(
-        note_path,
-        old_source_path,
-        new_source_path,
-      );
+    result: LinkRepairResult,
+  ): Promise<void> {
+    result.scanned += targets.length;
+    const applied = await this.search_port
+      .apply_note_link_rewrites(
+        vault_id,
+        targets.map((target) => target.note_path),
+        target_map,
+      )
+      .catch((error: unknown) => {
+        log.warn("Rewrite links failed", {
+          note_count: targets.length,
+          error: error_message(error),
+        });
+        return null;
+      });
+    if (!applied) {
+      result.failed.push(...targets.map((target) => target.note_path));
+      return;
+    }
 
-      const markdown = matched_open_note
-        ? matched_open_note.markdown
-        : (await this.notes_port.read_note(vault_id, note_path)).markdown;
+    result.rewritten += applied.rewritten.length;
+    result.failed.push(...applied.failed);
 
+    const rewritten = new Set(applied.rewritten);
+    for (const target of targets) {
+      if (!rewritten.has(target.note_path)) continue;
+      try {
+        await this.refresh_after_disk_rewrite({ vault_id, target });
+      } catch (error) {
+        log.warn("Refresh after link rewrite failed", {
+          note_path: target.note_path,
+          error: error_message(error),
+        });
+      }
+    }
+  }
+
+  private async rewrite_dirty_open_note(
+    target: RewriteTarget,
+    matched_open_note: NonNullable<EditorStore["open_note"]>,
+    target_map: Record<string, string>,
+  ): Promise<"unchanged" | "rewritten" | "failed"> {
+    try {
       const result = await this.search_port.rewrite_note_links(
-        markdown,
-        old_source_path,
-        new_source_path,
+        matched_open_note.markdown,
+        target.old_source_path,
+        target.note_path,
         target_map,
       );
 
       if (!result.changed) {
-        return { status: "unchanged" };
-      }
-
-      const rewritten = as_markdown_text(result.markdown);
-
-      if (matched_open_note) {
-        await this.persist_open_note_rewrite({
-          vault_id,
-          note_path,
-          rewritten_markdown: rewritten,
-          matched_open_note,
-        });
-        return { status: "rewritten" };
+        return "unchanged";
       }
 
-      await this.persist_closed_note_rewrite({
-        vault_id,
-        note_path,
-        old_source_path,
-        new_source_path,
-        rewritten_markdown: rewritten,
-      });
-      return { status: "rewritten" };
+      this.set_repaired_open_note(
+        matched_open_note,
+        as_markdown_text(result.markdown),
+      );
+      return "rewritten";
     } catch (error) {
       log.warn("Rewrite links failed", {
-        note_path,
-        old_source_path,
-        new_source_path,
+        note_path: target.note_path,
+        old_source_path: target.old_source_path,
         error: error_message(error),
       });
-      return { status: "failed" };
+      return "failed";
     }
   }
 }
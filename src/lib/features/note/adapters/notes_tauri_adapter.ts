@@ -8,6 +8,7 @@ import type {
 } from "$lib/shared/types/ids";
 import type { NoteDoc, NoteMeta } from "$lib/shared/types/note";
 import type {
+  DeleteOutcome,
   FolderContents,
   FolderStats,
   MoveItem,
@@ -90,7 +91,7 @@ export function create_notes_tauri_adapter(): NotesPort {
       });
     },
     async delete_note(vault_id: VaultId, note_id: NoteId) {
-      await invoke_notes_args<undefined>("delete_note", {
+      return await invoke_notes_args<DeleteOutcome>("delete_note", {
         vault_id,
         note_id,
       });
@@ -116,7 +117,7 @@ export function create_notes_tauri_adapter(): NotesPort {
       });
     },
     async delete_folder(vault_id: VaultId, folder_path: string) {
-      await invoke_notes_args<undefined>("delete_folder", {
+      return await invoke_notes_args<DeleteOutcome>("delete_folder", {
         vault_id,
         folder_path,
       });
@@ -532,6 +532,9 @@ export function register_note_actions(input: ActionRegistrationInput) {
         if (result.status !== "deleted") {
           return;
         }
+        if (!result.undoable) {
+          toast.warning(`Deleted ${note.path}, but it was too large to undo`);
+        }
 
         clear_parent_folder_filetree(note.path);
         close_delete_dialog(input);
@@ -21,7 +21,7 @@ import type {
   NoteRenameResult,
   NoteSaveResult,
 } from "$lib/features/note/types/note_service_result";
-import { error_message } from "$lib/shared/utils/error_message";
+import { error_code, error_message } from "$lib/shared/utils/error_message";
 import {
   create_untitled_open_note,
   is_draft_note_path,
@@ -161,7 +161,7 @@ export class NoteService {
         await this.index_port.upsert_note(vault_id, meta.id);
         return { meta, markdown: as_markdown_text("") };
       } catch (create_error) {
-        if (error_message(create_error).includes("note already exists")) {
+        if (this.is_note_exists_error(create_error)) {
           return await this.notes_port.read_note(vault_id, path);
         }
         throw create_error;
@@ -337,7 +337,7 @@ export class NoteService {
 
     try {
       this.on_file_written?.(note.id);
-      await this.notes_port.delete_note(vault_id, note.id);
+      const outcome = await this.notes_port.delete_note(vault_id, note.id);
       await this.index_port.remove_note(vault_id, note.id);
 
       const is_open_note = this.editor_store.open_note?.meta.id === note.id;
@@ -349,7 +349,7 @@ export class NoteService {
       }
 
       this.succeed_operation("note.delete");
-      return { status: "deleted" };
+      return { status: "deleted", undoable: outcome.undoable };
     } catch (error) {
       const message = this.fail_operation(
         "note.delete",
@@ -762,6 +762,31 @@ export class NoteService {
     await this.notes_port.write_note(vault.id, note_path, markdown);
   }
 
+  async create_note_with_markdown(
+    note_path: NotePath,
+    markdown: MarkdownText,
+  ): Promise<NoteMeta | null> {
+    const vault_id = this.get_active_vault_id();
+    if (!vault_id) return null;
+
+    this.start_operation("note.create");
+    try {
+      this.on_file_written?.(note_path);
+      const meta = await this.notes_port.create_note(
+        vault_id,
+        note_path,
+        markdown,
+      );
+      await this.index_port.upsert_note(vault_id, meta.id);
+      this.notes_store.add_note(meta);
+      this.succeed_operation("note.create");
+      return meta;
+    } catch (error) {
+      this.fail_operation("note.create", "Create note failed", error);
+      return null;
+    }
+  }
+
   private async rename_note_with_overwrite_if_needed(
     vault_id: VaultId,
     from_path: NotePath,
@@ -793,6 +818,7 @@ export class NoteService {
 
   private is_not_found_error(error: unknown): boolean {
     if (error instanceof Error && error.name === "NotFoundError") return true;
+    if (error_code(error) === "not_found") return true;
     const message = error_message(error).toLowerCase();
     return (
       message.includes("not found") ||
@@ -802,10 +828,11 @@ export class NoteService {
   }
 
   private is_mtime_conflict_error(error: unknown): boolean {
-    return error_message(error).startsWith("conflict:mtime_mismatch");
+    return error_message(error).includes("conflict:mtime_mismatch");
   }
 
   private is_note_exists_error(error: unknown): boolean {
+    if (error_code(error) === "already_exists") return true;
     const message = error_message(error).toLowerCase();
     return (
       message.includes("note already exists") ||
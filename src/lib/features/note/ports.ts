@@ -7,6 +7,7 @@ import type {
 } from "$lib/shared/types/ids";
 import type { NoteDoc, NoteMeta } from "$lib/shared/types/note";
 import type {
+  DeleteOutcome,
   FolderContents,
   FolderStats,
   MoveItem,
@@ -56,13 +57,16 @@ export interface NotesPort {
     folder_name: string,
   ): Promise<void>;
   rename_note(vault_id: VaultId, from: NotePath, to: NotePath): Promise<void>;
-  delete_note(vault_id: VaultId, note_id: NoteId): Promise<void>;
+  delete_note(vault_id: VaultId, note_id: NoteId): Promise<DeleteOutcome>;
   rename_folder(
     vault_id: VaultId,
     from_path: string,
     to_path: string,
   ): Promise<void>;
-  delete_folder(vault_id: VaultId, folder_path: string): Promise<void>;
+  delete_folder(
+    vault_id: VaultId,
+    folder_path: string,
+  ): Promise<DeleteOutcome>;
   list_folder_contents(
     vault_id: VaultId,
     folder_path: string,
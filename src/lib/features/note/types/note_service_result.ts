@@ -19,6 +19,7 @@ export type NoteOpenResult =
 export type NoteDeleteResult =
   | {
       status: "deleted";
+      undoable: boolean;
     }
   | {
       status: "skipped";
@@ -1,4 +1,5 @@
 import type {
+  LinkRewriteApplyResult,
   LocalNoteLinksSnapshot,
   NoteLinksSnapshot,
   RewriteResult,
@@ -210,6 +211,16 @@ export function create_search_tauri_adapter(): SearchPort {
       });
     },
 
+    async apply_note_link_rewrites(
+      vault_id: VaultId,
+      note_paths: string[],
+      target_map: Record<string, string>,
+    ): Promise<LinkRewriteApplyResult> {
+      return invoke_search<LinkRewriteApplyResult>("apply_note_link_rewrites", {
+        args: { vault_id, note_paths, target_map },
+      });
+    },
+
     async resolve_note_link(
       source_path: string,
       raw_target: string,
@@ -28,6 +28,11 @@ export type RewriteResult = {
   changed: boolean;
 };
 
+export type LinkRewriteApplyResult = {
+  rewritten: string[];
+  failed: string[];
+};
+
 export interface SearchPort {
   search_notes(
     vault_id: VaultId,
@@ -59,6 +64,11 @@ export interface SearchPort {
     new_source_path: string,
     target_map: Record<string, string>,
   ): Promise<RewriteResult>;
+  apply_note_link_rewrites(
+    vault_id: VaultId,
+    note_paths: string[],
+    target_map: Record<string, string>,
+  ): Promise<LinkRewriteApplyResult>;
   resolve_note_link(
     source_path: string,
     raw_target: string,
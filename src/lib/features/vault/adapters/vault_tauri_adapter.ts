@@ -24,6 +24,9 @@ export function create_vault_tauri_adapter(): VaultPort {
         vaultId: vault_id,
       });
     },
+    async open_vault_window(vault_id: VaultId) {
+      await invoke_vault<string>("open_vault_window", { vaultId: vault_id });
+    },
     async list_vaults() {
       return await invoke_vault<Vault[]>("list_vaults");
     },
@@ -350,6 +350,22 @@ export function register_vault_actions(input: ActionRegistrationInput) {
     },
   });
 
+  registry.register({
+    id: ACTION_IDS.vault_open_in_new_window,
+    label: "Open Vault in New Window",
+    execute: async (vault_id: unknown) => {
+      if (typeof vault_id !== "string") {
+        return;
+      }
+      const result = await services.vault.open_in_new_window(
+        vault_id as VaultId,
+      );
+      if (result.status === "failed") {
+        toast.error(result.error);
+      }
+    },
+  });
+
   registry.register({
     id: ACTION_IDS.vault_sync_index,
     label: "Sync Vault Index",
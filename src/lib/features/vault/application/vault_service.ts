@@ -37,6 +37,7 @@ const log = create_logger("vault_service");
 export type AppMountConfig = {
   reset_app_state: boolean;
   bootstrap_default_vault_path: VaultPath | null;
+  bootstrap_vault_id: VaultId | null;
 };
 
 const RECENT_NOTES_KEY = "recent_notes";
@@ -112,7 +113,14 @@ export class VaultService {
 
       const has_vault = this.vault_store.vault !== null;
 
-      if (!has_vault && config.bootstrap_default_vault_path) {
+      if (!has_vault && config.bootstrap_vault_id) {
+        const vault_id = config.bootstrap_vault_id;
+        const open_revision = await this.begin_open_revision();
+        editor_settings = await this.open_vault(
+          () => this.vault_port.open_vault_by_id(vault_id),
+          open_revision,
+        );
+      } else if (!has_vault && config.bootstrap_default_vault_path) {
         const default_path = config.bootstrap_default_vault_path;
         const open_revision = await this.begin_open_revision();
         editor_settings = await this.open_vault(
@@ -191,6 +199,19 @@ export class VaultService {
     );
   }
 
+  async open_in_new_window(
+    vault_id: VaultId,
+  ): Promise<{ status: "success" } | { status: "failed"; error: string }> {
+    try {
+      await this.vault_port.open_vault_window(vault_id);
+      return { status: "success" };
+    } catch (error) {
+      const message = error_message(error);
+      log.error("Open vault in new window failed", { error: message });
+      return { status: "failed", error: message };
+    }
+  }
+
   async toggle_vault_pin(
     vault_id: VaultId,
   ): Promise<{ status: "success" } | { status: "failed"; error: string }> {
@@ -2,7 +2,7 @@ import type { WatcherPort } from "$lib/features/watcher/ports";
 import type { VaultId } from "$lib/shared/types/ids";
 import type { VaultFsEvent } from "$lib/features/watcher/types/watcher";
 import { tauri_invoke } from "$lib/shared/adapters/tauri_invoke";
-import { listen } from "@tauri-apps/api/event";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 
 function subscribe_vault_fs_events(
   callback: (event: VaultFsEvent) => void,
@@ -10,18 +10,21 @@ function subscribe_vault_fs_events(
   let unlisten_fn: (() => void) | null = null;
   let is_disposed = false;
 
-  void listen<VaultFsEvent>("vault_fs_event", (event) => {
-    if (is_disposed) {
-      return;
-    }
-    callback(event.payload);
-  }).then((fn_ref) => {
-    if (is_disposed) {
-      fn_ref();
-      return;
-    }
-    unlisten_fn = fn_ref;
-  });
+  // The backend sends each vault's events only to the windows showing it.
+  void getCurrentWebviewWindow()
+    .listen<VaultFsEvent>("vault_fs_event", (event) => {
+      if (is_disposed) {
+        return;
+      }
+      callback(event.payload);
+    })
+    .then((fn_ref) => {
+      if (is_disposed) {
+        fn_ref();
+        return;
+      }
+      unlisten_fn = fn_ref;
+    });
 
   return () => {
     is_disposed = true;
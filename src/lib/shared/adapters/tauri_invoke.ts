@@ -1,5 +1,9 @@
 import { invoke } from "@tauri-apps/api/core";
 import { is_tauri } from "$lib/shared/utils/detect_platform";
+import {
+  error_message,
+  to_command_error,
+} from "$lib/shared/utils/error_message";
 
 export async function tauri_invoke<T>(
   command: string,
@@ -12,7 +16,7 @@ export async function tauri_invoke<T>(
   try {
     return await invoke<T>(command, args);
   } catch (e) {
-    const msg = e instanceof Error ? e.message : String(e);
-    throw new Error(`tauri invoke failed: ${command}: ${msg}`);
+    const msg = error_message(e);
+    throw to_command_error(e, `tauri invoke failed: ${command}: ${msg}`);
   }
 }
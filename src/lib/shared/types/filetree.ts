@@ -47,3 +47,7 @@ export type MoveItemResult = {
   success: boolean;
   error: string | null;
 };
+
+export type DeleteOutcome = {
+  undoable: boolean;
+};
@@ -8,4 +8,5 @@ export type Vault = {
   last_opened_at?: number | null;
   note_count?: number | null;
   is_available?: boolean;
+  pinned?: boolean;
 };
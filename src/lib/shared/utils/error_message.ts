@@ -1,5 +1,41 @@
+type CommandErrorPayload = { code: string; message: string };
+
+function is_command_error(err: unknown): err is CommandErrorPayload {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    typeof (err as CommandErrorPayload).code === "string" &&
+    typeof (err as CommandErrorPayload).message === "string"
+  );
+}
+
+/** A failed backend command, keeping the typed payload (`code`, `path`, ...). */
+export class CommandError extends Error {
+  readonly code: string;
+  readonly details: Record<string, unknown>;
+
+  constructor(message: string, payload: CommandErrorPayload) {
+    super(message);
+    this.name = "CommandError";
+    this.code = payload.code;
+    this.details = { ...payload };
+  }
+}
+
 export function error_message(err: unknown): string {
   if (err instanceof Error) return err.message;
   if (typeof err === "string") return err;
+  if (is_command_error(err)) return err.message;
   return "Unknown error";
 }
+
+/** Stable code of a typed backend error (`not_found`, `conflict`, ...). */
+export function error_code(err: unknown): string | null {
+  return is_command_error(err) ? err.code : null;
+}
+
+export function to_command_error(err: unknown, message: string): Error {
+  return is_command_error(err)
+    ? new CommandError(message, err)
+    : new Error(message);
+}
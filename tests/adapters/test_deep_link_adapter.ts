@@ -0,0 +1,12 @@
+import type { DeepLinkEvent, DeepLinkPort } from "$lib/features/deep_link";
+
+export function create_test_deep_link_adapter(): DeepLinkPort {
+  return {
+    async take_pending(): Promise<DeepLinkEvent[]> {
+      return [];
+    },
+    subscribe(_callback: (event: DeepLinkEvent) => void): () => void {
+      return () => {};
+    },
+  };
+}
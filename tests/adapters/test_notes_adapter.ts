@@ -9,6 +9,7 @@ import {
 } from "$lib/shared/types/ids";
 import type { NoteDoc, NoteMeta } from "$lib/shared/types/note";
 import type {
+  DeleteOutcome,
   FolderContents,
   MoveItem,
   MoveItemResult,
@@ -172,8 +173,8 @@ export function create_test_notes_adapter(): NotesPort {
       return Promise.resolve();
     },
 
-    delete_note(_vault_id: VaultId, _note_id: NoteId): Promise<void> {
-      return Promise.resolve();
+    delete_note(_vault_id: VaultId, _note_id: NoteId): Promise<DeleteOutcome> {
+      return Promise.resolve({ undoable: true });
     },
 
     async list_folder_contents(
@@ -265,7 +266,10 @@ export function create_test_notes_adapter(): NotesPort {
       return Promise.resolve();
     },
 
-    delete_folder(_vault_id: VaultId, folder_path: string): Promise<void> {
+    delete_folder(
+      _vault_id: VaultId,
+      folder_path: string,
+    ): Promise<DeleteOutcome> {
       const prefix = folder_path + "/";
 
       for (const folder of created_folders) {
@@ -273,7 +277,7 @@ export function create_test_notes_adapter(): NotesPort {
           created_folders.delete(folder);
         }
       }
-      return Promise.resolve();
+      return Promise.resolve({ undoable: true });
     },
 
     async get_folder_stats(
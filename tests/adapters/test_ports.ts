@@ -12,6 +12,7 @@ import { create_test_clipboard_adapter } from "./test_clipboard_adapter";
 import { create_test_shell_adapter } from "./test_shell_adapter";
 import { create_test_git_adapter } from "./test_git_adapter";
 import { create_test_watcher_adapter } from "./test_watcher_adapter";
+import { create_test_deep_link_adapter } from "./test_deep_link_adapter";
 
 export function create_test_ports(): Ports {
   const assets = create_test_assets_adapter();
@@ -33,5 +34,6 @@ export function create_test_ports(): Ports {
     shell: create_test_shell_adapter(),
     git: create_test_git_adapter(),
     watcher: create_test_watcher_adapter(),
+    deep_link: create_test_deep_link_adapter(),
   };
 }
@@ -61,6 +61,14 @@ export function create_test_search_adapter(): SearchPort {
       return Promise.resolve({ markdown, changed: false });
     },
 
+    apply_note_link_rewrites(
+      _vault_id: VaultId,
+      _note_paths: string[],
+      _target_map: Record<string, string>,
+    ) {
+      return Promise.resolve({ rewritten: [], failed: [] });
+    },
+
     resolve_note_link(_source_path: string, raw_target: string) {
       const cleaned = raw_target.replace(/^\//, "");
       if (!cleaned) return Promise.resolve(null);
@@ -92,6 +92,10 @@ export function create_test_vault_adapter(): VaultPort {
       );
     },
 
+    open_vault_window(_vault_id: VaultId): Promise<void> {
+      return Promise.resolve();
+    },
+
     list_vaults(): Promise<Vault[]> {
       return Promise.resolve(vaults);
     },
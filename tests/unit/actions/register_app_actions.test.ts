@@ -127,6 +127,9 @@ function create_harness(options: HarnessOptions = {}) {
       invalidate_cache: vi.fn(),
       remove_tab: vi.fn(),
     },
+    deep_link: {
+      start: vi.fn().mockResolvedValue(undefined),
+    },
   };
 
   const execute_folder_refresh_tree = vi.fn().mockResolvedValue(undefined);
@@ -140,6 +143,7 @@ function create_harness(options: HarnessOptions = {}) {
     default_mount_config: {
       reset_app_state: options.reset_app_state ?? false,
       bootstrap_default_vault_path: null,
+      bootstrap_vault_id: null,
     },
   });
 
@@ -208,6 +212,7 @@ describe("register_app_actions", () => {
       DEFAULT_HOTKEYS,
       [],
     );
+    expect(services.deep_link.start).toHaveBeenCalledTimes(1);
   });
 
   it("sets startup error state when vault initialization fails", async () => {
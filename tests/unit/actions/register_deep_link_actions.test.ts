@@ -0,0 +1,226 @@
+import { describe, expect, it, vi } from "vitest";
+import { ActionRegistry } from "$lib/app/action_registry/action_registry";
+import { ACTION_IDS } from "$lib/app/action_registry/action_ids";
+import { register_deep_link_actions } from "$lib/features/deep_link/application/deep_link_actions";
+import { UIStore } from "$lib/app/orchestration/ui_store.svelte";
+import { VaultStore } from "$lib/features/vault/state/vault_store.svelte";
+import { NotesStore } from "$lib/features/note/state/note_store.svelte";
+import { EditorStore } from "$lib/features/editor/state/editor_store.svelte";
+import { OpStore } from "$lib/app/orchestration/op_store.svelte";
+import { SearchStore } from "$lib/features/search/state/search_store.svelte";
+import { TabStore } from "$lib/features/tab/state/tab_store.svelte";
+import { GitStore } from "$lib/features/git/state/git_store.svelte";
+import { as_note_path, as_vault_id } from "$lib/shared/types/ids";
+import {
+  create_open_note_state,
+  create_test_note,
+  create_test_vault,
+} from "../helpers/test_fixtures";
+
+function create_deep_link_actions_harness() {
+  const registry = new ActionRegistry();
+  const stores = {
+    ui: new UIStore(),
+    vault: new VaultStore(),
+    notes: new NotesStore(),
+    editor: new EditorStore(),
+    op: new OpStore(),
+    search: new SearchStore(),
+    tab: new TabStore(),
+    git: new GitStore(),
+  };
+  stores.vault.set_vault(create_test_vault({ id: as_vault_id("vault-a") }));
+
+  const execute_vault_select = vi.fn((vault_id: unknown) => {
+    stores.vault.set_vault(
+      create_test_vault({ id: vault_id as ReturnType<typeof as_vault_id> }),
+    );
+    return Promise.resolve();
+  });
+  const execute_note_open = vi.fn((args: unknown) => {
+    const { note_path } = args as { note_path: string };
+    const id = note_path.replace(/\.md$/, "");
+    stores.editor.set_open_note(
+      create_open_note_state(create_test_note(id, id)),
+    );
+    return Promise.resolve();
+  });
+  const execute_omnibar_open = vi.fn().mockResolvedValue(undefined);
+  const execute_omnibar_open_all_vaults = vi.fn().mockResolvedValue(undefined);
+  const execute_omnibar_set_query = vi.fn().mockResolvedValue(undefined);
+
+  const services = {
+    editor: {
+      scroll_to_heading: vi.fn(),
+    },
+    note: {
+      create_note_with_markdown: vi
+        .fn()
+        .mockImplementation((note_path: string) => {
+          const id = note_path.replace(/\.md$/, "");
+          return Promise.resolve(create_test_note(id, id));
+        }),
+    },
+  };
+
+  register_deep_link_actions({
+    registry,
+    stores,
+    services: services as never,
+    default_mount_config: {
+      reset_app_state: true,
+      bootstrap_default_vault_path: null,
+      bootstrap_vault_id: null,
+    },
+  });
+
+  registry.register({
+    id: ACTION_IDS.vault_select,
+    label: "Select Vault",
+    execute: execute_vault_select,
+  });
+  registry.register({
+    id: ACTION_IDS.note_open,
+    label: "Open Note",
+    execute: execute_note_open,
+  });
+  registry.register({
+    id: ACTION_IDS.omnibar_open,
+    label: "Open Omnibar",
+    execute: execute_omnibar_open,
+  });
+  registry.register({
+    id: ACTION_IDS.omnibar_open_all_vaults,
+    label: "Open Omnibar (All Vaults)",
+    execute: execute_omnibar_open_all_vaults,
+  });
+  registry.register({
+    id: ACTION_IDS.omnibar_set_query,
+    label: "Set Omnibar Query",
+    execute: execute_omnibar_set_query,
+  });
+
+  return {
+    registry,
+    stores,
+    services,
+    execute_vault_select,
+    execute_note_open,
+    execute_omnibar_open,
+    execute_omnibar_open_all_vaults,
+    execute_omnibar_set_query,
+  };
+}
+
+describe("register_deep_link_actions", () => {
+  it("switches vault before opening a linked note", async () => {
+    const { registry, execute_vault_select, execute_note_open } =
+      create_deep_link_actions_harness();
+
+    await registry.execute(ACTION_IDS.deep_link_handle, {
+      type: "open_note",
+      vault_id: "vault-b",
+      note_path: "docs/alpha.md",
+      heading: null,
+    });
+
+    expect(execute_vault_select).toHaveBeenCalledWith(as_vault_id("vault-b"));
+    expect(execute_note_open).toHaveBeenCalledWith({
+      note_path: as_note_path("docs/alpha.md"),
+      cleanup_if_missing: true,
+    });
+  });
+
+  it("scrolls to the linked heading once the note is open", async () => {
+    const { registry, services } = create_deep_link_actions_harness();
+
+    await registry.execute(ACTION_IDS.deep_link_handle, {
+      type: "open_note",
+      vault_id: "vault-a",
+      note_path: "docs/alpha.md",
+      heading: "Next Steps",
+    });
+
+    expect(services.editor.scroll_to_heading).toHaveBeenCalledWith(
+      "Next Steps",
+    );
+  });
+
+  it("asks before creating a note from a new link", async () => {
+    const { registry, stores, services, execute_note_open } =
+      create_deep_link_actions_harness();
+
+    await registry.execute(ACTION_IDS.deep_link_handle, {
+      type: "confirm_new",
+      vault_id: "vault-a",
+      vault_name: "Vault A",
+      note_path: "inbox/idea.md",
+      content: "# Idea\n",
+    });
+
+    expect(services.note.create_note_with_markdown).not.toHaveBeenCalled();
+    expect(stores.ui.deep_link_new_confirm).toEqual({
+      open: true,
+      vault_id: as_vault_id("vault-a"),
+      vault_name: "Vault A",
+      note_path: as_note_path("inbox/idea.md"),
+      content: "# Idea\n",
+    });
+
+    await registry.execute(ACTION_IDS.deep_link_confirm_new);
+
+    expect(services.note.create_note_with_markdown).toHaveBeenCalledWith(
+      as_note_path("inbox/idea.md"),
+      "# Idea\n",
+    );
+    expect(execute_note_open).toHaveBeenCalledWith({
+      note_path: as_note_path("inbox/idea.md"),
+      cleanup_if_missing: true,
+    });
+    expect(stores.ui.deep_link_new_confirm.open).toBe(false);
+  });
+
+  it("drops a new link when the user cancels", async () => {
+    const { registry, stores, services } = create_deep_link_actions_harness();
+
+    await registry.execute(ACTION_IDS.deep_link_handle, {
+      type: "confirm_new",
+      vault_id: "vault-a",
+      vault_name: "Vault A",
+      note_path: "inbox/idea.md",
+      content: "",
+    });
+    await registry.execute(ACTION_IDS.deep_link_cancel_new);
+    await registry.execute(ACTION_IDS.deep_link_confirm_new);
+
+    expect(stores.ui.deep_link_new_confirm.open).toBe(false);
+    expect(services.note.create_note_with_markdown).not.toHaveBeenCalled();
+  });
+
+  it("opens the omnibar with the linked query", async () => {
+    const {
+      registry,
+      execute_omnibar_open,
+      execute_omnibar_open_all_vaults,
+      execute_omnibar_set_query,
+    } = create_deep_link_actions_harness();
+
+    await registry.execute(ACTION_IDS.deep_link_handle, {
+      type: "search",
+      vault_id: null,
+      query: "otters",
+    });
+    await registry.execute(ACTION_IDS.deep_link_handle, {
+      type: "search",
+      vault_id: "vault-a",
+      query: "rivers",
+    });
+
+    expect(execute_omnibar_open_all_vaults).toHaveBeenCalledTimes(1);
+    expect(execute_omnibar_open).toHaveBeenCalledTimes(1);
+    expect(execute_omnibar_set_query.mock.calls).toEqual([
+      ["otters"],
+      ["rivers"],
+    ]);
+  });
+});
@@ -68,6 +68,7 @@ function create_harness() {
     default_mount_config: {
       reset_app_state: false,
       bootstrap_default_vault_path: null,
+      bootstrap_vault_id: null,
     },
   });
 
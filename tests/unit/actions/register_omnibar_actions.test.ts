@@ -89,6 +89,7 @@ function create_omnibar_actions_harness() {
     default_mount_config: {
       reset_app_state: true,
       bootstrap_default_vault_path: null,
+      bootstrap_vault_id: null,
     },
   });
 
@@ -111,6 +111,7 @@ function create_tab_actions_harness() {
     default_mount_config: {
       reset_app_state: true,
       bootstrap_default_vault_path: null,
+      bootstrap_vault_id: null,
     },
   });
 
@@ -121,6 +122,7 @@ function create_tab_actions_harness() {
     default_mount_config: {
       reset_app_state: true,
       bootstrap_default_vault_path: null,
+      bootstrap_vault_id: null,
     },
   });
 
@@ -131,6 +133,7 @@ function create_tab_actions_harness() {
     default_mount_config: {
       reset_app_state: true,
       bootstrap_default_vault_path: null,
+      bootstrap_vault_id: null,
     },
   });
 
@@ -742,6 +745,7 @@ describe("register_tab_actions", () => {
         default_mount_config: {
           reset_app_state: true,
           bootstrap_default_vault_path: null,
+          bootstrap_vault_id: null,
         },
       });
 
@@ -767,6 +771,7 @@ describe("register_tab_actions", () => {
         default_mount_config: {
           reset_app_state: true,
           bootstrap_default_vault_path: null,
+          bootstrap_vault_id: null,
         },
       });
 
@@ -796,6 +801,7 @@ describe("register_tab_actions", () => {
         default_mount_config: {
           reset_app_state: true,
           bootstrap_default_vault_path: null,
+          bootstrap_vault_id: null,
         },
       });
 
@@ -40,6 +40,7 @@ describe("register_ui_actions", () => {
       default_mount_config: {
         reset_app_state: true,
         bootstrap_default_vault_path: null,
+        bootstrap_vault_id: null,
       },
     });
 
@@ -82,6 +83,7 @@ describe("register_ui_actions", () => {
       default_mount_config: {
         reset_app_state: true,
         bootstrap_default_vault_path: null,
+        bootstrap_vault_id: null,
       },
     });
 
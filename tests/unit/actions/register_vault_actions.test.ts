@@ -41,6 +41,7 @@ function create_vault_actions_harness() {
       select_pinned_vault_by_slot: vi.fn(),
       remove_vault_from_registry: vi.fn(),
       toggle_vault_pin: vi.fn(),
+      open_in_new_window: vi.fn().mockResolvedValue({ status: "success" }),
       rebuild_index: vi.fn(),
       sync_index: vi.fn().mockResolvedValue({ status: "success" }),
       reset_change_operation: vi.fn(),
@@ -89,6 +90,7 @@ function create_vault_actions_harness() {
     default_mount_config: {
       reset_app_state: true,
       bootstrap_default_vault_path: null,
+      bootstrap_vault_id: null,
     },
   });
 
@@ -335,6 +337,20 @@ describe("register_vault_actions", () => {
     expect(services.note.create_new_note).toHaveBeenCalledTimes(1);
   });
 
+  it("opens a vault in a new window without switching this one", async () => {
+    const { registry, services } = create_vault_actions_harness();
+
+    await registry.execute(
+      ACTION_IDS.vault_open_in_new_window,
+      as_vault_id("vault-other"),
+    );
+
+    expect(services.vault.open_in_new_window).toHaveBeenCalledWith(
+      as_vault_id("vault-other"),
+    );
+    expect(services.vault.change_vault_by_id).not.toHaveBeenCalled();
+  });
+
   describe("vault_sync_index", () => {
     it("calls sync_index on the vault service", async () => {
       const { registry, stores, services } = create_vault_actions_harness();
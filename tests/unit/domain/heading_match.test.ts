@@ -0,0 +1,19 @@
+import { describe, expect, it } from "vitest";
+import { heading_matches } from "$lib/features/editor/domain/heading_match";
+
+describe("heading_matches", () => {
+  it("matches heading text ignoring case and extra spaces", () => {
+    expect(heading_matches("Next  Steps", "next steps")).toBe(true);
+    expect(heading_matches("  Overview ", "Overview")).toBe(true);
+  });
+
+  it("matches slugs of the heading text", () => {
+    expect(heading_matches("Q3 Plan: Draft!", "q3-plan-draft")).toBe(true);
+    expect(heading_matches("Café notes", "café-notes")).toBe(true);
+  });
+
+  it("rejects other headings", () => {
+    expect(heading_matches("Next Steps", "Steps")).toBe(false);
+    expect(heading_matches("!!!", "")).toBe(false);
+  });
+});
@@ -10,9 +10,11 @@ import type {
 } from "$lib/shared/types/ids";
 import type { WatcherPort } from "$lib/features/watcher";
 import type { VaultFsEvent } from "$lib/features/watcher";
+import type { DeepLinkEvent, DeepLinkPort } from "$lib/features/deep_link";
 import type { Vault } from "$lib/shared/types/vault";
 import type { NoteMeta } from "$lib/shared/types/note";
 import type {
+  DeleteOutcome,
   FolderContents,
   MoveItem,
   MoveItemResult,
@@ -61,6 +63,9 @@ export function create_mock_vault_port(): VaultPort & {
     remember_last_vault(_vault_id: VaultId) {
       return Promise.resolve();
     },
+    open_vault_window(_vault_id: VaultId) {
+      return Promise.resolve();
+    },
     get_last_vault_id() {
       return Promise.resolve(null);
     },
@@ -211,7 +216,7 @@ export function create_mock_notes_port(): NotesPort & {
         vault_id,
         current.filter((note) => note.id !== note_id),
       );
-      return Promise.resolve();
+      return Promise.resolve<DeleteOutcome>({ undoable: true });
     },
     rename_note(
       vault_id: VaultId,
@@ -316,7 +321,7 @@ export function create_mock_notes_port(): NotesPort & {
         (path) => path !== folder_path && !path.startsWith(prefix),
       );
       mock._mock_folders.set(vault_id, updated_folders);
-      return Promise.resolve();
+      return Promise.resolve<DeleteOutcome>({ undoable: true });
     },
     get_folder_stats(
       vault_id: VaultId,
@@ -558,6 +563,32 @@ export function create_mock_watcher_port(): WatcherPort & {
   return mock;
 }
 
+export function create_mock_deep_link_port(
+  pending: DeepLinkEvent[] = [],
+): DeepLinkPort & {
+  _emit: (event: DeepLinkEvent) => void;
+  _is_subscribed: () => boolean;
+} {
+  let handler: ((event: DeepLinkEvent) => void) | null = null;
+  return {
+    _emit(event: DeepLinkEvent) {
+      handler?.(event);
+    },
+    _is_subscribed() {
+      return handler !== null;
+    },
+    take_pending() {
+      return Promise.resolve(pending.splice(0));
+    },
+    subscribe(callback: (event: DeepLinkEvent) => void) {
+      handler = callback;
+      return () => {
+        handler = null;
+      };
+    },
+  };
+}
+
 export function create_mock_ports() {
   return {
     vault: create_mock_vault_port(),
@@ -0,0 +1,43 @@
+import { describe, expect, it, vi } from "vitest";
+import { DeepLinkService } from "$lib/features/deep_link/application/deep_link_service";
+import type { DeepLinkEvent } from "$lib/features/deep_link";
+import { create_mock_deep_link_port } from "../helpers/mock_ports";
+
+const SEARCH_EVENT: DeepLinkEvent = {
+  type: "search",
+  vault_id: null,
+  query: "otters",
+};
+
+const OPEN_EVENT: DeepLinkEvent = {
+  type: "open_note",
+  vault_id: "v1",
+  note_path: "a.md",
+  heading: null,
+};
+
+describe("DeepLinkService", () => {
+  it("replays pending links and forwards new ones", async () => {
+    const port = create_mock_deep_link_port([SEARCH_EVENT]);
+    const service = new DeepLinkService(port);
+    const handler = vi.fn();
+
+    await service.start(handler);
+    port._emit(OPEN_EVENT);
+
+    expect(handler.mock.calls).toEqual([[SEARCH_EVENT], [OPEN_EVENT]]);
+  });
+
+  it("starts once and stops listening on stop", async () => {
+    const port = create_mock_deep_link_port([SEARCH_EVENT]);
+    const service = new DeepLinkService(port);
+    const handler = vi.fn();
+
+    await service.start(handler);
+    await service.start(vi.fn());
+    expect(handler).toHaveBeenCalledTimes(1);
+
+    service.stop();
+    expect(port._is_subscribed()).toBe(false);
+  });
+});
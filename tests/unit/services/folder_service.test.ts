@@ -169,7 +169,7 @@ describe("FolderService", () => {
     notes_store.add_recent_note(note_in_folder);
     notes_store.add_recent_note(note_outside);
 
-    notes_port.delete_folder = vi.fn().mockResolvedValue(undefined);
+    notes_port.delete_folder = vi.fn().mockResolvedValue({ undoable: true });
 
     const service = new FolderService(
       notes_port,
@@ -200,7 +200,7 @@ describe("FolderService", () => {
     const vault = create_test_vault();
     vault_store.set_vault(vault);
 
-    notes_port.delete_folder = vi.fn().mockResolvedValue(undefined);
+    notes_port.delete_folder = vi.fn().mockResolvedValue({ undoable: true });
 
     const service = new FolderService(
       notes_port,
@@ -220,6 +220,34 @@ describe("FolderService", () => {
     ]);
   });
 
+  it("reports when a deleted folder was too large to undo", async () => {
+    const vault_store = new VaultStore();
+    const notes_store = new NotesStore();
+    const editor_store = new EditorStore();
+    const op_store = new OpStore();
+    const tab_store = new TabStore();
+    const notes_port = create_mock_notes_port();
+    const index_port = create_mock_index_port();
+
+    vault_store.set_vault(create_test_vault());
+    notes_port.delete_folder = vi.fn().mockResolvedValue({ undoable: false });
+
+    const service = new FolderService(
+      notes_port,
+      index_port,
+      vault_store,
+      notes_store,
+      editor_store,
+      tab_store,
+      op_store,
+      () => 1,
+    );
+
+    const result = await service.delete_folder("docs");
+
+    expect(result).toEqual({ status: "success", undoable: false });
+  });
+
   it("uses dedicated folder.delete_stats op key for delete preflight failures", async () => {
     const vault_store = new VaultStore();
     const notes_store = new NotesStore();
@@ -2,16 +2,9 @@ import { describe, expect, it, vi } from "vitest";
 import { LinkRepairService } from "$lib/features/links/application/link_repair_service";
 import { EditorStore } from "$lib/features/editor/state/editor_store.svelte";
 import { TabStore } from "$lib/features/tab/state/tab_store.svelte";
-import {
-  as_markdown_text,
-  as_note_path,
-  type NoteId,
-} from "$lib/shared/types/ids";
+import { as_markdown_text, as_note_path } from "$lib/shared/types/ids";
 import { create_test_vault } from "../helpers/test_fixtures";
-import {
-  create_mock_index_port,
-  create_mock_notes_port,
-} from "../helpers/mock_ports";
+import { create_mock_notes_port } from "../helpers/mock_ports";
 import type { SearchPort } from "$lib/features/search/ports";
 
 const VAULT_ID = create_test_vault().id;
@@ -51,48 +44,37 @@ function create_mock_search_port(overrides?: Partial<SearchPort>): SearchPort {
       .mockImplementation((markdown: string) =>
         Promise.resolve({ markdown, changed: false }),
       ),
+    apply_note_link_rewrites: vi
+      .fn()
+      .mockResolvedValue({ rewritten: [], failed: [] }),
     resolve_note_link: vi.fn().mockResolvedValue(null),
     ...overrides,
   } as unknown as SearchPort;
 }
 
-function rewrite_always_changed() {
+function apply_rewrites_all() {
   return vi
     .fn()
-    .mockResolvedValue({ markdown: "See [Old](new.md)", changed: true });
+    .mockImplementation((_vault_id: unknown, note_paths: string[]) =>
+      Promise.resolve({ rewritten: note_paths, failed: [] }),
+    );
 }
 
 describe("LinkRepairService", () => {
-  it("rewrites inbound backlink sources on disk", async () => {
+  it("rewrites backlink sources and moved notes in one backend call", async () => {
     const editor_store = new EditorStore();
     const tab_store = new TabStore();
     const notes_port = create_mock_notes_port();
-    const index_port = create_mock_index_port();
-
-    notes_port.read_note = vi
-      .fn()
-      .mockImplementation((_vid: unknown, note_id: NoteId) => {
-        if (String(note_id) === SOURCE_PATH) {
-          return Promise.resolve({
-            meta: SOURCE_NOTE,
-            markdown: as_markdown_text("See [Old](old.md)"),
-          });
-        }
-        return Promise.resolve({
-          meta: { ...SOURCE_NOTE, id: note_id, path: as_note_path(note_id) },
-          markdown: as_markdown_text("# Content"),
-        });
-      });
+    const apply_note_link_rewrites = apply_rewrites_all();
 
     const search_port = create_mock_search_port({
       get_note_links_snapshot: vi.fn().mockResolvedValue(BACKLINKS_SNAPSHOT),
-      rewrite_note_links: rewrite_always_changed(),
+      apply_note_link_rewrites,
     });
 
     const service = new LinkRepairService(
       notes_port,
       search_port,
-      index_port,
       editor_store,
       tab_store,
       () => 1,
@@ -100,15 +82,13 @@ describe("LinkRepairService", () => {
 
     const result = await service.repair_links(VAULT_ID, RENAME_MAP);
 
-    expect(notes_port._calls.write_note).toContainEqual({
-      vault_id: VAULT_ID,
-      note_id: as_note_path(SOURCE_PATH),
-      markdown: as_markdown_text("See [Old](new.md)"),
-    });
-    expect(index_port._calls.upsert_note).toContainEqual({
-      vault_id: VAULT_ID,
-      note_id: as_note_path(SOURCE_PATH),
-    });
+    expect(apply_note_link_rewrites).toHaveBeenCalledTimes(1);
+    expect(apply_note_link_rewrites).toHaveBeenCalledWith(
+      VAULT_ID,
+      [SOURCE_PATH, "docs/new.md"],
+      { "docs/old.md": "docs/new.md" },
+    );
+    expect(notes_port._calls.write_note).toEqual([]);
     expect(result).toEqual({
       scanned: 2,
       rewritten: 2,
@@ -120,7 +100,7 @@ describe("LinkRepairService", () => {
     const editor_store = new EditorStore();
     const tab_store = new TabStore();
     const notes_port = create_mock_notes_port();
-    const index_port = create_mock_index_port();
+    const apply_note_link_rewrites = apply_rewrites_all();
 
     editor_store.set_open_note({
       meta: SOURCE_NOTE,
@@ -133,28 +113,13 @@ describe("LinkRepairService", () => {
       get_note_links_snapshot: vi.fn().mockResolvedValue(BACKLINKS_SNAPSHOT),
       rewrite_note_links: vi
         .fn()
-        .mockImplementation(
-          (
-            markdown: string,
-            old_source: string,
-            new_source: string,
-            _map: Record<string, string>,
-          ) => {
-            if (old_source === new_source) {
-              return Promise.resolve({
-                markdown: "See [Old](new.md)",
-                changed: true,
-              });
-            }
-            return Promise.resolve({ markdown, changed: false });
-          },
-        ),
+        .mockResolvedValue({ markdown: "See [Old](new.md)", changed: true }),
+      apply_note_link_rewrites,
     });
 
     const service = new LinkRepairService(
       notes_port,
       search_port,
-      index_port,
       editor_store,
       tab_store,
       () => 1,
@@ -168,13 +133,17 @@ describe("LinkRepairService", () => {
     expect(editor_store.open_note?.is_dirty).toBe(true);
     expect(editor_store.open_note?.buffer_id).toContain(":repair-links:");
     expect(notes_port._calls.write_note).toEqual([]);
+    expect(apply_note_link_rewrites).toHaveBeenCalledWith(
+      VAULT_ID,
+      ["docs/new.md"],
+      { "docs/old.md": "docs/new.md" },
+    );
   });
 
   it("invalidates tab cache and editor buffer for background notes", async () => {
     const editor_store = new EditorStore();
     const tab_store = new TabStore();
     const notes_port = create_mock_notes_port();
-    const index_port = create_mock_index_port();
     const close_editor_buffer = vi.fn();
 
     const source_tab = tab_store.open_tab(SOURCE_NOTE.path, SOURCE_NOTE.title);
@@ -185,20 +154,16 @@ describe("LinkRepairService", () => {
       is_dirty: false,
     });
 
-    notes_port.read_note = vi.fn().mockResolvedValue({
-      meta: SOURCE_NOTE,
-      markdown: as_markdown_text("See [Old](old.md)"),
-    });
-
     const search_port = create_mock_search_port({
       get_note_links_snapshot: vi.fn().mockResolvedValue(BACKLINKS_SNAPSHOT),
-      rewrite_note_links: rewrite_always_changed(),
+      apply_note_link_rewrites: vi
+        .fn()
+        .mockResolvedValue({ rewritten: [SOURCE_PATH], failed: [] }),
     });
 
     const service = new LinkRepairService(
       notes_port,
       search_port,
-      index_port,
       editor_store,
       tab_store,
       () => 1,
@@ -215,36 +180,38 @@ describe("LinkRepairService", () => {
     const editor_store = new EditorStore();
     const tab_store = new TabStore();
     const notes_port = create_mock_notes_port();
-    const index_port = create_mock_index_port();
 
-    const rewrite_note_links = vi
+    const apply_note_link_rewrites = vi
       .fn()
-      .mockResolvedValue({ markdown: "# Content", changed: false });
+      .mockResolvedValue({ rewritten: [], failed: [] });
     const search_port = create_mock_search_port({
       get_note_links_snapshot: vi.fn().mockResolvedValue(EMPTY_SNAPSHOT),
-      rewrite_note_links,
+      apply_note_link_rewrites,
     });
 
     const service = new LinkRepairService(
       notes_port,
       search_port,
-      index_port,
       editor_store,
       tab_store,
       () => 1,
     );
 
-    await service.repair_links(VAULT_ID, RENAME_MAP);
+    const result = await service.repair_links(VAULT_ID, RENAME_MAP);
 
     expect(notes_port._calls.write_note).toEqual([]);
-    expect(rewrite_note_links).toHaveBeenCalled();
+    expect(apply_note_link_rewrites).toHaveBeenCalledWith(
+      VAULT_ID,
+      ["docs/new.md"],
+      { "docs/old.md": "docs/new.md" },
+    );
+    expect(result).toEqual({ scanned: 1, rewritten: 0, failed: [] });
   });
 
-  it("rewrites outlinks of moved note even when editor has old path", async () => {
+  it("reloads the open moved note after its links are rewritten on disk", async () => {
     const editor_store = new EditorStore();
     const tab_store = new TabStore();
     const notes_port = create_mock_notes_port();
-    const index_port = create_mock_index_port();
 
     const old_path = "a/b/noteA.md";
     const new_path = "noteA.md";
@@ -264,20 +231,20 @@ describe("LinkRepairService", () => {
       is_dirty: false,
     });
 
-    const rewrite_note_links = vi.fn().mockResolvedValue({
-      markdown: "[Testing](Testing.md)",
-      changed: true,
+    notes_port.read_note = vi.fn().mockResolvedValue({
+      meta: { ...old_note, id: as_note_path(new_path) },
+      markdown: as_markdown_text("[Testing](Testing.md)"),
     });
 
+    const apply_note_link_rewrites = apply_rewrites_all();
     const search_port = create_mock_search_port({
       get_note_links_snapshot: vi.fn().mockResolvedValue(EMPTY_SNAPSHOT),
-      rewrite_note_links,
+      apply_note_link_rewrites,
     });
 
     const service = new LinkRepairService(
       notes_port,
       search_port,
-      index_port,
       editor_store,
       tab_store,
       () => 1,
@@ -286,13 +253,15 @@ describe("LinkRepairService", () => {
     const path_map = new Map([[old_path, new_path]]);
     await service.repair_links(VAULT_ID, path_map);
 
-    expect(rewrite_note_links).toHaveBeenCalledWith(
-      "[../../Testing](../../Testing.md)",
-      old_path,
-      new_path,
+    expect(apply_note_link_rewrites).toHaveBeenCalledWith(
+      VAULT_ID,
+      [new_path],
       { [old_path]: new_path },
     );
-
+    expect(notes_port.read_note).toHaveBeenCalledWith(
+      VAULT_ID,
+      as_note_path(new_path),
+    );
     expect(editor_store.open_note?.markdown).toBe(
       as_markdown_text("[Testing](Testing.md)"),
     );
@@ -303,7 +272,6 @@ describe("LinkRepairService", () => {
     const editor_store = new EditorStore();
     const tab_store = new TabStore();
     const notes_port = create_mock_notes_port();
-    const index_port = create_mock_index_port();
 
     const get_note_links_snapshot = vi.fn();
     const search_port = create_mock_search_port({ get_note_links_snapshot });
@@ -311,7 +279,6 @@ describe("LinkRepairService", () => {
     const service = new LinkRepairService(
       notes_port,
       search_port,
-      index_port,
       editor_store,
       tab_store,
       () => 1,
@@ -327,18 +294,17 @@ describe("LinkRepairService", () => {
     const editor_store = new EditorStore();
     const tab_store = new TabStore();
     const notes_port = create_mock_notes_port();
-    const index_port = create_mock_index_port();
-
-    notes_port.read_note = vi.fn().mockRejectedValue(new Error("disk failed"));
 
     const search_port = create_mock_search_port({
       get_note_links_snapshot: vi.fn().mockResolvedValue(BACKLINKS_SNAPSHOT),
+      apply_note_link_rewrites: vi
+        .fn()
+        .mockRejectedValue(new Error("disk failed")),
     });
 
     const service = new LinkRepairService(
       notes_port,
       search_port,
-      index_port,
       editor_store,
       tab_store,
       () => 1,
@@ -352,4 +318,34 @@ describe("LinkRepairService", () => {
       failed: [SOURCE_PATH, "docs/new.md"],
     });
   });
+
+  it("reports notes the backend could not rewrite", async () => {
+    const editor_store = new EditorStore();
+    const tab_store = new TabStore();
+    const notes_port = create_mock_notes_port();
+
+    const search_port = create_mock_search_port({
+      get_note_links_snapshot: vi.fn().mockResolvedValue(BACKLINKS_SNAPSHOT),
+      apply_note_link_rewrites: vi.fn().mockResolvedValue({
+        rewritten: ["docs/new.md"],
+        failed: [SOURCE_PATH],
+      }),
+    });
+
+    const service = new LinkRepairService(
+      notes_port,
+      search_port,
+      editor_store,
+      tab_store,
+      () => 1,
+    );
+
+    const result = await service.repair_links(VAULT_ID, RENAME_MAP);
+
+    expect(result).toEqual({
+      scanned: 2,
+      rewritten: 1,
+      failed: [SOURCE_PATH],
+    });
+  });
 });
@@ -57,6 +57,7 @@ describe("LinksService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -90,6 +91,7 @@ describe("LinksService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -139,6 +141,7 @@ describe("LinksService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -192,6 +195,7 @@ describe("LinksService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -232,6 +236,7 @@ describe("LinksService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
     const vault_store = new VaultStore();
@@ -278,6 +283,7 @@ describe("LinksService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
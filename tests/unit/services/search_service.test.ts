@@ -77,6 +77,7 @@ describe("SearchService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -120,6 +121,7 @@ describe("SearchService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -162,6 +164,7 @@ describe("SearchService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -209,6 +212,7 @@ describe("SearchService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -254,6 +258,7 @@ describe("SearchService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -317,6 +322,7 @@ describe("SearchService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -376,6 +382,7 @@ describe("SearchService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -456,6 +463,7 @@ describe("SearchService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -524,6 +532,7 @@ describe("SearchService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
@@ -570,6 +579,7 @@ describe("SearchService", () => {
         .mockImplementation((markdown: string) =>
           Promise.resolve({ markdown, changed: false }),
         ),
+      apply_note_link_rewrites: vi.fn(),
       resolve_note_link: vi.fn().mockResolvedValue(null),
     };
 
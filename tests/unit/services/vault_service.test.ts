@@ -491,6 +491,70 @@ describe("VaultService", () => {
     expect(skipped).toEqual({ status: "skipped" });
   });
 
+  it("opens the window's vault by id on startup", async () => {
+    const vault_b: Vault = {
+      id: as_vault_id("vault-b"),
+      name: "Vault B",
+      path: as_vault_path("/vault/b"),
+      created_at: 1,
+    };
+    const vault_port = {
+      choose_vault: vi.fn(),
+      open_vault: vi.fn(),
+      open_vault_by_id: vi.fn().mockResolvedValue(vault_b),
+      list_vaults: vi.fn().mockResolvedValue([vault_b]),
+      remember_last_vault: vi.fn().mockResolvedValue(undefined),
+      get_last_vault_id: vi.fn(),
+    };
+    const vault_store = new VaultStore();
+
+    const service = new VaultService(
+      vault_port as never,
+      {
+        list_folder_contents: vi.fn().mockResolvedValue({
+          notes: [],
+          subfolders: [],
+          total_count: 0,
+          has_more: false,
+        }),
+        get_folder_stats: vi.fn().mockResolvedValue({
+          note_count: 0,
+          folder_count: 0,
+        }),
+      } as never,
+      {
+        cancel_index: vi.fn(),
+        sync_index: vi.fn().mockResolvedValue(undefined),
+        subscribe_index_progress: vi.fn().mockReturnValue(() => {}),
+      } as never,
+      {
+        get_setting: vi.fn().mockResolvedValue(null),
+        set_setting: vi.fn().mockResolvedValue(undefined),
+      } as never,
+      {
+        get_vault_setting: vi.fn().mockResolvedValue(null),
+        set_vault_setting: vi.fn().mockResolvedValue(undefined),
+      } as never,
+      vault_store,
+      new NotesStore(),
+      new EditorStore(),
+      new OpStore(),
+      new SearchStore(),
+      () => 1,
+    );
+
+    const result = await service.initialize({
+      reset_app_state: false,
+      bootstrap_default_vault_path: null,
+      bootstrap_vault_id: vault_b.id,
+    });
+
+    expect(result).toMatchObject({ status: "ready", has_vault: true });
+    expect(vault_port.open_vault_by_id).toHaveBeenCalledWith(vault_b.id);
+    expect(vault_port.open_vault).not.toHaveBeenCalled();
+    expect(vault_store.vault?.id).toBe(vault_b.id);
+  });
+
   it("cancels in-flight index when vault changes", async () => {
     const vault_a: Vault = {
       id: as_vault_id("vault-a"),
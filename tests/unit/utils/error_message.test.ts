@@ -1,5 +1,10 @@
 import { describe, it, expect } from "vitest";
-import { error_message } from "$lib/shared/utils/error_message";
+import {
+  CommandError,
+  error_code,
+  error_message,
+  to_command_error,
+} from "$lib/shared/utils/error_message";
 
 describe("error_message", () => {
   it("extracts message from Error instances", () => {
@@ -11,6 +16,28 @@ describe("error_message", () => {
     expect(error_message("something broke")).toBe("something broke");
   });
 
+  it("reads message from typed command errors", () => {
+    const err = { code: "already_exists", message: "note already exists" };
+    expect(error_message(err)).toBe("note already exists");
+    expect(error_code(err)).toBe("already_exists");
+    expect(error_code("note already exists")).toBeNull();
+  });
+
+  it("wraps typed payloads into CommandError", () => {
+    const payload = {
+      code: "conflict",
+      message: "conflict:mtime_mismatch",
+      current_mtime_ms: 42,
+    };
+    const err = to_command_error(payload, "write failed");
+    expect(err).toBeInstanceOf(CommandError);
+    expect(error_code(err)).toBe("conflict");
+    expect((err as CommandError).details.current_mtime_ms).toBe(42);
+    expect(to_command_error("boom", "write failed")).not.toBeInstanceOf(
+      CommandError,
+    );
+  });
+
   it("returns fallback for unknown types", () => {
     expect(error_message(42)).toBe("Unknown error");
     expect(error_message(null)).toBe("Unknown error");